@@ -137,3 +137,33 @@ where
         self.body.size_hint()
     }
 }
+
+#[cfg(feature = "cache")]
+/// response body yielding buffered bytes before the remaining body.
+pub(crate) struct PrefixBody<'c> {
+    prefix: Option<Bytes>,
+    body: ResponseBody<'c>,
+}
+
+#[cfg(feature = "cache")]
+impl<'c> PrefixBody<'c> {
+    pub(crate) fn new(prefix: Bytes, body: ResponseBody<'c>) -> Self {
+        Self {
+            prefix: (!prefix.is_empty()).then_some(prefix),
+            body,
+        }
+    }
+}
+
+#[cfg(feature = "cache")]
+impl Stream for PrefixBody<'_> {
+    type Item = Result<Bytes, BodyError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match this.prefix.take() {
+            Some(bytes) => Poll::Ready(Some(Ok(bytes))),
+            None => Pin::new(&mut this.body).poll_next(cx),
+        }
+    }
+}
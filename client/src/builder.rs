@@ -7,7 +7,7 @@ use crate::{
     connect::Connect,
     date::DateTimeService,
     error::Error,
-    pool::Pool,
+    pool::{Pool, PoolConfig},
     resolver::{base_resolver, ResolverService},
     response::Response,
     service::{base_service, HttpService},
@@ -19,15 +19,20 @@ use crate::{
     },
 };
 
+#[cfg(any(feature = "openssl", feature = "rustls", feature = "http3"))]
+use crate::tls::config::TlsConfig;
+
 /// Builder type for [Client]. Offer configurations before a client instance is created.
 pub struct ClientBuilder {
     connector: Connector,
     resolver: ResolverService,
-    pool_capacity: usize,
+    pool_config: PoolConfig,
     timeout_config: TimeoutConfig,
     local_addr: Option<SocketAddr>,
     max_http_version: Version,
     service: HttpService,
+    #[cfg(feature = "http3")]
+    h3_configs: Option<connector::h3::H3Configs>,
 }
 
 impl Default for ClientBuilder {
@@ -41,11 +46,13 @@ impl ClientBuilder {
         ClientBuilder {
             connector: connector::nop(),
             resolver: base_resolver(),
-            pool_capacity: 128,
+            pool_config: PoolConfig::new(),
             timeout_config: TimeoutConfig::new(),
             local_addr: None,
             max_http_version: max_http_version(),
             service: base_service(),
+            #[cfg(feature = "http3")]
+            h3_configs: None,
         }
     }
 
@@ -119,16 +126,47 @@ impl ClientBuilder {
 
     #[cfg(feature = "openssl")]
     /// enable openssl as tls connector.
-    pub fn openssl(mut self) -> Self {
-        self.connector = connector::openssl::connect(self.alpn_from_version());
-        self
+    pub fn openssl(self) -> Self {
+        self.openssl_with_config(TlsConfig::new())
+            .expect("default TlsConfig must be valid")
+    }
+
+    #[cfg(feature = "openssl")]
+    /// enable openssl as tls connector with given [TlsConfig].
+    ///
+    /// When http3 feature is enabled the same config is applied to http/3 connections.
+    pub fn openssl_with_config(mut self, config: TlsConfig) -> Result<Self, Error> {
+        self.connector = connector::openssl::connect(self.alpn_from_version(), &config)?;
+        self.h3_with_config(&config)
     }
 
     #[cfg(feature = "rustls")]
     /// enable rustls as tls connector.
-    pub fn rustls(mut self) -> Self {
-        self.connector = connector::rustls::connect(self.alpn_from_version());
-        self
+    pub fn rustls(self) -> Self {
+        self.rustls_with_config(TlsConfig::new())
+            .expect("default TlsConfig must be valid")
+    }
+
+    #[cfg(feature = "rustls")]
+    /// enable rustls as tls connector with given [TlsConfig].
+    ///
+    /// When http3 feature is enabled the same config is applied to http/3 connections.
+    pub fn rustls_with_config(mut self, config: TlsConfig) -> Result<Self, Error> {
+        self.connector = connector::rustls::connect(self.alpn_from_version(), &config)?;
+        self.h3_with_config(&config)
+    }
+
+    #[cfg(any(feature = "openssl", feature = "rustls"))]
+    fn h3_with_config(self, _config: &TlsConfig) -> Result<Self, Error> {
+        #[cfg(feature = "http3")]
+        {
+            let mut this = self;
+            this.h3_configs = Some(connector::h3::configs(_config)?);
+            Ok(this)
+        }
+
+        #[cfg(not(feature = "http3"))]
+        Ok(self)
     }
 
     #[cfg(any(feature = "openssl", feature = "rustls"))]
@@ -288,7 +326,60 @@ impl ClientBuilder {
     /// When pass 0 as pool capacity.
     pub fn set_pool_capacity(mut self, cap: usize) -> Self {
         assert_ne!(cap, 0);
-        self.pool_capacity = cap;
+        self.pool_config.capacity = cap;
+        self
+    }
+
+    /// Set max number of concurrent connections client would open for a single host.
+    ///
+    /// The limit applies to non multiplexed connections (HTTP/1). Requests exceeding the limit would
+    /// wait until a connection is released back to pool. Multiplexed connections (HTTP/2) are shared
+    /// by concurrent requests and only bound by [ClientBuilder::set_pool_capacity].
+    ///
+    /// Default to no limit.
+    ///
+    /// # Panics:
+    /// When pass 0 as max connections.
+    pub fn set_pool_max_per_host(mut self, max: usize) -> Self {
+        assert_ne!(max, 0);
+        self.pool_config.max_per_host = max;
+        self
+    }
+
+    /// Set max number of idle connections kept in pool for a single host.
+    /// Connections released when the limit is reached would be closed.
+    ///
+    /// Default to no limit.
+    pub fn set_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_config.max_idle_per_host = max;
+        self
+    }
+
+    /// Set duration a connection can stay idle in pool before it's evicted.
+    ///
+    /// Default to 10 minutes.
+    pub fn set_pool_idle_timeout(mut self, dur: Duration) -> Self {
+        self.pool_config.idle_timeout = dur;
+        self
+    }
+
+    /// Set max duration a connection can live before it's evicted.
+    ///
+    /// Default to 1 hour.
+    pub fn set_pool_max_lifetime(mut self, dur: Duration) -> Self {
+        self.pool_config.max_lifetime = dur;
+        self
+    }
+
+    /// Set interval of background task evicting expired connections from pool.
+    /// Pass None to disable background eviction and expired connections would only be evicted when
+    /// they are acquired from pool.
+    ///
+    /// The task is spawned on first request client sends and it exits when client is dropped.
+    ///
+    /// Default to 60 seconds.
+    pub fn set_pool_reap_interval(mut self, dur: Option<Duration>) -> Self {
+        self.pool_config.reap_interval = dur;
         self
     }
 
@@ -310,35 +401,14 @@ impl ClientBuilder {
     pub fn finish(self) -> Client {
         #[cfg(feature = "http3")]
         {
-            use std::sync::Arc;
-
-            use h3_quinn::quinn::{ClientConfig, Endpoint};
-            use rustls_0dot21 as rustls;
+            use h3_quinn::quinn::Endpoint;
 
             #[cfg(not(feature = "dangerous"))]
-            let h3_client = {
-                use rustls::{OwnedTrustAnchor, RootCertStore};
-                use webpki_roots_0dot25::TLS_SERVER_ROOTS;
-
-                let mut root_certs = RootCertStore::empty();
-                for cert in TLS_SERVER_ROOTS {
-                    let cert = OwnedTrustAnchor::from_subject_spki_name_constraints(
-                        cert.subject,
-                        cert.spki,
-                        cert.name_constraints,
-                    );
-                    let certs = vec![cert].into_iter();
-                    root_certs.add_trust_anchors(certs);
-                }
-
-                let mut crypto = rustls::ClientConfig::builder()
-                    .with_safe_defaults()
-                    .with_root_certificates(root_certs)
-                    .with_no_client_auth();
-
-                crypto.alpn_protocols = vec![b"h3-29".to_vec()];
-
-                let config = ClientConfig::new(Arc::new(crypto));
+            let (h3_client, h3_hosts) = {
+                let (config, hosts) = match self.h3_configs {
+                    Some(configs) => configs,
+                    None => connector::h3::configs(&TlsConfig::new()).expect("default TlsConfig must be valid"),
+                };
 
                 let mut endpoint = match self.local_addr {
                     Some(addr) => Endpoint::client(addr).unwrap(),
@@ -347,11 +417,16 @@ impl ClientBuilder {
 
                 endpoint.set_default_client_config(config);
 
-                endpoint
+                (endpoint, hosts)
             };
 
             #[cfg(feature = "dangerous")]
-            let h3_client = {
+            let (h3_client, h3_hosts) = {
+                use std::sync::Arc;
+
+                use h3_quinn::quinn::ClientConfig;
+                use rustls_0dot21 as rustls;
+
                 struct SkipServerVerification;
 
                 impl SkipServerVerification {
@@ -388,11 +463,11 @@ impl ClientBuilder {
 
                 endpoint.set_default_client_config(config);
 
-                endpoint
+                (endpoint, std::collections::HashMap::new())
             };
 
             Client {
-                pool: Pool::with_capacity(self.pool_capacity),
+                pool: Pool::new(self.pool_config),
                 connector: self.connector,
                 resolver: self.resolver,
                 timeout_config: self.timeout_config,
@@ -401,12 +476,13 @@ impl ClientBuilder {
                 date_service: DateTimeService::new(),
                 service: self.service,
                 h3_client,
+                h3_hosts,
             }
         }
 
         #[cfg(not(feature = "http3"))]
         Client {
-            pool: Pool::with_capacity(self.pool_capacity),
+            pool: Pool::new(self.pool_config),
             connector: self.connector,
             resolver: self.resolver,
             timeout_config: self.timeout_config,
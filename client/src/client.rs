@@ -15,7 +15,7 @@ use crate::{
     date::DateTimeService,
     error::{Error, TimeoutError},
     http::{self, uri, Method, Version},
-    pool::Pool,
+    pool::{Pool, PoolStats},
     request::RequestBuilder,
     resolver::ResolverService,
     service::HttpService,
@@ -39,6 +39,8 @@ pub struct Client {
     pub(crate) service: HttpService,
     #[cfg(feature = "http3")]
     pub(crate) h3_client: h3_quinn::quinn::Endpoint,
+    #[cfg(feature = "http3")]
+    pub(crate) h3_hosts: std::collections::HashMap<Box<str>, h3_quinn::quinn::ClientConfig>,
 }
 
 impl Default for Client {
@@ -73,6 +75,13 @@ impl Client {
         ClientBuilder::new()
     }
 
+    /// Snapshot of connection pool state.
+    ///
+    /// Can be used to observe active, idle and pending connections of each host client communicates with.
+    pub fn pool_stats(&self) -> PoolStats {
+        self.pool.stats()
+    }
+
     /// Start a new HTTP request with given [http::Request].
     #[inline]
     pub fn request<B, E>(&self, req: http::Request<B>) -> RequestBuilder<'_>
@@ -181,20 +190,12 @@ impl Client {
     ) -> Result<Connection, Error> {
         match connect.uri {
             Uri::Tcp(_) => {
-                self.resolver
-                    .call(connect)
-                    .timeout(timer.as_mut())
-                    .await
-                    .map_err(|_| TimeoutError::Resolve)??;
+                self.resolve(connect, timer).await?;
 
                 self.make_tcp(connect, timer).await.map(Into::into)
             }
             Uri::Tls(_) => {
-                self.resolver
-                    .call(connect)
-                    .timeout(timer.as_mut())
-                    .await
-                    .map_err(|_| TimeoutError::Resolve)??;
+                self.resolve(connect, timer).await?;
 
                 #[cfg(feature = "http3")]
                 // TODO: find better way to discover http3.
@@ -208,14 +209,28 @@ impl Client {
                 self.make_tls(connect, timer, max_version).await
             }
             #[cfg(unix)]
-            Uri::Unix(uri) => self.make_unix(uri, timer).await,
+            Uri::Unix(uri) => self.make_unix(connect, uri, timer).await,
         }
     }
 
+    async fn resolve(&self, connect: &mut Connect<'_>, timer: &mut Pin<Box<Sleep>>) -> Result<(), Error> {
+        let start = Instant::now();
+
+        self.resolver
+            .call(connect)
+            .timeout(timer.as_mut())
+            .await
+            .map_err(|_| TimeoutError::Resolve)??;
+
+        connect.record(|t| t.resolve = Some(start.elapsed()));
+
+        Ok(())
+    }
+
     async fn make_tcp(&self, connect: &Connect<'_>, timer: &mut Pin<Box<Sleep>>) -> Result<TcpStream, Error> {
-        timer
-            .as_mut()
-            .reset(Instant::now() + self.timeout_config.connect_timeout);
+        let start = Instant::now();
+
+        timer.as_mut().reset(start + self.timeout_config.connect_timeout);
 
         let stream = self
             .make_tcp_inner(connect)
@@ -223,6 +238,8 @@ impl Client {
             .await
             .map_err(|_| TimeoutError::Connect)??;
 
+        connect.record(|t| t.connect = Some(start.elapsed()));
+
         // TODO: make nodelay configurable?
         let _ = stream.set_nodelay(true);
 
@@ -277,9 +294,9 @@ impl Client {
     ) -> Result<Connection, Error> {
         let stream = self.make_tcp(connect, timer).await?;
 
-        timer
-            .as_mut()
-            .reset(Instant::now() + self.timeout_config.tls_connect_timeout);
+        let start = Instant::now();
+
+        timer.as_mut().reset(start + self.timeout_config.tls_connect_timeout);
 
         let (stream, version) = self
             .connector
@@ -288,6 +305,8 @@ impl Client {
             .await
             .map_err(|_| TimeoutError::TlsHandshake)??;
 
+        connect.record(|t| t.tls = Some(start.elapsed()));
+
         let version = match (version, max_version) {
             (Version::HTTP_3, Version::HTTP_2 | Version::HTTP_11 | Version::HTTP_10 | Version::HTTP_09) => max_version,
             (Version::HTTP_2, Version::HTTP_11 | Version::HTTP_10 | Version::HTTP_09) => max_version,
@@ -318,9 +337,9 @@ impl Client {
 
     #[cfg(feature = "http3")]
     async fn make_h3(&self, connect: &Connect<'_>, timer: &mut Pin<Box<Sleep>>) -> Result<Connection, Error> {
-        timer
-            .as_mut()
-            .reset(Instant::now() + self.timeout_config.connect_timeout);
+        let start = Instant::now();
+
+        timer.as_mut().reset(start + self.timeout_config.connect_timeout);
 
         let stream = self
             .make_h3_inner(connect)
@@ -328,6 +347,9 @@ impl Client {
             .await
             .map_err(|_| TimeoutError::Connect)??;
 
+        // quic handshake establishes connection and tls session together.
+        connect.record(|t| t.connect = Some(start.elapsed()));
+
         Ok(stream)
     }
 
@@ -337,10 +359,13 @@ impl Client {
 
         let mut addr = iter.next().ok_or(Error::Resolve)?;
 
+        // per host config takes precedence over endpoint's default config.
+        let config = self.h3_hosts.get(connect.hostname());
+
         // try to connect with all addresses resolved by dns resolver.
         // return the last error when all are fail to be connected.
         loop {
-            match crate::h3::proto::connect(&self.h3_client, &addr, connect.hostname()).await {
+            match crate::h3::proto::connect(&self.h3_client, config, &addr, connect.hostname()).await {
                 Ok(connection) => return Ok(connection.into()),
                 Err(e) => match iter.next() {
                     Some(a) => addr = a,
@@ -351,10 +376,15 @@ impl Client {
     }
 
     #[cfg(unix)]
-    async fn make_unix(&self, uri: &uri::Uri, timer: &mut Pin<Box<Sleep>>) -> Result<Connection, Error> {
-        timer
-            .as_mut()
-            .reset(Instant::now() + self.timeout_config.connect_timeout);
+    async fn make_unix(
+        &self,
+        connect: &Connect<'_>,
+        uri: &uri::Uri,
+        timer: &mut Pin<Box<Sleep>>,
+    ) -> Result<Connection, Error> {
+        let start = Instant::now();
+
+        timer.as_mut().reset(start + self.timeout_config.connect_timeout);
 
         let path = format!(
             "/{}{}",
@@ -367,6 +397,8 @@ impl Client {
             .await
             .map_err(|_| TimeoutError::Connect)??;
 
+        connect.record(|t| t.connect = Some(start.elapsed()));
+
         Ok(stream.into())
     }
 }
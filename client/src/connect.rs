@@ -4,7 +4,7 @@ use std::{
     net::SocketAddr,
 };
 
-use crate::uri::Uri;
+use crate::{timing::RequestTiming, uri::Uri};
 
 pub trait Address {
     /// Get hostname part.
@@ -82,6 +82,7 @@ pub struct Connect<'a> {
     pub(crate) uri: Uri<'a>,
     pub(crate) port: u16,
     pub(crate) addr: Addrs,
+    pub(crate) timing: Option<RequestTiming>,
 }
 
 impl<'a> Connect<'a> {
@@ -93,6 +94,7 @@ impl<'a> Connect<'a> {
             uri,
             port: port.unwrap_or(0),
             addr: Addrs::None,
+            timing: None,
         }
     }
 
@@ -129,6 +131,14 @@ impl<'a> Connect<'a> {
     }
 }
 
+impl Connect<'_> {
+    pub(crate) fn record(&self, func: impl FnOnce(&mut crate::timing::TimingPhases)) {
+        if let Some(ref timing) = self.timing {
+            timing.record(func);
+        }
+    }
+}
+
 impl fmt::Display for Connect<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}:{}", self.hostname(), self.port())
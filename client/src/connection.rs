@@ -1,4 +1,5 @@
 use std::{
+    fmt,
     hash::{Hash, Hasher},
     io::{self, IoSlice},
     pin::Pin,
@@ -162,6 +163,16 @@ pub enum ConnectionKey {
     Unix(AuthorityWithPath),
 }
 
+impl fmt::Display for ConnectionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Regular(ref authority) => fmt::Display::fmt(authority, f),
+            #[cfg(unix)]
+            Self::Unix(ref a) => write!(f, "{}{}", a.authority, a.path_and_query),
+        }
+    }
+}
+
 #[doc(hidden)]
 #[derive(Eq, Debug, Clone)]
 pub struct AuthorityWithPath {
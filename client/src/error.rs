@@ -13,6 +13,8 @@ pub enum Error {
     Resolve,
     Timeout(TimeoutError),
     TlsNotEnabled,
+    #[cfg(any(feature = "openssl", feature = "rustls", feature = "http3"))]
+    TlsConfig(TlsConfigError),
     #[cfg(feature = "http1")]
     H1(crate::h1::Error),
     #[cfg(feature = "http2")]
@@ -101,11 +103,30 @@ impl From<TimeoutError> for Error {
     }
 }
 
+/// error produced when building tls connector from [TlsConfig](crate::TlsConfig).
+#[cfg(any(feature = "openssl", feature = "rustls", feature = "http3"))]
+#[derive(Debug)]
+pub enum TlsConfigError {
+    Pem(io::Error),
+    MissingCertificate,
+    MissingPrivateKey,
+    Backend(Box<dyn error::Error + Send + Sync>),
+}
+
+#[cfg(any(feature = "openssl", feature = "rustls", feature = "http3"))]
+impl From<TlsConfigError> for Error {
+    fn from(e: TlsConfigError) -> Self {
+        Self::TlsConfig(e)
+    }
+}
+
 #[derive(Debug)]
 pub enum ParseError {
     String(str::Utf8Error),
     #[cfg(feature = "json")]
     Json(serde_json::Error),
+    #[cfg(feature = "urlencoded")]
+    UrlEncoded(serde_urlencoded::ser::Error),
     #[cfg(feature = "websocket")]
     WebSocket(http_ws::ProtocolError),
 }
@@ -176,6 +197,13 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(feature = "urlencoded")]
+impl From<serde_urlencoded::ser::Error> for Error {
+    fn from(e: serde_urlencoded::ser::Error) -> Self {
+        Self::Parse(ParseError::UrlEncoded(e))
+    }
+}
+
 #[cfg(feature = "http1")]
 impl From<crate::h1::Error> for Error {
     fn from(e: crate::h1::Error) -> Self {
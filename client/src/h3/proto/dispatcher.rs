@@ -2,7 +2,7 @@ use core::{future::poll_fn, pin::pin};
 
 use std::net::SocketAddr;
 
-use ::h3_quinn::quinn::Endpoint;
+use ::h3_quinn::quinn::{ClientConfig, Endpoint};
 use futures_core::stream::Stream;
 use xitca_http::date::DateTime;
 
@@ -90,8 +90,17 @@ where
     Ok(res)
 }
 
-pub(crate) async fn connect(client: &Endpoint, addr: &SocketAddr, hostname: &str) -> Result<Connection, Error> {
-    let conn = client.connect(*addr, hostname)?.await?;
+pub(crate) async fn connect(
+    client: &Endpoint,
+    config: Option<&ClientConfig>,
+    addr: &SocketAddr,
+    hostname: &str,
+) -> Result<Connection, Error> {
+    let conn = match config {
+        Some(config) => client.connect_with(config.clone(), *addr, hostname)?,
+        None => client.connect(*addr, hostname)?,
+    }
+    .await?;
 
     let (mut task, conn) = h3::client::new(h3_quinn::Connection::new(conn)).await?;
 
@@ -41,6 +41,7 @@ mod resolver;
 mod response;
 mod service;
 mod timeout;
+mod timing;
 mod tls;
 mod tunnel;
 mod uri;
@@ -54,6 +55,9 @@ mod h2;
 #[cfg(feature = "http3")]
 mod h3;
 
+#[cfg(feature = "multipart")]
+pub mod multipart;
+
 #[cfg(feature = "websocket")]
 pub mod ws;
 
@@ -63,12 +67,17 @@ pub mod middleware;
 pub use self::builder::ClientBuilder;
 pub use self::client::Client;
 pub use self::connect::Connect;
+pub use self::pool::{HostStats, PoolStats};
 pub use self::request::RequestBuilder;
 pub use self::response::Response;
 pub use self::service::{HttpService, Service, ServiceRequest};
 pub use self::timeout::TimeoutConfig;
+pub use self::timing::{RequestTiming, TimingPhases};
 pub use self::tls::{connector::Connector, stream::Io};
 
+#[cfg(any(feature = "openssl", feature = "rustls", feature = "http3"))]
+pub use self::tls::config::{Identity, TlsConfig};
+
 // re-export http crate.
 pub use xitca_http::http;
 
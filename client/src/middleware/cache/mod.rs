@@ -0,0 +1,645 @@
+mod policy;
+mod storage;
+
+pub use storage::{CacheStorage, CachedResponse, DiskStorage, MemoryStorage};
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use tracing::warn;
+
+use crate::{
+    body::{BoxBody, PrefixBody, ResponseBody},
+    client::Client,
+    error::Error,
+    http::{
+        self,
+        header::{
+            HeaderMap, HeaderName, HeaderValue, AGE, CONNECTION, CONTENT_LENGTH, ETAG, IF_MATCH, IF_MODIFIED_SINCE,
+            IF_NONE_MATCH, IF_RANGE, IF_UNMODIFIED_SINCE, LAST_MODIFIED, RANGE, TRANSFER_ENCODING,
+        },
+        Method, StatusCode,
+    },
+    response::Response,
+    service::{Service, ServiceRequest},
+};
+
+use self::policy::CacheControl;
+
+const CONDITIONAL: [HeaderName; 6] = [
+    IF_NONE_MATCH,
+    IF_MODIFIED_SINCE,
+    IF_MATCH,
+    IF_UNMODIFIED_SINCE,
+    IF_RANGE,
+    RANGE,
+];
+
+/// middleware caching http responses following RFC 9111.
+///
+/// Only responses to GET requests are cached. Stale responses are revalidated with server using
+/// `ETag` and `Last-Modified` validators. Successful unsafe requests (POST, PUT, DELETE, etc.)
+/// invalidate stored response of the same uri.
+///
+/// # Examples
+/// ```rust
+/// use xitca_client::{middleware::{Cache, MemoryStorage}, Client};
+///
+/// # fn build() {
+/// let client = Client::builder()
+///     .middleware(|service| Cache::new(service).storage(MemoryStorage::new(256)))
+///     .finish();
+/// # }
+/// ```
+pub struct Cache<S, St = MemoryStorage> {
+    service: S,
+    storage: Arc<St>,
+    config: Config,
+    background: Option<Arc<Client>>,
+    revalidating: Arc<Mutex<HashSet<Box<str>>>>,
+}
+
+#[derive(Clone, Copy)]
+struct Config {
+    shared: bool,
+    max_body_size: usize,
+}
+
+impl<S> Cache<S> {
+    /// construct a new cache middleware with given http service type.
+    /// Responses are stored in a [MemoryStorage] holding at most 1024 responses.
+    pub fn new(service: S) -> Self {
+        Self {
+            service,
+            storage: Arc::new(MemoryStorage::new(1024)),
+            config: Config {
+                shared: false,
+                max_body_size: 1024 * 1024,
+            },
+            background: None,
+            revalidating: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+}
+
+impl<S, St> Cache<S, St> {
+    /// Use given storage for stored responses.
+    pub fn storage<St2>(self, storage: St2) -> Cache<S, St2> {
+        Cache {
+            service: self.service,
+            storage: Arc::new(storage),
+            config: self.config,
+            background: self.background,
+            revalidating: self.revalidating,
+        }
+    }
+
+    /// Act as shared cache. A shared cache does not store responses marked as private or
+    /// responses to requests with `Authorization` header unless server explicitly allows it.
+    ///
+    /// Default to false.
+    pub fn shared(mut self, shared: bool) -> Self {
+        self.config.shared = shared;
+        self
+    }
+
+    /// Set max size of response body in bytes can be stored. Responses with larger body are not stored.
+    ///
+    /// Default to 1 Mb.
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.config.max_body_size = size;
+        self
+    }
+
+    /// Set client used for revalidating stale responses in background.
+    ///
+    /// When set stale response allowed by `stale-while-revalidate` directive is returned immediately
+    /// and revalidation is done with given client in a spawned task. Without it stale responses are
+    /// always revalidated before returning.
+    ///
+    /// Background requests bypass the middleware stack of the client this middleware belongs to.
+    pub fn background_client(mut self, client: Client) -> Self {
+        self.background = Some(Arc::new(client));
+        self
+    }
+}
+
+impl<'r, 'c, S, St> Service<ServiceRequest<'r, 'c>> for Cache<S, St>
+where
+    S: for<'r2, 'c2> Service<ServiceRequest<'r2, 'c2>, Response = Response<'c2>, Error = Error> + Send + Sync,
+    St: CacheStorage,
+{
+    type Response = Response<'c>;
+    type Error = Error;
+
+    async fn call(&self, req: ServiceRequest<'r, 'c>) -> Result<Self::Response, Self::Error> {
+        let ServiceRequest { req, client, timeout } = req;
+
+        let key = req.uri().to_string();
+
+        match *req.method() {
+            Method::GET => {}
+            // safe methods other than GET are not cached.
+            Method::HEAD | Method::OPTIONS | Method::TRACE => {
+                return self.service.call(ServiceRequest { req, client, timeout }).await;
+            }
+            // unsafe methods invalidate stored response. (RFC 9111 section 4.4)
+            _ => {
+                let res = self.service.call(ServiceRequest { req, client, timeout }).await?;
+                if res.status().is_success() || res.status().is_redirection() {
+                    if let Err(e) = self.storage.remove(&key).await {
+                        warn!("failed to invalidate cached response: {e}");
+                    }
+                }
+                return Ok(res);
+            }
+        }
+
+        let req_cc = CacheControl::from_headers(req.headers());
+
+        // conditional and range requests are handled by server.
+        if req_cc.no_store || CONDITIONAL.iter().any(|h| req.headers().contains_key(h)) {
+            return self.service.call(ServiceRequest { req, client, timeout }).await;
+        }
+
+        let stored = match self.storage.get(&key).await {
+            Ok(stored) => stored.filter(|stored| vary_matches(stored, req.headers())),
+            Err(e) => {
+                warn!("failed to look up cached response: {e}");
+                None
+            }
+        };
+
+        let now = SystemTime::now();
+
+        if let Some(ref stored) = stored {
+            let res_cc = CacheControl::from_headers(&stored.headers);
+            let age = current_age(stored, now);
+            let lifetime = policy::freshness_lifetime(
+                stored.status,
+                &stored.headers,
+                &res_cc,
+                stored.response_time,
+                self.config.shared,
+            );
+
+            let revalidate = req_cc.no_cache || res_cc.no_cache;
+            let age_allowed = req_cc.max_age.is_none_or(|max| age <= max);
+
+            if !revalidate && age_allowed {
+                let min_fresh = req_cc.min_fresh.unwrap_or(Duration::ZERO);
+                if age.saturating_add(min_fresh) < lifetime {
+                    return Ok(cached_response(stored, age, client));
+                }
+
+                let staleness = age.saturating_sub(lifetime);
+                let may_serve_stale = !res_cc.must_revalidate && !(self.config.shared && res_cc.proxy_revalidate);
+
+                if may_serve_stale {
+                    if req_cc.max_stale.is_some_and(|max| staleness <= max) {
+                        return Ok(cached_response(stored, age, client));
+                    }
+
+                    if let (Some(swr), Some(background)) = (res_cc.stale_while_revalidate, &self.background) {
+                        if staleness <= swr {
+                            self.revalidate_in_background(background, key, req, stored.clone());
+                            return Ok(cached_response(stored, age, client));
+                        }
+                    }
+                }
+            }
+        }
+
+        if req_cc.only_if_cached {
+            return Ok(gateway_timeout(client));
+        }
+
+        if let Some(ref stored) = stored {
+            add_validators(req.headers_mut(), stored);
+        }
+
+        let method = req.method().clone();
+        let req_headers = req.headers().clone();
+
+        let res = self.service.call(ServiceRequest { req, client, timeout }).await?;
+
+        let ctx = Context {
+            storage: &*self.storage,
+            config: self.config,
+            key: &key,
+            method: &method,
+            req_headers: &req_headers,
+            req_cc: &req_cc,
+            request_time: now,
+        };
+
+        ctx.handle(res, stored, client).await
+    }
+}
+
+impl<S, St> Cache<S, St>
+where
+    St: CacheStorage,
+{
+    fn revalidate_in_background(
+        &self,
+        client: &Arc<Client>,
+        key: String,
+        req: &http::Request<BoxBody>,
+        stored: CachedResponse,
+    ) {
+        // only one revalidation for the same response can be in flight.
+        if !self.revalidating.lock().unwrap().insert(key.as_str().into()) {
+            return;
+        }
+
+        let client = client.clone();
+        let storage = self.storage.clone();
+        let revalidating = self.revalidating.clone();
+        let config = self.config;
+
+        let uri = req.uri().clone();
+        let mut headers = req.headers().clone();
+
+        tokio::spawn(async move {
+            add_validators(&mut headers, &stored);
+
+            let mut req = http::Request::new(BoxBody::default());
+            *req.uri_mut() = uri;
+            *req.headers_mut() = headers.clone();
+
+            let req_cc = CacheControl::from_headers(&headers);
+
+            let ctx = Context {
+                storage: &*storage,
+                config,
+                key: &key,
+                method: &Method::GET,
+                req_headers: &headers,
+                req_cc: &req_cc,
+                request_time: SystemTime::now(),
+            };
+
+            let res = match client.request(req).send().await {
+                Ok(res) => ctx.handle(res, Some(stored), &client).await,
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = res {
+                warn!("failed to revalidate cached response in background: {e}");
+            }
+
+            revalidating.lock().unwrap().remove(key.as_str());
+        });
+    }
+}
+
+// shared state for handling response of forwarded request.
+struct Context<'a, St> {
+    storage: &'a St,
+    config: Config,
+    key: &'a str,
+    method: &'a Method,
+    req_headers: &'a HeaderMap,
+    req_cc: &'a CacheControl,
+    request_time: SystemTime,
+}
+
+impl<St> Context<'_, St>
+where
+    St: CacheStorage,
+{
+    async fn handle<'c>(
+        &self,
+        res: Response<'c>,
+        stored: Option<CachedResponse>,
+        client: &'c Client,
+    ) -> Result<Response<'c>, Error> {
+        let response_time = SystemTime::now();
+
+        // stored response is still valid. update it with headers from 304 response. (RFC 9111 section 4.3.4)
+        if let (StatusCode::NOT_MODIFIED, Some(mut stored)) = (res.status(), stored) {
+            for name in res.headers().keys() {
+                if matches!(*name, CONTENT_LENGTH | TRANSFER_ENCODING | CONNECTION) {
+                    continue;
+                }
+                let values = res.headers().get_all(name).iter().cloned().collect::<Vec<_>>();
+                stored.headers.remove(name);
+                for value in values {
+                    stored.headers.append(name.clone(), value);
+                }
+            }
+
+            stored.response_time = response_time;
+            stored.initial_age = policy::initial_age(res.headers(), self.request_time, response_time);
+
+            let res_cc = CacheControl::from_headers(&stored.headers);
+            if res_cc.no_store {
+                self.remove().await;
+            } else {
+                self.put(stored.clone()).await;
+            }
+
+            return Ok(cached_response(&stored, stored.initial_age, client));
+        }
+
+        let res_cc = CacheControl::from_headers(res.headers());
+
+        if !policy::is_storable(
+            self.method,
+            self.req_headers,
+            self.req_cc,
+            res.status(),
+            res.headers(),
+            &res_cc,
+            self.config.shared,
+        ) {
+            if res_cc.no_store {
+                self.remove().await;
+            }
+            return Ok(res);
+        }
+
+        let too_large = res
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok())
+            .is_some_and(|len| len > self.config.max_body_size);
+
+        if too_large {
+            return Ok(res);
+        }
+
+        let (res, body) = res.buffer(self.config.max_body_size).await?;
+
+        let Some(body) = body else { return Ok(res) };
+
+        let mut headers = res.headers().clone();
+        headers.remove(TRANSFER_ENCODING);
+        headers.remove(CONNECTION);
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+
+        let vary = policy::vary_names(res.headers())
+            .filter_map(|name| HeaderName::from_bytes(name.as_bytes()).ok())
+            .map(|name| {
+                let value = joined(self.req_headers, &name);
+                (name, value)
+            })
+            .collect();
+
+        self.put(CachedResponse {
+            status: res.status(),
+            version: res.version(),
+            headers,
+            body,
+            vary,
+            response_time,
+            initial_age: policy::initial_age(res.headers(), self.request_time, response_time),
+        })
+        .await;
+
+        Ok(res)
+    }
+
+    async fn put(&self, res: CachedResponse) {
+        if let Err(e) = self.storage.put(self.key, res).await {
+            warn!("failed to store cached response: {e}");
+        }
+    }
+
+    async fn remove(&self) {
+        if let Err(e) = self.storage.remove(self.key).await {
+            warn!("failed to remove cached response: {e}");
+        }
+    }
+}
+
+fn current_age(stored: &CachedResponse, now: SystemTime) -> Duration {
+    let resident_time = now.duration_since(stored.response_time).unwrap_or(Duration::ZERO);
+    stored.initial_age + resident_time
+}
+
+// request header values joined by comma. used for Vary matching.
+fn joined(headers: &HeaderMap, name: &HeaderName) -> Option<HeaderValue> {
+    let mut values = headers.get_all(name).iter();
+    let first = values.next()?;
+    values.try_fold(first.clone(), |acc, v| {
+        let mut buf = acc.as_bytes().to_vec();
+        buf.extend_from_slice(b", ");
+        buf.extend_from_slice(v.as_bytes());
+        HeaderValue::from_bytes(&buf).ok()
+    })
+}
+
+fn vary_matches(stored: &CachedResponse, headers: &HeaderMap) -> bool {
+    stored.vary.iter().all(|(name, value)| joined(headers, name) == *value)
+}
+
+fn add_validators(headers: &mut HeaderMap, stored: &CachedResponse) {
+    if let Some(etag) = stored.headers.get(ETAG) {
+        headers.insert(IF_NONE_MATCH, etag.clone());
+    }
+    if let Some(modified) = stored.headers.get(LAST_MODIFIED) {
+        headers.insert(IF_MODIFIED_SINCE, modified.clone());
+    }
+}
+
+fn cached_response<'c>(stored: &CachedResponse, age: Duration, client: &'c Client) -> Response<'c> {
+    let body = ResponseBody::Unknown(Box::pin(PrefixBody::new(stored.body.clone(), ResponseBody::Eof)));
+
+    let mut res = http::Response::new(body);
+    *res.status_mut() = stored.status;
+    *res.version_mut() = stored.version;
+    *res.headers_mut() = stored.headers.clone();
+    res.headers_mut().insert(AGE, HeaderValue::from(age.as_secs()));
+
+    new_response(res, client)
+}
+
+// response for only-if-cached request without usable stored response. (RFC 9111 section 5.2.1.7)
+fn gateway_timeout(client: &Client) -> Response<'_> {
+    let mut res = http::Response::new(ResponseBody::Eof);
+    *res.status_mut() = StatusCode::GATEWAY_TIMEOUT;
+    new_response(res, client)
+}
+
+fn new_response<'c>(res: http::Response<ResponseBody<'c>>, client: &'c Client) -> Response<'c> {
+    let timeout = client.timeout_config.response_timeout;
+    Response::new(res, Box::pin(tokio::time::sleep(timeout)), timeout)
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{
+        bytes::Bytes,
+        http::{header::VARY, Uri},
+    };
+
+    use super::*;
+
+    type Handler = fn(&http::Request<BoxBody>) -> http::Response<Bytes>;
+
+    struct Mock {
+        hits: AtomicUsize,
+        handler: Handler,
+    }
+
+    impl<'r, 'c> Service<ServiceRequest<'r, 'c>> for Mock {
+        type Response = Response<'c>;
+        type Error = Error;
+
+        async fn call(&self, req: ServiceRequest<'r, 'c>) -> Result<Self::Response, Self::Error> {
+            self.hits.fetch_add(1, Ordering::SeqCst);
+            let res = (self.handler)(req.req)
+                .map(|body| ResponseBody::Unknown(Box::pin(PrefixBody::new(body, ResponseBody::Eof))));
+            Ok(new_response(res, req.client))
+        }
+    }
+
+    fn cache(handler: Handler) -> Cache<Mock> {
+        Cache::new(Mock {
+            hits: AtomicUsize::new(0),
+            handler,
+        })
+    }
+
+    async fn send(
+        cache: &Cache<Mock>,
+        client: &Client,
+        method: Method,
+        headers: &[(HeaderName, &'static str)],
+    ) -> (StatusCode, HeaderMap, String) {
+        let mut req = http::Request::new(BoxBody::default());
+        *req.method_mut() = method;
+        *req.uri_mut() = Uri::from_static("http://localhost/foo");
+        for (name, value) in headers {
+            req.headers_mut().insert(name.clone(), HeaderValue::from_static(value));
+        }
+
+        let res = cache
+            .call(ServiceRequest {
+                req: &mut req,
+                client,
+                timeout: Duration::from_secs(1),
+            })
+            .await
+            .unwrap();
+
+        let status = res.status();
+        let headers = res.headers().clone();
+        (status, headers, res.string().await.unwrap())
+    }
+
+    fn hits(cache: &Cache<Mock>) -> usize {
+        cache.service.hits.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn fresh_hit() {
+        let client = Client::new();
+        let cache = cache(|_| {
+            http::Response::builder()
+                .header("cache-control", "max-age=60")
+                .body(Bytes::from_static(b"hello"))
+                .unwrap()
+        });
+
+        let (_, _, body) = send(&cache, &client, Method::GET, &[]).await;
+        assert_eq!(body, "hello");
+
+        let (status, headers, body) = send(&cache, &client, Method::GET, &[]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "hello");
+        assert!(headers.contains_key(AGE));
+        assert_eq!(hits(&cache), 1);
+
+        // request no-cache forces revalidation.
+        send(
+            &cache,
+            &client,
+            Method::GET,
+            &[(http::header::CACHE_CONTROL, "no-cache")],
+        )
+        .await;
+        assert_eq!(hits(&cache), 2);
+
+        // unsafe method invalidates stored response.
+        send(&cache, &client, Method::POST, &[]).await;
+        send(&cache, &client, Method::GET, &[]).await;
+        assert_eq!(hits(&cache), 4);
+    }
+
+    #[tokio::test]
+    async fn revalidate() {
+        let client = Client::new();
+        let cache = cache(|req| {
+            let builder = http::Response::builder()
+                .header("cache-control", "max-age=0")
+                .header("etag", "\"v1\"");
+            match req.headers().get(IF_NONE_MATCH) {
+                Some(tag) if tag == "\"v1\"" => builder
+                    .status(StatusCode::NOT_MODIFIED)
+                    .header("x-revalidated", "1")
+                    .body(Bytes::new())
+                    .unwrap(),
+                _ => builder.body(Bytes::from_static(b"hello")).unwrap(),
+            }
+        });
+
+        send(&cache, &client, Method::GET, &[]).await;
+
+        let (status, headers, body) = send(&cache, &client, Method::GET, &[]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "hello");
+        assert_eq!(headers.get("x-revalidated").unwrap(), "1");
+        assert_eq!(hits(&cache), 2);
+    }
+
+    #[tokio::test]
+    async fn vary() {
+        let client = Client::new();
+        let cache = cache(|_| {
+            http::Response::builder()
+                .header("cache-control", "max-age=60")
+                .header(VARY, "accept")
+                .body(Bytes::from_static(b"hello"))
+                .unwrap()
+        });
+
+        let json = [(http::header::ACCEPT, "application/json")];
+        let text = [(http::header::ACCEPT, "text/plain")];
+
+        send(&cache, &client, Method::GET, &json).await;
+        send(&cache, &client, Method::GET, &json).await;
+        assert_eq!(hits(&cache), 1);
+
+        send(&cache, &client, Method::GET, &text).await;
+        assert_eq!(hits(&cache), 2);
+    }
+
+    #[tokio::test]
+    async fn no_store() {
+        let client = Client::new();
+        let cache = cache(|_| {
+            http::Response::builder()
+                .header("cache-control", "no-store")
+                .body(Bytes::from_static(b"hello"))
+                .unwrap()
+        });
+
+        send(&cache, &client, Method::GET, &[]).await;
+        send(&cache, &client, Method::GET, &[]).await;
+        assert_eq!(hits(&cache), 2);
+
+        let only_if_cached = [(http::header::CACHE_CONTROL, "only-if-cached")];
+        let (status, ..) = send(&cache, &client, Method::GET, &only_if_cached).await;
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(hits(&cache), 2);
+    }
+}
@@ -0,0 +1,352 @@
+//! caching rules from RFC 9111.
+
+use std::time::{Duration, SystemTime};
+
+use crate::http::{
+    header::{HeaderMap, HeaderName, AGE, AUTHORIZATION, CACHE_CONTROL, DATE, EXPIRES, LAST_MODIFIED, PRAGMA, VARY},
+    Method, StatusCode,
+};
+
+// delta-seconds larger than this value are treated as this value. (RFC 9111 section 1.2.2)
+const DELTA_SECONDS_MAX: u64 = 1 << 31;
+
+/// parsed Cache-Control directives of request or response.
+#[derive(Debug, Default)]
+pub(super) struct CacheControl {
+    pub(super) no_store: bool,
+    pub(super) no_cache: bool,
+    pub(super) private: bool,
+    pub(super) public: bool,
+    pub(super) must_revalidate: bool,
+    pub(super) proxy_revalidate: bool,
+    pub(super) only_if_cached: bool,
+    pub(super) max_age: Option<Duration>,
+    pub(super) s_maxage: Option<Duration>,
+    pub(super) max_stale: Option<Duration>,
+    pub(super) min_fresh: Option<Duration>,
+    pub(super) stale_while_revalidate: Option<Duration>,
+}
+
+impl CacheControl {
+    pub(super) fn from_headers(headers: &HeaderMap) -> Self {
+        let mut cc = Self::default();
+
+        let mut found = false;
+
+        for value in headers.get_all(CACHE_CONTROL).iter().filter_map(|v| v.to_str().ok()) {
+            found = true;
+
+            for directive in value.split(',') {
+                let (name, arg) = match directive.split_once('=') {
+                    Some((name, arg)) => (name.trim(), Some(arg.trim().trim_matches('"'))),
+                    None => (directive.trim(), None),
+                };
+
+                let seconds = || arg.and_then(parse_delta_seconds);
+
+                match name.to_ascii_lowercase().as_str() {
+                    "no-store" => cc.no_store = true,
+                    // qualified no-cache and private are treated as unqualified ones which is more strict.
+                    "no-cache" => cc.no_cache = true,
+                    "private" => cc.private = true,
+                    "public" => cc.public = true,
+                    "must-revalidate" => cc.must_revalidate = true,
+                    "proxy-revalidate" => cc.proxy_revalidate = true,
+                    "only-if-cached" => cc.only_if_cached = true,
+                    // invalid max-age is treated as stale response.
+                    "max-age" => cc.max_age = Some(seconds().unwrap_or(Duration::ZERO)),
+                    "s-maxage" => cc.s_maxage = seconds(),
+                    "max-stale" => {
+                        cc.max_stale = Some(arg.map_or(Duration::MAX, |_| seconds().unwrap_or(Duration::ZERO)))
+                    }
+                    "min-fresh" => cc.min_fresh = seconds(),
+                    "stale-while-revalidate" => cc.stale_while_revalidate = seconds(),
+                    _ => {}
+                }
+            }
+        }
+
+        // Pragma: no-cache is only honored when Cache-Control is absent. (RFC 9111 section 5.4)
+        if !found {
+            cc.no_cache = headers
+                .get_all(PRAGMA)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .any(|v| v.split(',').any(|v| v.trim().eq_ignore_ascii_case("no-cache")));
+        }
+
+        cc
+    }
+}
+
+fn parse_delta_seconds(s: &str) -> Option<Duration> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let secs = s.parse::<u64>().unwrap_or(DELTA_SECONDS_MAX).min(DELTA_SECONDS_MAX);
+    Some(Duration::from_secs(secs))
+}
+
+fn parse_date(headers: &HeaderMap, name: HeaderName) -> Option<SystemTime> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| httpdate::parse_http_date(v).ok())
+}
+
+// status codes that are heuristically cacheable. (RFC 9110 section 15.1)
+fn is_heuristically_cacheable(status: StatusCode) -> bool {
+    matches!(
+        status.as_u16(),
+        200 | 203 | 204 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+    )
+}
+
+/// check if response to given request can be stored. (RFC 9111 section 3)
+pub(super) fn is_storable(
+    method: &Method,
+    req_headers: &HeaderMap,
+    req_cc: &CacheControl,
+    status: StatusCode,
+    res_headers: &HeaderMap,
+    res_cc: &CacheControl,
+    shared: bool,
+) -> bool {
+    if method != Method::GET || req_cc.no_store || res_cc.no_store {
+        return false;
+    }
+
+    // partial content is not supported and 304 is only used for updating stored response.
+    if status.is_informational() || status == StatusCode::PARTIAL_CONTENT || status == StatusCode::NOT_MODIFIED {
+        return false;
+    }
+
+    if shared {
+        if res_cc.private {
+            return false;
+        }
+        if req_headers.contains_key(AUTHORIZATION)
+            && !(res_cc.public || res_cc.s_maxage.is_some() || res_cc.must_revalidate)
+        {
+            return false;
+        }
+    }
+
+    if vary_names(res_headers).any(|name| name == "*") {
+        return false;
+    }
+
+    res_cc.public
+        || res_cc.max_age.is_some()
+        || (shared && res_cc.s_maxage.is_some())
+        || res_headers.contains_key(EXPIRES)
+        || is_heuristically_cacheable(status)
+}
+
+/// field names listed in Vary header.
+pub(super) fn vary_names(headers: &HeaderMap) -> impl Iterator<Item = &str> {
+    headers
+        .get_all(VARY)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// calculate freshness lifetime of a stored response. (RFC 9111 section 4.2.1)
+pub(super) fn freshness_lifetime(
+    status: StatusCode,
+    headers: &HeaderMap,
+    cc: &CacheControl,
+    response_time: SystemTime,
+    shared: bool,
+) -> Duration {
+    if shared {
+        if let Some(age) = cc.s_maxage {
+            return age;
+        }
+    }
+
+    if let Some(age) = cc.max_age {
+        return age;
+    }
+
+    let date = parse_date(headers, DATE).unwrap_or(response_time);
+
+    if headers.contains_key(EXPIRES) {
+        // invalid Expires value means the response is already expired.
+        return parse_date(headers, EXPIRES)
+            .and_then(|expires| expires.duration_since(date).ok())
+            .unwrap_or(Duration::ZERO);
+    }
+
+    // heuristic freshness is 10% of the time since last modification. (RFC 9111 section 4.2.2)
+    if cc.public || is_heuristically_cacheable(status) {
+        if let Some(modified) = parse_date(headers, LAST_MODIFIED) {
+            return date.duration_since(modified).unwrap_or(Duration::ZERO) / 10;
+        }
+    }
+
+    Duration::ZERO
+}
+
+/// calculate corrected initial age of a response at the time it's received. (RFC 9111 section 4.2.3)
+pub(super) fn initial_age(headers: &HeaderMap, request_time: SystemTime, response_time: SystemTime) -> Duration {
+    let age_value = headers
+        .get(AGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_delta_seconds)
+        .unwrap_or(Duration::ZERO);
+
+    let apparent_age = parse_date(headers, DATE)
+        .and_then(|date| response_time.duration_since(date).ok())
+        .unwrap_or(Duration::ZERO);
+
+    let response_delay = response_time.duration_since(request_time).unwrap_or(Duration::ZERO);
+
+    apparent_age.max(age_value + response_delay)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::http::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(pairs: &[(HeaderName, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(name.clone(), HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn cache_control_parse() {
+        let h = headers(&[
+            (CACHE_CONTROL, "max-age=60, s-maxage=\"120\", Must-Revalidate"),
+            (
+                CACHE_CONTROL,
+                "stale-while-revalidate=30, max-stale, no-cache=\"set-cookie\"",
+            ),
+        ]);
+        let cc = CacheControl::from_headers(&h);
+        assert_eq!(cc.max_age, Some(Duration::from_secs(60)));
+        assert_eq!(cc.s_maxage, Some(Duration::from_secs(120)));
+        assert_eq!(cc.stale_while_revalidate, Some(Duration::from_secs(30)));
+        assert_eq!(cc.max_stale, Some(Duration::MAX));
+        assert!(cc.must_revalidate);
+        assert!(cc.no_cache);
+        assert!(!cc.no_store);
+
+        let cc = CacheControl::from_headers(&headers(&[(CACHE_CONTROL, "max-age=abc, max-age=99999999999")]));
+        assert_eq!(cc.max_age, Some(Duration::from_secs(DELTA_SECONDS_MAX)));
+
+        let cc = CacheControl::from_headers(&headers(&[(PRAGMA, "no-cache")]));
+        assert!(cc.no_cache);
+
+        let cc = CacheControl::from_headers(&headers(&[(PRAGMA, "no-cache"), (CACHE_CONTROL, "max-age=1")]));
+        assert!(!cc.no_cache);
+    }
+
+    #[test]
+    fn storable() {
+        let req = HeaderMap::new();
+        let req_cc = CacheControl::default();
+
+        let check = |status, res: &HeaderMap, shared| {
+            let res_cc = CacheControl::from_headers(res);
+            is_storable(&Method::GET, &req, &req_cc, status, res, &res_cc, shared)
+        };
+
+        assert!(check(StatusCode::OK, &HeaderMap::new(), false));
+        assert!(!check(StatusCode::CREATED, &HeaderMap::new(), false));
+        assert!(check(
+            StatusCode::CREATED,
+            &headers(&[(CACHE_CONTROL, "max-age=1")]),
+            false
+        ));
+        assert!(!check(StatusCode::OK, &headers(&[(CACHE_CONTROL, "no-store")]), false));
+        assert!(check(StatusCode::OK, &headers(&[(CACHE_CONTROL, "private")]), false));
+        assert!(!check(StatusCode::OK, &headers(&[(CACHE_CONTROL, "private")]), true));
+        assert!(!check(StatusCode::OK, &headers(&[(VARY, "accept, *")]), false));
+        assert!(!check(
+            StatusCode::PARTIAL_CONTENT,
+            &headers(&[(CACHE_CONTROL, "max-age=1")]),
+            false
+        ));
+
+        let res = HeaderMap::new();
+        let res_cc = CacheControl::default();
+        assert!(!is_storable(
+            &Method::POST,
+            &req,
+            &req_cc,
+            StatusCode::OK,
+            &res,
+            &res_cc,
+            false
+        ));
+
+        let req = headers(&[(AUTHORIZATION, "Bearer token")]);
+        assert!(!is_storable(
+            &Method::GET,
+            &req,
+            &req_cc,
+            StatusCode::OK,
+            &res,
+            &res_cc,
+            true
+        ));
+        assert!(is_storable(
+            &Method::GET,
+            &req,
+            &req_cc,
+            StatusCode::OK,
+            &res,
+            &res_cc,
+            false
+        ));
+    }
+
+    #[test]
+    fn freshness() {
+        let now = httpdate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+
+        let lifetime = |res: &HeaderMap, shared| {
+            let cc = CacheControl::from_headers(res);
+            freshness_lifetime(StatusCode::OK, res, &cc, now, shared)
+        };
+
+        let res = headers(&[(CACHE_CONTROL, "max-age=10, s-maxage=20")]);
+        assert_eq!(lifetime(&res, false), Duration::from_secs(10));
+        assert_eq!(lifetime(&res, true), Duration::from_secs(20));
+
+        let res = headers(&[
+            (DATE, "Sun, 06 Nov 1994 08:49:37 GMT"),
+            (EXPIRES, "Sun, 06 Nov 1994 08:50:37 GMT"),
+        ]);
+        assert_eq!(lifetime(&res, false), Duration::from_secs(60));
+
+        let res = headers(&[(EXPIRES, "0")]);
+        assert_eq!(lifetime(&res, false), Duration::ZERO);
+
+        let res = headers(&[(LAST_MODIFIED, "Sun, 06 Nov 1994 08:32:57 GMT")]);
+        assert_eq!(lifetime(&res, false), Duration::from_secs(100));
+
+        assert_eq!(lifetime(&HeaderMap::new(), false), Duration::ZERO);
+    }
+
+    #[test]
+    fn age() {
+        let request_time = httpdate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        let response_time = request_time + Duration::from_secs(2);
+
+        let res = headers(&[(AGE, "10")]);
+        assert_eq!(initial_age(&res, request_time, response_time), Duration::from_secs(12));
+
+        let res = headers(&[(DATE, "Sun, 06 Nov 1994 08:49:07 GMT"), (AGE, "3")]);
+        assert_eq!(initial_age(&res, request_time, response_time), Duration::from_secs(32));
+    }
+}
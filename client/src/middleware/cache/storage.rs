@@ -0,0 +1,667 @@
+use core::future::Future;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    bytes::Bytes,
+    error::Error,
+    http::{
+        header::{HeaderMap, HeaderName, HeaderValue},
+        StatusCode, Version,
+    },
+};
+
+/// trait for pluggable storage of [Cache](super::Cache) middleware.
+///
+/// Storage failures are not fatal. The middleware would log them and fall back to sending request
+/// to server.
+pub trait CacheStorage: Send + Sync + 'static {
+    /// look up stored response with given key.
+    fn get(&self, key: &str) -> impl Future<Output = Result<Option<CachedResponse>, Error>> + Send;
+
+    /// store response with given key. existing response with the same key should be replaced.
+    fn put(&self, key: &str, res: CachedResponse) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// remove stored response with given key.
+    fn remove(&self, key: &str) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+/// a response stored by [CacheStorage].
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    pub(super) status: StatusCode,
+    pub(super) version: Version,
+    pub(super) headers: HeaderMap,
+    pub(super) body: Bytes,
+    // request header values selected by response's Vary header.
+    pub(super) vary: Vec<(HeaderName, Option<HeaderValue>)>,
+    pub(super) response_time: SystemTime,
+    pub(super) initial_age: Duration,
+}
+
+const MAGIC: &[u8; 4] = b"XCR1";
+
+impl CachedResponse {
+    /// status code of stored response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// headers of stored response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// body of stored response.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// approximate size of stored response in bytes.
+    pub fn size(&self) -> usize {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(k, v)| k.as_str().len() + v.len())
+            .sum::<usize>();
+        self.body.len() + headers
+    }
+
+    /// serialize response to bytes. Used by storage types persisting responses out of memory.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.size() + 64);
+
+        buf.extend_from_slice(MAGIC);
+        buf.extend_from_slice(&self.status.as_u16().to_be_bytes());
+        buf.push(match self.version {
+            Version::HTTP_09 => 0,
+            Version::HTTP_10 => 1,
+            Version::HTTP_2 => 3,
+            Version::HTTP_3 => 4,
+            _ => 2,
+        });
+
+        let time = self.response_time.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
+        put_duration(&mut buf, time);
+        put_duration(&mut buf, self.initial_age);
+
+        put_len(&mut buf, self.headers.len());
+        for (name, value) in self.headers.iter() {
+            put_bytes(&mut buf, name.as_str().as_bytes());
+            put_bytes(&mut buf, value.as_bytes());
+        }
+
+        put_len(&mut buf, self.vary.len());
+        for (name, value) in self.vary.iter() {
+            put_bytes(&mut buf, name.as_str().as_bytes());
+            match value {
+                Some(value) => {
+                    buf.push(1);
+                    put_bytes(&mut buf, value.as_bytes());
+                }
+                None => buf.push(0),
+            }
+        }
+
+        put_bytes(&mut buf, &self.body);
+
+        buf
+    }
+
+    /// deserialize response from bytes produced by [CachedResponse::encode].
+    /// Return None when given bytes are malformed.
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader(buf);
+
+        if r.take(4)? != MAGIC {
+            return None;
+        }
+
+        let status = StatusCode::from_u16(u16::from_be_bytes(r.array()?)).ok()?;
+        let version = match r.array::<1>()?[0] {
+            0 => Version::HTTP_09,
+            1 => Version::HTTP_10,
+            2 => Version::HTTP_11,
+            3 => Version::HTTP_2,
+            4 => Version::HTTP_3,
+            _ => return None,
+        };
+
+        let response_time = UNIX_EPOCH.checked_add(r.duration()?)?;
+        let initial_age = r.duration()?;
+
+        let len = r.len()?;
+        let mut headers = HeaderMap::with_capacity(len.min(128));
+        for _ in 0..len {
+            let name = HeaderName::from_bytes(r.bytes()?).ok()?;
+            let value = HeaderValue::from_bytes(r.bytes()?).ok()?;
+            headers.append(name, value);
+        }
+
+        let len = r.len()?;
+        let mut vary = Vec::with_capacity(len.min(16));
+        for _ in 0..len {
+            let name = HeaderName::from_bytes(r.bytes()?).ok()?;
+            let value = match r.array::<1>()?[0] {
+                0 => None,
+                _ => Some(HeaderValue::from_bytes(r.bytes()?).ok()?),
+            };
+            vary.push((name, value));
+        }
+
+        let body = Bytes::copy_from_slice(r.bytes()?);
+
+        r.0.is_empty().then_some(Self {
+            status,
+            version,
+            headers,
+            body,
+            vary,
+            response_time,
+            initial_age,
+        })
+    }
+}
+
+fn put_duration(buf: &mut Vec<u8>, dur: Duration) {
+    buf.extend_from_slice(&dur.as_secs().to_be_bytes());
+    buf.extend_from_slice(&dur.subsec_nanos().to_be_bytes());
+}
+
+fn put_len(buf: &mut Vec<u8>, len: usize) {
+    buf.extend_from_slice(&(len as u64).to_be_bytes());
+}
+
+fn put_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    put_len(buf, bytes.len());
+    buf.extend_from_slice(bytes);
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N).and_then(|b| b.try_into().ok())
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        self.array()
+            .map(u64::from_be_bytes)
+            .and_then(|len| usize::try_from(len).ok())
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn duration(&mut self) -> Option<Duration> {
+        let secs = u64::from_be_bytes(self.array()?);
+        let nanos = u32::from_be_bytes(self.array()?);
+        (nanos < 1_000_000_000).then(|| Duration::new(secs, nanos))
+    }
+}
+
+/// in memory storage evicting least recently used responses.
+pub struct MemoryStorage {
+    inner: Mutex<Lru>,
+    max_entries: usize,
+    max_bytes: usize,
+}
+
+struct Lru {
+    entries: HashMap<Box<str>, (u64, CachedResponse)>,
+    order: BTreeMap<u64, Box<str>>,
+    tick: u64,
+    bytes: usize,
+}
+
+impl Lru {
+    fn remove(&mut self, key: &str) {
+        if let Some((tick, res)) = self.entries.remove(key) {
+            self.order.remove(&tick);
+            self.bytes -= res.size();
+        }
+    }
+}
+
+impl MemoryStorage {
+    /// construct a new storage holding at most given number of responses.
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            inner: Mutex::new(Lru {
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+                tick: 0,
+                bytes: 0,
+            }),
+            max_entries,
+            max_bytes: usize::MAX,
+        }
+    }
+
+    /// Set max total size of stored responses in bytes.
+    ///
+    /// Default to no limit.
+    pub fn max_bytes(mut self, max: usize) -> Self {
+        self.max_bytes = max;
+        self
+    }
+
+    /// number of stored responses.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    /// check if storage is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CacheStorage for MemoryStorage {
+    async fn get(&self, key: &str) -> Result<Option<CachedResponse>, Error> {
+        let mut lru = self.inner.lock().unwrap();
+        let lru = &mut *lru;
+
+        lru.tick += 1;
+        let tick = lru.tick;
+
+        Ok(lru.entries.get_mut(key).map(|(t, res)| {
+            let key = lru.order.remove(t).expect("LRU order must be in sync with entries");
+            lru.order.insert(tick, key);
+            *t = tick;
+            res.clone()
+        }))
+    }
+
+    async fn put(&self, key: &str, res: CachedResponse) -> Result<(), Error> {
+        let size = res.size();
+
+        let mut lru = self.inner.lock().unwrap();
+
+        lru.remove(key);
+
+        if size > self.max_bytes || self.max_entries == 0 {
+            return Ok(());
+        }
+
+        while lru.entries.len() >= self.max_entries || lru.bytes + size > self.max_bytes {
+            let Some((_, key)) = lru.order.pop_first() else { break };
+            let (_, res) = lru
+                .entries
+                .remove(&key)
+                .expect("LRU order must be in sync with entries");
+            lru.bytes -= res.size();
+        }
+
+        lru.tick += 1;
+        let tick = lru.tick;
+        let key = Box::<str>::from(key);
+        lru.order.insert(tick, key.clone());
+        lru.entries.insert(key, (tick, res));
+        lru.bytes += size;
+
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), Error> {
+        self.inner.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+/// on disk storage persisting each response as a file inside given directory.
+pub struct DiskStorage {
+    dir: PathBuf,
+    max_bytes: u64,
+    // usage index of stored files. only tracked when size limit is set and lazily loaded from directory.
+    index: Mutex<Option<DiskIndex>>,
+}
+
+impl DiskStorage {
+    /// construct a new storage using given directory. The directory is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: u64::MAX,
+            index: Mutex::new(None),
+        }
+    }
+
+    /// Set max total size of stored files in bytes. Least recently used files are removed when
+    /// the limit is exceeded.
+    ///
+    /// Files already existing in the directory are counted toward the limit on first write.
+    /// The limit is tracked per [DiskStorage] instance and multiple instances (or processes) sharing
+    /// the same directory would not observe each other's usage.
+    ///
+    /// Default to no limit.
+    pub fn max_bytes(mut self, max: u64) -> Self {
+        self.max_bytes = max;
+        self
+    }
+
+    fn is_bounded(&self) -> bool {
+        self.max_bytes != u64::MAX
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        // FNV-1a is stable across builds so stored files stay valid between process restarts.
+        let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+        self.dir.join(format!("{hash:016x}"))
+    }
+
+    fn tmp_path(path: &Path) -> PathBuf {
+        // concurrent writes to the same key must not share temporary file.
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        path.with_extension(format!("{}-{seq}.tmp", std::process::id()))
+    }
+
+    async fn load_index(&self) -> Result<(), Error> {
+        if self.index.lock().unwrap().is_some() {
+            return Ok(());
+        }
+
+        let mut files = Vec::new();
+        let mut dir = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            // temporary files have extension.
+            if path.extension().is_some() {
+                continue;
+            }
+            let meta = entry.metadata().await?;
+            if meta.is_file() {
+                files.push((meta.modified().unwrap_or(UNIX_EPOCH), meta.len(), path));
+            }
+        }
+
+        // oldest files are least recently used.
+        files.sort_by_key(|(modified, ..)| *modified);
+
+        let mut index = self.index.lock().unwrap();
+        if index.is_none() {
+            let mut new = DiskIndex::default();
+            for (_, size, path) in files {
+                new.touch(path, Some(size));
+            }
+            *index = Some(new);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct DiskIndex {
+    files: HashMap<PathBuf, (u64, u64)>,
+    order: BTreeMap<u64, PathBuf>,
+    tick: u64,
+    bytes: u64,
+}
+
+impl DiskIndex {
+    // mark file as most recently used. update it's size when given.
+    fn touch(&mut self, path: PathBuf, size: Option<u64>) {
+        self.tick += 1;
+        let tick = self.tick;
+
+        match self.files.get_mut(&path) {
+            Some((t, s)) => {
+                self.order.remove(t);
+                *t = tick;
+                if let Some(size) = size {
+                    self.bytes = self.bytes - *s + size;
+                    *s = size;
+                }
+            }
+            None => {
+                let Some(size) = size else { return };
+                self.files.insert(path.clone(), (tick, size));
+                self.bytes += size;
+            }
+        }
+
+        self.order.insert(tick, path);
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some((tick, size)) = self.files.remove(path) {
+            self.order.remove(&tick);
+            self.bytes -= size;
+        }
+    }
+
+    // pop least recently used files until total size fits in limit.
+    fn evict(&mut self, max: u64) -> Vec<PathBuf> {
+        let mut evicted = Vec::new();
+        while self.bytes > max {
+            let Some((_, path)) = self.order.pop_first() else { break };
+            let (_, size) = self.files.remove(&path).expect("disk index must be in sync with order");
+            self.bytes -= size;
+            evicted.push(path);
+        }
+        evicted
+    }
+}
+
+impl CacheStorage for DiskStorage {
+    async fn get(&self, key: &str) -> Result<Option<CachedResponse>, Error> {
+        let path = self.path(key);
+        let file = match tokio::fs::read(&path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        // file starts with the full key to tell apart hash collisions.
+        let mut r = Reader(&file);
+        match r.bytes() {
+            Some(k) if k == key.as_bytes() => {
+                if let Some(index) = self.index.lock().unwrap().as_mut() {
+                    index.touch(path, None);
+                }
+                Ok(CachedResponse::decode(r.0))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn put(&self, key: &str, res: CachedResponse) -> Result<(), Error> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let mut file = Vec::new();
+        put_bytes(&mut file, key.as_bytes());
+        file.extend_from_slice(&res.encode());
+
+        let size = file.len() as u64;
+        if size > self.max_bytes {
+            return self.remove(key).await;
+        }
+
+        if self.is_bounded() {
+            self.load_index().await?;
+        }
+
+        // write to temporary file and rename it so readers never observe partial file.
+        let path = self.path(key);
+        let tmp = Self::tmp_path(&path);
+        if let Err(e) = tokio::fs::write(&tmp, file).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(e.into());
+        }
+        tokio::fs::rename(&tmp, &path).await?;
+
+        let evicted = match self.index.lock().unwrap().as_mut() {
+            Some(index) => {
+                index.touch(path, Some(size));
+                index.evict(self.max_bytes)
+            }
+            None => Vec::new(),
+        };
+
+        for path in evicted {
+            match tokio::fs::remove_file(path).await {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), Error> {
+        let path = self.path(key);
+        if let Some(index) = self.index.lock().unwrap().as_mut() {
+            index.remove(&path);
+        }
+        match tokio::fs::remove_file(path).await {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn response(body: &'static str) -> CachedResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert("etag", HeaderValue::from_static("\"1\""));
+        CachedResponse {
+            status: StatusCode::OK,
+            version: Version::HTTP_11,
+            headers,
+            body: Bytes::from_static(body.as_bytes()),
+            vary: vec![(HeaderName::from_static("accept"), None)],
+            response_time: UNIX_EPOCH + Duration::new(1, 2),
+            initial_age: Duration::from_secs(3),
+        }
+    }
+
+    #[test]
+    fn encode_decode() {
+        let res = response("hello");
+        let buf = res.encode();
+        let decoded = CachedResponse::decode(&buf).unwrap();
+
+        assert_eq!(decoded.status, res.status);
+        assert_eq!(decoded.headers, res.headers);
+        assert_eq!(decoded.body, res.body);
+        assert_eq!(decoded.vary, res.vary);
+        assert_eq!(decoded.response_time, res.response_time);
+        assert_eq!(decoded.initial_age, res.initial_age);
+
+        assert!(CachedResponse::decode(&buf[..buf.len() - 1]).is_none());
+    }
+
+    #[tokio::test]
+    async fn memory_lru() {
+        let storage = MemoryStorage::new(2);
+
+        storage.put("a", response("a")).await.unwrap();
+        storage.put("b", response("b")).await.unwrap();
+        // touch a so b becomes least recently used.
+        assert!(storage.get("a").await.unwrap().is_some());
+        storage.put("c", response("c")).await.unwrap();
+
+        assert!(storage.get("b").await.unwrap().is_none());
+        assert!(storage.get("a").await.unwrap().is_some());
+        assert!(storage.get("c").await.unwrap().is_some());
+
+        storage.remove("a").await.unwrap();
+        assert_eq!(storage.len(), 1);
+
+        let size = response("c").size();
+        let storage = MemoryStorage::new(8).max_bytes(size * 2);
+        for key in ["a", "b", "c"] {
+            storage.put(key, response("c")).await.unwrap();
+        }
+        assert_eq!(storage.len(), 2);
+        assert!(storage.get("a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn disk() {
+        let dir = std::env::temp_dir().join(format!("xitca-client-cache-{}", std::process::id()));
+        let storage = DiskStorage::new(&dir);
+
+        assert!(storage.get("a").await.unwrap().is_none());
+        storage.put("a", response("a")).await.unwrap();
+        assert_eq!(storage.get("a").await.unwrap().unwrap().body(), "a");
+        storage.remove("a").await.unwrap();
+        assert!(storage.get("a").await.unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn disk_concurrent_put() {
+        let dir = std::env::temp_dir().join(format!("xitca-client-cache-concurrent-{}", std::process::id()));
+        let storage = DiskStorage::new(&dir);
+
+        let puts = (0..8).map(|_| storage.put("a", response("a")));
+        for res in futures::future::join_all(puts).await {
+            res.unwrap();
+        }
+        assert_eq!(storage.get("a").await.unwrap().unwrap().body(), "a");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn disk_max_bytes() {
+        let dir = std::env::temp_dir().join(format!("xitca-client-cache-bounded-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // size of a stored file with single byte key.
+        let size = (8 + 1 + response("a").encode().len()) as u64;
+        let storage = DiskStorage::new(&dir).max_bytes(size * 2);
+
+        storage.put("a", response("a")).await.unwrap();
+        storage.put("b", response("b")).await.unwrap();
+        // touch a so b becomes least recently used.
+        assert!(storage.get("a").await.unwrap().is_some());
+        storage.put("c", response("c")).await.unwrap();
+
+        assert!(storage.get("b").await.unwrap().is_none());
+        assert!(storage.get("a").await.unwrap().is_some());
+        assert!(storage.get("c").await.unwrap().is_some());
+
+        // existing files are counted by new storage instance.
+        let storage = DiskStorage::new(&dir).max_bytes(size * 2);
+        storage.put("d", response("d")).await.unwrap();
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        assert!(storage.get("d").await.unwrap().is_some());
+
+        // response larger than limit is not stored.
+        let storage = DiskStorage::new(&dir).max_bytes(1);
+        storage.put("d", response("d")).await.unwrap();
+        assert!(storage.get("d").await.unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
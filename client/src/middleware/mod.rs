@@ -1,11 +1,19 @@
 //! middleware offer extended functionality to http client.
 
 mod redirect;
+mod trace;
+
+#[cfg(feature = "cache")]
+mod cache;
 
 #[cfg(feature = "compress")]
 mod decompress;
 
+#[cfg(feature = "cache")]
+pub use cache::{Cache, CacheStorage, CachedResponse, DiskStorage, MemoryStorage};
+
 #[cfg(feature = "compress")]
 pub use decompress::Decompress;
 
 pub use redirect::FollowRedirect;
+pub use trace::Trace;
@@ -0,0 +1,352 @@
+use tracing::{field::Empty, span, Instrument, Level, Span};
+
+use crate::{
+    error::Error,
+    response::Response,
+    service::{Service, ServiceRequest},
+    timing::RequestTiming,
+};
+
+/// middleware creating a [tracing] span for every request.
+///
+/// Span fields follow OpenTelemetry semantic conventions for http client. Time spent on each phase of
+/// request is recorded as `timing.*` fields in milliseconds. The same [RequestTiming] is inserted into
+/// response's extensions.
+///
+/// With `opentelemetry` feature enabled W3C `traceparent` and `tracestate` headers are injected into
+/// request from the span's OpenTelemetry context. This requires a `tracing-opentelemetry` layer installed
+/// to tracing subscriber.
+///
+/// # Examples
+/// ```rust
+/// use xitca_client::{middleware::Trace, Client};
+///
+/// # fn build() {
+/// let client = Client::builder().middleware(Trace::new).finish();
+/// # }
+/// ```
+pub struct Trace<S> {
+    service: S,
+    level: Level,
+}
+
+impl<S> Trace<S> {
+    /// construct a new trace middleware with given http service type. Spans are created with [Level::INFO].
+    pub const fn new(service: S) -> Self {
+        Self {
+            service,
+            level: Level::INFO,
+        }
+    }
+
+    /// Set verbosity [Level] of created spans.
+    pub const fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+}
+
+impl<'r, 'c, S> Service<ServiceRequest<'r, 'c>> for Trace<S>
+where
+    S: for<'r2, 'c2> Service<ServiceRequest<'r2, 'c2>, Response = Response<'c2>, Error = Error> + Send + Sync,
+{
+    type Response = Response<'c>;
+    type Error = Error;
+
+    async fn call(&self, req: ServiceRequest<'r, 'c>) -> Result<Self::Response, Self::Error> {
+        macro_rules! span2 {
+            ($lvl:expr, $name:expr, $($fields:tt)*) => {
+                match $lvl {
+                    Level::TRACE => span!(Level::TRACE, $name, $($fields)*),
+                    Level::DEBUG => span!(Level::DEBUG, $name, $($fields)*),
+                    Level::INFO => span!(Level::INFO, $name, $($fields)*),
+                    Level::WARN => span!(Level::WARN, $name, $($fields)*),
+                    Level::ERROR => span!(Level::ERROR, $name, $($fields)*),
+                }
+            }
+        }
+
+        let method = req.req.method().clone();
+        let uri = req.req.uri();
+
+        let span = span2!(
+            self.level,
+            "http.client.request",
+            otel.name = %method,
+            otel.kind = "client",
+            otel.status_code = Empty,
+            http.request.method = %method,
+            url.full = %uri,
+            server.address = uri.host().unwrap_or(""),
+            server.port = Empty,
+            network.protocol.version = Empty,
+            http.response.status_code = Empty,
+            error.type = Empty,
+            timing.resolve_ms = Empty,
+            timing.connect_ms = Empty,
+            timing.tls_ms = Empty,
+            timing.ttfb_ms = Empty,
+            timing.reused = Empty,
+        );
+
+        let port = uri.port_u16().or_else(|| match uri.scheme_str() {
+            Some("https" | "wss") => Some(443),
+            Some("http" | "ws") => Some(80),
+            _ => None,
+        });
+
+        if let Some(port) = port {
+            span.record("server.port", port);
+        }
+
+        #[cfg(feature = "opentelemetry")]
+        otel::inject(&span, req.req.headers_mut());
+
+        let timing = match req.req.extensions().get::<RequestTiming>() {
+            Some(timing) => timing.clone(),
+            None => {
+                let timing = RequestTiming::new();
+                req.req.extensions_mut().insert(timing.clone());
+                timing
+            }
+        };
+
+        let res = self.service.call(req).instrument(span.clone()).await;
+
+        record_timing(&span, &timing);
+
+        match res {
+            Ok(mut res) => {
+                let status = res.status();
+                span.record("http.response.status_code", status.as_u16());
+                span.record("network.protocol.version", version(res.version()));
+                // client side treats 4xx and 5xx status as error.
+                if status.is_client_error() || status.is_server_error() {
+                    span.record("otel.status_code", "ERROR");
+                    span.record("error.type", status.as_str());
+                }
+                res.extensions_mut().insert(timing);
+                Ok(res)
+            }
+            Err(e) => {
+                span.record("otel.status_code", "ERROR");
+                span.record("error.type", tracing::field::debug(&e));
+                Err(e)
+            }
+        }
+    }
+}
+
+fn record_timing(span: &Span, timing: &RequestTiming) {
+    let phases = timing.phases();
+
+    let ms = |dur: core::time::Duration| dur.as_secs_f64() * 1000.0;
+
+    if let Some(dur) = phases.resolve {
+        span.record("timing.resolve_ms", ms(dur));
+    }
+    if let Some(dur) = phases.connect {
+        span.record("timing.connect_ms", ms(dur));
+    }
+    if let Some(dur) = phases.tls {
+        span.record("timing.tls_ms", ms(dur));
+    }
+    if let Some(dur) = phases.ttfb {
+        span.record("timing.ttfb_ms", ms(dur));
+    }
+    span.record("timing.reused", phases.reused);
+}
+
+fn version(version: crate::http::Version) -> &'static str {
+    use crate::http::Version;
+    match version {
+        Version::HTTP_09 => "0.9",
+        Version::HTTP_10 => "1.0",
+        Version::HTTP_2 => "2",
+        Version::HTTP_3 => "3",
+        _ => "1.1",
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+mod otel {
+    use opentelemetry::trace::TraceContextExt;
+    use tracing::Span;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    use crate::http::header::{HeaderMap, HeaderName, HeaderValue};
+
+    const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+    const TRACESTATE: HeaderName = HeaderName::from_static("tracestate");
+
+    // inject W3C trace context headers. headers set by user are left untouched.
+    pub(super) fn inject(span: &Span, headers: &mut HeaderMap) {
+        if headers.contains_key(TRACEPARENT) {
+            return;
+        }
+
+        let cx = span.context();
+        let span = cx.span();
+        let span_cx = span.span_context();
+
+        if !span_cx.is_valid() {
+            return;
+        }
+
+        let parent = format!(
+            "00-{:032x}-{:016x}-{:02x}",
+            span_cx.trace_id(),
+            span_cx.span_id(),
+            span_cx.trace_flags().to_u8()
+        );
+
+        if let Ok(value) = HeaderValue::from_str(&parent) {
+            headers.insert(TRACEPARENT, value);
+        }
+
+        let state = span_cx.trace_state().header();
+        if !state.is_empty() {
+            if let Ok(value) = HeaderValue::from_str(&state) {
+                headers.insert(TRACESTATE, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::SocketAddr;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    use crate::Client;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn build_trace_mw() {
+        let _ = Client::builder()
+            .middleware(|service| Trace::new(service).level(Level::DEBUG))
+            .finish();
+    }
+
+    // minimal http/1 server responding empty body and sending received request heads through channel.
+    async fn serve() -> (SocketAddr, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    loop {
+                        let mut chunk = [0; 1024];
+                        match stream.read(&mut chunk).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                        }
+                        while let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                            let head = buf.drain(..pos + 4).collect::<Vec<_>>();
+                            let _ = tx.send(String::from_utf8(head).unwrap());
+                            let res = b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n";
+                            if stream.write_all(res).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        (addr, rx)
+    }
+
+    #[tokio::test]
+    async fn request_timing() {
+        let (addr, _rx) = serve().await;
+
+        let client = Client::builder().middleware(Trace::new).finish();
+        let url = format!("http://{addr}/");
+
+        let res = client.get(&url).unwrap().send().await.unwrap();
+        let phases = res.extensions().get::<RequestTiming>().unwrap().phases();
+        assert!(phases.connect.is_some());
+        assert!(phases.ttfb.is_some());
+        assert!(phases.tls.is_none());
+        assert!(!phases.reused);
+        drop(res);
+
+        // user provided recorder is shared with middleware.
+        let timing = RequestTiming::new();
+        let mut req = client.get(&url).unwrap();
+        req.extensions_mut().insert(timing.clone());
+        let _res = req.send().await.unwrap();
+
+        let phases = timing.phases();
+        assert!(phases.reused);
+        assert!(phases.connect.is_none());
+        assert!(phases.ttfb.is_some());
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    #[tokio::test]
+    async fn traceparent() {
+        use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        // provider must outlive tracer.
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder().build();
+        let tracer = provider.tracer("xitca-client-test");
+        let subscriber = tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let (addr, mut rx) = serve().await;
+        let client = Client::builder().middleware(Trace::new).finish();
+        let url = format!("http://{addr}/");
+
+        fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+            head.lines().find_map(|line| {
+                let (k, v) = line.split_once(':')?;
+                k.eq_ignore_ascii_case(name).then(|| v.trim())
+            })
+        }
+
+        // trace context of parent span is propagated to request.
+        let parent = tracing::info_span!("parent");
+        let trace_id = parent.context().span().span_context().trace_id();
+
+        client
+            .get(&url)
+            .unwrap()
+            .send()
+            .instrument(parent.clone())
+            .await
+            .unwrap();
+
+        let head = rx.recv().await.unwrap();
+        let traceparent = header(&head, "traceparent").unwrap();
+        let parts = traceparent.split('-').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[0], "00");
+        assert_eq!(parts[1], format!("{trace_id:032x}"));
+        assert_eq!(parts[2].len(), 16);
+
+        // traceparent set by user is left untouched.
+        let user = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+        let mut req = client.get(&url).unwrap();
+        req.headers_mut()
+            .insert("traceparent", crate::http::header::HeaderValue::from_static(user));
+        req.send().instrument(parent).await.unwrap();
+
+        let head = rx.recv().await.unwrap();
+        assert_eq!(header(&head, "traceparent"), Some(user));
+    }
+}
@@ -0,0 +1,5 @@
+//! multipart/form-data request body.
+//!
+//! Encoded format is compatible with [http_multipart::multipart] parser and extractors built on top of it.
+
+pub use http_multipart::{Form, FormStream, Part};
@@ -1,72 +1,321 @@
 use std::{
     collections::{HashMap, VecDeque},
+    fmt,
     hash::Hash,
     ops::{Deref, DerefMut},
-    sync::Mutex,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
     time::{Duration, Instant},
 };
 
-use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, SemaphorePermit};
 
 use crate::{connection::Multiplex, error::Error};
 
+/// configuration for connection pool.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PoolConfig {
+    pub(crate) capacity: usize,
+    pub(crate) max_per_host: usize,
+    pub(crate) max_idle_per_host: usize,
+    pub(crate) idle_timeout: Duration,
+    pub(crate) max_lifetime: Duration,
+    pub(crate) reap_interval: Option<Duration>,
+}
+
+impl PoolConfig {
+    pub(crate) const fn new() -> Self {
+        Self {
+            capacity: 128,
+            max_per_host: Semaphore::MAX_PERMITS,
+            max_idle_per_host: usize::MAX,
+            idle_timeout: Duration::from_secs(600),
+            max_lifetime: Duration::from_secs(3600),
+            reap_interval: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
 #[doc(hidden)]
 pub struct Pool<K, C> {
-    conns: Mutex<HashMap<K, Value<C>>>,
+    conns: Arc<Mutex<HashMap<K, Entry<C>>>>,
     permits: Semaphore,
+    config: PoolConfig,
+    reaper_spawned: AtomicBool,
 }
 
-enum Value<C> {
-    Multiplexable(PooledConn<C>),
-    NonMultiplexable(VecDeque<PooledConn<C>>),
+struct Entry<C> {
+    // non multiplexable connections waiting to be reused.
+    idle: VecDeque<PooledConn<C>>,
+    // multiplexable connection shared by concurrent requests.
+    multiplex: Option<PooledConn<C>>,
+    // limit of concurrent non multiplexable connections for host.
+    permits: Arc<Semaphore>,
+    active: usize,
+    pending: usize,
+    streams: usize,
 }
 
-impl<K, C> Pool<K, C>
+impl<C> Entry<C> {
+    fn new(config: &PoolConfig) -> Self {
+        Self {
+            idle: VecDeque::new(),
+            multiplex: None,
+            permits: Arc::new(Semaphore::new(config.max_per_host)),
+            active: 0,
+            pending: 0,
+            streams: 0,
+        }
+    }
+
+    fn is_unused(&self) -> bool {
+        self.idle.is_empty() && self.multiplex.is_none() && self.active == 0 && self.pending == 0 && self.streams == 0
+    }
+
+    fn evict_expired(&mut self, config: &PoolConfig) {
+        self.idle.retain(|conn| !conn.state.is_expired(config));
+        if matches!(self.multiplex, Some(ref conn) if conn.state.is_expired(config)) {
+            self.multiplex = None;
+        }
+    }
+}
+
+impl<C> Entry<C>
 where
-    K: Eq + Hash + Clone,
     C: Multiplex,
 {
-    pub(crate) fn with_capacity(size: usize) -> Self {
+    fn try_multiplex(&mut self, config: &PoolConfig) -> Option<PooledConn<C>> {
+        match self.multiplex {
+            Some(ref conn) if conn.state.is_expired(config) => {
+                self.multiplex = None;
+                None
+            }
+            Some(ref mut conn) => Some(conn.multiplex()),
+            None => None,
+        }
+    }
+}
+
+impl<K, C> Pool<K, C>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    C: Multiplex + Send + 'static,
+{
+    pub(crate) fn new(config: PoolConfig) -> Self {
         Self {
-            conns: Mutex::new(HashMap::new()),
-            permits: Semaphore::new(size),
+            conns: Arc::new(Mutex::new(HashMap::new())),
+            permits: Semaphore::new(config.capacity),
+            config,
+            reaper_spawned: AtomicBool::new(false),
         }
     }
 
     pub(crate) async fn acquire(&self, key: impl Into<K>) -> Result<Conn<'_, K, C>, Error> {
-        // permit is needed to operate on pool.
-        let permit = self.permits.acquire().await.unwrap();
+        self.try_spawn_reaper();
 
         let key = key.into();
 
-        let conn = {
-            let mut conns = self.conns.lock().unwrap();
+        let pending = PendingGuard::new(self, &key);
 
-            let opt = conns.get_mut(&key);
-            match opt {
-                Some(Value::NonMultiplexable(queue)) => loop {
-                    match queue.pop_front() {
-                        // drop connection that are expired.
-                        Some(conn) if conn.state.is_expired() => drop(conn),
-                        conn => break conn,
-                    }
-                },
-                Some(Value::Multiplexable(conn)) if conn.state.is_expired() => {
-                    conns.remove(&key);
-                    None
-                }
-                Some(Value::Multiplexable(conn)) => Some(conn.multiplex()),
+        loop {
+            let host_permits = {
+                let mut conns = self.conns.lock().unwrap();
+                let entry = self.entry(&mut conns, &key);
+                // per host permit is needed for non multiplexable connection.
+                entry.multiplex.is_none().then(|| entry.permits.clone())
+            };
+
+            // host permit is acquired before pool permit so requests waiting on a saturated host do not
+            // hold pool capacity and starve other hosts.
+            let host_permit = match host_permits {
+                Some(permits) => Some(permits.acquire_owned().await.unwrap()),
                 None => None,
+            };
+
+            // permit is needed to operate on pool.
+            let permit = self.permits.acquire().await.unwrap();
+
+            let mut conns = self.conns.lock().unwrap();
+            let entry = self.entry(&mut conns, &key);
+
+            // multiplexable connection could be added to pool while waiting for permits.
+            if let Some(conn) = entry.try_multiplex(&self.config) {
+                entry.streams += 1;
+                pending.finish(entry);
+                drop(conns);
+                return Ok(Conn::new(self, key, Some(conn), permit, None, true));
             }
+
+            // multiplexable connection is gone while waiting for pool permit. retry with host permit.
+            let Some(host_permit) = host_permit else {
+                continue;
+            };
+
+            pending.finish(entry);
+
+            let conn = loop {
+                match entry.idle.pop_front() {
+                    // drop connection that are expired.
+                    Some(conn) if conn.state.is_expired(&self.config) => drop(conn),
+                    conn => break conn,
+                }
+            };
+
+            entry.active += 1;
+            drop(conns);
+
+            return Ok(Conn::new(self, key, conn, permit, Some(host_permit), false));
+        }
+    }
+
+    // background task evicting expired connections. spawned lazily when pool is used inside tokio runtime.
+    fn try_spawn_reaper(&self) {
+        let Some(interval) = self.config.reap_interval else {
+            return;
         };
 
-        Ok(Conn {
-            pool: self,
-            key,
-            conn,
-            permit,
-            destroy_on_drop: false,
-        })
+        if self.reaper_spawned.load(Ordering::Relaxed) || self.reaper_spawned.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            self.reaper_spawned.store(false, Ordering::Release);
+            return;
+        };
+
+        let conns = Arc::downgrade(&self.conns);
+        let config = self.config;
+        handle.spawn(reap(conns, config, interval));
+    }
+}
+
+impl<K, C> Pool<K, C>
+where
+    K: Eq + Hash + Clone,
+{
+    fn entry<'m>(&self, conns: &'m mut HashMap<K, Entry<C>>, key: &K) -> &'m mut Entry<C> {
+        conns.entry(key.clone()).or_insert_with(|| Entry::new(&self.config))
+    }
+}
+
+async fn reap<K, C>(conns: Weak<Mutex<HashMap<K, Entry<C>>>>, config: PoolConfig, interval: Duration)
+where
+    K: Eq + Hash,
+{
+    let mut interval = tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // first tick completes immediately.
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        // pool is dropped together with client. exit task.
+        let Some(conns) = conns.upgrade() else { break };
+
+        let mut conns = conns.lock().unwrap();
+        conns.retain(|_, entry| {
+            entry.evict_expired(&config);
+            !entry.is_unused()
+        });
+    }
+}
+
+impl<K, C> Pool<K, C>
+where
+    K: Eq + Hash + fmt::Display,
+{
+    /// snapshot of current pool state.
+    pub(crate) fn stats(&self) -> PoolStats {
+        let conns = self.conns.lock().unwrap();
+        let hosts = conns
+            .iter()
+            .map(|(key, entry)| HostStats {
+                host: key.to_string(),
+                active: entry.active,
+                idle: entry.idle.len(),
+                pending: entry.pending,
+                multiplexed: entry.multiplex.is_some(),
+                streams: entry.streams,
+            })
+            .collect();
+
+        PoolStats {
+            available: self.permits.available_permits(),
+            hosts,
+        }
+    }
+}
+
+/// Snapshot of connection pool state of [Client].
+///
+/// [Client]: crate::Client
+#[derive(Clone, Debug)]
+pub struct PoolStats {
+    /// Number of requests can still acquire connections from pool before it hits it's capacity.
+    pub available: usize,
+    /// State of pooled connections for each host.
+    pub hosts: Vec<HostStats>,
+}
+
+/// State of pooled connections for a single host.
+#[derive(Clone, Debug)]
+pub struct HostStats {
+    /// Host in the form of `authority` (or `authority/path` for unix domain socket).
+    pub host: String,
+    /// Number of non multiplexed connections in use. (including connections in the middle of establishing)
+    pub active: usize,
+    /// Number of non multiplexed connections waiting for reuse.
+    pub idle: usize,
+    /// Number of requests waiting for a connection permit.
+    pub pending: usize,
+    /// Pool contains a multiplexed connection (HTTP/2) for host.
+    pub multiplexed: bool,
+    /// Number of in flight requests (streams) on multiplexed connection.
+    pub streams: usize,
+}
+
+// guard for tracking waiting requests. the counter is restored when acquire future is dropped
+// before connection permit is granted.
+struct PendingGuard<'a, K, C>
+where
+    K: Eq + Hash + Clone,
+{
+    pool: &'a Pool<K, C>,
+    key: Option<K>,
+}
+
+impl<'a, K, C> PendingGuard<'a, K, C>
+where
+    K: Eq + Hash + Clone,
+{
+    fn new(pool: &'a Pool<K, C>, key: &K) -> Self {
+        let mut conns = pool.conns.lock().unwrap();
+        pool.entry(&mut conns, key).pending += 1;
+        Self {
+            pool,
+            key: Some(key.clone()),
+        }
+    }
+
+    fn finish(mut self, entry: &mut Entry<C>) {
+        self.key = None;
+        entry.pending -= 1;
+    }
+}
+
+impl<K, C> Drop for PendingGuard<'_, K, C>
+where
+    K: Eq + Hash + Clone,
+{
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            if let Some(entry) = self.pool.conns.lock().unwrap().get_mut(&key) {
+                entry.pending -= 1;
+            }
+        }
     }
 }
 
@@ -78,7 +327,9 @@ where
     pool: &'a Pool<K, C>,
     key: K,
     conn: Option<PooledConn<C>>,
-    permit: SemaphorePermit<'a>,
+    _permit: SemaphorePermit<'a>,
+    host_permit: Option<OwnedSemaphorePermit>,
+    multiplexed: bool,
     destroy_on_drop: bool,
 }
 
@@ -109,17 +360,48 @@ where
     }
 }
 
-impl<K, C> Conn<'_, K, C>
+impl<'a, K, C> Conn<'a, K, C>
 where
     K: Eq + Hash + Clone,
     C: Multiplex,
 {
+    fn new(
+        pool: &'a Pool<K, C>,
+        key: K,
+        conn: Option<PooledConn<C>>,
+        permit: SemaphorePermit<'a>,
+        host_permit: Option<OwnedSemaphorePermit>,
+        multiplexed: bool,
+    ) -> Self {
+        Self {
+            pool,
+            key,
+            conn,
+            _permit: permit,
+            host_permit,
+            multiplexed,
+            destroy_on_drop: false,
+        }
+    }
+
     pub(crate) fn is_none(&self) -> bool {
         self.conn.is_none()
     }
 
     pub(crate) fn add(&mut self, conn: C) {
         debug_assert!(self.is_none());
+
+        // newly established multiplexable connection does not occupy host permit.
+        if conn.is_multiplexable() {
+            let mut conns = self.pool.conns.lock().unwrap();
+            if let Some(entry) = conns.get_mut(&self.key) {
+                entry.active -= 1;
+                entry.streams += 1;
+            }
+            self.multiplexed = true;
+            self.host_permit = None;
+        }
+
         self.conn = Some(PooledConn {
             conn,
             state: ConnState::new(),
@@ -143,29 +425,35 @@ where
     C: Multiplex,
 {
     fn drop(&mut self) {
+        let config = &self.pool.config;
+        let mut conns = self.pool.conns.lock().unwrap();
+
+        let Some(entry) = conns.get_mut(&self.key) else {
+            return;
+        };
+
+        if self.multiplexed {
+            entry.streams -= 1;
+        } else {
+            entry.active -= 1;
+        }
+
         if let Some(mut conn) = self.conn.take() {
-            let want_drop = conn.state.is_expired() || self.destroy_on_drop;
-            let mut conns = self.pool.conns.lock().unwrap();
-            match conns.get_mut(&self.key) {
-                Some(Value::NonMultiplexable(_)) | None if want_drop => return,
-                Some(Value::NonMultiplexable(queue)) => {
-                    conn.state.update_idle();
-                    queue.push_back(conn);
-                }
-                Some(Value::Multiplexable(_)) if want_drop => {
-                    conns.remove(&self.key);
-                }
-                Some(Value::Multiplexable(conn)) => conn.state.update_idle(),
-                None if conn.is_multiplexable() => {
-                    conns.insert(self.key.clone(), Value::Multiplexable(conn));
-                }
-                None => {
-                    let queue = VecDeque::from([conn]);
-                    conns.insert(self.key.clone(), Value::NonMultiplexable(queue));
+            let want_drop = conn.state.is_expired(config) || self.destroy_on_drop;
+
+            if self.multiplexed {
+                match entry.multiplex {
+                    _ if want_drop => entry.multiplex = None,
+                    Some(ref mut conn) => conn.state.update_idle(),
+                    None => {
+                        conn.state.update_idle();
+                        entry.multiplex = Some(conn);
+                    }
                 }
+            } else if !want_drop && entry.idle.len() < config.max_idle_per_host {
+                conn.state.update_idle();
+                entry.idle.push_back(conn);
             }
-
-            let _ = self.permit;
         }
     }
 }
@@ -209,8 +497,8 @@ impl ConnState {
         self.idle_since = Instant::now();
     }
 
-    fn is_expired(&self) -> bool {
-        self.born.elapsed() > Duration::from_secs(3600) || self.idle_since.elapsed() > Duration::from_secs(600)
+    fn is_expired(&self, config: &PoolConfig) -> bool {
+        self.born.elapsed() > config.max_lifetime || self.idle_since.elapsed() > config.idle_timeout
     }
 }
 
@@ -226,3 +514,143 @@ impl<C: Multiplex> Multiplex for PooledConn<C> {
         self.conn.is_multiplexable()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct TestConn(bool);
+
+    impl Multiplex for TestConn {
+        fn multiplex(&mut self) -> Self {
+            Self(self.0)
+        }
+
+        fn is_multiplexable(&self) -> bool {
+            self.0
+        }
+    }
+
+    fn pool(config: PoolConfig) -> Pool<&'static str, TestConn> {
+        Pool::new(PoolConfig {
+            reap_interval: None,
+            ..config
+        })
+    }
+
+    fn host<'a>(stats: &'a PoolStats, host: &str) -> &'a HostStats {
+        stats.hosts.iter().find(|h| h.host == host).unwrap()
+    }
+
+    #[tokio::test]
+    async fn max_idle_per_host() {
+        let pool = pool(PoolConfig {
+            max_idle_per_host: 1,
+            ..PoolConfig::new()
+        });
+
+        let mut c1 = pool.acquire("a").await.unwrap();
+        let mut c2 = pool.acquire("a").await.unwrap();
+        c1.add(TestConn(false));
+        c2.add(TestConn(false));
+
+        let stats = pool.stats();
+        assert_eq!(host(&stats, "a").active, 2);
+        assert_eq!(stats.available, 126);
+
+        drop((c1, c2));
+
+        let stats = pool.stats();
+        let a = host(&stats, "a");
+        assert_eq!(a.active, 0);
+        assert_eq!(a.idle, 1);
+        assert_eq!(stats.available, 128);
+    }
+
+    #[tokio::test]
+    async fn max_per_host() {
+        let pool = pool(PoolConfig {
+            max_per_host: 1,
+            ..PoolConfig::new()
+        });
+
+        let mut c1 = pool.acquire("a").await.unwrap();
+        c1.add(TestConn(false));
+
+        // other host is not affected by limit.
+        let _c2 = pool.acquire("b").await.unwrap();
+
+        {
+            let mut fut = core::pin::pin!(pool.acquire("a"));
+            assert!(futures::FutureExt::now_or_never(fut.as_mut()).is_none());
+            assert_eq!(host(&pool.stats(), "a").pending, 1);
+        }
+
+        // dropped acquire future must not leave pending count behind.
+        assert_eq!(host(&pool.stats(), "a").pending, 0);
+
+        drop(c1);
+
+        let c3 = pool.acquire("a").await.unwrap();
+        assert!(!c3.is_none());
+    }
+
+    #[tokio::test]
+    async fn saturated_host_not_starve_others() {
+        let pool = pool(PoolConfig {
+            capacity: 2,
+            max_per_host: 1,
+            ..PoolConfig::new()
+        });
+
+        let mut c1 = pool.acquire("a").await.unwrap();
+        c1.add(TestConn(false));
+
+        // request waiting for host permit must not occupy pool capacity.
+        let mut fut = core::pin::pin!(pool.acquire("a"));
+        assert!(futures::FutureExt::now_or_never(fut.as_mut()).is_none());
+        assert_eq!(pool.stats().available, 1);
+
+        let c2 = futures::FutureExt::now_or_never(pool.acquire("b"));
+        assert!(c2.is_some());
+    }
+
+    #[tokio::test]
+    async fn multiplex() {
+        let pool = pool(PoolConfig {
+            max_per_host: 1,
+            ..PoolConfig::new()
+        });
+
+        let mut c1 = pool.acquire("a").await.unwrap();
+        c1.add(TestConn(true));
+        drop(c1);
+
+        // multiplexed connection does not wait for host permit.
+        let c1 = pool.acquire("a").await.unwrap();
+        let c2 = pool.acquire("a").await.unwrap();
+        assert!(!c1.is_none() && !c2.is_none());
+
+        let stats = pool.stats();
+        let a = host(&stats, "a");
+        assert!(a.multiplexed);
+        assert_eq!(a.streams, 2);
+        assert_eq!(a.active, 0);
+    }
+
+    #[test]
+    fn evict_expired() {
+        let config = PoolConfig {
+            idle_timeout: Duration::ZERO,
+            ..PoolConfig::new()
+        };
+        let mut entry = Entry::new(&config);
+        entry.idle.push_back(PooledConn {
+            conn: TestConn(false),
+            state: ConnState::new(),
+        });
+        std::thread::sleep(Duration::from_millis(1));
+        entry.evict_expired(&config);
+        assert!(entry.is_unused());
+    }
+}
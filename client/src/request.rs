@@ -112,6 +112,51 @@ impl<'a> RequestBuilder<'a> {
         Ok(self.body(body))
     }
 
+    #[cfg(feature = "urlencoded")]
+    /// Use url encoded form object as request body.
+    ///
+    /// [CONTENT_TYPE] header would be set with value: `application/x-www-form-urlencoded`.
+    pub fn form(mut self, body: impl serde::ser::Serialize) -> Result<RequestBuilder<'a>, Error> {
+        let body = serde_urlencoded::to_string(&body)?;
+
+        self.headers_mut()
+            .insert(CONTENT_TYPE, const_header_value::APPLICATION_WWW_FORM_URLENCODED);
+        Ok(self.body(body))
+    }
+
+    #[cfg(feature = "multipart")]
+    /// Use multipart form as request body.
+    ///
+    /// [CONTENT_TYPE] header would be set with value: `multipart/form-data; boundary=<boundary>`.
+    /// [CONTENT_LENGTH] header would be set when all parts of the form have known size.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_client::{multipart::{Form, Part}, Client};
+    ///
+    /// # async fn send(client: Client) -> Result<(), xitca_client::error::Error> {
+    /// let form = Form::new()
+    ///     .text("name", "xitca")
+    ///     .part(Part::bytes("file", "file content").file_name("foo.txt").content_type("text/plain"));
+    ///
+    /// let _res = client.post("http://localhost:8080/upload")?.multipart(form).send().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn multipart(mut self, form: crate::multipart::Form) -> RequestBuilder<'a> {
+        let headers = self.headers_mut();
+        headers.insert(CONTENT_TYPE, form.content_type());
+        match form.content_length() {
+            Some(len) => {
+                headers.insert(CONTENT_LENGTH, HeaderValue::from(len));
+            }
+            None => {
+                headers.remove(CONTENT_LENGTH);
+            }
+        }
+        self.map_body(form.into_stream())
+    }
+
     /// Use pre allocated bytes as request body.
     ///
     /// Input type must implement [From] trait with [Bytes].
@@ -162,3 +207,95 @@ impl<'a> RequestBuilder<'a> {
             .await
     }
 }
+
+#[cfg(all(test, any(feature = "urlencoded", feature = "multipart")))]
+mod test {
+    use futures::StreamExt;
+
+    use super::*;
+
+    async fn collect_body(req: RequestBuilder<'_>) -> Vec<u8> {
+        let mut body = req.req.into_body();
+        let mut buf = Vec::new();
+        while let Some(bytes) = body.next().await {
+            buf.extend_from_slice(&bytes.unwrap());
+        }
+        buf
+    }
+
+    #[cfg(feature = "urlencoded")]
+    #[tokio::test]
+    async fn form() {
+        let client = Client::new();
+        let req = client
+            .post("http://localhost")
+            .unwrap()
+            .form([("name", "xitca"), ("lang", "rust & c")])
+            .unwrap();
+
+        let body = "name=xitca&lang=rust+%26+c";
+        assert_eq!(
+            req.headers().get(CONTENT_TYPE).unwrap(),
+            "application/x-www-form-urlencoded"
+        );
+        assert_eq!(
+            req.headers().get(CONTENT_LENGTH).unwrap(),
+            body.len().to_string().as_str()
+        );
+        assert_eq!(collect_body(req).await, body.as_bytes());
+    }
+
+    #[cfg(feature = "multipart")]
+    #[tokio::test]
+    async fn multipart() {
+        use futures::stream;
+
+        use crate::multipart::{Form, Part};
+
+        let client = Client::new();
+
+        let form = Form::with_boundary("xitca-boundary").text("name", "xitca").part(
+            Part::bytes("file", "file content")
+                .file_name("foo.txt")
+                .content_type("text/plain"),
+        );
+        let req = client.post("http://localhost").unwrap().multipart(form);
+
+        let body = "--xitca-boundary\r\n\
+            content-disposition: form-data; name=\"name\"\r\n\
+            \r\n\
+            xitca\r\n\
+            --xitca-boundary\r\n\
+            content-disposition: form-data; name=\"file\"; filename=\"foo.txt\"\r\n\
+            content-type: text/plain\r\n\
+            \r\n\
+            file content\r\n\
+            --xitca-boundary--\r\n";
+        assert_eq!(
+            req.headers().get(CONTENT_TYPE).unwrap(),
+            "multipart/form-data; boundary=xitca-boundary"
+        );
+        assert_eq!(
+            req.headers().get(CONTENT_LENGTH).unwrap(),
+            body.len().to_string().as_str()
+        );
+        assert_eq!(String::from_utf8(collect_body(req).await).unwrap(), body);
+
+        // streaming part with unknown size removes content length.
+        let part = Part::stream("file", stream::iter([Ok::<_, core::convert::Infallible>("file")]));
+        let req = client
+            .post("http://localhost")
+            .unwrap()
+            .body("stale")
+            .multipart(Form::with_boundary("xitca-boundary").part(part));
+        assert!(req.headers().get(CONTENT_LENGTH).is_none());
+        assert_eq!(
+            String::from_utf8(collect_body(req).await).unwrap(),
+            "--xitca-boundary\r\n\
+            content-disposition: form-data; name=\"file\"\r\n\
+            \r\n\
+            file\r\n\
+            --xitca-boundary--\r\n"
+        );
+    }
+}
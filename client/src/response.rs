@@ -46,7 +46,7 @@ impl<const PAYLOAD_LIMIT: usize> fmt::Debug for Response<'_, PAYLOAD_LIMIT> {
 }
 
 impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
-    #[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
+    #[cfg(any(feature = "http1", feature = "http2", feature = "http3", feature = "cache"))]
     pub(crate) fn new(res: http::Response<ResponseBody<'a>>, timer: Pin<Box<Sleep>>, timeout: Duration) -> Self {
         Self { res, timer, timeout }
     }
@@ -165,6 +165,62 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
         Ok(b)
     }
 
+    #[cfg(feature = "cache")]
+    /// Buffer response body in memory.
+    ///
+    /// When body size exceeds limit the buffered bytes are chained with remaining body and
+    /// `None` is returned alongside the response.
+    pub(crate) async fn buffer(self, limit: usize) -> Result<(Self, Option<xitca_http::bytes::Bytes>), Error> {
+        use crate::body::PrefixBody;
+
+        let (parts, mut body) = self.res.into_parts();
+        let mut timer = self.timer;
+
+        let mut buf = BytesMut::new();
+
+        timer.as_mut().reset(Instant::now() + self.timeout);
+
+        let complete = loop {
+            match poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+                .timeout(timer.as_mut())
+                .await
+            {
+                Ok(Some(Ok(bytes))) => {
+                    buf.extend_from_slice(&bytes);
+                    if buf.len() > limit {
+                        break false;
+                    }
+                }
+                Ok(Some(Err(e))) => {
+                    body.destroy_on_drop();
+                    return Err(e.into());
+                }
+                Ok(None) => break true,
+                Err(_) => {
+                    body.destroy_on_drop();
+                    return Err(TimeoutError::Response.into());
+                }
+            }
+        };
+
+        let buf = buf.freeze();
+
+        let (body, buf) = if complete {
+            let body = ResponseBody::Unknown(Box::pin(PrefixBody::new(buf.clone(), ResponseBody::Eof)));
+            (body, Some(buf))
+        } else {
+            (ResponseBody::Unknown(Box::pin(PrefixBody::new(buf, body))), None)
+        };
+
+        let res = Response {
+            res: http::Response::from_parts(parts, body),
+            timer,
+            timeout: self.timeout,
+        };
+
+        Ok((res, buf))
+    }
+
     // TODO: use a type to collect all information needed for testing.
     #[doc(hidden)]
     #[cold]
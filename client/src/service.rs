@@ -3,7 +3,8 @@ use core::{future::Future, pin::Pin, time::Duration};
 use tokio::time::Instant;
 
 use crate::{
-    body::BoxBody, client::Client, connect::Connect, error::Error, http::Request, response::Response, uri::Uri,
+    body::BoxBody, client::Client, connect::Connect, error::Error, http::Request, response::Response,
+    timing::RequestTiming, uri::Uri,
 };
 
 type BoxFuture<'f, T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'f>>;
@@ -89,6 +90,8 @@ pub(crate) fn base_service() -> HttpService {
 
             let conn_is_none = conn.is_none();
 
+            let timing = req.extensions().get::<RequestTiming>().cloned();
+
             // setup timer according to outcome and timeout configs.
             let dur = if conn_is_none {
                 client.timeout_config.resolve_timeout
@@ -102,15 +105,25 @@ pub(crate) fn base_service() -> HttpService {
             // Nothing in the pool. construct new connection and add it to Conn.
             if conn_is_none {
                 let mut connect = Connect::new(uri);
+                connect.timing = timing.clone();
                 let c = client.make_connection(&mut connect, &mut timer, req.version()).await?;
                 conn.add(c);
+            } else if let Some(ref timing) = timing {
+                timing.record(|t| t.reused = true);
             }
 
             let _date = client.date_service.handle();
 
-            timer
-                .as_mut()
-                .reset(Instant::now() + client.timeout_config.request_timeout);
+            let start = Instant::now();
+
+            timer.as_mut().reset(start + client.timeout_config.request_timeout);
+
+            #[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
+            let record_ttfb = || {
+                if let Some(ref timing) = timing {
+                    timing.record(|t| t.ttfb = Some(start.elapsed()));
+                }
+            };
 
             let _res = match *conn {
                 #[cfg(feature = "http1")]
@@ -141,6 +154,7 @@ pub(crate) fn base_service() -> HttpService {
                         .await
                     {
                         Ok(Ok(res)) => {
+                            record_ttfb();
                             let timeout = client.timeout_config.response_timeout;
                             Ok(Response::new(res, timer, timeout))
                         }
@@ -163,6 +177,7 @@ pub(crate) fn base_service() -> HttpService {
                         .await
                     {
                         Ok(Ok(res)) => {
+                            record_ttfb();
                             let timeout = client.timeout_config.response_timeout;
                             Ok(Response::new(res, timer, timeout))
                         }
@@ -183,6 +198,8 @@ pub(crate) fn base_service() -> HttpService {
             #[cfg(feature = "http1")]
             match _res {
                 Ok(Ok((res, buf, chunk, decoder, is_close))) => {
+                    record_ttfb();
+
                     if is_close {
                         conn.destroy_on_drop();
                     }
@@ -0,0 +1,61 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Recorder of time spent in each phase of a request.
+///
+/// The phases follow the ones configured by [TimeoutConfig](crate::TimeoutConfig). When a recorder is
+/// inserted into request's [Extensions](crate::http::Extensions) client would record phase durations
+/// into it.
+///
+/// # Examples
+/// ```rust
+/// use xitca_client::{Client, RequestTiming};
+///
+/// # async fn send(client: &Client) -> Result<(), xitca_client::error::Error> {
+/// let timing = RequestTiming::new();
+///
+/// let mut req = client.get("http://localhost/")?;
+/// req.extensions_mut().insert(timing.clone());
+/// let _ = req.send().await?;
+///
+/// println!("time to first byte: {:?}", timing.phases().ttfb);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RequestTiming(Arc<Mutex<TimingPhases>>);
+
+/// Durations of request phases recorded by [RequestTiming].
+///
+/// Connection phases are None when request is sent through a connection from pool.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimingPhases {
+    /// time spent on DNS resolving.
+    pub resolve: Option<Duration>,
+    /// time spent on establishing connection.
+    pub connect: Option<Duration>,
+    /// time spent on tls handshake.
+    pub tls: Option<Duration>,
+    /// time from sending request to receiving response head.
+    pub ttfb: Option<Duration>,
+    /// request is sent through a connection from pool.
+    pub reused: bool,
+}
+
+impl RequestTiming {
+    /// construct a new recorder with no phase recorded.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a copy of recorded phases.
+    pub fn phases(&self) -> TimingPhases {
+        *self.0.lock().unwrap()
+    }
+
+    pub(crate) fn record(&self, func: impl FnOnce(&mut TimingPhases)) {
+        func(&mut self.0.lock().unwrap())
+    }
+}
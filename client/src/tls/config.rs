@@ -0,0 +1,295 @@
+use std::{collections::HashMap, fmt, io};
+
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use sha2::{Digest, Sha256};
+use xitca_http::{bytes::Bytes, http::PeerCertificate};
+
+use crate::error::{Error, TlsConfigError};
+
+/// Client side tls configuration.
+///
+/// The same configuration is shared by openssl, rustls and http/3 connectors.
+///
+/// # Examples
+/// ```rust
+/// use xitca_client::{Client, Identity, TlsConfig};
+///
+/// # fn build(ca: &[u8], cert: &[u8], key: &[u8]) -> Result<(), xitca_client::error::Error> {
+/// let config = TlsConfig::new()
+///     // trust additional certificate authorities.
+///     .add_root_certificates_pem(ca)?
+///     // present client certificate to all servers.
+///     .identity(Identity::from_pem(cert, key)?);
+///
+/// let client = Client::builder().rustls_with_config(config)?.finish();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TlsConfig {
+    pub(crate) roots: Vec<CertificateDer<'static>>,
+    pub(crate) webpki_roots: bool,
+    pub(crate) system_roots: bool,
+    pub(crate) identity: Option<Identity>,
+    pub(crate) host_identities: HashMap<Box<str>, Identity>,
+    pub(crate) pins: Pins,
+}
+
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsConfig")
+            .field("roots", &self.roots.len())
+            .field("webpki_roots", &self.webpki_roots)
+            .field("system_roots", &self.system_roots)
+            .field("identity", &self.identity.is_some())
+            .field("host_identities", &self.host_identities.keys().collect::<Vec<_>>())
+            .field("pins", &self.pins.len())
+            .finish()
+    }
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TlsConfig {
+    /// Construct a new config trusting bundled webpki root certificates.
+    pub fn new() -> Self {
+        Self {
+            roots: Vec::new(),
+            webpki_roots: true,
+            system_roots: false,
+            identity: None,
+            host_identities: HashMap::new(),
+            pins: Pins::default(),
+        }
+    }
+
+    /// Trust given DER encoded certificate authority.
+    pub fn add_root_certificate(mut self, der: impl Into<Vec<u8>>) -> Self {
+        self.roots.push(CertificateDer::from(der.into()));
+        self
+    }
+
+    /// Trust all certificate authorities found in given PEM encoded bundle.
+    pub fn add_root_certificates_pem(mut self, pem: &[u8]) -> Result<Self, Error> {
+        let certs = certs_from_pem(pem)?;
+        self.roots.extend(certs);
+        Ok(self)
+    }
+
+    /// Trust root certificates bundled from webpki-roots crate.
+    ///
+    /// openssl connector can not use bundled certificates and would trust system root certificates
+    /// instead when this is enabled.
+    ///
+    /// Default to true.
+    pub fn webpki_roots(mut self, enable: bool) -> Self {
+        self.webpki_roots = enable;
+        self
+    }
+
+    /// Trust root certificates from operating system's certificate store.
+    ///
+    /// Default to false.
+    pub fn system_roots(mut self, enable: bool) -> Self {
+        self.system_roots = enable;
+        self
+    }
+
+    /// Present given client certificate when server requests client authentication.
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Present given client certificate to server with given host name.
+    ///
+    /// Identity for specific host takes precedence over the one set by [TlsConfig::identity].
+    pub fn host_identity(mut self, host: impl Into<Box<str>>, identity: Identity) -> Self {
+        self.host_identities.insert(host.into(), identity);
+        self
+    }
+
+    /// Pin SHA-256 hash of a DER encoded certificate.
+    ///
+    /// When any pin is set a server is only trusted when at least one certificate from it's
+    /// certificate chain matches a pin. Pinning is checked on top of regular certificate verification.
+    pub fn pin_certificate_sha256(mut self, hash: [u8; 32]) -> Self {
+        self.pins.certs.push(hash);
+        self
+    }
+
+    /// Pin SHA-256 hash of a DER encoded SubjectPublicKeyInfo. (the same value used by HPKP)
+    ///
+    /// See [TlsConfig::pin_certificate_sha256] for detail.
+    pub fn pin_spki_sha256(mut self, hash: [u8; 32]) -> Self {
+        self.pins.spkis.push(hash);
+        self
+    }
+
+    #[cfg(any(feature = "rustls", feature = "http3"))]
+    pub(crate) fn system_root_certificates(&self) -> Result<Vec<CertificateDer<'static>>, Error> {
+        if !self.system_roots {
+            return Ok(Vec::new());
+        }
+        rustls_native_certs::load_native_certs().map_err(|e| TlsConfigError::Pem(e).into())
+    }
+}
+
+/// Client certificate chain and it's private key used for client authentication (mTLS).
+pub struct Identity {
+    pub(crate) chain: Vec<CertificateDer<'static>>,
+    pub(crate) key: PrivateKeyDer<'static>,
+}
+
+impl Clone for Identity {
+    fn clone(&self) -> Self {
+        Self {
+            chain: self.chain.clone(),
+            key: self.key.clone_key(),
+        }
+    }
+}
+
+impl fmt::Debug for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Identity").field("chain", &self.chain.len()).finish()
+    }
+}
+
+impl Identity {
+    /// Construct identity from PEM encoded certificate chain and private key.
+    ///
+    /// Private key can be in PKCS#1, PKCS#8 or SEC1 format.
+    pub fn from_pem(cert_chain: &[u8], key: &[u8]) -> Result<Self, Error> {
+        let chain = certs_from_pem(cert_chain)?;
+        let key = rustls_pemfile::private_key(&mut io::BufReader::new(key))
+            .map_err(TlsConfigError::Pem)?
+            .ok_or(TlsConfigError::MissingPrivateKey)?;
+        Ok(Self { chain, key })
+    }
+
+    /// Construct identity from DER encoded certificate chain and PKCS#8 private key.
+    pub fn from_pkcs8_der(cert_chain: Vec<Vec<u8>>, key: Vec<u8>) -> Result<Self, Error> {
+        if cert_chain.is_empty() {
+            return Err(TlsConfigError::MissingCertificate.into());
+        }
+        Ok(Self {
+            chain: cert_chain.into_iter().map(CertificateDer::from).collect(),
+            key: PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key)),
+        })
+    }
+
+    #[cfg(feature = "openssl")]
+    /// Construct identity from DER encoded PKCS#12 archive.
+    pub fn from_pkcs12(der: &[u8], password: &str) -> Result<Self, Error> {
+        use openssl_crate::pkcs12::Pkcs12;
+
+        let backend = |e: openssl_crate::error::ErrorStack| TlsConfigError::Backend(Box::new(e));
+
+        let parsed = Pkcs12::from_der(der)
+            .and_then(|p| p.parse2(password))
+            .map_err(backend)?;
+
+        let cert = parsed.cert.ok_or(TlsConfigError::MissingCertificate)?;
+        let key = parsed.pkey.ok_or(TlsConfigError::MissingPrivateKey)?;
+
+        let mut chain = vec![cert.to_der().map_err(backend)?];
+        for ca in parsed.ca.iter().flatten() {
+            chain.push(ca.to_der().map_err(backend)?);
+        }
+
+        Self::from_pkcs8_der(chain, key.private_key_to_pkcs8().map_err(backend)?)
+    }
+}
+
+fn certs_from_pem(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, Error> {
+    let certs = rustls_pemfile::certs(&mut io::BufReader::new(pem))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(TlsConfigError::Pem)?;
+    if certs.is_empty() {
+        return Err(TlsConfigError::MissingCertificate.into());
+    }
+    Ok(certs)
+}
+
+/// SHA-256 pins of certificates and public keys.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Pins {
+    certs: Vec<[u8; 32]>,
+    spkis: Vec<[u8; 32]>,
+}
+
+impl Pins {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.certs.is_empty() && self.spkis.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.certs.len() + self.spkis.len()
+    }
+
+    /// check DER encoded certificate chain against pins. empty pins always match.
+    pub(crate) fn matches<'a>(&self, chain: impl IntoIterator<Item = &'a [u8]>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        chain.into_iter().any(|cert| {
+            let cert_hash: [u8; 32] = Sha256::digest(cert).into();
+            if self.certs.contains(&cert_hash) {
+                return true;
+            }
+
+            !self.spkis.is_empty()
+                && spki(cert).is_some_and(|spki| {
+                    let spki_hash: [u8; 32] = Sha256::digest(spki).into();
+                    self.spkis.contains(&spki_hash)
+                })
+        })
+    }
+}
+
+// extract DER encoded SubjectPublicKeyInfo from DER encoded X.509 certificate.
+fn spki(cert: &[u8]) -> Option<Bytes> {
+    let cert = PeerCertificate::from_der_chain([Bytes::copy_from_slice(cert)])?;
+    let spki = cert.subject_public_key_info()?;
+    Some(cert.chain()[0].slice_ref(spki))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // minimal certificate shaped DER structure.
+    // SEQUENCE { SEQUENCE { [0] { INTEGER 2 }, INTEGER 1, SEQUENCE {}, SEQUENCE {}, SEQUENCE {}, SEQUENCE {}, SEQUENCE { NULL } } }
+    const CERT: &[u8] = &[
+        0x30, 0x16, 0x30, 0x14, 0xa0, 0x03, 0x02, 0x01, 0x02, 0x02, 0x01, 0x01, 0x30, 0x00, 0x30, 0x00, 0x30, 0x00,
+        0x30, 0x00, 0x30, 0x02, 0x05, 0x00,
+    ];
+
+    #[test]
+    fn spki_extract() {
+        assert_eq!(spki(CERT).unwrap(), &[0x30, 0x02, 0x05, 0x00][..]);
+        assert!(spki(&CERT[..10]).is_none());
+    }
+
+    #[test]
+    fn pin_match() {
+        let cert = CERT;
+
+        assert!(Pins::default().matches([cert]));
+
+        let config = TlsConfig::new().pin_certificate_sha256(Sha256::digest(cert).into());
+        assert!(config.pins.matches([cert]));
+
+        let config = TlsConfig::new().pin_spki_sha256(Sha256::digest([0x30, 0x02, 0x05, 0x00]).into());
+        assert!(config.pins.matches([b"invalid".as_slice(), cert]));
+
+        let config = TlsConfig::new().pin_spki_sha256([0; 32]);
+        assert!(!config.pins.matches([cert]));
+    }
+}
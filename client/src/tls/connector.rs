@@ -39,14 +39,72 @@ pub(crate) fn nop() -> Connector {
     Box::new(Nop)
 }
 
+#[cfg(any(feature = "openssl", feature = "rustls"))]
+/// connector selecting inner connector by server name. used for per host client certificate.
+struct HostConnector<C> {
+    default: C,
+    hosts: std::collections::HashMap<Box<str>, C>,
+}
+
+#[cfg(any(feature = "openssl", feature = "rustls"))]
+impl<C> HostConnector<C>
+where
+    C: for<'n> Service<(&'n str, Box<dyn Io>), Response = (Box<dyn Io>, Version), Error = Error>
+        + Send
+        + Sync
+        + 'static,
+{
+    fn try_new<F>(config: &super::config::TlsConfig, func: F) -> Result<Connector, Error>
+    where
+        F: Fn(Option<&super::config::Identity>) -> Result<C, Error>,
+    {
+        let default = func(config.identity.as_ref())?;
+
+        if config.host_identities.is_empty() {
+            return Ok(Box::new(default));
+        }
+
+        let hosts = config
+            .host_identities
+            .iter()
+            .map(|(host, identity)| func(Some(identity)).map(|c| (host.clone(), c)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Box::new(Self { default, hosts }))
+    }
+}
+
+#[cfg(any(feature = "openssl", feature = "rustls"))]
+impl<'n, C> Service<(&'n str, Box<dyn Io>)> for HostConnector<C>
+where
+    C: for<'n2> Service<(&'n2 str, Box<dyn Io>), Response = (Box<dyn Io>, Version), Error = Error> + Send + Sync,
+{
+    type Response = (Box<dyn Io>, Version);
+    type Error = Error;
+
+    async fn call(&self, (name, io): (&'n str, Box<dyn Io>)) -> Result<Self::Response, Self::Error> {
+        self.hosts.get(name).unwrap_or(&self.default).call((name, io)).await
+    }
+}
+
 #[cfg(feature = "openssl")]
 pub(crate) mod openssl {
     use core::pin::Pin;
 
-    use openssl_crate::ssl::{SslConnector, SslMethod};
+    use openssl_crate::{
+        error::ErrorStack,
+        pkey::PKey,
+        ssl::{SslConnector, SslMethod, SslVerifyMode},
+        x509::{store::X509StoreBuilder, X509},
+    };
     use tokio_openssl::SslStream;
     use xitca_http::bytes::BufMut;
 
+    use crate::{
+        error::TlsConfigError,
+        tls::config::{Identity, TlsConfig},
+    };
+
     use super::*;
 
     impl<'n> Service<(&'n str, Box<dyn Io>)> for SslConnector {
@@ -74,19 +132,170 @@ pub(crate) mod openssl {
         }
     }
 
-    pub(crate) fn connect(protocols: &[&[u8]]) -> Connector {
+    pub(crate) fn connect(protocols: &[&[u8]], config: &TlsConfig) -> Result<Connector, Error> {
         let mut alpn = Vec::with_capacity(20);
         for proto in protocols {
             alpn.put_u8(proto.len() as u8);
             alpn.put(*proto);
         }
 
-        let mut ssl = SslConnector::builder(SslMethod::tls()).unwrap();
+        HostConnector::try_new(config, |identity| {
+            build(&alpn, config, identity).map_err(|e| TlsConfigError::Backend(Box::new(e)).into())
+        })
+    }
+
+    fn build(alpn: &[u8], config: &TlsConfig, identity: Option<&Identity>) -> Result<SslConnector, ErrorStack> {
+        // connector builder loads system root certificates by default.
+        let mut ssl = SslConnector::builder(SslMethod::tls())?;
+
+        ssl.set_alpn_protos(alpn)?;
+
+        // webpki root certificates can not be used by openssl. system store is used as fallback.
+        if !config.webpki_roots && !config.system_roots {
+            // replace default store so only custom root certificates are trusted.
+            let mut store = X509StoreBuilder::new()?;
+            for root in config.roots.iter() {
+                store.add_cert(X509::from_der(root)?)?;
+            }
+            ssl.set_cert_store(store.build());
+        } else {
+            for root in config.roots.iter() {
+                ssl.cert_store_mut().add_cert(X509::from_der(root)?)?;
+            }
+        }
+
+        if let Some(identity) = identity {
+            let (cert, chain) = identity
+                .chain
+                .split_first()
+                .expect("Identity must contain at least one certificate");
+            ssl.set_certificate(X509::from_der(cert)?.as_ref())?;
+            for cert in chain {
+                ssl.add_extra_chain_cert(X509::from_der(cert)?)?;
+            }
+            ssl.set_private_key(PKey::private_key_from_der(identity.key.secret_der())?.as_ref())?;
+            ssl.check_private_key()?;
+        }
+
+        if !config.pins.is_empty() {
+            let pins = config.pins.clone();
+            ssl.set_verify_callback(SslVerifyMode::PEER, move |preverify_ok, ctx| {
+                // pins are checked against the whole chain when verifying end entity certificate.
+                if !preverify_ok || ctx.error_depth() != 0 {
+                    return preverify_ok;
+                }
+
+                let Some(chain) = ctx.chain() else { return false };
+
+                let chain = chain.iter().filter_map(|cert| cert.to_der().ok()).collect::<Vec<_>>();
+                pins.matches(chain.iter().map(Vec::as_slice))
+            });
+        }
+
+        Ok(ssl.build())
+    }
+
+    #[cfg(test)]
+    mod test {
+        use openssl_crate::{
+            asn1::Asn1Time,
+            bn::BigNum,
+            ec::{EcGroup, EcKey},
+            hash::MessageDigest,
+            nid::Nid,
+            pkey::Private,
+            ssl::SslAcceptor,
+            x509::{
+                extension::{BasicConstraints, SubjectAlternativeName},
+                X509Name,
+            },
+        };
+        use tokio::net::{TcpListener, TcpStream};
 
-        ssl.set_alpn_protos(&alpn)
-            .unwrap_or_else(|e| panic!("Can not set ALPN protocol: {e:?}"));
+        use super::*;
+
+        fn key() -> PKey<Private> {
+            let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+            PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+        }
 
-        Box::new(ssl.build())
+        fn cert(cn: &str, key: &PKey<Private>, issuer: Option<(&X509, &PKey<Private>)>) -> X509 {
+            let mut name = X509Name::builder().unwrap();
+            name.append_entry_by_text("CN", cn).unwrap();
+            let name = name.build();
+
+            let mut builder = X509::builder().unwrap();
+            builder.set_version(2).unwrap();
+            builder
+                .set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+                .unwrap();
+            builder.set_subject_name(&name).unwrap();
+            builder.set_pubkey(key).unwrap();
+            builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+            builder.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+
+            match issuer {
+                Some((ca, ca_key)) => {
+                    builder.set_issuer_name(ca.subject_name()).unwrap();
+                    let san = SubjectAlternativeName::new()
+                        .dns(cn)
+                        .build(&builder.x509v3_context(Some(ca), None))
+                        .unwrap();
+                    builder.append_extension(san).unwrap();
+                    builder.sign(ca_key, MessageDigest::sha256()).unwrap();
+                }
+                None => {
+                    builder.set_issuer_name(&name).unwrap();
+                    builder
+                        .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+                        .unwrap();
+                    builder.sign(key, MessageDigest::sha256()).unwrap();
+                }
+            }
+
+            builder.build()
+        }
+
+        #[tokio::test]
+        async fn custom_root_only() {
+            let ca_key = key();
+            let ca = cert("xitca test ca", &ca_key, None);
+            let leaf_key = key();
+            let leaf = cert("localhost", &leaf_key, Some((&ca, &ca_key)));
+
+            let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+            acceptor.set_certificate(&leaf).unwrap();
+            acceptor.set_private_key(&leaf_key).unwrap();
+            let acceptor = acceptor.build();
+
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            tokio::spawn(async move {
+                loop {
+                    let (stream, _) = listener.accept().await.unwrap();
+                    let ssl = openssl_crate::ssl::Ssl::new(acceptor.context()).unwrap();
+                    let mut stream = SslStream::new(ssl, stream).unwrap();
+                    let _ = Pin::new(&mut stream).accept().await;
+                }
+            });
+
+            let connect = |config: TlsConfig| async move {
+                let connector = build(b"\x08http/1.1", &config, None).unwrap();
+                let io = TcpStream::connect(addr).await.unwrap();
+                Service::call(&connector, ("localhost", Box::new(io) as _)).await
+            };
+
+            let config = TlsConfig::new().webpki_roots(false).system_roots(false);
+
+            // custom root is trusted when no other root store is enabled.
+            let res = connect(config.clone().add_root_certificate(ca.to_der().unwrap())).await;
+            assert!(res.is_ok());
+
+            // server is not trusted without custom root.
+            let res = connect(config).await;
+            assert!(res.is_err());
+        }
     }
 }
 
@@ -94,13 +303,24 @@ pub(crate) mod openssl {
 pub(crate) mod rustls {
     use std::sync::Arc;
 
-    use rustls_pki_types::ServerName;
+    use rustls_pki_types::{CertificateDer, ServerName, UnixTime};
     use tokio_rustls::{
-        rustls::{ClientConfig, RootCertStore},
+        rustls::{
+            client::{
+                danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+                WebPkiServerVerifier,
+            },
+            ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+        },
         TlsConnector,
     };
     use webpki_roots::TLS_SERVER_ROOTS;
 
+    use crate::{
+        error::TlsConfigError,
+        tls::config::{Identity, Pins, TlsConfig},
+    };
+
     use super::*;
 
     impl<'n> Service<(&'n str, Box<dyn Io>)> for TlsConnector {
@@ -125,17 +345,228 @@ pub(crate) mod rustls {
         }
     }
 
-    pub(crate) fn connect(protocols: &[&[u8]]) -> Connector {
+    pub(crate) fn connect(protocols: &[&[u8]], config: &TlsConfig) -> Result<Connector, Error> {
         let mut root_certs = RootCertStore::empty();
 
-        root_certs.extend(TLS_SERVER_ROOTS.iter().cloned());
+        if config.webpki_roots {
+            root_certs.extend(TLS_SERVER_ROOTS.iter().cloned());
+        }
 
-        let mut config = ClientConfig::builder()
-            .with_root_certificates(root_certs)
-            .with_no_client_auth();
+        root_certs.add_parsable_certificates(config.system_root_certificates()?);
 
-        config.alpn_protocols = protocols.iter().map(|p| p.to_vec()).collect();
+        for root in config.roots.iter() {
+            root_certs.add(root.clone()).map_err(backend)?;
+        }
+
+        let root_certs = Arc::new(root_certs);
+
+        HostConnector::try_new(config, |identity| {
+            let mut config = build(&root_certs, &config.pins, identity)?;
+            config.alpn_protocols = protocols.iter().map(|p| p.to_vec()).collect();
+            Ok(TlsConnector::from(Arc::new(config)))
+        })
+    }
 
-        Box::new(TlsConnector::from(Arc::new(config)))
+    fn build(root_certs: &Arc<RootCertStore>, pins: &Pins, identity: Option<&Identity>) -> Result<ClientConfig, Error> {
+        let builder = ClientConfig::builder();
+
+        let builder = if pins.is_empty() {
+            builder.with_root_certificates(root_certs.clone())
+        } else {
+            let inner = WebPkiServerVerifier::builder(root_certs.clone())
+                .build()
+                .map_err(backend)?;
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(PinnedVerifier {
+                    inner,
+                    pins: pins.clone(),
+                }))
+        };
+
+        match identity {
+            Some(identity) => builder
+                .with_client_auth_cert(identity.chain.clone(), identity.key.clone_key())
+                .map_err(backend),
+            None => Ok(builder.with_no_client_auth()),
+        }
+    }
+
+    fn backend<E>(e: E) -> Error
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        TlsConfigError::Backend(Box::new(e)).into()
+    }
+
+    // verifier checking certificate pins after regular webpki verification.
+    #[derive(Debug)]
+    struct PinnedVerifier {
+        inner: Arc<WebPkiServerVerifier>,
+        pins: Pins,
+    }
+
+    impl ServerCertVerifier for PinnedVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &CertificateDer<'_>,
+            intermediates: &[CertificateDer<'_>],
+            server_name: &ServerName<'_>,
+            ocsp_response: &[u8],
+            now: UnixTime,
+        ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+            let verified = self
+                .inner
+                .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+
+            let chain = core::iter::once(end_entity).chain(intermediates).map(|c| c.as_ref());
+            if self.pins.matches(chain) {
+                Ok(verified)
+            } else {
+                Err(tokio_rustls::rustls::Error::InvalidCertificate(
+                    tokio_rustls::rustls::CertificateError::ApplicationVerificationFailure,
+                ))
+            }
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+            self.inner.verify_tls12_signature(message, cert, dss)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+            self.inner.verify_tls13_signature(message, cert, dss)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.inner.supported_verify_schemes()
+        }
+    }
+}
+
+#[cfg(feature = "http3")]
+pub(crate) mod h3 {
+    use std::{collections::HashMap, sync::Arc, time::SystemTime};
+
+    use h3_quinn::quinn::ClientConfig;
+    use rustls_0dot21::{
+        client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
+        Certificate, OwnedTrustAnchor, PrivateKey, RootCertStore, ServerName,
+    };
+    use webpki_roots_0dot25::TLS_SERVER_ROOTS;
+
+    use crate::{
+        error::{Error, TlsConfigError},
+        tls::config::{Identity, Pins, TlsConfig},
+    };
+
+    /// quinn client configs for http/3 connections. default config and per host configs.
+    pub(crate) type H3Configs = (ClientConfig, HashMap<Box<str>, ClientConfig>);
+
+    // http/3 is bound to rustls 0.21 and the config is translated from shared TlsConfig.
+    pub(crate) fn configs(config: &TlsConfig) -> Result<H3Configs, Error> {
+        let mut root_certs = RootCertStore::empty();
+
+        if config.webpki_roots {
+            root_certs.add_trust_anchors(TLS_SERVER_ROOTS.iter().map(|cert| {
+                OwnedTrustAnchor::from_subject_spki_name_constraints(cert.subject, cert.spki, cert.name_constraints)
+            }));
+        }
+
+        root_certs.add_parsable_certificates(&config.system_root_certificates()?);
+
+        for root in config.roots.iter() {
+            root_certs.add(&Certificate(root.to_vec())).map_err(backend)?;
+        }
+
+        let build = |identity| build(&root_certs, &config.pins, identity).map(|c| ClientConfig::new(Arc::new(c)));
+
+        let default = build(config.identity.as_ref())?;
+
+        let hosts = config
+            .host_identities
+            .iter()
+            .map(|(host, identity)| build(Some(identity)).map(|c| (host.clone(), c)))
+            .collect::<Result<_, _>>()?;
+
+        Ok((default, hosts))
+    }
+
+    fn build(
+        root_certs: &RootCertStore,
+        pins: &Pins,
+        identity: Option<&Identity>,
+    ) -> Result<rustls_0dot21::ClientConfig, Error> {
+        let builder = rustls_0dot21::ClientConfig::builder().with_safe_defaults();
+
+        // rustls 0.21 builder changes it's type state with root certificates. use verifier in both cases.
+        let inner = WebPkiVerifier::new(root_certs.clone(), None);
+        let verifier: Arc<dyn ServerCertVerifier> = if pins.is_empty() {
+            Arc::new(inner)
+        } else {
+            Arc::new(PinnedVerifier {
+                inner,
+                pins: pins.clone(),
+            })
+        };
+        let builder = builder.with_custom_certificate_verifier(verifier);
+
+        let mut crypto = match identity {
+            Some(identity) => {
+                let chain = identity.chain.iter().map(|c| Certificate(c.to_vec())).collect();
+                let key = PrivateKey(identity.key.secret_der().to_vec());
+                builder.with_client_auth_cert(chain, key).map_err(backend)?
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        crypto.alpn_protocols = vec![b"h3-29".to_vec()];
+
+        Ok(crypto)
+    }
+
+    fn backend(e: rustls_0dot21::Error) -> Error {
+        TlsConfigError::Backend(Box::new(e)).into()
+    }
+
+    struct PinnedVerifier {
+        inner: WebPkiVerifier,
+        pins: Pins,
+    }
+
+    impl ServerCertVerifier for PinnedVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &Certificate,
+            intermediates: &[Certificate],
+            server_name: &ServerName,
+            scts: &mut dyn Iterator<Item = &[u8]>,
+            ocsp_response: &[u8],
+            now: SystemTime,
+        ) -> Result<ServerCertVerified, rustls_0dot21::Error> {
+            let verified =
+                self.inner
+                    .verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now)?;
+
+            let chain = core::iter::once(end_entity)
+                .chain(intermediates)
+                .map(|c| c.0.as_slice());
+            if self.pins.matches(chain) {
+                Ok(verified)
+            } else {
+                Err(rustls_0dot21::Error::InvalidCertificate(
+                    rustls_0dot21::CertificateError::ApplicationVerificationFailure,
+                ))
+            }
+        }
     }
 }
@@ -1,2 +1,4 @@
+#[cfg(any(feature = "openssl", feature = "rustls", feature = "http3"))]
+pub(crate) mod config;
 pub(crate) mod connector;
 pub(crate) mod stream;
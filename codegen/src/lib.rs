@@ -1,7 +1,9 @@
 mod error;
 mod route;
+mod schema;
 mod service;
 mod state;
+mod validate;
 
 use proc_macro::TokenStream;
 use syn::{spanned::Spanned, Error, ImplItem, ImplItemFn};
@@ -12,6 +14,18 @@ pub fn state_impl(item: TokenStream) -> TokenStream {
     state::state(item).unwrap_or_else(|e| e.to_compile_error().into())
 }
 
+#[proc_macro_derive(Schema)]
+pub fn schema_impl(item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item);
+    schema::schema(item).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn validate_impl(item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item);
+    validate::validate(item).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
 /// attribute macro for `xitca-web` application.
 ///
 /// # Pattern
@@ -26,6 +40,9 @@ pub fn state_impl(item: TokenStream) -> TokenStream {
 ///   `method = get` for example.
 /// - `enclosed = <type>`: typed middleware applied to route.
 /// - `enclosed_fn = <async function>`: async function as middleware applied to route
+/// - `name = "<name>"`: name of route for url generation with `WebContext::url_for`.
+/// - `summary = "<summary>"`: summary of route in OpenAPI document generated with `openapi` feature of
+///   `xitca-web`. request and response schemas are derived from argument and return types of function.
 ///
 /// # Example
 /// ```rust(no_run)
@@ -4,7 +4,7 @@ use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
     spanned::Spanned,
-    Error, Expr, FnArg, GenericArgument, ItemFn, PathArguments, Type,
+    Error, Expr, FnArg, GenericArgument, ItemFn, Lifetime, PathArguments, ReturnType, Type,
 };
 
 pub(crate) fn route(attr: Args, input: ItemFn) -> Result<TokenStream, Error> {
@@ -21,6 +21,8 @@ pub(crate) fn route(attr: Args, input: ItemFn) -> Result<TokenStream, Error> {
     let method = &*method.right;
 
     let mut middlewares = quote! {};
+    let mut route_name = quote! {};
+    let mut summary = quote! { ::core::option::Option::<&'static str>::None };
 
     for attr in attrs {
         let Expr::Assign(pair) = attr else {
@@ -67,6 +69,22 @@ pub(crate) fn route(attr: Args, input: ItemFn) -> Result<TokenStream, Error> {
                 }
                 _ => return Err(Error::new(pair.right.span(), "expect type path or function")),
             },
+            "name" => {
+                let Expr::Lit(ref value) = *pair.right else {
+                    return Err(Error::new(pair.right.span(), "expect <value> to be string literal"));
+                };
+                route_name = quote! {
+                    fn name() -> Option<&'static str> {
+                        Some(#value)
+                    }
+                };
+            }
+            "summary" => {
+                let Expr::Lit(ref value) = *pair.right else {
+                    return Err(Error::new(pair.right.span(), "expect <value> to be string literal"));
+                };
+                summary = quote! { ::core::option::Option::Some(#value) };
+            }
             _ => {}
         }
     }
@@ -156,6 +174,28 @@ pub(crate) fn route(attr: Args, input: ItemFn) -> Result<TokenStream, Error> {
         }
     }
 
+    // argument and return types are used to describe route in OpenAPI document. impl Trait types can't be named
+    // and are skipped.
+    let arg_types = input.sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(ty) if !matches!(*ty.ty, Type::ImplTrait(_)) => {
+            let mut ty = (*ty.ty).clone();
+            elide_lifetimes(&mut ty);
+            Some(ty)
+        }
+        _ => None,
+    });
+
+    let res_type = match input.sig.output {
+        ReturnType::Type(_, ref ty) if !matches!(**ty, Type::ImplTrait(_)) => {
+            let mut ty = (**ty).clone();
+            elide_lifetimes(&mut ty);
+            quote! { #ty }
+        }
+        _ => quote! { () },
+    };
+
+    let ident_str = ident.to_string();
+
     let handler = if is_async {
         quote! { ::xitca_web::handler::handler_service }
     } else {
@@ -183,6 +223,8 @@ pub(crate) fn route(attr: Args, input: ItemFn) -> Result<TokenStream, Error> {
                 #path
             }
 
+            #route_name
+
             fn route() -> Self::Route {
                 #input
 
@@ -191,13 +233,56 @@ pub(crate) fn route(attr: Args, input: ItemFn) -> Result<TokenStream, Error> {
                 use xitca_web::route::#method;
                 use xitca_web::service::ServiceExt;
 
-                WebContext::<'_, #state_ident>::into_object(#method(#handler(#ident)#middlewares))
+                WebContext::<'_, #state_ident>::into_object(::xitca_web::codegen::__private::document!(
+                    #method(#handler(#ident)#middlewares),
+                    #ident_str,
+                    #summary,
+                    [#(#arg_types),*],
+                    #res_type
+                ))
             }
         }
     }
     .into())
 }
 
+// named lifetimes of handler function are not in scope of generated code. replace them with elided lifetime.
+fn elide_lifetimes(ty: &mut Type) {
+    match ty {
+        Type::Reference(ty) => {
+            if let Some(ref mut lifetime) = ty.lifetime {
+                elide_lifetime(lifetime);
+            }
+            elide_lifetimes(&mut ty.elem);
+        }
+        Type::Path(ty) => {
+            for seg in ty.path.segments.iter_mut() {
+                if let PathArguments::AngleBracketed(ref mut arg) = seg.arguments {
+                    for arg in arg.args.iter_mut() {
+                        match arg {
+                            GenericArgument::Lifetime(lifetime) => elide_lifetime(lifetime),
+                            GenericArgument::Type(ty) => elide_lifetimes(ty),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        Type::Array(ty) => elide_lifetimes(&mut ty.elem),
+        Type::Slice(ty) => elide_lifetimes(&mut ty.elem),
+        Type::Paren(ty) => elide_lifetimes(&mut ty.elem),
+        Type::Group(ty) => elide_lifetimes(&mut ty.elem),
+        Type::Tuple(ty) => ty.elems.iter_mut().for_each(elide_lifetimes),
+        _ => {}
+    }
+}
+
+fn elide_lifetime(lifetime: &mut Lifetime) {
+    if lifetime.ident != "static" {
+        *lifetime = Lifetime::new("'_", lifetime.span());
+    }
+}
+
 pub struct Args {
     vars: Vec<Expr>,
 }
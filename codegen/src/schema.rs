@@ -0,0 +1,65 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, GenericArgument, PathArguments, Type};
+
+pub(crate) fn schema(input: DeriveInput) -> Result<TokenStream, Error> {
+    let ty_ident = &input.ident;
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::xitca_web::openapi::Schema));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let schema = match input.data {
+        Data::Struct(ref ty) => {
+            let Fields::Named(ref fields) = ty.fields else {
+                return Err(Error::new(ty_ident.span(), "expect Struct with named fields"));
+            };
+
+            let fields = fields.named.iter().map(|field| {
+                let name = field.ident.as_ref().unwrap().to_string();
+                let ty = &field.ty;
+                let required = !is_option(ty);
+                quote! {
+                    (#name, <#ty as ::xitca_web::openapi::Schema>::schema(), #required)
+                }
+            });
+
+            quote! { ::xitca_web::openapi::object([#(#fields),*]) }
+        }
+        Data::Enum(ref ty) => {
+            let variants = ty
+                .variants
+                .iter()
+                .map(|variant| match variant.fields {
+                    Fields::Unit => Ok(variant.ident.to_string()),
+                    _ => Err(Error::new(variant.ident.span(), "expect unit variant")),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            quote! { ::xitca_web::openapi::string_enum(&[#(#variants),*]) }
+        }
+        Data::Union(_) => return Err(Error::new(ty_ident.span(), "expect Struct or Enum")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::xitca_web::openapi::Schema for #ty_ident #ty_generics #where_clause {
+            fn schema() -> ::xitca_web::openapi::Value {
+                #schema
+            }
+        }
+    }
+    .into())
+}
+
+pub(crate) fn is_option(ty: &Type) -> bool {
+    let Type::Path(ty) = ty else {
+        return false;
+    };
+
+    ty.path.segments.last().is_some_and(|seg| {
+        seg.ident == "Option"
+            && matches!(seg.arguments, PathArguments::AngleBracketed(ref arg) if matches!(arg.args.first(), Some(GenericArgument::Type(_))))
+    })
+}
@@ -0,0 +1,129 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{meta::ParseNestedMeta, Data, DeriveInput, Error, Expr, Fields, LitStr, Path, Result};
+
+use crate::schema::is_option;
+
+pub(crate) fn validate(input: DeriveInput) -> Result<TokenStream> {
+    let ty_ident = &input.ident;
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::xitca_web::validate::Validate));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let Data::Struct(ref ty) = input.data else {
+        return Err(Error::new(ty_ident.span(), "expect Struct"));
+    };
+
+    let Fields::Named(ref fields) = ty.fields else {
+        return Err(Error::new(ty_ident.span(), "expect Struct with named fields"));
+    };
+
+    let mut checks = Vec::new();
+
+    for field in fields.named.iter() {
+        let ident = field.ident.as_ref().unwrap();
+        let name = ident.to_string();
+
+        let mut rules = Vec::new();
+
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("validate")) {
+            attr.parse_nested_meta(|meta| {
+                let rule = if meta.path.is_ident("length") {
+                    let (min, max) = bounds(&meta)?;
+                    quote! { ::xitca_web::validate::rule::length(value, #min, #max) }
+                } else if meta.path.is_ident("range") {
+                    let (min, max) = bounds(&meta)?;
+                    quote! { ::xitca_web::validate::rule::range(value, #min, #max) }
+                } else if meta.path.is_ident("regex") {
+                    let pattern = meta.value()?.parse::<LitStr>()?;
+                    quote! {{
+                        static RE: ::std::sync::OnceLock<::xitca_web::validate::rule::Regex> = ::std::sync::OnceLock::new();
+                        let re = RE.get_or_init(|| {
+                            ::xitca_web::validate::rule::Regex::new(#pattern).expect("invalid regex pattern")
+                        });
+                        ::xitca_web::validate::rule::regex(value, re)
+                    }}
+                } else if meta.path.is_ident("email") {
+                    quote! { ::xitca_web::validate::rule::email(value) }
+                } else if meta.path.is_ident("custom") {
+                    let func = meta.value()?.parse::<Path>()?;
+                    quote! { #func(value) }
+                } else if meta.path.is_ident("nested") {
+                    checks.push(quote! {
+                        if let ::core::result::Result::Err(e) = ::xitca_web::validate::Validate::validate(&self.#ident) {
+                            errors.nest(#name, e);
+                        }
+                    });
+                    return Ok(());
+                } else {
+                    return Err(meta.error("unknown validate rule"));
+                };
+
+                rules.push(rule);
+                Ok(())
+            })?;
+        }
+
+        if rules.is_empty() {
+            continue;
+        }
+
+        let rules = rules.iter().map(|rule| {
+            quote! {
+                if let ::core::result::Result::Err(e) = #rule {
+                    errors.add(#name, e);
+                }
+            }
+        });
+
+        // rules of optional field are only checked when value is present.
+        let value = if is_option(&field.ty) {
+            quote! { if let ::core::option::Option::Some(value) = self.#ident.as_ref() }
+        } else {
+            quote! { let value = &self.#ident; }
+        };
+
+        checks.push(quote! {
+            {
+                #value
+                {
+                    #(#rules)*
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::xitca_web::validate::Validate for #ty_ident #ty_generics #where_clause {
+            fn validate(&self) -> ::core::result::Result<(), ::xitca_web::validate::ValidationError> {
+                let mut errors = ::xitca_web::validate::ValidationError::new();
+                #(#checks)*
+                errors.into_result()
+            }
+        }
+    }
+    .into())
+}
+
+// parse (min = <expr>, max = <expr>) into Option expressions.
+fn bounds(meta: &ParseNestedMeta) -> Result<(Expr, Expr)> {
+    let mut min = syn::parse_quote!(::core::option::Option::None);
+    let mut max = syn::parse_quote!(::core::option::Option::None);
+
+    meta.parse_nested_meta(|meta| {
+        let expr = meta.value()?.parse::<Expr>()?;
+        if meta.path.is_ident("min") {
+            min = syn::parse_quote!(::core::option::Option::Some(#expr));
+        } else if meta.path.is_ident("max") {
+            max = syn::parse_quote!(::core::option::Option::Some(#expr));
+        } else {
+            return Err(meta.error("expect min or max"));
+        }
+        Ok(())
+    })?;
+
+    Ok((min, max))
+}
@@ -200,8 +200,9 @@ where
 {
     match service.call(req).await {
         Ok(res) => Ok(res),
-        Err(RouterError::Match(_)) => not_found(),
+        Err(RouterError::Match(_) | RouterError::Recommend(_)) => not_found(),
         Err(RouterError::NotAllowed(_)) => Response::error("MethodNotAllowed", 405),
+        Err(RouterError::Rejected(_)) => Response::error("BadRequest", 400),
         Err(RouterError::Service(e)) => {
             console_log!("unhandled error: {e}");
             internal()
@@ -57,6 +57,8 @@ async fn root(StateRef(s): StateRef<'_, String>) -> String {
 #[route(
     "/sync", 
     method = get,
+    // name of route for url generation with WebContext::url_for.
+    name = "sync",
     // sync function handler has it's specialized function middleware type.
     enclosed = SyncMiddleware::new(middleware_fn_sync)
 )]
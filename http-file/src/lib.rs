@@ -12,8 +12,10 @@ mod error;
 pub use self::{chunk::ChunkReader, error::ServeError};
 
 use std::{
+    fs::File,
     io::SeekFrom,
     path::{Component, Path, PathBuf},
+    sync::Arc,
 };
 
 use http::{
@@ -113,6 +115,7 @@ impl<FS: AsyncFs> ServeDir<FS> {
         let mut res = Response::new(());
 
         let mut size = file.len();
+        let mut start = 0;
 
         if let Some(range) = req
             .headers()
@@ -121,7 +124,8 @@ impl<FS: AsyncFs> ServeDir<FS> {
             .and_then(|range| http_range_header::parse_range_header(range).ok())
             .map(|range| range.validate(size))
         {
-            let (start, end) = range
+            let end;
+            (start, end) = range
                 .map_err(|_| ServeError::RangeNotSatisfied(size))?
                 .pop()
                 .expect("http_range_header produced empty range")
@@ -149,6 +153,13 @@ impl<FS: AsyncFs> ServeDir<FS> {
         let stream = if matches!(*req.method(), Method::HEAD) {
             ChunkReader::empty()
         } else {
+            if let Some(file) = file.std_file() {
+                res.extensions_mut().insert(FileRange {
+                    file,
+                    offset: start,
+                    len: size,
+                });
+            }
             ChunkReader::reader(file, size, self.chunk_size)
         };
 
@@ -156,6 +167,17 @@ impl<FS: AsyncFs> ServeDir<FS> {
     }
 }
 
+/// file range served by response body. inserted into response extensions by [ServeDir::serve] when
+/// async file system supports [ChunkRead::std_file].
+///
+/// It can be used by http server for zero copy file transfer of response body.
+#[derive(Clone, Debug)]
+pub struct FileRange {
+    pub file: Arc<File>,
+    pub offset: u64,
+    pub len: u64,
+}
+
 impl<FS: AsyncFs> ServeDir<FS> {
     fn path_check(&self, path: &str) -> Result<PathBuf, ServeError> {
         let path = path.trim_start_matches('/').as_bytes();
@@ -308,6 +330,28 @@ mod test {
         assert_eq!(res, "hello, world!");
     }
 
+    #[tokio::test]
+    async fn file_range() {
+        let dir = ServeDir::new("sample");
+        let req = Request::builder()
+            .uri("/test.txt")
+            .header("range", "bytes=2-12")
+            .body(())
+            .unwrap();
+        let res = dir.serve(&req).await.unwrap();
+        let range = res.extensions().get::<FileRange>().unwrap();
+        assert_eq!(range.offset, 2);
+        assert_eq!(range.len, 11);
+
+        let req = Request::builder()
+            .method(Method::HEAD)
+            .uri("/test.txt")
+            .body(())
+            .unwrap();
+        let res = dir.serve(&req).await.unwrap();
+        assert!(res.extensions().get::<FileRange>().is_none());
+    }
+
     #[tokio::test]
     async fn basic() {
         _basic(ServeDir::new("sample")).await;
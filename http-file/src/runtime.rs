@@ -3,8 +3,10 @@
 use core::future::Future;
 
 use std::{
+    fs,
     io::{self, SeekFrom},
     path::PathBuf,
+    sync::Arc,
     time::SystemTime,
 };
 
@@ -51,6 +53,14 @@ pub trait ChunkRead: Sized {
     /// return Ok(None) when self has reached EOF and can not do more read anymore.
     /// return Err(io::Error) when read error occur.
     fn next(self, buf: BytesMut) -> Self::Future;
+
+    /// std file handle of Self for zero copy file transfer. Position of handle's cursor is not relied upon.
+    ///
+    /// # Default
+    /// None is returned and file can only be read in chunks.
+    fn std_file(&self) -> Option<Arc<fs::File>> {
+        None
+    }
 }
 
 #[cfg(feature = "tokio")]
@@ -79,8 +89,10 @@ mod tokio_impl {
                     let meta = file.metadata()?;
                     let modified_time = meta.modified().ok();
                     let len = meta.len();
+                    let std_file = file.try_clone().ok().map(Arc::new);
                     Ok(TokioFile {
                         file: file.into(),
+                        std_file,
                         modified_time,
                         len,
                     })
@@ -93,6 +105,7 @@ mod tokio_impl {
 
     pub struct TokioFile {
         file: File,
+        std_file: Option<Arc<fs::File>>,
         modified_time: Option<SystemTime>,
         len: u64,
     }
@@ -108,7 +121,10 @@ mod tokio_impl {
     }
 
     impl ChunkRead for TokioFile {
-        type SeekFuture<'f> = impl Future<Output = io::Result<()>> + Send + 'f where Self: 'f;
+        type SeekFuture<'f>
+            = impl Future<Output = io::Result<()>> + Send + 'f
+        where
+            Self: 'f;
 
         type Future = impl Future<Output = io::Result<Option<(Self, BytesMut, usize)>>> + Send;
 
@@ -126,6 +142,10 @@ mod tokio_impl {
                 }
             }
         }
+
+        fn std_file(&self) -> Option<Arc<fs::File>> {
+            self.std_file.clone()
+        }
     }
 }
 
@@ -157,8 +177,11 @@ mod tokio_uring_impl {
                 .await
                 .unwrap()?;
 
+                let std_file = file.try_clone().ok().map(Arc::new);
+
                 Ok(TokioUringFile {
                     file: File::from_std(file),
+                    std_file,
                     pos: 0,
                     modified_time,
                     len,
@@ -169,6 +192,7 @@ mod tokio_uring_impl {
 
     pub struct TokioUringFile {
         file: File,
+        std_file: Option<Arc<fs::File>>,
         pos: u64,
         modified_time: Option<SystemTime>,
         len: u64,
@@ -185,7 +209,10 @@ mod tokio_uring_impl {
     }
 
     impl ChunkRead for TokioUringFile {
-        type SeekFuture<'f> = impl Future<Output = io::Result<()>> + 'f where Self: 'f;
+        type SeekFuture<'f>
+            = impl Future<Output = io::Result<()>> + 'f
+        where
+            Self: 'f;
 
         type Future = impl Future<Output = io::Result<Option<(Self, BytesMut, usize)>>>;
 
@@ -209,5 +236,9 @@ mod tokio_uring_impl {
                 }
             }
         }
+
+        fn std_file(&self) -> Option<Arc<fs::File>> {
+            self.std_file.clone()
+        }
     }
 }
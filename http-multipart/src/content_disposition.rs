@@ -10,9 +10,9 @@ pub struct ContentDisposition {
 }
 
 impl ContentDisposition {
-    const FORM: &'static [u8; 11] = b"form-data; ";
-    const NAME: &'static [u8; 5] = b"name=";
-    const FILE_NAME: &'static [u8; 9] = b"filename=";
+    pub(super) const FORM: &'static [u8; 11] = b"form-data; ";
+    pub(super) const NAME: &'static [u8; 5] = b"name=";
+    pub(super) const FILE_NAME: &'static [u8; 9] = b"filename=";
 
     pub(super) fn try_from_header(headers: &HeaderMap) -> Result<Self, MultipartError> {
         let header = headers
@@ -0,0 +1,521 @@
+use core::{
+    fmt,
+    hash::{BuildHasher, Hasher},
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use std::{collections::hash_map::RandomState, time::SystemTime};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_core::stream::Stream;
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_DISPOSITION, CONTENT_TYPE};
+use pin_project_lite::pin_project;
+
+use super::{content_disposition::ContentDisposition, error::PayloadError, DOUBLE_HYPHEN};
+
+const CR_LF: &[u8; 2] = b"\r\n";
+const HEADER_SEP: &[u8; 2] = b": ";
+
+type BoxStream = Pin<Box<dyn Stream<Item = Result<Bytes, PayloadError>> + Send>>;
+
+/// Builder type for encoding multipart/form-data request body.
+///
+/// The encoded format is the same [Multipart](crate::Multipart) expects when parsing.
+///
+/// # Examples:
+/// ```rust
+/// use http_multipart::{Form, Part};
+///
+/// let form = Form::new()
+///     .text("name", "xitca")
+///     .part(Part::bytes("file", "file content").file_name("foo.txt").content_type("text/plain"));
+///
+/// // form with only in memory parts has a known body size.
+/// assert!(form.content_length().is_some());
+///
+/// // content type header value with boundary of the form.
+/// let _ = form.content_type();
+///
+/// // streaming body of the form.
+/// let _ = form.into_stream();
+/// ```
+pub struct Form {
+    boundary: Box<str>,
+    parts: Vec<Part>,
+}
+
+impl fmt::Debug for Form {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Form")
+            .field("boundary", &self.boundary)
+            .field("parts", &self.parts)
+            .finish()
+    }
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Form {
+    /// Construct a new form with randomly generated boundary.
+    pub fn new() -> Self {
+        Self {
+            boundary: gen_boundary(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Construct a new form with given boundary.
+    ///
+    /// # Panics:
+    /// When boundary is empty, longer than 70 bytes or contains bytes not allowed in header value.
+    pub fn with_boundary(boundary: impl Into<Box<str>>) -> Self {
+        let boundary = boundary.into();
+        assert!(
+            !boundary.is_empty() && boundary.len() <= 70,
+            "boundary length must be in range of 1 to 70 bytes"
+        );
+        assert!(
+            boundary.bytes().all(|b| b.is_ascii_graphic() && b != b';'),
+            "boundary must be visible ascii and not contain ';'"
+        );
+        Self {
+            boundary,
+            parts: Vec::new(),
+        }
+    }
+
+    /// Boundary of the form.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Add a text part with given name and value.
+    pub fn text(self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.part(Part::text(name, value))
+    }
+
+    /// Add a part to the form.
+    pub fn part(mut self, part: Part) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    /// [CONTENT_TYPE] header value of the form. boundary is included.
+    pub fn content_type(&self) -> HeaderValue {
+        let val = format!("multipart/form-data; boundary={}", self.boundary);
+        HeaderValue::try_from(val).expect("boundary is checked to be valid header value")
+    }
+
+    /// Total size of the encoded form in bytes.
+    ///
+    /// Return None when any part of the form is a stream with unknown size.
+    pub fn content_length(&self) -> Option<u64> {
+        let boundary_len = self.boundary.len() as u64;
+
+        self.parts.iter().try_fold(
+            // closing boundary: "--{boundary}--\r\n"
+            boundary_len + 6,
+            |len, part| {
+                let body = part.body.len()?;
+                // "--{boundary}\r\n" + headers + body + "\r\n"
+                Some(len + boundary_len + 4 + part.encode_headers().len() as u64 + body + 2)
+            },
+        )
+    }
+
+    /// Consume form and produce a streaming body.
+    pub fn into_stream(self) -> FormStream {
+        FormStream {
+            boundary: self.boundary,
+            parts: self.parts.into_iter(),
+            first: true,
+            state: State::Next,
+        }
+    }
+}
+
+/// A single part of [Form].
+pub struct Part {
+    name: String,
+    file_name: Option<String>,
+    headers: HeaderMap,
+    body: PartBody,
+}
+
+impl fmt::Debug for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Part")
+            .field("name", &self.name)
+            .field("file_name", &self.file_name)
+            .field("headers", &self.headers)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+enum PartBody {
+    Bytes(Bytes),
+    Stream { stream: BoxStream, len: Option<u64> },
+}
+
+impl fmt::Debug for PartBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Bytes(ref bytes) => f.debug_tuple("PartBody::Bytes").field(&bytes.len()).finish(),
+            Self::Stream { len, .. } => f.debug_struct("PartBody::Stream").field("len", &len).finish(),
+        }
+    }
+}
+
+impl PartBody {
+    fn len(&self) -> Option<u64> {
+        match *self {
+            Self::Bytes(ref bytes) => Some(bytes.len() as u64),
+            Self::Stream { len, .. } => len,
+        }
+    }
+}
+
+impl Part {
+    /// Construct a text part. [CONTENT_TYPE] is not set and server side would treat it as plain text.
+    pub fn text(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::new(name, PartBody::Bytes(Bytes::from(value.into())))
+    }
+
+    /// Construct a part from pre allocated bytes.
+    pub fn bytes<B>(name: impl Into<String>, bytes: B) -> Self
+    where
+        Bytes: From<B>,
+    {
+        Self::new(name, PartBody::Bytes(Bytes::from(bytes)))
+    }
+
+    /// Construct a part from streaming type with unknown size.
+    ///
+    /// [Form] contains any part constructed from this method would not be able to compute it's
+    /// [Form::content_length].
+    pub fn stream<S, T, E>(name: impl Into<String>, stream: S) -> Self
+    where
+        S: Stream<Item = Result<T, E>> + Send + 'static,
+        Bytes: From<T>,
+        E: Into<PayloadError>,
+    {
+        Self::new(
+            name,
+            PartBody::Stream {
+                stream: Box::pin(MapStream { stream }),
+                len: None,
+            },
+        )
+    }
+
+    /// Construct a part from streaming type with known size in bytes.
+    ///
+    /// The stream must yield exactly `len` bytes. Stream yields less or more than given length
+    /// would result in malformed request body.
+    pub fn sized_stream<S, T, E>(name: impl Into<String>, stream: S, len: u64) -> Self
+    where
+        S: Stream<Item = Result<T, E>> + Send + 'static,
+        Bytes: From<T>,
+        E: Into<PayloadError>,
+    {
+        Self::new(
+            name,
+            PartBody::Stream {
+                stream: Box::pin(MapStream { stream }),
+                len: Some(len),
+            },
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    /// Construct a part from async reader type with unknown size.
+    pub fn reader<R>(name: impl Into<String>, reader: R) -> Self
+    where
+        R: tokio::io::AsyncRead + Send + 'static,
+    {
+        Self::stream(name, reader::ReaderStream::new(reader))
+    }
+
+    #[cfg(feature = "tokio")]
+    /// Construct a part from async reader type with known size in bytes.
+    ///
+    /// See [Part::sized_stream] for detail.
+    pub fn sized_reader<R>(name: impl Into<String>, reader: R, len: u64) -> Self
+    where
+        R: tokio::io::AsyncRead + Send + 'static,
+    {
+        Self::sized_stream(name, reader::ReaderStream::new(reader), len)
+    }
+
+    fn new(name: impl Into<String>, body: PartBody) -> Self {
+        Self {
+            name: name.into(),
+            file_name: None,
+            headers: HeaderMap::new(),
+            body,
+        }
+    }
+
+    /// Set file name of part. It would be encoded into [CONTENT_DISPOSITION] header.
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Set [CONTENT_TYPE] header of part.
+    ///
+    /// # Panics:
+    /// When given value is not a valid header value.
+    pub fn content_type(self, content_type: impl AsRef<str>) -> Self {
+        let value = HeaderValue::try_from(content_type.as_ref()).expect("invalid content type header value");
+        self.header(CONTENT_TYPE, value)
+    }
+
+    /// Add custom header to part.
+    ///
+    /// [CONTENT_DISPOSITION] header is generated from part's name and file name and can not be
+    /// overwritten.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        if name != CONTENT_DISPOSITION {
+            self.headers.append(name, value);
+        }
+        self
+    }
+
+    fn encode_headers(&self) -> BytesMut {
+        let mut buf = BytesMut::new();
+
+        buf.put_slice(CONTENT_DISPOSITION.as_str().as_bytes());
+        buf.put_slice(HEADER_SEP);
+        buf.put_slice(ContentDisposition::FORM);
+        buf.put_slice(ContentDisposition::NAME);
+        put_quoted(&mut buf, &self.name);
+
+        if let Some(ref file_name) = self.file_name {
+            buf.put_slice(b"; ");
+            buf.put_slice(ContentDisposition::FILE_NAME);
+            put_quoted(&mut buf, file_name);
+        }
+
+        buf.put_slice(CR_LF);
+
+        for (name, value) in self.headers.iter() {
+            buf.put_slice(name.as_str().as_bytes());
+            buf.put_slice(HEADER_SEP);
+            buf.put_slice(value.as_bytes());
+            buf.put_slice(CR_LF);
+        }
+
+        buf.put_slice(CR_LF);
+
+        buf
+    }
+}
+
+// quote and escape field value. line breaks and quotes are percent encoded as html5 form does.
+fn put_quoted(buf: &mut BytesMut, value: &str) {
+    buf.put_u8(b'"');
+    for b in value.bytes() {
+        match b {
+            b'"' => buf.put_slice(b"%22"),
+            b'\r' => buf.put_slice(b"%0D"),
+            b'\n' => buf.put_slice(b"%0A"),
+            b => buf.put_u8(b),
+        }
+    }
+    buf.put_u8(b'"');
+}
+
+fn gen_boundary() -> Box<str> {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+
+    let hash = |seed: u64| {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(seed);
+        hasher.finish()
+    };
+
+    format!("{:016x}{:016x}", hash(nanos), hash(!nanos)).into_boxed_str()
+}
+
+/// Streaming body type produced by [Form::into_stream].
+pub struct FormStream {
+    boundary: Box<str>,
+    parts: std::vec::IntoIter<Part>,
+    first: bool,
+    state: State,
+}
+
+enum State {
+    Next,
+    Stream(BoxStream),
+    Eof,
+}
+
+impl FormStream {
+    // CRLF preceding boundary is part of the delimiter. it's always written together with the boundary
+    // so parser on the other side can find the end of a part without looking back into previous chunk.
+    fn put_delimiter(&mut self, buf: &mut BytesMut, last: bool) {
+        if !core::mem::replace(&mut self.first, false) {
+            buf.put_slice(CR_LF);
+        }
+        buf.put_slice(DOUBLE_HYPHEN);
+        buf.put_slice(self.boundary.as_bytes());
+        if last {
+            buf.put_slice(DOUBLE_HYPHEN);
+        }
+        buf.put_slice(CR_LF);
+    }
+}
+
+impl Stream for FormStream {
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                State::Next => {
+                    // in memory parts are coalesced into one chunk until a streaming part is met.
+                    let mut buf = BytesMut::new();
+                    loop {
+                        match this.parts.next() {
+                            Some(part) => {
+                                this.put_delimiter(&mut buf, false);
+                                buf.extend_from_slice(&part.encode_headers());
+                                match part.body {
+                                    PartBody::Bytes(bytes) => buf.extend_from_slice(&bytes),
+                                    PartBody::Stream { stream, .. } => {
+                                        this.state = State::Stream(stream);
+                                        break;
+                                    }
+                                }
+                            }
+                            None => {
+                                this.put_delimiter(&mut buf, true);
+                                this.state = State::Eof;
+                                break;
+                            }
+                        }
+                    }
+                    return Poll::Ready(Some(Ok(buf.freeze())));
+                }
+                State::Stream(ref mut stream) => match ready!(stream.as_mut().poll_next(cx)) {
+                    Some(Ok(bytes)) if bytes.is_empty() => {}
+                    Some(Ok(bytes)) => return Poll::Ready(Some(Ok(bytes))),
+                    Some(Err(e)) => {
+                        this.state = State::Eof;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    None => this.state = State::Next,
+                },
+                State::Eof => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl From<Form> for FormStream {
+    fn from(form: Form) -> Self {
+        form.into_stream()
+    }
+}
+
+pin_project! {
+    struct MapStream<S> {
+        #[pin]
+        stream: S
+    }
+}
+
+impl<S, T, E> Stream for MapStream<S>
+where
+    S: Stream<Item = Result<T, E>>,
+    Bytes: From<T>,
+    E: Into<PayloadError>,
+{
+    type Item = Result<Bytes, PayloadError>;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project()
+            .stream
+            .poll_next(cx)
+            .map_ok(Bytes::from)
+            .map_err(Into::into)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod reader {
+    use super::*;
+
+    use tokio::io::{AsyncRead, ReadBuf};
+
+    const CHUNK_SIZE: usize = 4096;
+
+    pin_project! {
+        pub(super) struct ReaderStream<R> {
+            #[pin]
+            reader: Option<R>,
+            buf: BytesMut,
+        }
+    }
+
+    impl<R> ReaderStream<R> {
+        pub(super) fn new(reader: R) -> Self {
+            Self {
+                reader: Some(reader),
+                buf: BytesMut::new(),
+            }
+        }
+    }
+
+    impl<R> Stream for ReaderStream<R>
+    where
+        R: AsyncRead,
+    {
+        type Item = Result<Bytes, std::io::Error>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let mut this = self.project();
+
+            let Some(reader) = this.reader.as_mut().as_pin_mut() else {
+                return Poll::Ready(None);
+            };
+
+            this.buf.resize(CHUNK_SIZE, 0);
+            let mut read_buf = ReadBuf::new(this.buf);
+
+            match ready!(reader.poll_read(cx, &mut read_buf)) {
+                Ok(_) => {
+                    let n = read_buf.filled().len();
+                    if n == 0 {
+                        this.reader.set(None);
+                        return Poll::Ready(None);
+                    }
+                    this.buf.truncate(n);
+                    Poll::Ready(Some(Ok(this.buf.split().freeze())))
+                }
+                Err(e) => {
+                    this.reader.set(None);
+                    Poll::Ready(Some(Err(e)))
+                }
+            }
+        }
+    }
+}
@@ -3,9 +3,14 @@
 mod content_disposition;
 mod error;
 mod field;
+mod form;
 mod header;
 
-pub use self::{error::MultipartError, field::Field};
+pub use self::{
+    error::MultipartError,
+    field::Field,
+    form::{Form, FormStream, Part},
+};
 
 use core::{future::poll_fn, pin::Pin};
 
@@ -435,4 +440,100 @@ mod test {
             MultipartError::BufferOverflow
         ));
     }
+
+    #[test]
+    fn form_round_trip() {
+        let form = Form::new()
+            .text("name", "xitca")
+            .part(
+                Part::bytes("file", "testdata")
+                    .file_name("foo.txt")
+                    .content_type("text/plain"),
+            )
+            .part(Part::stream(
+                "stream",
+                futures_util::stream::iter([Ok::<_, Infallible>("test"), Ok("data2")]),
+            ));
+
+        assert!(form.content_length().is_none());
+
+        let mut req = Request::new(());
+        *req.method_mut() = Method::POST;
+        req.headers_mut().insert(CONTENT_TYPE, form.content_type());
+
+        let body = form.into_stream();
+
+        let multipart = multipart(&req, body).unwrap();
+
+        let mut multipart = pin!(multipart);
+
+        {
+            let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert_eq!(field.name().unwrap(), "name");
+            assert!(field.file_name().is_none());
+            assert_eq!(
+                field.try_next().now_or_never().unwrap().unwrap().unwrap().chunk(),
+                b"xitca"
+            );
+            assert!(field.try_next().now_or_never().unwrap().unwrap().is_none());
+        }
+
+        {
+            let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert_eq!(field.name().unwrap(), "file");
+            assert_eq!(field.file_name().unwrap(), "foo.txt");
+            assert_eq!(
+                field.headers().get(CONTENT_TYPE).unwrap(),
+                HeaderValue::from_static("text/plain")
+            );
+            assert_eq!(
+                field.try_next().now_or_never().unwrap().unwrap().unwrap().chunk(),
+                b"testdata"
+            );
+            assert!(field.try_next().now_or_never().unwrap().unwrap().is_none());
+        }
+
+        {
+            let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert_eq!(field.name().unwrap(), "stream");
+            let mut data = Vec::new();
+            while let Some(chunk) = field.try_next().now_or_never().unwrap().unwrap() {
+                data.extend_from_slice(chunk.chunk());
+            }
+            assert_eq!(data, b"testdata2");
+        }
+
+        assert!(multipart.try_next().now_or_never().unwrap().unwrap().is_none());
+    }
+
+    #[test]
+    fn form_content_length() {
+        let form = Form::with_boundary("12345")
+            .text("name", "xitca")
+            .part(Part::sized_stream(
+                "file",
+                futures_util::stream::once(async { Ok::<_, Infallible>("testdata") }),
+                8,
+            ));
+
+        let len = form.content_length().unwrap();
+
+        let mut body = Vec::new();
+        let mut stream = pin!(form.into_stream());
+        while let Some(chunk) = futures_util::StreamExt::next(&mut stream).now_or_never().unwrap() {
+            body.extend_from_slice(&chunk.unwrap());
+        }
+
+        assert_eq!(len, body.len() as u64);
+        assert_eq!(
+            body,
+            b"--12345\r\n\
+            content-disposition: form-data; name=\"name\"\r\n\r\n\
+            xitca\r\n\
+            --12345\r\n\
+            content-disposition: form-data; name=\"file\"\r\n\r\n\
+            testdata\r\n\
+            --12345--\r\n"
+        );
+    }
 }
@@ -14,7 +14,7 @@ use core::{
     task::{Context, Poll},
 };
 
-use std::{borrow::Cow, error};
+use std::{borrow::Cow, error, fs::File, sync::Arc};
 
 use futures_core::stream::{LocalBoxStream, Stream};
 use pin_project_lite::pin_project;
@@ -480,6 +480,59 @@ impl<B> From<Cow<'static, str>> for ResponseBody<B> {
     }
 }
 
+/// A file range that can be attached to response's [Extensions](crate::http::Extensions) as hint for
+/// zero copy transfer of response body.
+///
+/// When the connection supports it (plain Tcp/Unix stream on linux with `sendfile` crate feature) http/1
+/// dispatcher writes the file range to connection directly with `sendfile(2)` and the response body is dropped
+/// without being polled. Other
+/// wise the hint is ignored and response body is streamed as usual. Therefore response body MUST produce
+/// the exact same bytes as the file range.
+///
+/// The hint is only used when response body has an exact size equal to the range length and response does
+/// not have `content-encoding` header.
+#[derive(Clone, Debug)]
+pub struct SendFile {
+    file: Arc<File>,
+    offset: u64,
+    len: u64,
+}
+
+impl SendFile {
+    /// Construct a hint for sending `len` bytes of given file starting from `offset`.
+    pub fn new(file: impl Into<Arc<File>>, offset: u64, len: u64) -> Self {
+        Self {
+            file: file.into(),
+            offset,
+            len,
+        }
+    }
+
+    /// File to send.
+    #[inline]
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Start position of range in file.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Length of range.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Return true when range is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
 /// Body size hint.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum BodySize {
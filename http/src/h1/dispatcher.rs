@@ -6,16 +6,19 @@ use core::{
     time::Duration,
 };
 
-use std::{io, net::SocketAddr};
+use std::{io, net::SocketAddr, sync::Arc};
 
 use futures_core::stream::Stream;
 use tracing::trace;
-use xitca_io::io::{AsyncIo, Interest, Ready};
+use xitca_io::{
+    io::{AsyncIo, Interest, Ready},
+    shutdown::GracefulShutdown,
+};
 use xitca_service::Service;
 use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 
 use crate::{
-    body::NoneBody,
+    body::{NoneBody, SendFile},
     bytes::{Bytes, EitherBuf},
     config::HttpServiceConfig,
     date::DateTime,
@@ -24,8 +27,9 @@ use crate::{
         error::Error,
     },
     http::{
+        header::CONTENT_ENCODING,
         response::{Parts, Response},
-        StatusCode,
+        PeerCertificate, StatusCode,
     },
     util::{
         buffered::{BufferedIo, ListWriteBuf, ReadBuf, WriteBuf},
@@ -58,6 +62,7 @@ pub(crate) async fn run<
 >(
     io: &'a mut St,
     addr: SocketAddr,
+    peer: Option<Arc<PeerCertificate>>,
     timer: Pin<&'a mut KeepAlive>,
     config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
     service: &'a S,
@@ -76,9 +81,9 @@ where
         EitherBuf::Right(WriteBuf::<WRITE_BUF_LIMIT>::default())
     };
 
-    Dispatcher::new(io, addr, timer, config, service, date, write_buf)
-        .run()
-        .await
+    let mut dispatcher = Dispatcher::new(io, addr, timer, config, service, date, write_buf);
+    dispatcher.ctx.set_peer_certificate(peer);
+    dispatcher.run().await
 }
 
 /// Http/1 dispatcher
@@ -87,6 +92,7 @@ struct Dispatcher<'a, St, S, ReqB, W, D, const HEADER_LIMIT: usize, const READ_B
     timer: Timer<'a>,
     ctx: Context<'a, D, HEADER_LIMIT>,
     service: &'a S,
+    shutdown: GracefulShutdown,
     _phantom: PhantomData<ReqB>,
 }
 
@@ -179,6 +185,7 @@ where
             timer: Timer::new(timer, config.keep_alive_timeout, config.request_head_timeout),
             ctx: Context::with_addr(addr, date),
             service,
+            shutdown: GracefulShutdown::current(),
             _phantom: PhantomData,
         }
     }
@@ -210,11 +217,26 @@ where
 
     async fn _run(&mut self) -> Result<(), Error<S::Error, BE>> {
         self.timer.update(self.ctx.date().now());
-        self.io
-            .read()
-            .timeout(self.timer.get())
-            .await
-            .map_err(|_| self.timer.map_to_err())??;
+
+        // idle connection is closed immediately when server is shutting down gracefully.
+        let shutdown = &self.shutdown;
+        let idle = self.io.read_buf.is_empty();
+        let shutdown = async move {
+            if idle {
+                shutdown.triggered().await
+            } else {
+                pending().await
+            }
+        };
+
+        match self.io.read().timeout(self.timer.get()).select(shutdown).await {
+            SelectOutput::A(res) => res.map_err(|_| self.timer.map_to_err())??,
+            SelectOutput::B(_) => {
+                trace!(target: "h1_dispatcher", "Server is shutting down. Closing idle connection");
+                self.ctx.set_close();
+                return Ok(());
+            }
+        }
 
         while let Some((req, decoder)) = self.ctx.decode_head::<READ_BUF_LIMIT>(&mut self.io.read_buf)? {
             self.timer.reset_state();
@@ -222,7 +244,7 @@ where
             let (mut body_reader, body) = BodyReader::from_coding(decoder);
             let req = req.map(|ext| ext.map_body(|_| ReqB::from(body)));
 
-            let (parts, body) = match self
+            let (mut parts, body) = match self
                 .service
                 .call(req)
                 .select(self.request_body_handler(&mut body_reader))
@@ -234,36 +256,51 @@ where
                 SelectOutput::B(Ok(i)) => match i {},
             };
 
+            // in-flight request is finished with connection close when server is shutting down gracefully.
+            if self.shutdown.is_triggered() {
+                self.ctx.set_close();
+            }
+
+            let sendfile = take_sendfile(&mut parts, &body);
+
             let encoder = &mut self.encode_head(parts, &body)?;
+
+            let is_sent = match sendfile {
+                Some(ref file) if matches!(*encoder, TransferCoding::Length(_)) => self.try_sendfile(file).await?,
+                _ => false,
+            };
+
             let mut body = pin!(body);
 
-            loop {
-                match self
-                    .try_poll_body(body.as_mut())
-                    .select(self.io_ready(&mut body_reader))
-                    .await
-                {
-                    SelectOutput::A(Some(Ok(bytes))) => encoder.encode(bytes, &mut self.io.write_buf),
-                    SelectOutput::B(Ok(ready)) => {
-                        if ready.is_readable() {
-                            if let Err(e) = self.io.try_read() {
-                                body_reader.feed_error(e);
+            if !is_sent {
+                loop {
+                    match self
+                        .try_poll_body(body.as_mut())
+                        .select(self.io_ready(&mut body_reader))
+                        .await
+                    {
+                        SelectOutput::A(Some(Ok(bytes))) => encoder.encode(bytes, &mut self.io.write_buf),
+                        SelectOutput::B(Ok(ready)) => {
+                            if ready.is_readable() {
+                                if let Err(e) = self.io.try_read() {
+                                    body_reader.feed_error(e);
+                                }
+                            }
+                            if ready.is_writable() {
+                                self.io.try_write()?;
                             }
                         }
-                        if ready.is_writable() {
-                            self.io.try_write()?;
+                        SelectOutput::A(None) => {
+                            encoder.encode_eof(&mut self.io.write_buf);
+                            break;
                         }
+                        SelectOutput::B(Err(e)) => return Err(e.into()),
+                        SelectOutput::A(Some(Err(e))) => return Err(Error::Body(e)),
                     }
-                    SelectOutput::A(None) => {
-                        encoder.encode_eof(&mut self.io.write_buf);
-                        break;
-                    }
-                    SelectOutput::B(Err(e)) => return Err(e.into()),
-                    SelectOutput::A(Some(Err(e))) => return Err(Error::Body(e)),
                 }
             }
 
-            if !body_reader.decoder.is_eof() {
+            if !body_reader.decoder.is_eof() || self.shutdown.is_triggered() {
                 self.ctx.set_close();
                 break;
             }
@@ -276,6 +313,38 @@ where
         self.ctx.encode_head(parts, body, &mut self.io.write_buf)
     }
 
+    // write response body with zero copy file transfer. return Ok(false) when io type does not support it and
+    // response body must be streamed instead.
+    async fn try_sendfile(&mut self, file: &SendFile) -> io::Result<bool> {
+        let mut offset = file.offset();
+
+        // zero length transfer to probe if io type supports it.
+        match self.io.io.try_sendfile(file.file(), &mut offset, 0) {
+            Err(ref e) if e.kind() == io::ErrorKind::Unsupported => return Ok(false),
+            _ => {}
+        }
+
+        // response head must be written before file.
+        self.io.drain_write().await?;
+
+        let end = file.offset() + file.len();
+
+        while offset < end {
+            let count = usize::try_from(end - offset).unwrap_or(usize::MAX);
+            match self.io.io.try_sendfile(file.file(), &mut offset, count) {
+                // file is truncated and can not fulfill content-length anymore.
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(_) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    self.io.io.ready(Interest::WRITABLE).await?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(true)
+    }
+
     // an associated future of self.service that runs until service is resolved or error produced.
     async fn request_body_handler(&mut self, body_reader: &mut BodyReader) -> Result<Infallible, Error<S::Error, BE>> {
         if self.ctx.is_expect_header() {
@@ -380,6 +449,16 @@ impl BodyReader {
     }
 }
 
+// take SendFile hint from response. the hint is dropped when response body is transformed into something else
+// than the file range it points to.
+pub(super) fn take_sendfile(parts: &mut Parts, body: &impl Stream) -> Option<SendFile> {
+    let file = parts.extensions.remove::<SendFile>()?;
+    match body.size_hint() {
+        (_, Some(len)) if len as u64 == file.len() && !parts.headers.contains_key(CONTENT_ENCODING) => Some(file),
+        _ => None,
+    }
+}
+
 #[cold]
 #[inline(never)]
 pub(super) fn status_only(status: StatusCode) -> Response<NoneBody<Bytes>> {
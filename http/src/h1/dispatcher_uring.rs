@@ -13,6 +13,7 @@ use std::{
     io,
     net::{Shutdown, SocketAddr},
     rc::Rc,
+    sync::Arc,
 };
 
 use futures_core::stream::Stream;
@@ -20,23 +21,24 @@ use pin_project_lite::pin_project;
 use tracing::trace;
 use xitca_io::{
     bytes::BytesMut,
-    io_uring::{write_all, AsyncBufRead, AsyncBufWrite, IoBuf},
+    io_uring::{write_all, write_all_zc, AsyncBufRead, AsyncBufWrite, IoBuf},
+    shutdown::GracefulShutdown,
 };
 use xitca_service::Service;
-use xitca_unsafe_collection::futures::SelectOutput;
+use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 
 use crate::{
-    body::NoneBody,
+    body::{NoneBody, SendFile},
     bytes::Bytes,
     config::HttpServiceConfig,
     date::DateTime,
     h1::{body::RequestBody, error::Error},
-    http::{response::Response, StatusCode},
+    http::{response::Response, PeerCertificate, StatusCode},
     util::timer::{KeepAlive, Timeout},
 };
 
 use super::{
-    dispatcher::{status_only, Timer},
+    dispatcher::{status_only, take_sendfile, Timer},
     proto::{
         codec::{ChunkResult, TransferCoding},
         context::Context,
@@ -56,9 +58,13 @@ pub(super) struct Dispatcher<'a, Io, S, ReqB, D, const H_LIMIT: usize, const R_L
     read_buf: BufOwned,
     write_buf: BufOwned,
     notify: Notify<BufOwned>,
+    shutdown: GracefulShutdown,
     _phantom: PhantomData<ReqB>,
 }
 
+// buffered bytes larger than this are written without copying them into kernel.
+const WRITE_ZC_THRESHOLD: usize = 64 * 1024;
+
 #[derive(Default)]
 struct BufOwned {
     buf: Option<BytesMut>,
@@ -99,13 +105,42 @@ impl BufOwned {
         res
     }
 
+    // read into buffer selected by kernel when data arrives so idle connection does not hold a read buffer of
+    // it's own while waiting. fall back to plain read when io type or kernel does not support it.
+    async fn read_idle(&mut self, io: &impl AsyncBufRead) -> io::Result<usize> {
+        match io.read_provided().await {
+            Ok(buf) => {
+                self.extend_from_slice(&buf);
+                Ok(buf.len())
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::Unsupported => self.read_io(io).await,
+            Err(e) => Err(e),
+        }
+    }
+
     async fn write_io(&mut self, io: &impl AsyncBufWrite) -> io::Result<()> {
         let buf = self.buf.take().unwrap();
-        let (res, mut buf) = write_all(io, buf).await;
+        let (res, mut buf) = if buf.len() >= WRITE_ZC_THRESHOLD {
+            write_all_zc(io, buf).await
+        } else {
+            write_all(io, buf).await
+        };
         buf.clear();
         self.buf = Some(buf);
         res
     }
+
+    // write buffered response head and then response body with zero copy file transfer. return Ok(false) when
+    // io type does not support it and response body must be streamed instead.
+    async fn write_file(&mut self, io: &impl AsyncBufWrite, file: &SendFile) -> io::Result<bool> {
+        self.write_io(io).await?;
+
+        match io.sendfile(file.file(), file.offset(), file.len()).await {
+            Ok(_) => Ok(true),
+            Err(ref e) if e.kind() == io::ErrorKind::Unsupported => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 impl<'a, Io, S, ReqB, ResB, BE, D, const H_LIMIT: usize, const R_LIMIT: usize, const W_LIMIT: usize>
@@ -120,19 +155,24 @@ where
     pub(super) fn new(
         io: Io,
         addr: SocketAddr,
+        peer: Option<Arc<PeerCertificate>>,
         timer: Pin<&'a mut KeepAlive>,
         config: HttpServiceConfig<H_LIMIT, R_LIMIT, W_LIMIT>,
         service: &'a S,
         date: &'a D,
     ) -> Self {
+        let mut ctx = Context::<_, H_LIMIT>::with_addr(addr, date);
+        ctx.set_peer_certificate(peer);
+
         Self {
             io: Rc::new(io),
             timer: Timer::new(timer, config.keep_alive_timeout, config.request_head_timeout),
-            ctx: Context::<_, H_LIMIT>::with_addr(addr, date),
+            ctx,
             service,
             read_buf: BufOwned::new(),
             write_buf: BufOwned::new(),
             notify: Notify::new(),
+            shutdown: GracefulShutdown::current(),
             _phantom: PhantomData,
         }
     }
@@ -164,12 +204,34 @@ where
     async fn _run(&mut self) -> Result<(), Error<S::Error, BE>> {
         self.timer.update(self.ctx.date().now());
 
-        let read = self
-            .read_buf
-            .read_io(&*self.io)
-            .timeout(self.timer.get())
-            .await
-            .map_err(|_| self.timer.map_to_err())??;
+        // idle connection is closed immediately when server is shutting down gracefully.
+        let shutdown = &self.shutdown;
+        let idle = self.read_buf.is_empty();
+        let shutdown = async move {
+            if idle {
+                shutdown.triggered().await
+            } else {
+                core::future::pending().await
+            }
+        };
+
+        let (read_buf, io) = (&mut self.read_buf, &*self.io);
+        let read = async move {
+            if idle {
+                read_buf.read_idle(io).await
+            } else {
+                read_buf.read_io(io).await
+            }
+        };
+
+        let read = match read.timeout(self.timer.get()).select(shutdown).await {
+            SelectOutput::A(res) => res.map_err(|_| self.timer.map_to_err())??,
+            SelectOutput::B(_) => {
+                trace!(target: "h1_dispatcher", "Server is shutting down. Closing idle connection");
+                self.ctx.set_close();
+                return Ok(());
+            }
+        };
 
         if read == 0 {
             self.ctx.set_close();
@@ -196,14 +258,30 @@ where
 
             let req = req.map(|ext| ext.map_body(|_| ReqB::from(body)));
 
-            let (parts, body) = self.service.call(req).await.map_err(Error::Service)?.into_parts();
+            let (mut parts, body) = self.service.call(req).await.map_err(Error::Service)?.into_parts();
+
+            // in-flight request is finished with connection close when server is shutting down gracefully.
+            if self.shutdown.is_triggered() {
+                self.ctx.set_close();
+            }
+
+            let sendfile = take_sendfile(&mut parts, &body);
 
             let mut encoder = self.ctx.encode_head(parts, &body, &mut *self.write_buf)?;
 
+            let is_sent = match sendfile {
+                Some(ref file) if matches!(encoder, TransferCoding::Length(_)) => {
+                    self.write_buf.write_file(&*self.io, file).await?
+                }
+                _ => false,
+            };
+
             // this block is necessary. ResB has to be dropped asap as it may hold ownership of
             // Body type which if not dropped before Notifier::notify is called would prevent
             // Notifier from waking up Notify.
-            {
+            if is_sent {
+                drop(body);
+            } else {
                 let mut body = pin!(body);
 
                 loop {
@@ -241,6 +319,10 @@ where
                     }
                 }
             }
+
+            if self.shutdown.is_triggered() {
+                break;
+            }
         }
 
         Ok(())
@@ -1,12 +1,13 @@
 use core::mem;
 
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
-use crate::http::{header::HeaderMap, Extensions};
+use crate::http::{header::HeaderMap, Extensions, PeerCertificate};
 
 /// Context is connection specific struct contain states for processing.
 pub struct Context<'a, D, const HEADER_LIMIT: usize> {
     addr: SocketAddr,
+    peer: Option<Arc<PeerCertificate>>,
     state: ContextState,
     // header map reused by next request.
     header: Option<HeaderMap>,
@@ -62,6 +63,7 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
     pub fn with_addr(addr: SocketAddr, date: &'a D) -> Self {
         Self {
             addr,
+            peer: None,
             state: ContextState::new(),
             header: None,
             exts: Extensions::new(),
@@ -69,6 +71,12 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
         }
     }
 
+    /// Set verified certificate chain of tls peer context associated with.
+    #[inline]
+    pub fn set_peer_certificate(&mut self, peer: Option<Arc<PeerCertificate>>) {
+        self.peer = peer;
+    }
+
     /// Get Date type from Context.
     #[inline]
     pub fn date(&self) -> &D {
@@ -168,4 +176,10 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
     pub fn socket_addr(&self) -> &SocketAddr {
         &self.addr
     }
+
+    /// Get verified certificate chain of tls peer context associated with.
+    #[inline]
+    pub fn peer_certificate(&self) -> Option<&Arc<PeerCertificate>> {
+        self.peer.as_ref()
+    }
 }
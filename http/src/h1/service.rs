@@ -9,7 +9,7 @@ use xitca_service::Service;
 use crate::{
     bytes::Bytes,
     error::{HttpServiceError, TimeoutError},
-    http::{Request, RequestExt, Response},
+    http::{PeerCertificate, Request, RequestExt, Response},
     service::HttpService,
     util::timer::Timeout,
 };
@@ -43,7 +43,9 @@ where
             .await
             .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
 
-        super::dispatcher::run(&mut io, addr, timer, self.config, &self.service, self.date.get())
+        let peer = PeerCertificate::from_io_chain(io.peer_certificate_chain());
+
+        super::dispatcher::run(&mut io, addr, peer, timer, self.config, &self.service, self.date.get())
             .await
             .map_err(Into::into)
     }
@@ -115,7 +117,9 @@ where
             .await
             .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
 
-        super::dispatcher_uring::Dispatcher::new(io, addr, timer, self.config, &self.service, self.date.get())
+        let peer = PeerCertificate::from_io_chain(io.peer_certificate_chain());
+
+        super::dispatcher_uring::Dispatcher::new(io, addr, peer, timer, self.config, &self.service, self.date.get())
             .run()
             .await
             .map_err(Into::into)
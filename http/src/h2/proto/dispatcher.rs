@@ -7,7 +7,7 @@ use core::{
     time::Duration,
 };
 
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 use ::h2::{
     server::{Connection, SendResponse},
@@ -15,7 +15,10 @@ use ::h2::{
 };
 use futures_core::stream::Stream;
 use tracing::trace;
-use xitca_io::io::{AsyncRead, AsyncWrite};
+use xitca_io::{
+    io::{AsyncRead, AsyncWrite},
+    shutdown::GracefulShutdown,
+};
 use xitca_service::Service;
 use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 
@@ -27,7 +30,7 @@ use crate::{
     h2::{body::RequestBody, error::Error},
     http::{
         header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRAILER},
-        Extension, Request, RequestExt, Response, Version,
+        Extension, PeerCertificate, Request, RequestExt, Response, Version,
     },
     util::{futures::Queue, timer::KeepAlive},
 };
@@ -36,6 +39,7 @@ use crate::{
 pub(crate) struct Dispatcher<'a, TlsSt, S, ReqB> {
     io: &'a mut Connection<TlsSt, Bytes>,
     addr: SocketAddr,
+    peer: Option<Arc<PeerCertificate>>,
     keep_alive: Pin<&'a mut KeepAlive>,
     ka_dur: Duration,
     service: &'a S,
@@ -57,6 +61,7 @@ where
     pub(crate) fn new(
         io: &'a mut Connection<TlsSt, Bytes>,
         addr: SocketAddr,
+        peer: Option<Arc<PeerCertificate>>,
         keep_alive: Pin<&'a mut KeepAlive>,
         ka_dur: Duration,
         service: &'a S,
@@ -65,6 +70,7 @@ where
         Self {
             io,
             addr,
+            peer,
             keep_alive,
             ka_dur,
             service,
@@ -77,6 +83,7 @@ where
         let Self {
             io,
             addr,
+            peer,
             mut keep_alive,
             ka_dur,
             service,
@@ -101,14 +108,21 @@ where
 
         let mut queue = Queue::new();
 
+        // graceful shutdown signal is taken when it's triggered so GOAWAY is only sent once.
+        let mut shutdown = Some(GracefulShutdown::current());
+
         loop {
-            match io.accept().select(try_poll_queue(&mut queue, &mut ping_pong)).await {
+            match io
+                .accept()
+                .select(try_poll_queue(&mut queue, &mut ping_pong, &mut shutdown))
+                .await
+            {
                 SelectOutput::A(Some(Ok((req, tx)))) => {
                     // Convert http::Request body type to crate::h2::Body
                     // and reconstruct as HttpRequest.
                     let req = req.map(|body| {
                         let body = ReqB::from(RequestBody::from(body));
-                        RequestExt::from_parts(body, Extension::new(addr))
+                        RequestExt::from_parts(body, Extension::new(addr).with_peer_certificate(peer.clone()))
                     });
 
                     queue.push(async move {
@@ -135,9 +149,12 @@ where
     }
 }
 
+// poll queued requests, ping pong and graceful shutdown signal. SelectOutput::A is returned when connection
+// should go into graceful shutdown.
 async fn try_poll_queue<F, E, S, B>(
     queue: &mut Queue<F>,
     ping_ping: &mut H2PingPong<'_>,
+    shutdown: &mut Option<GracefulShutdown>,
 ) -> SelectOutput<(), Result<(), ::h2::Error>>
 where
     F: Future<Output = Result<ConnectionState, E>>,
@@ -145,15 +162,30 @@ where
     S: fmt::Debug,
     B: fmt::Debug,
 {
-    loop {
-        if queue.is_empty() {
-            return SelectOutput::B(ping_ping.await);
+    let poll_queue = async {
+        loop {
+            if queue.is_empty() {
+                return SelectOutput::B(ping_ping.await);
+            }
+
+            match queue.next2().await {
+                Ok(ConnectionState::KeepAlive) => {}
+                Ok(ConnectionState::Close) => return SelectOutput::A(()),
+                Err(e) => HttpServiceError::from(e).log("h2_dispatcher"),
+            }
         }
+    };
+
+    let Some(signal) = shutdown.as_ref() else {
+        return poll_queue.await;
+    };
 
-        match queue.next2().await {
-            Ok(ConnectionState::KeepAlive) => {}
-            Ok(ConnectionState::Close) => return SelectOutput::A(()),
-            Err(e) => HttpServiceError::from(e).log("h2_dispatcher"),
+    match poll_queue.select(signal.triggered()).await {
+        SelectOutput::A(res) => res,
+        SelectOutput::B(_) => {
+            trace!("Server is shutting down. Sending GOAWAY");
+            *shutdown = None;
+            SelectOutput::A(())
         }
     }
 }
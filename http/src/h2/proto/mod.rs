@@ -32,7 +32,7 @@ mod io_uring {
         task::{Context, Poll, Waker},
     };
 
-    use std::{collections::HashMap, io};
+    use std::{collections::HashMap, io, sync::Arc};
 
     use futures_core::stream::Stream;
     use pin_project_lite::pin_project;
@@ -50,7 +50,7 @@ mod io_uring {
         body::BodySize,
         bytes::Bytes,
         error::BodyError,
-        http::{header::CONTENT_LENGTH, HeaderMap, Request, RequestExt, Response, Version},
+        http::{header::CONTENT_LENGTH, Extension, HeaderMap, PeerCertificate, Request, RequestExt, Response, Version},
         util::futures::Queue,
     };
 
@@ -80,6 +80,7 @@ mod io_uring {
         flow: &'a SharedFlowControl,
         stream_map: HashMap<StreamId, RequestBodySender>,
         writer_tx: &'a UnboundedSender<Message>,
+        peer: Option<Arc<PeerCertificate>>,
     }
 
     enum Message {
@@ -106,7 +107,11 @@ mod io_uring {
     type SharedFlowControl = RefCell<FlowControl>;
 
     impl<'a> DecodeContext<'a> {
-        fn new(flow: &'a SharedFlowControl, writer_tx: &'a UnboundedSender<Message>) -> Self {
+        fn new(
+            flow: &'a SharedFlowControl,
+            writer_tx: &'a UnboundedSender<Message>,
+            peer: Option<Arc<PeerCertificate>>,
+        ) -> Self {
             Self {
                 remote_setting: Settings::default(),
                 max_header_list_size: settings::DEFAULT_SETTINGS_MAX_HEADER_LIST_SIZE,
@@ -116,6 +121,7 @@ mod io_uring {
                 flow,
                 stream_map: HashMap::new(),
                 writer_tx,
+                peer,
             }
         }
 
@@ -276,7 +282,8 @@ mod io_uring {
                     return;
                 }
                 None => {
-                    let mut req = Request::new(RequestExt::<()>::default());
+                    let ext = Extension::new(crate::unspecified_socket_addr()).with_peer_certificate(self.peer.clone());
+                    let mut req = Request::new(RequestExt::from_parts((), ext));
                     *req.version_mut() = Version::HTTP_2;
                     *req.headers_mut() = headers;
                     *req.method_mut() = pseudo.method.unwrap();
@@ -355,6 +362,8 @@ mod io_uring {
         ResB: Stream<Item = Result<Bytes, ResBE>>,
         ResBE: fmt::Debug,
     {
+        let peer = PeerCertificate::from_io_chain(io.peer_certificate_chain());
+
         let mut read_buf = BytesMut::new();
         let mut write_buf = BytesMut::new();
 
@@ -379,7 +388,7 @@ mod io_uring {
             map: HashMap::new(),
         });
 
-        let mut ctx = DecodeContext::new(&flow, &tx);
+        let mut ctx = DecodeContext::new(&flow, &tx, peer);
         let mut queue = Queue::new();
 
         let mut write_task = pin!(async {
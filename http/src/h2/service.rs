@@ -9,7 +9,7 @@ use xitca_service::Service;
 use crate::{
     bytes::Bytes,
     error::{HttpServiceError, TimeoutError},
-    http::{Request, RequestExt, Response},
+    http::{PeerCertificate, Request, RequestExt, Response},
     service::HttpService,
     util::timer::Timeout,
 };
@@ -58,6 +58,8 @@ where
             .await
             .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
 
+        let peer = PeerCertificate::from_io_chain(tls_stream.peer_certificate_chain());
+
         // update timer to first request timeout.
         self.update_first_request_deadline(timer.as_mut());
 
@@ -71,6 +73,7 @@ where
         let dispatcher = Dispatcher::new(
             &mut conn,
             addr,
+            peer,
             timer,
             self.config.keep_alive_timeout,
             &self.service,
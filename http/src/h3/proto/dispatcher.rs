@@ -1,6 +1,6 @@
 use core::{
     fmt,
-    future::{poll_fn, Future},
+    future::{pending, poll_fn, Future},
     marker::PhantomData,
     pin::{pin, Pin},
     task::{ready, Context, Poll},
@@ -14,7 +14,8 @@ use ::h3::{
 };
 use futures_core::stream::Stream;
 use pin_project_lite::pin_project;
-use xitca_io::net::UdpStream;
+use tracing::trace;
+use xitca_io::{net::UdpStream, shutdown::GracefulShutdown};
 use xitca_service::Service;
 use xitca_unsafe_collection::futures::{Select, SelectOutput};
 
@@ -22,7 +23,7 @@ use crate::{
     bytes::{Buf, Bytes},
     error::HttpServiceError,
     h3::{body::RequestBody, error::Error},
-    http::{Extension, Request, RequestExt, Response},
+    http::{Extension, PeerCertificate, Request, RequestExt, Response},
     util::futures::Queue,
 };
 
@@ -57,16 +58,28 @@ where
         // wait for connecting.
         let conn = self.io.connecting().await?;
 
+        let peer = PeerCertificate::from_io_chain(xitca_io::net::peer_certificate_chain(&conn));
+
         // construct h3 connection from quinn connection.
         let conn = h3_quinn::Connection::new(conn);
         let mut conn = server::Connection::new(conn).await?;
 
         let mut queue = Queue::new();
 
+        // graceful shutdown signal is taken when it's triggered so GOAWAY is only sent once.
+        let mut shutdown = Some(GracefulShutdown::current());
+
         // accept loop
         loop {
-            match conn.accept().select(queue.next()).await {
-                SelectOutput::A(Ok(Some((req, stream)))) => {
+            let graceful = async {
+                match shutdown {
+                    Some(ref signal) => signal.triggered().await,
+                    None => pending().await,
+                }
+            };
+
+            match conn.accept().select(queue.next()).select(graceful).await {
+                SelectOutput::A(SelectOutput::A(Ok(Some((req, stream))))) => {
                     let (tx, rx) = stream.split();
 
                     let body = Box::pin(AsyncStream::new(rx, |mut stream| async move {
@@ -79,7 +92,7 @@ where
                     // Reconstruct Request to attach crate body type.
                     let req = req.map(|_| {
                         let body = ReqB::from(RequestBody(body));
-                        RequestExt::from_parts(body, Extension::new(self.addr))
+                        RequestExt::from_parts(body, Extension::new(self.addr).with_peer_certificate(peer.clone()))
                     });
 
                     queue.push(async move {
@@ -87,13 +100,19 @@ where
                         h3_handler(fut, tx).await
                     });
                 }
-                SelectOutput::A(Ok(None)) => break,
-                SelectOutput::A(Err(e)) => return Err(e.into()),
-                SelectOutput::B(res) => {
+                SelectOutput::A(SelectOutput::A(Ok(None))) => break,
+                SelectOutput::A(SelectOutput::A(Err(e))) => return Err(e.into()),
+                SelectOutput::A(SelectOutput::B(res)) => {
                     if let Err(e) = res {
                         HttpServiceError::from(e).log("h3_dispatcher");
                     }
                 }
+                SelectOutput::B(_) => {
+                    trace!("Server is shutting down. Sending GOAWAY");
+                    shutdown = None;
+                    // allow request already on flight to be accepted.
+                    conn.shutdown(1).await?;
+                }
             }
         }
 
@@ -2,6 +2,8 @@
 
 pub use ::http::*;
 
+pub use crate::tls::PeerCertificate;
+
 use core::{
     borrow::{Borrow, BorrowMut},
     mem,
@@ -9,7 +11,7 @@ use core::{
     task::{Context, Poll},
 };
 
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 use futures_core::stream::Stream;
 use pin_project_lite::pin_project;
@@ -150,15 +152,22 @@ impl Extension {
     pub(crate) fn new(addr: SocketAddr) -> Self {
         Self(Box::new(_Extension {
             addr,
+            peer: None,
             #[cfg(feature = "router")]
             params: Default::default(),
         }))
     }
+
+    pub(crate) fn with_peer_certificate(mut self, peer: Option<Arc<PeerCertificate>>) -> Self {
+        self.0.peer = peer;
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
 struct _Extension {
     addr: SocketAddr,
+    peer: Option<Arc<PeerCertificate>>,
     #[cfg(feature = "router")]
     params: Params,
 }
@@ -183,6 +192,16 @@ impl<B> RequestExt<B> {
         &mut self.ext.0.addr
     }
 
+    /// retrieve tls peer's certificate chain verified during tls handshake.
+    ///
+    /// # Default
+    /// None is returned for plain text connections and tls connections where client certificate verification is
+    /// not enabled or client didn't provide one.
+    #[inline]
+    pub fn peer_certificate(&self) -> Option<&PeerCertificate> {
+        self.ext.0.peer.as_deref()
+    }
+
     /// map body type of self to another type with given function closure.
     #[inline]
     pub fn map_body<F, B1>(self, func: F) -> RequestExt<B1>
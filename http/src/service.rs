@@ -14,6 +14,7 @@ use super::{
     date::{DateTime, DateTimeService},
     error::{HttpServiceError, TimeoutError},
     http::{Request, RequestExt, Response},
+    tls::PeerCertificate,
     util::timer::{KeepAlive, Timeout},
     version::AsVersion,
 };
@@ -111,11 +112,14 @@ where
                     _tls_stream.as_version()
                 };
 
+                let _peer = PeerCertificate::from_io_chain(_tls_stream.peer_certificate_chain());
+
                 match version {
                     #[cfg(feature = "http1")]
                     super::http::Version::HTTP_11 | super::http::Version::HTTP_10 => super::h1::dispatcher::run(
                         &mut _tls_stream,
                         _addr,
+                        _peer,
                         timer.as_mut(),
                         self.config,
                         &self.service,
@@ -138,6 +142,7 @@ where
                         super::h2::Dispatcher::new(
                             &mut conn,
                             _addr,
+                            _peer,
                             timer.as_mut(),
                             self.config.keep_alive_timeout,
                             &self.service,
@@ -164,6 +169,7 @@ where
                     super::h1::dispatcher::run(
                         &mut io,
                         crate::unspecified_socket_addr(),
+                        None,
                         timer.as_mut(),
                         self.config,
                         &self.service,
@@ -14,8 +14,10 @@ pub(crate) mod rustls;
 pub(crate) mod rustls_uring;
 
 mod error;
+mod peer;
 
 pub use error::TlsError;
+pub use peer::PeerCertificate;
 
 use xitca_service::Service;
 
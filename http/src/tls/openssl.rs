@@ -13,11 +13,12 @@ use std::io;
 use openssl::{
     error::ErrorStack,
     ssl::{Error, ErrorCode, ShutdownResult, Ssl, SslStream},
+    x509::X509VerifyResult,
 };
 use xitca_io::io::{AsyncIo, Interest, Ready};
 use xitca_service::Service;
 
-use crate::{http::Version, version::AsVersion};
+use crate::{bytes::Bytes, http::Version, version::AsVersion};
 
 use super::error::TlsError;
 
@@ -134,6 +135,28 @@ impl<Io: AsyncIo> AsyncIo for TlsStream<Io> {
 
         AsyncIo::poll_shutdown(Pin::new(this.io.get_mut()), cx)
     }
+
+    fn peer_certificate_chain(&self) -> Option<Vec<Bytes>> {
+        let ssl = self.io.ssl();
+
+        // openssl can be configured to continue handshake with unverified peer certificate.
+        if ssl.verify_result() != X509VerifyResult::OK {
+            return None;
+        }
+
+        let mut chain = vec![Bytes::from(ssl.peer_certificate()?.to_der().ok()?)];
+
+        // on server side peer certificate chain does not contain peer's leaf certificate.
+        for cert in ssl.peer_cert_chain().into_iter().flatten() {
+            if let Ok(cert) = cert.to_der() {
+                if cert != chain[0] {
+                    chain.push(Bytes::from(cert));
+                }
+            }
+        }
+
+        Some(chain)
+    }
 }
 
 impl<Io: AsyncIo> io::Read for TlsStream<Io> {
@@ -0,0 +1,365 @@
+use std::{net::IpAddr, ops::Range, sync::Arc};
+
+use crate::bytes::Bytes;
+
+/// Certificate chain of tls peer verified during handshake and identities parsed from it's end entity
+/// certificate.
+///
+/// Identities are parsed from subject common name and subject alternative name extension of end entity
+/// certificate. When parsing fails the identities are left empty while certificate chain is kept as is.
+#[derive(Clone, Debug, Default)]
+pub struct PeerCertificate {
+    chain: Vec<Bytes>,
+    spki: Option<Range<usize>>,
+    common_name: Option<String>,
+    dns_names: Vec<String>,
+    uris: Vec<String>,
+    emails: Vec<String>,
+    ip_addrs: Vec<IpAddr>,
+}
+
+impl PeerCertificate {
+    /// Construct from DER encoded certificate chain with end entity certificate at first position.
+    ///
+    /// Return None when chain is empty.
+    pub fn from_der_chain<I, B>(chain: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<Bytes>,
+    {
+        let chain = chain.into_iter().map(Into::into).collect::<Vec<_>>();
+
+        let mut cert = Self::default();
+        parse(chain.first()?, &mut cert);
+        cert.chain = chain;
+
+        Some(cert)
+    }
+
+    // parse certificate chain exposed by io types. see xitca_io::io::AsyncIo::peer_certificate_chain.
+    pub(crate) fn from_io_chain(chain: Option<Vec<Bytes>>) -> Option<Arc<Self>> {
+        chain.and_then(Self::from_der_chain).map(Arc::new)
+    }
+
+    /// DER encoded certificate chain with end entity certificate at first position.
+    #[inline]
+    pub fn chain(&self) -> &[Bytes] {
+        &self.chain
+    }
+
+    /// DER encoded end entity certificate.
+    #[inline]
+    pub fn end_entity(&self) -> &[u8] {
+        &self.chain[0]
+    }
+
+    /// DER encoded subject public key info of end entity certificate.
+    #[inline]
+    pub fn subject_public_key_info(&self) -> Option<&[u8]> {
+        self.spki.clone().map(|range| &self.chain[0][range])
+    }
+
+    /// Common name attribute of certificate subject.
+    #[inline]
+    pub fn common_name(&self) -> Option<&str> {
+        self.common_name.as_deref()
+    }
+
+    /// DNS names of subject alternative name extension.
+    #[inline]
+    pub fn dns_names(&self) -> &[String] {
+        &self.dns_names
+    }
+
+    /// URIs of subject alternative name extension.
+    #[inline]
+    pub fn uris(&self) -> &[String] {
+        &self.uris
+    }
+
+    /// Email addresses of subject alternative name extension.
+    #[inline]
+    pub fn emails(&self) -> &[String] {
+        &self.emails
+    }
+
+    /// Ip addresses of subject alternative name extension.
+    #[inline]
+    pub fn ip_addrs(&self) -> &[IpAddr] {
+        &self.ip_addrs
+    }
+
+    /// [SPIFFE ID](https://github.com/spiffe/spiffe/blob/main/standards/X509-SVID.md) of the peer. It's the
+    /// URI with `spiffe` scheme from subject alternative name extension.
+    pub fn spiffe_id(&self) -> Option<&str> {
+        self.uris
+            .iter()
+            .find(|uri| uri.len() > 9 && uri[..9].eq_ignore_ascii_case("spiffe://"))
+            .map(String::as_str)
+    }
+}
+
+const SEQUENCE: u8 = 0x30;
+const SET: u8 = 0x31;
+const INTEGER: u8 = 0x02;
+const BOOLEAN: u8 = 0x01;
+const OCTET_STRING: u8 = 0x04;
+const OID: u8 = 0x06;
+const VERSION: u8 = 0xa0;
+const EXTENSIONS: u8 = 0xa3;
+
+// 2.5.4.3
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+// 2.5.29.17
+const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+
+// minimal DER reader for the part of x509 certificate identities are parsed from. other crates parse x509
+// certificate through PeerCertificate instead of their own reader. (xitca-client's certificate pinning for example)
+struct Der<'a>(&'a [u8]);
+
+impl<'a> Der<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.0.first().copied()
+    }
+
+    // read next tag and value.
+    fn next(&mut self) -> Option<(u8, &'a [u8])> {
+        let (&tag, rest) = self.0.split_first()?;
+        let (&len, mut rest) = rest.split_first()?;
+
+        let len = if len < 0x80 {
+            len as usize
+        } else {
+            let n = (len & 0x7f) as usize;
+            if n == 0 || n > 4 || rest.len() < n {
+                return None;
+            }
+            let (len, r) = rest.split_at(n);
+            rest = r;
+            len.iter().fold(0, |len, b| len << 8 | *b as usize)
+        };
+
+        if rest.len() < len {
+            return None;
+        }
+
+        let (value, rest) = rest.split_at(len);
+        self.0 = rest;
+        Some((tag, value))
+    }
+
+    fn expect(&mut self, tag: u8) -> Option<&'a [u8]> {
+        self.next().and_then(|(t, value)| (t == tag).then_some(value))
+    }
+
+    // same as expect but return full encoding including tag and length.
+    fn expect_raw(&mut self, tag: u8) -> Option<&'a [u8]> {
+        let input = self.0;
+        self.expect(tag)?;
+        Some(&input[..input.len() - self.0.len()])
+    }
+}
+
+fn parse(der: &[u8], cert: &mut PeerCertificate) -> Option<()> {
+    let mut certificate = Der(Der(der).expect(SEQUENCE)?);
+    let mut tbs = Der(certificate.expect(SEQUENCE)?);
+
+    if tbs.peek()? == VERSION {
+        tbs.next()?;
+    }
+
+    // serial number, signature algorithm, issuer and validity.
+    tbs.expect(INTEGER)?;
+    tbs.expect(SEQUENCE)?;
+    tbs.expect(SEQUENCE)?;
+    tbs.expect(SEQUENCE)?;
+
+    let subject = tbs.expect(SEQUENCE)?;
+    cert.common_name = common_name(subject);
+
+    let spki = tbs.expect_raw(SEQUENCE)?;
+    let start = spki.as_ptr() as usize - der.as_ptr() as usize;
+    cert.spki = Some(start..start + spki.len());
+
+    // skip optional unique identifiers.
+    while let Some((tag, value)) = tbs.next() {
+        if tag == EXTENSIONS {
+            extensions(Der(value).expect(SEQUENCE)?, cert);
+        }
+    }
+
+    Some(())
+}
+
+fn common_name(name: &[u8]) -> Option<String> {
+    let mut rdns = Der(name);
+    while let Some(rdn) = rdns.expect(SET) {
+        let mut rdn = Der(rdn);
+        while let Some(attr) = rdn.expect(SEQUENCE) {
+            let mut attr = Der(attr);
+            if attr.expect(OID)? == OID_COMMON_NAME {
+                let (tag, value) = attr.next()?;
+                return string(tag, value);
+            }
+        }
+    }
+    None
+}
+
+fn string(tag: u8, value: &[u8]) -> Option<String> {
+    match tag {
+        // UTF8String, PrintableString and IA5String.
+        0x0c | 0x13 | 0x16 => std::str::from_utf8(value).ok().map(String::from),
+        // BMPString
+        0x1e => {
+            let chunks = value.chunks_exact(2);
+            if !chunks.remainder().is_empty() {
+                return None;
+            }
+            let units = chunks.map(|b| u16::from_be_bytes([b[0], b[1]]));
+            char::decode_utf16(units).collect::<Result<_, _>>().ok()
+        }
+        _ => None,
+    }
+}
+
+fn extensions(exts: &[u8], cert: &mut PeerCertificate) -> Option<()> {
+    let mut exts = Der(exts);
+    while let Some(ext) = exts.expect(SEQUENCE) {
+        let mut ext = Der(ext);
+
+        if ext.expect(OID)? != OID_SUBJECT_ALT_NAME {
+            continue;
+        }
+
+        if ext.peek()? == BOOLEAN {
+            ext.next()?;
+        }
+
+        let value = ext.expect(OCTET_STRING)?;
+        let mut names = Der(Der(value).expect(SEQUENCE)?);
+
+        while let Some((tag, value)) = names.next() {
+            match tag {
+                // rfc822Name, dNSName and uniformResourceIdentifier are IA5String.
+                0x81 => cert.emails.extend(string(0x16, value)),
+                0x82 => cert.dns_names.extend(string(0x16, value)),
+                0x86 => cert.uris.extend(string(0x16, value)),
+                0x87 => match *value {
+                    [a, b, c, d] => cert.ip_addrs.push(IpAddr::from([a, b, c, d])),
+                    _ => {
+                        if let Ok(octets) = <[u8; 16]>::try_from(value) {
+                            cert.ip_addrs.push(IpAddr::from(octets));
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    Some(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tlv(tag: u8, value: &[u8]) -> Vec<u8> {
+        let mut buf = vec![tag];
+        match value.len() {
+            len @ 0..=0x7f => buf.push(len as u8),
+            len @ 0x80..=0xff => buf.extend_from_slice(&[0x81, len as u8]),
+            len => buf.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+        }
+        buf.extend_from_slice(value);
+        buf
+    }
+
+    fn cert_der() -> Vec<u8> {
+        let cn = tlv(
+            SET,
+            &tlv(SEQUENCE, &[tlv(OID, OID_COMMON_NAME), tlv(0x0c, b"client")].concat()),
+        );
+        let org = tlv(
+            SET,
+            &tlv(SEQUENCE, &[tlv(OID, &[0x55, 0x04, 0x0a]), tlv(0x13, b"xitca")].concat()),
+        );
+        let subject = tlv(SEQUENCE, &[org, cn].concat());
+
+        let names = tlv(
+            SEQUENCE,
+            &[
+                tlv(0x82, b"client.example.com"),
+                tlv(0x86, b"spiffe://example.com/ns/default/sa/client"),
+                tlv(0x81, b"client@example.com"),
+                tlv(0x87, &[127, 0, 0, 1]),
+            ]
+            .concat(),
+        );
+        let san = tlv(
+            SEQUENCE,
+            &[
+                tlv(OID, OID_SUBJECT_ALT_NAME),
+                tlv(BOOLEAN, &[0]),
+                tlv(OCTET_STRING, &names),
+            ]
+            .concat(),
+        );
+        let basic = tlv(
+            SEQUENCE,
+            &[tlv(OID, &[0x55, 0x1d, 0x13]), tlv(OCTET_STRING, &tlv(SEQUENCE, &[]))].concat(),
+        );
+        let exts = tlv(EXTENSIONS, &tlv(SEQUENCE, &[basic, san].concat()));
+
+        let tbs = tlv(
+            SEQUENCE,
+            &[
+                tlv(VERSION, &tlv(INTEGER, &[2])),
+                tlv(INTEGER, &[1]),
+                tlv(SEQUENCE, &[]),
+                tlv(SEQUENCE, &[]),
+                tlv(SEQUENCE, &[]),
+                subject,
+                tlv(SEQUENCE, &[]),
+                exts,
+            ]
+            .concat(),
+        );
+
+        tlv(SEQUENCE, &[tbs, tlv(SEQUENCE, &[]), tlv(0x03, &[0])].concat())
+    }
+
+    #[test]
+    fn identities() {
+        let cert = PeerCertificate::from_der_chain([cert_der()]).unwrap();
+
+        assert_eq!(cert.chain().len(), 1);
+        assert_eq!(cert.subject_public_key_info(), Some(&[0x30, 0x00][..]));
+        assert_eq!(cert.common_name(), Some("client"));
+        assert_eq!(cert.dns_names(), ["client.example.com"]);
+        assert_eq!(cert.emails(), ["client@example.com"]);
+        assert_eq!(cert.ip_addrs(), [IpAddr::from([127, 0, 0, 1])]);
+        assert_eq!(cert.spiffe_id(), Some("spiffe://example.com/ns/default/sa/client"));
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(PeerCertificate::from_der_chain(Vec::<Bytes>::new()).is_none());
+
+        let cert = PeerCertificate::from_der_chain([Bytes::from_static(&[0x30, 0x82, 0xff])]).unwrap();
+        assert_eq!(cert.chain().len(), 1);
+        assert!(cert.subject_public_key_info().is_none());
+        assert!(cert.common_name().is_none());
+        assert!(cert.uris().is_empty());
+
+        // truncated certificate and length field larger than input.
+        let der = cert_der();
+        for len in [1, 2, 10, der.len() - 1] {
+            let cert = PeerCertificate::from_der_chain([der[..len].to_vec()]).unwrap();
+            assert!(cert.subject_public_key_info().is_none());
+        }
+        let cert = PeerCertificate::from_der_chain([vec![0x30, 0x84, 0xff, 0xff, 0xff, 0xff]]).unwrap();
+        assert!(cert.common_name().is_none());
+    }
+}
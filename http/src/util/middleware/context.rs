@@ -161,9 +161,11 @@ mod service {
 
 #[cfg(feature = "router")]
 mod router_impl {
+    use std::collections::HashMap;
+
     use xitca_service::object::ServiceObject;
 
-    use crate::util::service::router::{IntoObject, PathGen, RouteGen, RouteObject};
+    use crate::util::service::router::{IntoObject, PathGen, RouteGen, RouteInfo, RouteObject};
 
     use super::*;
 
@@ -189,6 +191,14 @@ mod router_impl {
                 fn path_gen(&mut self, prefix: &str) -> String {
                     self.0.path_gen(prefix)
                 }
+
+                fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+                    self.0.named_path_gen()
+                }
+
+                fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+                    self.0.route_info(info, routes)
+                }
             }
 
             impl<I, Req, C> RouteGen for Builder<I, Req, C>
@@ -5,6 +5,10 @@ mod logger;
 #[cfg(feature = "runtime")]
 mod socket_config;
 
+#[cfg(not(target_family = "wasm"))]
+#[cfg(feature = "runtime")]
+pub mod proxy_protocol;
+
 pub mod catch_unwind;
 pub mod context;
 
@@ -12,4 +16,4 @@ pub use self::{extension::Extension, logger::Logger};
 
 #[cfg(not(target_family = "wasm"))]
 #[cfg(feature = "runtime")]
-pub use self::socket_config::SocketConfig;
+pub use self::{proxy_protocol::ProxyProtocol, socket_config::SocketConfig};
@@ -0,0 +1,635 @@
+//! PROXY protocol v1 and v2 support.
+//!
+//! See <https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt> for specification.
+
+use core::{fmt, time::Duration};
+
+use std::{
+    error, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+
+use tracing::{debug, warn};
+use xitca_io::{
+    io::{AsyncIo, Interest},
+    net::{Stream as ServerStream, TcpStream},
+};
+use xitca_service::{ready::ReadyService, Service};
+
+const V1_PREFIX: &[u8] = b"PROXY ";
+const V1_MAX_LEN: usize = 107;
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+
+// the shortest possible header of both versions. reading it would not consume data after the header.
+const MIN_LEN: usize = 15;
+
+const PP2_TYPE_ALPN: u8 = 0x01;
+const PP2_TYPE_AUTHORITY: u8 = 0x02;
+const PP2_TYPE_UNIQUE_ID: u8 = 0x05;
+const PP2_TYPE_SSL: u8 = 0x20;
+const PP2_SUBTYPE_SSL_VERSION: u8 = 0x21;
+const PP2_SUBTYPE_SSL_CN: u8 = 0x22;
+const PP2_SUBTYPE_SSL_CIPHER: u8 = 0x23;
+const PP2_SUBTYPE_SSL_SIG_ALG: u8 = 0x24;
+const PP2_SUBTYPE_SSL_KEY_ALG: u8 = 0x25;
+
+const PP2_CLIENT_SSL: u8 = 0x01;
+const PP2_CLIENT_CERT_CONN: u8 = 0x02;
+const PP2_CLIENT_CERT_SESS: u8 = 0x04;
+
+/// Information carried by PROXY protocol header.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProxyHeader {
+    /// address of client connected to proxy.
+    ///
+    /// None when proxy sends header for itself (health check for example) or client address is not
+    /// an inet address.
+    pub source: Option<SocketAddr>,
+    /// address client connected to on proxy.
+    pub destination: Option<SocketAddr>,
+    /// application protocol negotiated between client and proxy.
+    pub alpn: Option<Vec<u8>>,
+    /// host name client sent to proxy. Usually from tls SNI extension.
+    pub authority: Option<String>,
+    /// unique id of connection generated by proxy.
+    pub unique_id: Option<Vec<u8>>,
+    /// tls information of connection between client and proxy.
+    pub ssl: Option<SslInfo>,
+    /// TLVs in type and value pair not covered by fields above.
+    pub tlvs: Vec<(u8, Vec<u8>)>,
+}
+
+/// Tls information of connection between client and proxy. Only available from PROXY protocol v2.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SslInfo {
+    /// bit field of `PP2_CLIENT_*` flags.
+    pub client: u8,
+    /// zero when client presented a certificate and it was successfully verified.
+    pub verify: u32,
+    pub version: Option<String>,
+    /// common name of client certificate's subject.
+    pub common_name: Option<String>,
+    pub cipher: Option<String>,
+    pub sig_alg: Option<String>,
+    pub key_alg: Option<String>,
+}
+
+impl SslInfo {
+    /// client connected to proxy over tls.
+    pub fn is_ssl(&self) -> bool {
+        self.client & PP2_CLIENT_SSL != 0
+    }
+
+    /// client presented a certificate and it was verified by proxy.
+    pub fn is_verified(&self) -> bool {
+        self.client & (PP2_CLIENT_CERT_CONN | PP2_CLIENT_CERT_SESS) != 0 && self.verify == 0
+    }
+}
+
+/// A middleware parsing PROXY protocol header from `TcpStream` before passing it to inner service.
+///
+/// Source address from header replaces peer address of stream. Which means [RequestExt::socket_addr]
+/// and everything depending on it observe the address of real client. The middleware should enclose
+/// [HttpService] so header is parsed before tls and http dispatching.
+///
+/// Connections from trusted peers must start with a valid header or they are closed. Connections from
+/// peers not trusted are passed to inner service as is. No peer is trusted by default and at least one
+/// network must be trusted with [ProxyProtocol::trusted] for header to be parsed.
+///
+/// # Examples:
+/// ```rust
+/// # use std::net::{IpAddr, Ipv4Addr};
+/// # use xitca_http::util::middleware::ProxyProtocol;
+/// // trust proxy servers from 10.0.0.0/8
+/// let proxy_protocol = ProxyProtocol::new().trusted(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8);
+/// ```
+///
+/// [RequestExt::socket_addr]: crate::http::RequestExt::socket_addr
+/// [HttpService]: crate::HttpService
+#[derive(Clone)]
+pub struct ProxyProtocol {
+    trusted: Vec<(IpAddr, u8)>,
+    header_timeout: Duration,
+    on_header: Option<OnHeader>,
+}
+
+type OnHeader = Arc<dyn Fn(&ProxyHeader) -> bool + Send + Sync>;
+
+impl Default for ProxyProtocol {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProxyProtocol {
+    /// Construct a new middleware trusting no peer.
+    pub const fn new() -> Self {
+        Self {
+            trusted: Vec::new(),
+            header_timeout: Duration::from_secs(5),
+            on_header: None,
+        }
+    }
+
+    /// Trust peers in network of given address and prefix length. Can be called multiple times.
+    ///
+    /// Trusted peers can claim arbitrary source address. Only networks of proxy servers should be trusted.
+    pub fn trusted(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.trusted.push((addr, prefix_len));
+        self
+    }
+
+    /// Timeout for receiving header from trusted peer.
+    ///
+    /// Default to 5 seconds.
+    pub fn header_timeout(mut self, dur: Duration) -> Self {
+        self.header_timeout = dur;
+        self
+    }
+
+    /// Inspect parsed header. Connection is closed when given function returns false.
+    ///
+    /// Useful for examining TLVs like [SslInfo].
+    pub fn on_header<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&ProxyHeader) -> bool + Send + Sync + 'static,
+    {
+        self.on_header = Some(Arc::new(func));
+        self
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.trusted.iter().any(|(net, len)| net_contains(*net, *len, ip))
+    }
+}
+
+impl fmt::Debug for ProxyProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProxyProtocol")
+            .field("trusted", &self.trusted)
+            .field("header_timeout", &self.header_timeout)
+            .finish()
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for ProxyProtocol {
+    type Response = ProxyProtocolService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        if self.trusted.is_empty() {
+            warn!("ProxyProtocol has no trusted network. PROXY protocol header would not be parsed");
+        }
+
+        res.map(|service| ProxyProtocolService {
+            config: self.clone(),
+            service,
+        })
+    }
+}
+
+pub struct ProxyProtocolService<S> {
+    config: ProxyProtocol,
+    service: S,
+}
+
+impl<S> ReadyService for ProxyProtocolService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+impl<S> Service<ServerStream> for ProxyProtocolService<S>
+where
+    S: Service<ServerStream>,
+{
+    type Response = S::Response;
+    type Error = ProxyProtocolError<S::Error>;
+
+    async fn call(&self, stream: ServerStream) -> Result<Self::Response, Self::Error> {
+        let stream = match stream {
+            ServerStream::Tcp(tcp, addr) if self.config.is_trusted(addr.ip()) => {
+                let (tcp, addr) = self.accept(tcp, addr).await.map_err(ProxyProtocolError::Header)?;
+                ServerStream::Tcp(tcp, addr)
+            }
+            stream => stream,
+        };
+
+        self.service.call(stream).await.map_err(ProxyProtocolError::Service)
+    }
+}
+
+impl<S> ProxyProtocolService<S> {
+    async fn accept(
+        &self,
+        tcp: std::net::TcpStream,
+        addr: SocketAddr,
+    ) -> io::Result<(std::net::TcpStream, SocketAddr)> {
+        let mut io = TcpStream::from_std(tcp)?;
+
+        let header = tokio::time::timeout(self.config.header_timeout, read_header(&mut io))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "PROXY protocol header timed out"))??;
+
+        debug!(target: "ProxyProtocol", ?header);
+
+        if let Some(ref func) = self.config.on_header {
+            if !func(&header) {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "PROXY protocol header is rejected",
+                ));
+            }
+        }
+
+        let addr = header.source.unwrap_or(addr);
+
+        io.into_std().map(|tcp| (tcp, addr))
+    }
+}
+
+/// error type of [ProxyProtocolService].
+pub enum ProxyProtocolError<E> {
+    /// failed to receive valid header from peer.
+    Header(io::Error),
+    Service(E),
+}
+
+impl<E> fmt::Debug for ProxyProtocolError<E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Header(ref e) => write!(f, "PROXY protocol error: {e:?}"),
+            Self::Service(ref e) => fmt::Debug::fmt(e, f),
+        }
+    }
+}
+
+impl<E> fmt::Display for ProxyProtocolError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Header(ref e) => write!(f, "PROXY protocol error: {e}"),
+            Self::Service(ref e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl<E> error::Error for ProxyProtocolError<E> where E: fmt::Debug + fmt::Display {}
+
+// read header without consuming any byte after it.
+async fn read_header(io: &mut TcpStream) -> io::Result<ProxyHeader> {
+    let mut buf = [0; V1_MAX_LEN];
+    read_exact(io, &mut buf[..MIN_LEN]).await?;
+
+    if buf.starts_with(V2_SIGNATURE) {
+        read_exact(io, &mut buf[MIN_LEN..16]).await?;
+        let len = u16::from_be_bytes([buf[14], buf[15]]) as usize;
+        let mut payload = vec![0; len];
+        read_exact(io, &mut payload).await?;
+        decode_v2(&buf[..16], &payload)
+    } else if buf.starts_with(V1_PREFIX) {
+        let mut len = MIN_LEN;
+        while !buf[..len].ends_with(b"\r\n") {
+            if len == V1_MAX_LEN {
+                return Err(invalid("v1 header is too long"));
+            }
+            read_exact(io, &mut buf[len..len + 1]).await?;
+            len += 1;
+        }
+        decode_v1(&buf[..len])
+    } else {
+        Err(invalid("header not found"))
+    }
+}
+
+async fn read_exact(io: &mut TcpStream, buf: &mut [u8]) -> io::Result<()> {
+    let mut n = 0;
+    while n < buf.len() {
+        match io::Read::read(io, &mut buf[n..]) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(i) => n += i,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                io.ready(Interest::READABLE).await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid PROXY protocol header: {msg}"),
+    )
+}
+
+fn decode_v1(line: &[u8]) -> io::Result<ProxyHeader> {
+    let line = line
+        .strip_suffix(b"\r\n")
+        .and_then(|line| std::str::from_utf8(line).ok())
+        .ok_or_else(|| invalid("v1 header is not valid text"))?;
+
+    let mut parts = line.split(' ');
+
+    if parts.next() != Some("PROXY") {
+        return Err(invalid("v1 prefix not found"));
+    }
+
+    let is_v4 = match parts.next() {
+        Some("TCP4") => true,
+        Some("TCP6") => false,
+        // proxy does not know the protocol and rest of line must be ignored.
+        Some("UNKNOWN") => return Ok(ProxyHeader::default()),
+        _ => return Err(invalid("unknown v1 protocol")),
+    };
+
+    let mut next = || parts.next().ok_or_else(|| invalid("v1 address missing"));
+
+    let src = next()?.parse::<IpAddr>().map_err(|_| invalid("v1 source address"))?;
+    let dst = next()?
+        .parse::<IpAddr>()
+        .map_err(|_| invalid("v1 destination address"))?;
+    let src_port = next()?.parse::<u16>().map_err(|_| invalid("v1 source port"))?;
+    let dst_port = next()?.parse::<u16>().map_err(|_| invalid("v1 destination port"))?;
+
+    if parts.next().is_some() || src.is_ipv4() != is_v4 || dst.is_ipv4() != is_v4 {
+        return Err(invalid("v1 address mismatch"));
+    }
+
+    Ok(ProxyHeader {
+        source: Some(SocketAddr::new(src, src_port)),
+        destination: Some(SocketAddr::new(dst, dst_port)),
+        ..Default::default()
+    })
+}
+
+fn decode_v2(head: &[u8], payload: &[u8]) -> io::Result<ProxyHeader> {
+    let ver_cmd = head[12];
+
+    if ver_cmd >> 4 != 2 {
+        return Err(invalid("unknown v2 version"));
+    }
+
+    match ver_cmd & 0x0f {
+        // LOCAL command. connection is established by proxy itself and address must be ignored.
+        0x0 => return Ok(ProxyHeader::default()),
+        // PROXY command.
+        0x1 => {}
+        _ => return Err(invalid("unknown v2 command")),
+    }
+
+    let mut header = ProxyHeader::default();
+
+    let tlvs = match head[13] >> 4 {
+        // AF_UNSPEC. address information must be ignored.
+        0x0 => return Ok(header),
+        // AF_INET
+        0x1 => {
+            let addrs = payload.get(..12).ok_or_else(|| invalid("v2 ipv4 address"))?;
+            let ip = |i: usize| IpAddr::V4(Ipv4Addr::new(addrs[i], addrs[i + 1], addrs[i + 2], addrs[i + 3]));
+            let port = |i: usize| u16::from_be_bytes([addrs[i], addrs[i + 1]]);
+            header.source = Some(SocketAddr::new(ip(0), port(8)));
+            header.destination = Some(SocketAddr::new(ip(4), port(10)));
+            &payload[12..]
+        }
+        // AF_INET6
+        0x2 => {
+            let addrs = payload.get(..36).ok_or_else(|| invalid("v2 ipv6 address"))?;
+            let ip = |i: usize| IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(&addrs[i..i + 16]).unwrap()));
+            let port = |i: usize| u16::from_be_bytes([addrs[i], addrs[i + 1]]);
+            header.source = Some(SocketAddr::new(ip(0), port(32)));
+            header.destination = Some(SocketAddr::new(ip(16), port(34)));
+            &payload[36..]
+        }
+        // AF_UNIX. path can not be represented by SocketAddr.
+        0x3 => payload.get(216..).ok_or_else(|| invalid("v2 unix address"))?,
+        _ => return Err(invalid("unknown v2 address family")),
+    };
+
+    for_each_tlv(tlvs, |ty, value| {
+        match ty {
+            PP2_TYPE_ALPN => header.alpn = Some(value.to_vec()),
+            PP2_TYPE_AUTHORITY => header.authority = Some(utf8(value)?),
+            PP2_TYPE_UNIQUE_ID => header.unique_id = Some(value.to_vec()),
+            PP2_TYPE_SSL => header.ssl = Some(decode_ssl(value)?),
+            _ => header.tlvs.push((ty, value.to_vec())),
+        }
+        Ok(())
+    })?;
+
+    Ok(header)
+}
+
+fn decode_ssl(value: &[u8]) -> io::Result<SslInfo> {
+    if value.len() < 5 {
+        return Err(invalid("v2 ssl tlv"));
+    }
+
+    let mut ssl = SslInfo {
+        client: value[0],
+        verify: u32::from_be_bytes([value[1], value[2], value[3], value[4]]),
+        ..Default::default()
+    };
+
+    for_each_tlv(&value[5..], |ty, value| {
+        let field = match ty {
+            PP2_SUBTYPE_SSL_VERSION => &mut ssl.version,
+            PP2_SUBTYPE_SSL_CN => &mut ssl.common_name,
+            PP2_SUBTYPE_SSL_CIPHER => &mut ssl.cipher,
+            PP2_SUBTYPE_SSL_SIG_ALG => &mut ssl.sig_alg,
+            PP2_SUBTYPE_SSL_KEY_ALG => &mut ssl.key_alg,
+            _ => return Ok(()),
+        };
+        *field = Some(utf8(value)?);
+        Ok(())
+    })?;
+
+    Ok(ssl)
+}
+
+fn for_each_tlv<F>(mut buf: &[u8], mut func: F) -> io::Result<()>
+where
+    F: FnMut(u8, &[u8]) -> io::Result<()>,
+{
+    while !buf.is_empty() {
+        if buf.len() < 3 {
+            return Err(invalid("v2 tlv"));
+        }
+        let len = u16::from_be_bytes([buf[1], buf[2]]) as usize;
+        let value = buf.get(3..3 + len).ok_or_else(|| invalid("v2 tlv length"))?;
+        func(buf[0], value)?;
+        buf = &buf[3 + len..];
+    }
+    Ok(())
+}
+
+fn utf8(value: &[u8]) -> io::Result<String> {
+    String::from_utf8(value.to_vec()).map_err(|_| invalid("v2 tlv is not valid utf-8"))
+}
+
+fn net_contains(net: IpAddr, prefix_len: u8, ip: IpAddr) -> bool {
+    match (net.to_canonical(), ip) {
+        (IpAddr::V4(net), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix_len.min(32))).unwrap_or(0);
+            u32::from(net) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(net), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix_len.min(128))).unwrap_or(0);
+            u128::from(net) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use xitca_service::fn_service;
+
+    use super::*;
+
+    fn v2(fam: u8, addrs: &[u8], tlvs: &[u8]) -> Vec<u8> {
+        let mut buf = V2_SIGNATURE.to_vec();
+        buf.push(0x21);
+        buf.push(fam);
+        buf.extend_from_slice(&((addrs.len() + tlvs.len()) as u16).to_be_bytes());
+        buf.extend_from_slice(addrs);
+        buf.extend_from_slice(tlvs);
+        buf
+    }
+
+    #[test]
+    fn v1() {
+        let header = decode_v1(b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 443\r\n").unwrap();
+        assert_eq!(header.source, Some("192.168.0.1:56324".parse().unwrap()));
+        assert_eq!(header.destination, Some("192.168.0.11:443".parse().unwrap()));
+
+        let header = decode_v1(b"PROXY TCP6 ::1 ::2 1 2\r\n").unwrap();
+        assert_eq!(header.source, Some("[::1]:1".parse().unwrap()));
+
+        let header = decode_v1(b"PROXY UNKNOWN ffff::1 ::2 1 2\r\n").unwrap();
+        assert_eq!(header, ProxyHeader::default());
+
+        assert!(decode_v1(b"PROXY TCP4 ::1 ::2 1 2\r\n").is_err());
+        assert!(decode_v1(b"PROXY TCP4 127.0.0.1 127.0.0.1 1\r\n").is_err());
+        assert!(decode_v1(b"PROXY UDP4 127.0.0.1 127.0.0.1 1 2\r\n").is_err());
+    }
+
+    #[test]
+    fn v2_tlv() {
+        let addrs = [127, 0, 0, 1, 127, 0, 0, 2, 0x1f, 0x90, 0x01, 0xbb];
+
+        let mut ssl = vec![PP2_CLIENT_SSL | PP2_CLIENT_CERT_CONN, 0, 0, 0, 0];
+        ssl.extend_from_slice(&[PP2_SUBTYPE_SSL_VERSION, 0, 7]);
+        ssl.extend_from_slice(b"TLSv1.3");
+        ssl.extend_from_slice(&[PP2_SUBTYPE_SSL_CN, 0, 6]);
+        ssl.extend_from_slice(b"client");
+
+        let mut tlvs = vec![PP2_TYPE_ALPN, 0, 2];
+        tlvs.extend_from_slice(b"h2");
+        tlvs.extend_from_slice(&[PP2_TYPE_SSL, 0, ssl.len() as u8]);
+        tlvs.extend_from_slice(&ssl);
+        tlvs.extend_from_slice(&[0xe0, 0, 1, 9]);
+
+        let buf = v2(0x11, &addrs, &tlvs);
+        let header = decode_v2(&buf[..16], &buf[16..]).unwrap();
+
+        assert_eq!(header.source, Some("127.0.0.1:8080".parse().unwrap()));
+        assert_eq!(header.destination, Some("127.0.0.2:443".parse().unwrap()));
+        assert_eq!(header.alpn.as_deref(), Some(&b"h2"[..]));
+        assert_eq!(header.tlvs, vec![(0xe0, vec![9])]);
+
+        let ssl = header.ssl.unwrap();
+        assert!(ssl.is_ssl());
+        assert!(ssl.is_verified());
+        assert_eq!(ssl.version.as_deref(), Some("TLSv1.3"));
+        assert_eq!(ssl.common_name.as_deref(), Some("client"));
+
+        // truncated tlv.
+        let buf = v2(0x11, &addrs, &[PP2_TYPE_ALPN, 0, 3, b'h']);
+        assert!(decode_v2(&buf[..16], &buf[16..]).is_err());
+
+        // local command.
+        let mut buf = v2(0x11, &addrs, &[]);
+        buf[12] = 0x20;
+        assert_eq!(decode_v2(&buf[..16], &buf[16..]).unwrap(), ProxyHeader::default());
+    }
+
+    #[test]
+    fn trusted() {
+        let proxy = ProxyProtocol::new()
+            .trusted(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8)
+            .trusted(IpAddr::V6(Ipv6Addr::LOCALHOST), 128);
+
+        assert!(proxy.is_trusted("10.1.2.3".parse().unwrap()));
+        assert!(proxy.is_trusted("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(proxy.is_trusted("::1".parse().unwrap()));
+        assert!(!proxy.is_trusted("11.0.0.1".parse().unwrap()));
+        assert!(!proxy.is_trusted("::2".parse().unwrap()));
+
+        // no peer is trusted by default.
+        assert!(!ProxyProtocol::new().is_trusted("11.0.0.1".parse().unwrap()));
+        assert!(!ProxyProtocol::new().is_trusted("127.0.0.1".parse().unwrap()));
+        assert!(net_contains(
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            0,
+            "1.2.3.4".parse().unwrap()
+        ));
+    }
+
+    #[test]
+    fn replace_addr() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, addr) = listener.accept().unwrap();
+        server.set_nonblocking(true).unwrap();
+
+        client
+            .write_all(b"PROXY TCP4 1.2.3.4 5.6.7.8 1000 80\r\nGET / HTTP/1.1\r\n\r\n")
+            .unwrap();
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(async {
+            let service = fn_service(|stream: ServerStream| async move {
+                let ServerStream::Tcp(mut tcp, addr) = stream else {
+                    unreachable!()
+                };
+                tcp.set_nonblocking(false).unwrap();
+                let mut buf = [0; 18];
+                io::Read::read_exact(&mut tcp, &mut buf).unwrap();
+                assert_eq!(&buf, b"GET / HTTP/1.1\r\n\r\n");
+                Ok::<_, ()>(addr)
+            })
+            .call(())
+            .await;
+
+            let service = ProxyProtocol::new()
+                .trusted(IpAddr::V4(Ipv4Addr::LOCALHOST), 32)
+                .call(service)
+                .await
+                .unwrap();
+
+            let addr = service.call(ServerStream::Tcp(server, addr)).await.unwrap();
+            assert_eq!(addr, "1.2.3.4:1000".parse().unwrap());
+        });
+    }
+}
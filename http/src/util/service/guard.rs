@@ -0,0 +1,453 @@
+//! request guards for route selection beyond http method. See [Route::guard](super::route::Route::guard)
+//! for detail.
+
+use core::fmt;
+
+use std::error;
+
+use crate::http::{
+    header::{HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE},
+    BorrowReq, HeaderMap, Uri,
+};
+
+/// predicate on request for selecting route.
+pub trait Guard<Req> {
+    /// check if request satisfies the guard. [Quality] of request's preference for the route is returned when
+    /// it does and [Rejection] is returned when it does not.
+    fn check(&self, req: &Req) -> Result<Quality, Rejection>;
+}
+
+// unguarded route. it's only selected when no guarded route sharing method passes.
+impl<Req> Guard<Req> for () {
+    #[inline]
+    fn check(&self, _: &Req) -> Result<Quality, Rejection> {
+        Ok(Quality::MIN)
+    }
+}
+
+/// preference of request for route passed [Guard]. routes sharing method are ranked by it and the most
+/// preferred one is selected. earlier route wins when preferences are equal.
+///
+/// value is in thousandths, the precision of http quality value. `q=0.8` equals to `Quality::new(800)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Quality(u16);
+
+impl Quality {
+    /// preference of guard passing request without expressing it. [accept] guard produces it for `q=1`.
+    pub const MAX: Self = Self(1000);
+
+    /// least preference. unguarded route produces it.
+    pub const MIN: Self = Self(0);
+
+    /// construct quality from thousandths. value greater than 1000 is treated as 1000.
+    pub const fn new(thousandths: u16) -> Self {
+        if thousandths > Self::MAX.0 {
+            Self::MAX
+        } else {
+            Self(thousandths)
+        }
+    }
+
+    // parse http quality value. ex: "0.8"
+    fn parse(q: &str) -> Option<Self> {
+        q.trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|q| (0.0..=1.0).contains(q))
+            .map(|q| Self((q * 1000.0).round() as u16))
+    }
+}
+
+/// reason of request being rejected by [Guard].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// request can not accept response of route. produced by [accept] guard.
+    NotAcceptable,
+    /// request body's media type is not supported by route. produced by [content_type] guard.
+    UnsupportedMediaType,
+    /// request does not satisfy the guard.
+    Unmatched,
+}
+
+impl Rejection {
+    // prefer rejection with specific reason. earlier one wins when both are specific.
+    pub(super) fn merge(self, other: Self) -> Self {
+        match self {
+            Self::Unmatched => other,
+            _ => self,
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::NotAcceptable => f.write_str("router error: response of route is not acceptable"),
+            Self::UnsupportedMediaType => f.write_str("router error: request media type is not supported"),
+            Self::Unmatched => f.write_str("router error: request rejected by route guard"),
+        }
+    }
+}
+
+impl error::Error for Rejection {}
+
+/// guard passes when request has header with given name and value.
+pub fn header(name: HeaderName, value: &'static str) -> Header {
+    Header {
+        name,
+        value: Some(HeaderValue::from_static(value)),
+    }
+}
+
+/// guard passes when request has header with given name.
+pub fn header_exists(name: HeaderName) -> Header {
+    Header { name, value: None }
+}
+
+/// See [header] and [header_exists].
+#[derive(Clone)]
+pub struct Header {
+    name: HeaderName,
+    value: Option<HeaderValue>,
+}
+
+impl<Req> Guard<Req> for Header
+where
+    Req: BorrowReq<HeaderMap>,
+{
+    fn check(&self, req: &Req) -> Result<Quality, Rejection> {
+        let mut values = req.borrow().get_all(&self.name).iter();
+        let found = match self.value {
+            Some(ref value) => values.any(|v| v == value),
+            None => values.next().is_some(),
+        };
+        found.then_some(Quality::MAX).ok_or(Rejection::Unmatched)
+    }
+}
+
+/// guard passes when media type of request's `Content-Type` header equals to given one. parameters
+/// of media type are ignored. Rejects with [Rejection::UnsupportedMediaType].
+///
+/// # Example
+/// ```rust
+/// # use xitca_http::util::service::guard::content_type;
+/// // matches "application/json" and "application/json; charset=utf-8"
+/// content_type("application/json");
+/// ```
+pub fn content_type(mime: &'static str) -> ContentType {
+    ContentType(mime)
+}
+
+/// See [content_type].
+#[derive(Clone)]
+pub struct ContentType(&'static str);
+
+impl<Req> Guard<Req> for ContentType
+where
+    Req: BorrowReq<HeaderMap>,
+{
+    fn check(&self, req: &Req) -> Result<Quality, Rejection> {
+        req.borrow()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| essence(v).eq_ignore_ascii_case(self.0))
+            .map(|_| Quality::MAX)
+            .ok_or(Rejection::UnsupportedMediaType)
+    }
+}
+
+/// guard passes when request's `Accept` header accepts given media type. media ranges like `*/*` and
+/// `text/*` are respected and the most specific range matching given media type decides it's [Quality].
+/// media type with `q=0` is not acceptable. Request without `Accept` header accepts any media type.
+/// Rejects with [Rejection::NotAcceptable].
+///
+/// routes guarded by accept and sharing method are selected by quality of their media types. For
+/// `text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8` route accepting "text/html" is
+/// preferred over route accepting "application/json" regardless of their order.
+///
+/// # Example
+/// ```rust
+/// # use xitca_http::util::service::guard::accept;
+/// // matches "application/json", "application/*" and "*/*"
+/// accept("application/json");
+/// ```
+pub fn accept(mime: &'static str) -> Accept {
+    Accept(mime)
+}
+
+/// See [accept].
+#[derive(Clone)]
+pub struct Accept(&'static str);
+
+impl Accept {
+    // specificity and quality of media range when it matches media type of guard.
+    fn matches(&self, range: &str) -> Option<(u8, Quality)> {
+        let mut params = range.split(';');
+        let range = params.next().unwrap_or_default().trim();
+
+        let specificity = match range.split_once('/') {
+            Some(("*", "*")) => 0,
+            Some((ty, "*")) => {
+                let (t, _) = self.0.split_once('/')?;
+                t.eq_ignore_ascii_case(ty).then_some(1)?
+            }
+            _ => range.eq_ignore_ascii_case(self.0).then_some(2)?,
+        };
+
+        // malformed quality value is ignored and range is treated as fully acceptable.
+        let quality = params
+            .find_map(|p| p.trim().strip_prefix("q="))
+            .and_then(Quality::parse)
+            .unwrap_or(Quality::MAX);
+
+        Some((specificity, quality))
+    }
+}
+
+impl<Req> Guard<Req> for Accept
+where
+    Req: BorrowReq<HeaderMap>,
+{
+    fn check(&self, req: &Req) -> Result<Quality, Rejection> {
+        let mut values = req.borrow().get_all(ACCEPT).iter().peekable();
+
+        if values.peek().is_none() {
+            return Ok(Quality::MAX);
+        }
+
+        // the most specific matching range decides quality. earlier one wins when ranges are equally specific.
+        let mut matched: Option<(u8, Quality)> = None;
+        for (specificity, quality) in values
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|range| self.matches(range))
+        {
+            if matched.is_none_or(|(s, _)| specificity > s) {
+                matched = Some((specificity, quality));
+            }
+        }
+
+        // media type with zero quality is explicitly not acceptable.
+        matched
+            .map(|(_, quality)| quality)
+            .filter(|quality| *quality > Quality::MIN)
+            .ok_or(Rejection::NotAcceptable)
+    }
+}
+
+/// guard passes when request's uri query contains parameter with given name.
+pub fn query(name: &'static str) -> Query {
+    Query(name)
+}
+
+/// See [query].
+#[derive(Clone)]
+pub struct Query(&'static str);
+
+impl<Req> Guard<Req> for Query
+where
+    Req: BorrowReq<Uri>,
+{
+    fn check(&self, req: &Req) -> Result<Quality, Rejection> {
+        req.borrow()
+            .query()
+            .is_some_and(|q| q.split('&').any(|p| p.split('=').next() == Some(self.0)))
+            .then_some(Quality::MAX)
+            .ok_or(Rejection::Unmatched)
+    }
+}
+
+/// guard with custom closure. guard passes when closure returns true.
+///
+/// # Example
+/// ```rust
+/// # use xitca_http::{http::Request, util::service::guard::fn_guard};
+/// fn_guard(|req: &Request<()>| req.uri().path().ends_with(".json"));
+/// ```
+pub fn fn_guard<F>(func: F) -> FnGuard<F> {
+    FnGuard(func)
+}
+
+/// See [fn_guard].
+#[derive(Clone)]
+pub struct FnGuard<F>(F);
+
+impl<F, Req> Guard<Req> for FnGuard<F>
+where
+    F: Fn(&Req) -> bool,
+{
+    fn check(&self, req: &Req) -> Result<Quality, Rejection> {
+        (self.0)(req).then_some(Quality::MAX).ok_or(Rejection::Unmatched)
+    }
+}
+
+/// guard passes when both guards pass with the lower [Quality] of them. rejection of the first failed guard
+/// is returned.
+pub fn and<A, B>(a: A, b: B) -> And<A, B> {
+    And(a, b)
+}
+
+/// See [and].
+#[derive(Clone)]
+pub struct And<A, B>(A, B);
+
+impl<A, B, Req> Guard<Req> for And<A, B>
+where
+    A: Guard<Req>,
+    B: Guard<Req>,
+{
+    fn check(&self, req: &Req) -> Result<Quality, Rejection> {
+        let q = self.0.check(req)?;
+        self.1.check(req).map(|q2| q.min(q2))
+    }
+}
+
+/// guard passes when either of guards passes with the higher [Quality] of passed ones.
+pub fn or<A, B>(a: A, b: B) -> Or<A, B> {
+    Or(a, b)
+}
+
+/// See [or].
+#[derive(Clone)]
+pub struct Or<A, B>(A, B);
+
+impl<A, B, Req> Guard<Req> for Or<A, B>
+where
+    A: Guard<Req>,
+    B: Guard<Req>,
+{
+    fn check(&self, req: &Req) -> Result<Quality, Rejection> {
+        match (self.0.check(req), self.1.check(req)) {
+            (Ok(q), Ok(q2)) => Ok(q.max(q2)),
+            (Ok(q), Err(_)) | (Err(_), Ok(q)) => Ok(q),
+            (Err(e), Err(e2)) => Err(e.merge(e2)),
+        }
+    }
+}
+
+/// guard passes when given guard rejects.
+pub fn not<G>(guard: G) -> Not<G> {
+    Not(guard)
+}
+
+/// See [not].
+#[derive(Clone)]
+pub struct Not<G>(G);
+
+impl<G, Req> Guard<Req> for Not<G>
+where
+    G: Guard<Req>,
+{
+    fn check(&self, req: &Req) -> Result<Quality, Rejection> {
+        match self.0.check(req) {
+            Ok(_) => Err(Rejection::Unmatched),
+            Err(_) => Ok(Quality::MAX),
+        }
+    }
+}
+
+// media type without parameters.
+fn essence(mime: &str) -> &str {
+    mime.split(';').next().unwrap_or_default().trim()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::http::Request;
+
+    use super::*;
+
+    fn req(headers: &[(HeaderName, &'static str)], uri: &str) -> Request<()> {
+        let mut req = Request::builder().uri(uri);
+        for (name, value) in headers {
+            req = req.header(name, *value);
+        }
+        req.body(()).unwrap()
+    }
+
+    #[test]
+    fn accept_guard() {
+        let json = accept("application/json");
+
+        assert_eq!(json.check(&req(&[], "/")), Ok(Quality::MAX));
+        assert_eq!(json.check(&req(&[(ACCEPT, "application/json")], "/")), Ok(Quality::MAX));
+        assert_eq!(
+            json.check(&req(&[(ACCEPT, "text/html, application/*;q=0.8")], "/")),
+            Ok(Quality::new(800))
+        );
+        assert_eq!(json.check(&req(&[(ACCEPT, "*/*")], "/")), Ok(Quality::MAX));
+        // the most specific range wins over earlier and higher quality ones.
+        assert_eq!(
+            json.check(&req(
+                &[(ACCEPT, "*/*, application/*;q=0.5, application/json;q=0.2")],
+                "/"
+            )),
+            Ok(Quality::new(200))
+        );
+        assert_eq!(
+            json.check(&req(&[(ACCEPT, "*/*;q=0.8, application/json;q=0")], "/")),
+            Err(Rejection::NotAcceptable)
+        );
+
+        let accept_browser = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+        assert_eq!(
+            json.check(&req(&[(ACCEPT, accept_browser)], "/")),
+            Ok(Quality::new(800))
+        );
+        assert_eq!(
+            accept("text/html").check(&req(&[(ACCEPT, accept_browser)], "/")),
+            Ok(Quality::MAX)
+        );
+        assert_eq!(
+            json.check(&req(&[(ACCEPT, "text/html, application/json;q=0")], "/")),
+            Err(Rejection::NotAcceptable)
+        );
+        assert_eq!(
+            json.check(&req(&[(ACCEPT, "text/*")], "/")),
+            Err(Rejection::NotAcceptable)
+        );
+    }
+
+    #[test]
+    fn content_type_guard() {
+        let json = content_type("application/json");
+
+        assert!(json
+            .check(&req(&[(CONTENT_TYPE, "Application/JSON; charset=utf-8")], "/"))
+            .is_ok());
+        assert_eq!(json.check(&req(&[], "/")), Err(Rejection::UnsupportedMediaType));
+        assert_eq!(
+            json.check(&req(&[(CONTENT_TYPE, "text/plain")], "/")),
+            Err(Rejection::UnsupportedMediaType)
+        );
+    }
+
+    #[test]
+    fn compose_guard() {
+        let guard = and(
+            or(header(ACCEPT, "text/plain"), header_exists(CONTENT_TYPE)),
+            not(query("debug")),
+        );
+
+        assert!(guard.check(&req(&[(ACCEPT, "text/plain")], "/")).is_ok());
+        assert!(guard.check(&req(&[(CONTENT_TYPE, "text/plain")], "/?page=1")).is_ok());
+        assert!(guard.check(&req(&[(ACCEPT, "text/plain")], "/?page=1&debug")).is_err());
+        assert!(guard.check(&req(&[], "/")).is_err());
+
+        let guard = or(accept("application/json"), accept("text/html"));
+        assert_eq!(
+            guard.check(&req(&[(ACCEPT, "text/html;q=0.5, application/json;q=0.3")], "/")),
+            Ok(Quality::new(500))
+        );
+
+        let guard = and(accept("application/json"), accept("application/xml"));
+        assert_eq!(
+            guard.check(&req(&[(ACCEPT, "application/xml;q=0.5, application/json;q=0.3")], "/")),
+            Ok(Quality::new(300))
+        );
+
+        let guard = fn_guard(|req: &Request<()>| req.uri().path() == "/");
+        assert!(guard.check(&req(&[], "/")).is_ok());
+        assert_eq!(guard.check(&req(&[], "/foo")), Err(Rejection::Unmatched));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod handler;
 
+#[cfg(feature = "router")]
+pub mod guard;
 #[cfg(feature = "router")]
 pub mod route;
 #[cfg(feature = "router")]
@@ -1,6 +1,6 @@
 //! routing [`Service`] with given [`Method`] that support a wide range of http method including custom ones.
 
-use core::{fmt, marker::PhantomData};
+use core::{fmt, future::Future, marker::PhantomData};
 
 use std::error;
 
@@ -8,7 +8,10 @@ use xitca_service::{ready::ReadyService, Service};
 
 use crate::http::{BorrowReq, Method};
 
-use super::router::RouterError;
+use super::{
+    guard::{Guard, Quality, Rejection},
+    router::RouterError,
+};
 
 macro_rules! method {
     ($method_fn: ident, $method: ident) => {
@@ -31,10 +34,14 @@ method!(patch, PATCH);
 method!(trace, TRACE);
 
 /// a tree type able of routing multiple [Method] against multiple [Service] types in linear manner.
-pub struct Route<R, N, const M: usize> {
+///
+/// Route can be guarded by [Guard] for selecting service beyond http method. See [Route::guard] for detail.
+pub struct Route<R, N, const M: usize, G = ()> {
     methods: [Method; M],
     route: R,
     next: N,
+    guard: G,
+    guarded: bool,
 }
 
 type DefaultRoute<R, const M: usize> = Route<R, MethodNotAllowedBuilder<R>, M>;
@@ -60,6 +67,65 @@ impl<const M: usize> DefaultRoute<(), M> {
             methods,
             route,
             next: MethodNotAllowedBuilder::new(),
+            guard: (),
+            guarded: false,
+        }
+    }
+}
+
+impl<R, N, const M: usize> Route<R, N, M> {
+    /// guard current Route with given [Guard]. request with matched method is only routed to the service
+    /// of current Route when guard passes.
+    ///
+    /// guarded Route can share methods with Routes chained after it with [Route::next]. When multiple Routes
+    /// pass their guards the one with highest [Quality] is selected and earlier Route wins when qualities are
+    /// equal. Route without guard has the lowest quality and it's only selected when no guarded Route passes.
+    /// When no Route passes it's guard the request is rejected with [RouterError::Rejected].
+    ///
+    /// guard only applies to current Route. Routes chained with [Route::next] before calling this method
+    /// are not guarded.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use xitca_http::{
+    /// #   http::{header::ACCEPT, Request, RequestExt, Response},
+    /// #   util::service::{guard::{accept, Rejection}, route::get, RouterError},
+    /// # };
+    /// # use xitca_service::{fn_service, Service};
+    /// # use xitca_unsafe_collection::futures::NowOrPanic;
+    /// # async fn json(_: Request<RequestExt<()>>) -> Result<Response<&'static str>, Infallible> {
+    /// #   Ok(Response::new("json"))
+    /// # }
+    /// # async fn html(_: Request<RequestExt<()>>) -> Result<Response<&'static str>, Infallible> {
+    /// #   Ok(Response::new("html"))
+    /// # }
+    /// // two services share GET method and they are selected by Accept header.
+    /// let route = get(fn_service(json))
+    ///     .guard(accept("application/json"))
+    ///     .next(get(fn_service(html)).guard(accept("text/html")));
+    ///
+    /// let service = route.call(()).now_or_panic().unwrap();
+    ///
+    /// let req = |accept| Request::builder().header(ACCEPT, accept).body(Default::default()).unwrap();
+    ///
+    /// let res = service.call(req("text/html")).now_or_panic().unwrap();
+    /// assert_eq!(*res.body(), "html");
+    ///
+    /// // html is preferred by quality of it's media type.
+    /// let res = service.call(req("application/json;q=0.5, text/html")).now_or_panic().unwrap();
+    /// assert_eq!(*res.body(), "html");
+    ///
+    /// let err = service.call(req("image/png")).now_or_panic().err().unwrap();
+    /// assert!(matches!(err, RouterError::Rejected(Rejection::NotAcceptable)));
+    /// ```
+    pub fn guard<G>(self, guard: G) -> Route<R, N, M, G> {
+        Route {
+            methods: self.methods,
+            route: self.route,
+            next: self.next,
+            guard,
+            guarded: true,
         }
     }
 }
@@ -68,36 +134,32 @@ macro_rules! route_method {
     ($method_fn: ident, $method: ident) => {
         #[doc = concat!("appending [Method::",stringify!($method),"] guarded route to current Route.")]
         /// Act as a shortcut of [Route::next].
-        pub fn $method_fn<R1>(self, $method_fn: R1) -> Route<R, Route<R1, N, 1>, M> {
+        pub fn $method_fn<R1>(self, $method_fn: R1) -> Route<R, N::Output, M, G>
+        where
+            N: sealed::Push<R1, 1, ()>,
+        {
             self.next(Route::_new([Method::$method], $method_fn))
         }
     };
 }
 
-impl<R, N, const M: usize> Route<R, N, M> {
-    /// append another Route to existing Route type.
+impl<R, N, const M: usize, G> Route<R, N, M, G> {
+    /// append another Route to the end of existing Route type. chained Routes are matched in the order
+    /// they are appended.
     ///
     /// # Panics
     ///
-    /// panic when chained Routes contain overlapping [Method]. Route only do liner method matching
-    /// and overlapped method(s) will always enter the first Route matched against.
-    pub fn next<R1, const M1: usize>(self, next: DefaultRoute<R1, M1>) -> Route<R, Route<R1, N, M1>, M> {
-        for m in next.methods.iter() {
-            if self.methods.contains(m) {
-                panic!("{m} method already exists. Route can not contain overlapping methods.");
-            }
-        }
-
-        // TODO is this really the intended behavior? insert `next` between `self` and `self.next`?
-        Route {
-            methods: self.methods,
-            route: self.route,
-            next: Route {
-                methods: next.methods,
-                route: next.route,
-                next: self.next,
-            },
-        }
+    /// panic when appended Route contains [Method] overlapping with any existing Route that is not guarded.
+    /// Route only do liner method matching and overlapped method(s) will always enter the first Route
+    /// matched against. See [Route::guard] for sharing methods between Routes.
+    pub fn next<R1, const M1: usize, G1>(
+        self,
+        next: Route<R1, MethodNotAllowedBuilder<R1>, M1, G1>,
+    ) -> Route<R, N::Output, M, G>
+    where
+        N: sealed::Push<R1, M1, G1>,
+    {
+        sealed::Push::push(self, next)
     }
 
     route_method!(get, GET);
@@ -111,13 +173,186 @@ impl<R, N, const M: usize> Route<R, N, M> {
     route_method!(trace, TRACE);
 }
 
-impl<Arg, R, N, const M: usize> Service<Arg> for Route<R, N, M>
+impl<R, N, const M: usize, G> Route<R, N, M, G>
+where
+    N: sealed::Methods,
+{
+    /// collect methods of current Route and it's chained Routes.
+    pub(super) fn methods_into(&self, methods: &mut Vec<Method>) {
+        sealed::Methods::methods_into(self, methods)
+    }
+}
+
+pub(super) mod sealed {
+    use super::*;
+
+    // collect methods of chained Routes.
+    pub trait Methods {
+        fn methods_into(&self, methods: &mut Vec<Method>);
+    }
+
+    impl<R, N, const M: usize, G> Methods for Route<R, N, M, G>
+    where
+        N: Methods,
+    {
+        fn methods_into(&self, methods: &mut Vec<Method>) {
+            // guarded Routes can share methods.
+            for method in self.methods.iter() {
+                if !methods.contains(method) {
+                    methods.push(method.clone());
+                }
+            }
+            self.next.methods_into(methods);
+        }
+    }
+
+    impl<R> Methods for MethodNotAllowedBuilder<R> {
+        fn methods_into(&self, _: &mut Vec<Method>) {}
+    }
+
+    // append Route to the tail of chained Routes.
+    pub trait Push<R1, const M1: usize, G1> {
+        type Output;
+
+        fn push(self, next: Route<R1, MethodNotAllowedBuilder<R1>, M1, G1>) -> Self::Output;
+    }
+
+    impl<R, N, const M: usize, G, R1, const M1: usize, G1> Push<R1, M1, G1> for Route<R, N, M, G>
+    where
+        N: Push<R1, M1, G1>,
+    {
+        type Output = Route<R, N::Output, M, G>;
+
+        fn push(self, next: Route<R1, MethodNotAllowedBuilder<R1>, M1, G1>) -> Self::Output {
+            if !self.guarded {
+                for m in next.methods.iter() {
+                    if self.methods.contains(m) {
+                        panic!("{m} method already exists. Route can not contain overlapping methods.");
+                    }
+                }
+            }
+
+            Route {
+                methods: self.methods,
+                route: self.route,
+                next: self.next.push(next),
+                guard: self.guard,
+                guarded: self.guarded,
+            }
+        }
+    }
+
+    impl<R, R1, const M1: usize, G1> Push<R1, M1, G1> for MethodNotAllowedBuilder<R> {
+        type Output = Route<R1, MethodNotAllowedBuilder<R>, M1, G1>;
+
+        fn push(self, next: Route<R1, MethodNotAllowedBuilder<R1>, M1, G1>) -> Self::Output {
+            Route {
+                methods: next.methods,
+                route: next.route,
+                next: self,
+                guard: next.guard,
+                guarded: next.guarded,
+            }
+        }
+    }
+
+    // route selection state of chained RouteServices.
+    #[derive(Default)]
+    pub struct Selection {
+        // position and quality of the most preferred route.
+        pub(super) route: Option<(usize, Quality)>,
+        // merged rejection of routes with matched method.
+        pub(super) rejection: Option<Rejection>,
+    }
+
+    // select route from chained RouteServices and call it.
+    pub trait Select<Req> {
+        type Response;
+        type Error;
+
+        fn select(&self, req: &Req, nth: usize, selection: &mut Selection);
+
+        fn call_nth(&self, nth: usize, req: Req) -> impl Future<Output = Result<Self::Response, Self::Error>>;
+
+        fn allowed_into(&self, methods: &mut Vec<Method>);
+    }
+
+    impl<R, N, G, Req, const M: usize> Select<Req> for RouteService<R, N, M, G>
+    where
+        R: Service<Req>,
+        N: Select<Req, Response = R::Response, Error = R::Error>,
+        G: Guard<Req>,
+        Req: BorrowReq<Method>,
+    {
+        type Response = R::Response;
+        type Error = R::Error;
+
+        fn select(&self, req: &Req, nth: usize, selection: &mut Selection) {
+            if self.methods.contains(req.borrow()) {
+                match self.guard.check(req) {
+                    Ok(quality) => {
+                        if selection.route.is_none_or(|(_, q)| quality > q) {
+                            selection.route = Some((nth, quality));
+                        }
+                        // later route can not be more preferred.
+                        if quality == Quality::MAX {
+                            return;
+                        }
+                    }
+                    Err(rejection) => {
+                        selection.rejection = Some(match selection.rejection {
+                            Some(rejected) => rejected.merge(rejection),
+                            None => rejection,
+                        })
+                    }
+                }
+            }
+            self.next.select(req, nth + 1, selection)
+        }
+
+        async fn call_nth(&self, nth: usize, req: Req) -> Result<Self::Response, Self::Error> {
+            if nth == 0 {
+                self.route.call(req).await
+            } else {
+                self.next.call_nth(nth - 1, req).await
+            }
+        }
+
+        fn allowed_into(&self, methods: &mut Vec<Method>) {
+            self.next.allowed_into(methods);
+            for method in self.methods.iter() {
+                if !methods.contains(method) {
+                    methods.push(method.clone());
+                }
+            }
+        }
+    }
+
+    impl<R, Req> Select<Req> for MethodNotAllowedService<R>
+    where
+        R: Service<Req>,
+    {
+        type Response = R::Response;
+        type Error = R::Error;
+
+        fn select(&self, _: &Req, _: usize, _: &mut Selection) {}
+
+        async fn call_nth(&self, _: usize, _: Req) -> Result<Self::Response, Self::Error> {
+            unreachable!("selected route is out of bound of chained routes")
+        }
+
+        fn allowed_into(&self, _: &mut Vec<Method>) {}
+    }
+}
+
+impl<Arg, R, N, G, const M: usize> Service<Arg> for Route<R, N, M, G>
 where
     R: Service<Arg>,
     N: Service<Arg, Error = R::Error>,
+    G: Clone,
     Arg: Clone,
 {
-    type Response = RouteService<R::Response, N::Response, M>;
+    type Response = RouteService<R::Response, N::Response, M, G>;
     type Error = R::Error;
 
     async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
@@ -127,48 +362,58 @@ where
             methods: self.methods.clone(),
             route,
             next,
+            guard: self.guard.clone(),
         })
     }
 }
 
-pub struct RouteService<R, N, const M: usize> {
+pub struct RouteService<R, N, const M: usize, G = ()> {
     methods: [Method; M],
     route: R,
     next: N,
+    guard: G,
 }
 
-impl<R, N, Req, E, const M: usize> Service<Req> for RouteService<R, N, M>
+impl<R, N, G, Req, const M: usize> Service<Req> for RouteService<R, N, M, G>
 where
-    R: Service<Req, Error = E>,
-    N: Service<Req, Response = R::Response, Error = RouterError<E>>,
-    Req: BorrowReq<Method>,
+    Self: sealed::Select<Req>,
 {
-    type Response = R::Response;
-    type Error = RouterError<E>;
+    type Response = <Self as sealed::Select<Req>>::Response;
+    type Error = RouterError<<Self as sealed::Select<Req>>::Error>;
 
     #[inline]
     async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
-        if self.methods.contains(req.borrow()) {
-            self.route.call(req).await.map_err(RouterError::Service)
-        } else {
-            self.next
-                .call(req)
+        let mut selection = sealed::Selection::default();
+        sealed::Select::select(self, &req, 0, &mut selection);
+        match selection.route {
+            Some((nth, _)) => sealed::Select::call_nth(self, nth, req)
                 .await
-                .map_err(|e| try_append_allowed(e, &self.methods))
+                .map_err(RouterError::Service),
+            None => Err(self.reject(selection.rejection)),
         }
     }
 }
 
-#[cold]
-#[inline(never)]
-fn try_append_allowed<E>(mut e: RouterError<E>, methods: &[Method]) -> RouterError<E> {
-    if let RouterError::NotAllowed(ref mut e) = e {
-        e.0.extend_from_slice(methods);
+impl<R, N, const M: usize, G> RouteService<R, N, M, G> {
+    // request method is allowed but guards of routes rejected it. otherwise method is not allowed.
+    #[cold]
+    #[inline(never)]
+    fn reject<Req, E>(&self, rejection: Option<Rejection>) -> RouterError<E>
+    where
+        Self: sealed::Select<Req>,
+    {
+        match rejection {
+            Some(rejection) => RouterError::Rejected(rejection),
+            None => {
+                let mut methods = Vec::new();
+                sealed::Select::<Req>::allowed_into(self, &mut methods);
+                RouterError::NotAllowed(MethodNotAllowed(Box::new(methods)))
+            }
+        }
     }
-    e
 }
 
-impl<R, N, const M: usize> ReadyService for RouteService<R, N, M> {
+impl<R, N, G, const M: usize> ReadyService for RouteService<R, N, M, G> {
     type Ready = ();
 
     #[inline]
@@ -350,9 +595,9 @@ mod test {
         assert_eq!(allowed.len(), 5);
         // strict allowed method order does not matter.
         // as long as the test can produce deterministic prediction it's fine.
-        assert_eq!(allowed[0], Method::GET);
+        assert_eq!(allowed[0], Method::TRACE);
         assert_eq!(allowed[1], Method::OPTIONS);
-        assert_eq!(allowed[2], Method::TRACE);
+        assert_eq!(allowed[2], Method::GET);
         assert_eq!(allowed[3], Method::POST);
         assert_eq!(allowed[4], Method::PUT);
 
@@ -367,6 +612,163 @@ mod test {
         assert_eq!(res.status().as_u16(), 200);
     }
 
+    #[test]
+    fn route_guard() {
+        use crate::{
+            http::header::{HeaderValue, CONTENT_TYPE},
+            util::service::guard::{content_type, query},
+        };
+
+        async fn form(_: Request<RequestBody>) -> Result<Response<ResponseBody>, Infallible> {
+            let mut res = Response::new(ResponseBody::none());
+            *res.status_mut() = crate::http::StatusCode::ACCEPTED;
+            Ok(res)
+        }
+
+        let route = post(fn_service(index))
+            .guard(content_type("application/json"))
+            .next(post(fn_service(form)).guard(content_type("application/x-www-form-urlencoded")))
+            .next(get(fn_service(index)).guard(query("page")));
+
+        let service = route.call(()).now_or_panic().ok().unwrap();
+
+        let req = |method, mime: Option<&'static str>| {
+            let mut req = Request::new(RequestBody::None);
+            *req.method_mut() = method;
+            if let Some(mime) = mime {
+                req.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(mime));
+            }
+            req
+        };
+
+        let res = service.call(req(Method::POST, Some("application/json"))).now_or_panic();
+        assert_eq!(res.ok().unwrap().status().as_u16(), 200);
+
+        let res = service
+            .call(req(Method::POST, Some("application/x-www-form-urlencoded")))
+            .now_or_panic();
+        assert_eq!(res.ok().unwrap().status().as_u16(), 202);
+
+        let err = service
+            .call(req(Method::POST, Some("text/plain")))
+            .now_or_panic()
+            .err()
+            .unwrap();
+        assert!(matches!(err, RouterError::Rejected(Rejection::UnsupportedMediaType)));
+
+        let err = service.call(req(Method::GET, None)).now_or_panic().err().unwrap();
+        assert!(matches!(err, RouterError::Rejected(Rejection::Unmatched)));
+
+        let RouterError::NotAllowed(e) = service.call(req(Method::PUT, None)).now_or_panic().err().unwrap() else {
+            panic!("route does not return error on unallowed method request");
+        };
+        assert_eq!(e.allowed_methods(), [Method::GET, Method::POST]);
+    }
+
+    #[test]
+    fn route_guard_order() {
+        use crate::{
+            http::header::{HeaderValue, ACCEPT},
+            util::service::guard::accept,
+        };
+
+        async fn body(_: Request<RequestBody>, body: &'static str) -> Result<Response<&'static str>, Infallible> {
+            Ok(Response::new(body))
+        }
+
+        let route = get(fn_service(|req| body(req, "json")))
+            .guard(accept("application/json"))
+            .next(get(fn_service(|req| body(req, "html"))).guard(accept("text/html")))
+            .next(get(fn_service(|req| body(req, "fallback"))));
+
+        let service = route.call(()).now_or_panic().ok().unwrap();
+
+        let req = |accept: &'static str| {
+            let mut req = Request::new(RequestBody::None);
+            req.headers_mut().insert(ACCEPT, HeaderValue::from_static(accept));
+            req
+        };
+
+        let res = service.call(req("application/json")).now_or_panic().ok().unwrap();
+        assert_eq!(*res.body(), "json");
+
+        let res = service.call(req("text/html")).now_or_panic().ok().unwrap();
+        assert_eq!(*res.body(), "html");
+
+        let res = service.call(req("image/png")).now_or_panic().ok().unwrap();
+        assert_eq!(*res.body(), "fallback");
+    }
+
+    #[test]
+    fn route_guard_quality() {
+        use crate::{
+            http::header::{HeaderValue, ACCEPT},
+            util::service::guard::accept,
+        };
+
+        async fn body(_: Request<RequestBody>, body: &'static str) -> Result<Response<&'static str>, Infallible> {
+            Ok(Response::new(body))
+        }
+
+        let route = get(fn_service(|req| body(req, "json")))
+            .guard(accept("application/json"))
+            .next(get(fn_service(|req| body(req, "html"))).guard(accept("text/html")))
+            .next(get(fn_service(|req| body(req, "fallback"))));
+
+        let service = route.call(()).now_or_panic().ok().unwrap();
+
+        let req = |accept: &'static str| {
+            let mut req = Request::new(RequestBody::None);
+            req.headers_mut().insert(ACCEPT, HeaderValue::from_static(accept));
+            req
+        };
+
+        // accept header of browser navigation. json route only matches */* with lower quality.
+        let res = service
+            .call(req(
+                "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8",
+            ))
+            .now_or_panic()
+            .ok()
+            .unwrap();
+        assert_eq!(*res.body(), "html");
+
+        let res = service
+            .call(req("text/html;q=0.5, application/json;q=0.9"))
+            .now_or_panic()
+            .ok()
+            .unwrap();
+        assert_eq!(*res.body(), "json");
+
+        // earlier route wins on equal quality.
+        let res = service.call(req("*/*")).now_or_panic().ok().unwrap();
+        assert_eq!(*res.body(), "json");
+
+        let res = service
+            .call(req("text/html;q=0.5, application/json;q=0.5"))
+            .now_or_panic()
+            .ok()
+            .unwrap();
+        assert_eq!(*res.body(), "json");
+
+        // unguarded route is only selected when no guarded route passes.
+        let res = service
+            .call(req("text/html;q=0.1, application/json;q=0"))
+            .now_or_panic()
+            .ok()
+            .unwrap();
+        assert_eq!(*res.body(), "html");
+    }
+
+    #[test]
+    #[should_panic]
+    fn route_guard_overlap_panic() {
+        let _ = get(fn_service(index))
+            .guard(crate::util::service::guard::query("page"))
+            .next(get(fn_service(index)))
+            .next(get(fn_service(index)));
+    }
+
     #[test]
     fn route_accept_crate_request() {
         get(fn_service(|_: Request<()>| async {
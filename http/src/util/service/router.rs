@@ -1,4 +1,4 @@
-pub use xitca_router::{params::Params, MatchError};
+pub use xitca_router::{params::Params, InsertError, MatchError, Recommendation};
 
 use core::{fmt, marker::PhantomData};
 
@@ -6,14 +6,18 @@ use std::{collections::HashMap, error};
 
 use xitca_service::{object::BoxedServiceObject, pipeline::PipelineT, BoxFuture, FnService, Service};
 
-use crate::http::Request;
+use crate::http::{Extensions, Method, Request};
 
 use super::{
+    guard::Rejection,
     handler::HandlerService,
-    route::{MethodNotAllowed, Route},
+    route::{sealed::Methods, MethodNotAllowed, Route},
 };
 
-pub use self::object::RouteObject;
+pub use self::{
+    dynamic::{DynamicRouter, DynamicRouterService, RouteTable, RouterHandle},
+    object::RouteObject,
+};
 
 /// Simple router for matching path and call according service.
 ///
@@ -23,6 +27,11 @@ pub struct Router<Obj> {
     // record for last time PathGen is called with certain route string prefix.
     prefix: Option<usize>,
     routes: HashMap<String, Obj>,
+    hosts: HashMap<&'static str, Obj>,
+    names: HashMap<&'static str, String>,
+    // host patterns of named routes registered to host services.
+    host_names: HashMap<&'static str, &'static str>,
+    recommend: bool,
 }
 
 impl<Obj> Default for Router<Obj> {
@@ -36,8 +45,52 @@ impl<Obj> Router<Obj> {
         Router {
             prefix: None,
             routes: HashMap::new(),
+            hosts: HashMap::new(),
+            names: HashMap::new(),
+            host_names: HashMap::new(),
+            recommend: false,
         }
     }
+
+    /// Enable path recommendation for request path failed to match any route. When a trailing slash fixed
+    /// or case insensitive fixed path can be found the router service would produce [RouterError::Recommend]
+    /// instead of [RouterError::Match].
+    ///
+    /// Path recommendation is only applied to routes directly inserted into the router. Nested routers
+    /// must enable it separately.
+    pub fn enable_recommend(mut self) -> Self {
+        self.recommend = true;
+        self
+    }
+
+    /// Iterate over named routes and their paths. Names of nested routers are included and their paths
+    /// are prefixed with the path nested routers are inserted to.
+    ///
+    /// See [Router::insert_named] for detail.
+    pub fn named_paths(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        self.names.iter().map(|(name, path)| (*name, path.as_str()))
+    }
+
+    /// Collect information of routes registered to router and it's nested routers, sorted by host and path.
+    ///
+    /// Paths of routes from nested routers are prefixed with the path nested routers are inserted to.
+    /// Routes of host services inserted with [Router::insert_host] carry the host pattern.
+    pub fn routes(&self) -> Vec<RouteInfo>
+    where
+        Obj: PathGen,
+    {
+        let mut routes = Vec::new();
+        self.route_info(RouteInfo::default(), &mut routes);
+        routes.sort_by(|a, b| (a.host, &a.path).cmp(&(b.host, &b.path)));
+        routes
+    }
+
+    fn insert_name(&mut self, name: &'static str, path: String) {
+        assert!(
+            self.names.insert(name, path).is_none(),
+            "route name \"{name}\" is already registered"
+        );
+    }
 }
 
 impl<Obj> Router<Obj> {
@@ -55,6 +108,9 @@ impl<Obj> Router<Obj> {
         Req: IntoObject<F::Route<F>, Arg, Object = Obj>,
     {
         let path = builder.path_gen(path);
+        for (name, path) in builder.named_path_gen() {
+            self.insert_name(name, path);
+        }
         assert!(self
             .routes
             .insert(path, Req::into_object(F::route_gen(builder)))
@@ -62,6 +118,69 @@ impl<Obj> Router<Obj> {
         self
     }
 
+    /// Insert a new service builder to given path with a name. The name can be used to look up the path
+    /// for generating url of the route. See [Router::insert] for detail.
+    ///
+    /// # Panic:
+    ///
+    /// When multiple services inserted to the same path or multiple routes registered with the same name.
+    pub fn insert_named<F, Arg, Req>(mut self, name: &'static str, path: &'static str, builder: F) -> Self
+    where
+        F: Service<Arg> + RouteGen + Send + Sync,
+        F::Response: Service<Req>,
+        Req: IntoObject<F::Route<F>, Arg, Object = Obj>,
+    {
+        self.insert_name(name, String::from(path));
+        self.insert(path, builder)
+    }
+
+    /// Insert a new service builder to given host pattern. Request with host matching the pattern is
+    /// dispatched to the service before matching request path against routes of current router.
+    /// Request with unmatched host falls back to path routing of current router.
+    ///
+    /// Request host is taken from uri authority (http/2 and http/3 `:authority` pseudo header) or `Host`
+    /// header with port stripped and ascii lowercased. Host pattern shares the syntax of path and
+    /// parameters captured from it are kept in front of path parameters:
+    ///
+    /// - exact host: `"example.com"`
+    /// - wildcard subdomain with captured parameter: `":sub.example.com"`
+    ///
+    /// `:` always starts a parameter in host pattern and ipv6 address can not be used as exact host.
+    ///
+    /// Routes registered to the service builder are matched against the same request path as routes of
+    /// current router. Named routes of the service builder are registered to current router and they share
+    /// the same name space. Their paths can be looked up with [Router::named_paths] without the host.
+    ///
+    /// # Panic:
+    ///
+    /// When multiple services inserted to the same host pattern, host pattern is invalid or conflicting with
+    /// existing host pattern (ex: `"*.example.com"`) or multiple routes registered with the same name.
+    pub fn insert_host<F, Arg, Req>(mut self, host: &'static str, mut builder: F) -> Self
+    where
+        F: Service<Arg> + RouteGen + Send + Sync,
+        F::Response: Service<Req>,
+        Req: IntoObject<F::Route<F>, Arg, Object = Obj>,
+    {
+        // host patterns are inserted to router when building service. validate them early to avoid panic there.
+        let mut hosts = xitca_router::Router::new();
+        for pattern in self.hosts.keys().chain([&host]) {
+            if let Err(e) = hosts.insert(*pattern, ()) {
+                panic!("host pattern \"{host}\" can not be inserted: {e}");
+            }
+        }
+
+        for (name, path) in builder.named_path_gen() {
+            self.insert_name(name, path);
+            self.host_names.insert(name, host);
+        }
+
+        assert!(self
+            .hosts
+            .insert(host, Req::into_object(F::route_gen(builder)))
+            .is_none());
+        self
+    }
+
     #[doc(hidden)]
     /// See [TypedRoute] for detail.
     pub fn insert_typed<T, M>(mut self, _: T) -> Router<Obj>
@@ -69,6 +188,9 @@ impl<Obj> Router<Obj> {
         T: TypedRoute<M, Route = Obj>,
     {
         let path = T::path();
+        if let Some(name) = T::name() {
+            self.insert_name(name, String::from(path));
+        }
         let route = T::route();
         assert!(self.routes.insert(String::from(path), route).is_none());
         self
@@ -91,9 +213,21 @@ where
             router.insert(path.to_string(), service).unwrap();
         }
 
+        let mut hosts = None;
+
+        for (host, service) in self.hosts.iter() {
+            let service = service.call(arg.clone()).await?;
+            hosts
+                .get_or_insert_with(xitca_router::Router::new)
+                .insert(*host, service)
+                .unwrap();
+        }
+
         Ok(service::RouterService {
             prefix: self.prefix,
+            recommend: self.recommend,
             router,
+            hosts,
         })
     }
 }
@@ -102,8 +236,14 @@ where
 pub enum RouterError<E> {
     /// failed to match on a routed service.
     Match(MatchError),
+    /// failed to match on a routed service but a recommended path can be matched.
+    /// See [Router::enable_recommend] for detail.
+    Recommend(Recommendation),
     /// a match of service is found but it's not allowed for access.
     NotAllowed(MethodNotAllowed),
+    /// a match of service with allowed method is found but request is rejected by it's guard.
+    /// See [Route::guard] for detail.
+    Rejected(Rejection),
     /// error produced by routed service.
     Service(E),
 }
@@ -115,7 +255,9 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             Self::Match(ref e) => fmt::Debug::fmt(e, f),
+            Self::Recommend(ref e) => fmt::Debug::fmt(e, f),
             Self::NotAllowed(ref e) => fmt::Debug::fmt(e, f),
+            Self::Rejected(ref e) => fmt::Debug::fmt(e, f),
             Self::Service(ref e) => fmt::Debug::fmt(e, f),
         }
     }
@@ -128,7 +270,9 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             Self::Match(ref e) => fmt::Display::fmt(e, f),
+            Self::Recommend(ref e) => fmt::Display::fmt(e, f),
             Self::NotAllowed(ref e) => fmt::Display::fmt(e, f),
+            Self::Rejected(ref e) => fmt::Display::fmt(e, f),
             Self::Service(ref e) => fmt::Display::fmt(e, f),
         }
     }
@@ -136,6 +280,54 @@ where
 
 impl<E> error::Error for RouterError<E> where E: error::Error {}
 
+/// information of route registered to [Router]. See [Router::routes] for detail.
+#[derive(Clone, Debug, Default)]
+pub struct RouteInfo {
+    host: Option<&'static str>,
+    path: String,
+    methods: Vec<Method>,
+    name: Option<&'static str>,
+    extensions: Extensions,
+}
+
+impl RouteInfo {
+    /// host pattern of route. See [Router::insert_host] for detail.
+    #[inline]
+    pub fn host(&self) -> Option<&'static str> {
+        self.host
+    }
+
+    /// full path of route.
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// methods route accepts. empty when route accepts any method.
+    #[inline]
+    pub fn methods(&self) -> &[Method] {
+        &self.methods
+    }
+
+    /// name of route. See [Router::insert_named] for detail.
+    #[inline]
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// extended information of route attached by service builders.
+    #[inline]
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// mutable reference of extended information of route.
+    #[inline]
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+}
+
 /// trait for specialized route generation when utilizing [Router::insert].
 pub trait PathGen {
     /// path generator.
@@ -144,6 +336,22 @@ pub trait PathGen {
     fn path_gen(&mut self, prefix: &str) -> String {
         String::from(prefix)
     }
+
+    /// named path generator. called after [PathGen::path_gen] and the returned route names and their
+    /// paths are registered to the router service builder is inserted to.
+    ///
+    /// default to no named path.
+    fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+        HashMap::new()
+    }
+
+    /// route information generator. called by [Router::routes] with information of current route and
+    /// the information of current route and it's nested routes should be pushed to routes.
+    ///
+    /// default to push the information of current route as is.
+    fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+        routes.push(info);
+    }
 }
 
 /// trait for specialized route generation when utilizing [Router::insert].
@@ -179,10 +387,52 @@ where
             v.path_gen(path.as_str());
         });
 
+        // routes of host services are matched against full request path. they share the same prefix.
+        self.hosts.iter_mut().for_each(|(_, v)| {
+            v.path_gen(path.as_str());
+        });
+
+        self.names.values_mut().for_each(|v| v.insert_str(0, path.as_str()));
+
         path.push_str("/*");
 
         path
     }
+
+    fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+        core::mem::take(&mut self.names)
+    }
+
+    fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+        let prefix = info.path.strip_suffix("/*").unwrap_or(&info.path);
+        let start = routes.len();
+
+        for (path, v) in self.routes.iter() {
+            let mut info = info.clone();
+            info.path = format!("{prefix}{path}");
+            v.route_info(info, routes);
+        }
+
+        for (host, v) in self.hosts.iter() {
+            let mut info = info.clone();
+            info.host = Some(host);
+            info.path = format!("{prefix}/*");
+            v.route_info(info, routes);
+        }
+
+        // names of nested routers are taken by current router and routes are named by their paths. name
+        // registered by host service only names route of the same host. a name only names one route.
+        for i in start..routes.len() {
+            if routes[i].name.is_some() {
+                continue;
+            }
+            routes[i].name = self.names.iter().find_map(|(name, path)| {
+                let host_matched = self.host_names.get(name).is_none_or(|h| Some(*h) == routes[i].host);
+                let named = routes[start..].iter().any(|r| r.name == Some(*name));
+                (*path == routes[i].path && host_matched && !named).then_some(*name)
+            });
+        }
+    }
 }
 
 impl<Obj> RouteGen for Router<Obj>
@@ -196,9 +446,20 @@ where
     }
 }
 
-impl<R, N, const M: usize> PathGen for Route<R, N, M> {}
+impl<R, N, const M: usize, G> PathGen for Route<R, N, M, G>
+where
+    N: Methods,
+{
+    fn route_info(&self, mut info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+        self.methods_into(&mut info.methods);
+        routes.push(info);
+    }
+}
 
-impl<R, N, const M: usize> RouteGen for Route<R, N, M> {
+impl<R, N, const M: usize, G> RouteGen for Route<R, N, M, G>
+where
+    N: Methods,
+{
     type Route<R1> = R1;
 
     fn route_gen<R1>(route: R1) -> Self::Route<R1> {
@@ -233,6 +494,14 @@ where
     fn path_gen(&mut self, prefix: &str) -> String {
         self.first.path_gen(prefix)
     }
+
+    fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+        self.first.named_path_gen()
+    }
+
+    fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+        self.first.route_info(info, routes)
+    }
 }
 
 impl<F, S, M> RouteGen for PipelineT<F, S, M>
@@ -256,6 +525,14 @@ where
     fn path_gen(&mut self, prefix: &str) -> String {
         self.0.path_gen(prefix)
     }
+
+    fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+        self.0.named_path_gen()
+    }
+
+    fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+        self.0.route_info(info, routes)
+    }
 }
 
 impl<S> RouteGen for RouterMapErr<S>
@@ -327,6 +604,14 @@ mod object {
         fn path_gen(&mut self, prefix: &str) -> String {
             self.0.path_gen(prefix)
         }
+
+        fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+            self.0.named_path_gen()
+        }
+
+        fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+            self.0.route_info(info, routes)
+        }
     }
 
     impl<Arg, S, E> RouteGen for RouteObject<Arg, S, E> {
@@ -365,6 +650,14 @@ where
             fn path_gen(&mut self, prefix: &str) -> String {
                 self.0.path_gen(prefix)
             }
+
+            fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+                self.0.named_path_gen()
+            }
+
+            fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+                self.0.route_info(info, routes)
+            }
         }
 
         impl<T, Req> RouteGen for Builder<T, Req>
@@ -406,28 +699,91 @@ pub trait TypedRoute<M = ()> {
     /// method for providing matching path of Self::Route.
     fn path() -> &'static str;
 
+    /// method for providing name of Self::Route. See [Router::insert_named] for detail.
+    ///
+    /// default to unnamed route.
+    fn name() -> Option<&'static str> {
+        None
+    }
+
     /// method for generating typed route.
     fn route() -> Self::Route;
 }
 
+mod dynamic;
+
 mod service {
+    use std::borrow::Cow;
+
     use xitca_service::ready::ReadyService;
 
-    use crate::http::{BorrowReq, BorrowReqMut, Uri};
+    use crate::http::{header::HOST, BorrowReq, BorrowReqMut, HeaderMap, Uri};
 
-    use super::{Params, RouterError, Service};
+    use super::{MatchError, Params, RouterError, Service};
 
     pub struct RouterService<S> {
         // a length record of prefix of current router.
         // when it's Some the request path has to be sliced to exclude the string path prefix.
         pub(super) prefix: Option<usize>,
+        pub(super) recommend: bool,
         pub(super) router: xitca_router::Router<S>,
+        pub(super) hosts: Option<xitca_router::Router<S>>,
+    }
+
+    impl<S> RouterService<S> {
+        #[cold]
+        #[inline(never)]
+        fn match_error<E>(&self, full_path: &str, path: &str, e: MatchError) -> RouterError<E> {
+            if self.recommend {
+                if let Some(mut recommend) = self.router.recommend(path) {
+                    // add back the prefix path sliced by parent router.
+                    recommend
+                        .path_mut()
+                        .insert_str(0, &full_path[..full_path.len() - path.len()]);
+                    return RouterError::Recommend(recommend);
+                }
+            }
+            RouterError::Match(e)
+        }
+
+        fn match_host<Req>(&self, req: &Req) -> Option<xitca_router::Match<&S>>
+        where
+            Req: BorrowReq<Uri> + BorrowReq<HeaderMap>,
+        {
+            let hosts = self.hosts.as_ref()?;
+            let host = host(req)?;
+            hosts.at(&host).ok()
+        }
+    }
+
+    // request host from uri authority or host header with port stripped and ascii lowercased.
+    fn host<Req>(req: &Req) -> Option<Cow<'_, str>>
+    where
+        Req: BorrowReq<Uri> + BorrowReq<HeaderMap>,
+    {
+        let host = match BorrowReq::<Uri>::borrow(req).host() {
+            Some(host) => host,
+            None => {
+                let host = BorrowReq::<HeaderMap>::borrow(req).get(HOST)?.to_str().ok()?;
+                match host.rfind(':') {
+                    // colon inside brackets is part of ipv6 address.
+                    Some(i) if !host[i..].contains(']') => &host[..i],
+                    _ => host,
+                }
+            }
+        };
+
+        Some(if host.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(host.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(host)
+        })
     }
 
     impl<S, Req, E> Service<Req> for RouterService<S>
     where
         S: Service<Req, Error = RouterError<E>>,
-        Req: BorrowReq<Uri> + BorrowReqMut<Params>,
+        Req: BorrowReq<Uri> + BorrowReq<HeaderMap> + BorrowReqMut<Params>,
     {
         type Response = S::Response;
         type Error = S::Error;
@@ -438,14 +794,23 @@ mod service {
         #[inline]
         fn call(&self, mut req: Req) -> impl core::future::Future<Output = Result<Self::Response, Self::Error>> {
             async {
-                let mut path = req.borrow().path();
+                if let Some(xitca_router::Match { value, params }) = self.match_host(&req) {
+                    let p = BorrowReqMut::<Params>::borrow_mut(&mut req);
+                    p.replace(params);
+                    p.retain();
+                    return Service::call(value, req).await;
+                }
+
+                let full_path = BorrowReq::<Uri>::borrow(&req).path();
+                let mut path = full_path;
 
                 if let Some(prefix) = self.prefix {
                     path = &path[prefix..];
                 }
 
-                let xitca_router::Match { value, params } = self.router.at(path).map_err(RouterError::Match)?;
-                *req.borrow_mut() = params;
+                let xitca_router::Match { value, params } =
+                    self.router.at(path).map_err(|e| self.match_error(full_path, path, e))?;
+                req.borrow_mut().replace(params);
                 Service::call(value, req).await
             }
         }
@@ -622,6 +987,199 @@ mod test {
             .unwrap();
     }
 
+    #[test]
+    fn router_recommend() {
+        let req = |uri| Request::builder().uri(uri).body(Default::default()).unwrap();
+
+        let service = Router::new()
+            .insert("/users/", fn_service(func))
+            .insert(
+                "/api",
+                Router::new().insert("/v2/login", fn_service(func)).enable_recommend(),
+            )
+            .enable_recommend()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let recommend = |uri| match service.call(req(uri)).now_or_panic() {
+            Err(RouterError::Recommend(r)) => Some(r),
+            Err(RouterError::Match(_)) => None,
+            _ => panic!("unexpected router result"),
+        };
+
+        assert_eq!(
+            recommend("/users").unwrap(),
+            Recommendation::TrailingSlash("/users/".into())
+        );
+        assert_eq!(
+            recommend("/USERS").unwrap(),
+            Recommendation::CaseInsensitive("/users/".into())
+        );
+        assert_eq!(
+            recommend("/api/V2/Login/").unwrap(),
+            Recommendation::CaseInsensitive("/api/v2/login".into())
+        );
+        assert!(recommend("/foo").is_none());
+
+        let service = Router::new()
+            .insert("/users/", fn_service(func))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+        assert!(matches!(
+            service.call(req("/users")).now_or_panic(),
+            Err(RouterError::Match(_))
+        ));
+    }
+
+    #[test]
+    fn router_host() {
+        async fn sub(req: Request<RequestExt<()>>) -> Result<Response<()>, Infallible> {
+            assert_eq!(req.body().params().get("sub"), Some("foo"));
+            assert_eq!(req.body().params().get("id"), Some("996"));
+            let mut res = Response::new(());
+            *res.status_mut() = crate::http::StatusCode::ACCEPTED;
+            Ok(res)
+        }
+
+        let service = Router::new()
+            .insert_host("localhost", fn_service(func))
+            .insert_host(":sub.example.com", Router::new().insert("/users/:id", fn_service(sub)))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |uri, host| {
+            let req = Request::builder()
+                .uri(uri)
+                .header(crate::http::header::HOST, host)
+                .body(Default::default())
+                .unwrap();
+            service.call(req).now_or_panic().map(|res| res.status().as_u16())
+        };
+
+        assert_eq!(call("/", "localhost:8080").ok(), Some(200));
+        assert_eq!(call("/users/996", "Foo.Example.com").ok(), Some(202));
+        assert!(matches!(call("/users/996", "example.com"), Err(RouterError::Match(_))));
+    }
+
+    #[test]
+    fn router_dynamic() {
+        let func = fn_service(func).call(()).now_or_panic().unwrap();
+
+        let handle = RouterHandle::new();
+        handle.insert("/users/:id", func.clone()).unwrap();
+
+        let service = Router::new()
+            .insert("/gw", DynamicRouter::new(handle.clone()))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |uri| {
+            let req = Request::builder().uri(uri).body(Default::default()).unwrap();
+            service.call(req).now_or_panic().map(|res| res.status().as_u16())
+        };
+
+        assert_eq!(call("/gw/users/996").ok(), Some(200));
+        assert!(matches!(call("/gw/posts/1"), Err(RouterError::Match(_))));
+
+        let mut table = RouteTable::new();
+        table.insert("/posts/:id", func.clone()).unwrap();
+        handle.merge(table).unwrap();
+        assert_eq!(call("/gw/posts/1").ok(), Some(200));
+
+        // failed update leaves routing table unchanged.
+        assert!(handle.insert("/posts/:name", func.clone()).is_err());
+        assert_eq!(call("/gw/posts/1").ok(), Some(200));
+
+        assert!(handle.remove("/users/:id").unwrap().is_some());
+        assert!(matches!(call("/gw/users/996"), Err(RouterError::Match(_))));
+
+        let old = handle.swap(RouteTable::new());
+        assert!(old.at("/posts/1").is_ok());
+        assert!(matches!(call("/gw/posts/1"), Err(RouterError::Match(_))));
+    }
+
+    #[test]
+    fn router_routes() {
+        use crate::util::service::route::{get, post};
+
+        let routes = Router::new()
+            .insert("/", fn_service(func))
+            .insert(
+                "/api",
+                Router::new().insert_named("user", "/users/:id", get(fn_service(func)).post(fn_service(func))),
+            )
+            .insert_host(
+                "localhost",
+                Router::new().insert_named("home", "/", post(fn_service(func))),
+            )
+            .routes();
+
+        assert_eq!(routes.len(), 3);
+
+        assert_eq!(routes[0].host(), None);
+        assert_eq!(routes[0].path(), "/");
+        assert!(routes[0].methods().is_empty());
+
+        assert_eq!(routes[1].path(), "/api/users/:id");
+        assert_eq!(routes[1].methods(), [Method::GET, Method::POST]);
+        assert_eq!(routes[1].name(), Some("user"));
+
+        assert_eq!(routes[2].host(), Some("localhost"));
+        assert_eq!(routes[2].path(), "/");
+        assert_eq!(routes[2].methods(), [Method::POST]);
+        assert_eq!(routes[2].name(), Some("home"));
+        // unnamed route sharing path with named route of host service.
+        assert_eq!(routes[0].name(), None);
+    }
+
+    #[test]
+    fn router_host_named() {
+        let router = Router::new().insert(
+            "/api",
+            Router::new().insert_named("index", "/", fn_service(func)).insert_host(
+                ":sub.example.com",
+                Router::new().insert_named("user", "/users/:id", fn_service(func)),
+            ),
+        );
+
+        let mut names = router.named_paths().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, [("index", "/api/"), ("user", "/api/users/:id")]);
+
+        let routes = router.routes();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].host(), None);
+        assert_eq!(routes[0].name(), Some("index"));
+        assert_eq!(routes[1].host(), Some(":sub.example.com"));
+        assert_eq!(routes[1].name(), Some("user"));
+
+        let service = router.call(()).now_or_panic().unwrap();
+        let req = Request::builder()
+            .uri("/api/users/996")
+            .header(crate::http::header::HOST, "foo.example.com")
+            .body(Default::default())
+            .unwrap();
+        assert!(service.call(req).now_or_panic().is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn router_host_invalid_panic() {
+        let _ = Router::new().insert_host("*.example.com", fn_service(func));
+    }
+
+    #[test]
+    #[should_panic]
+    fn router_host_conflict_panic() {
+        let _ = Router::new()
+            .insert_host(":sub.example.com", fn_service(func))
+            .insert_host(":name.example.com", fn_service(func));
+    }
+
     #[test]
     fn router_service_call_size() {
         let service = Router::new()
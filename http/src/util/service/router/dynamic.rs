@@ -0,0 +1,441 @@
+use core::{
+    cell::RefCell,
+    convert::Infallible,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use std::{
+    rc::Rc,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use xitca_router::InsertError;
+use xitca_service::{ready::ReadyService, Service};
+
+use crate::http::{BorrowReq, BorrowReqMut, Uri};
+
+use super::{Params, PathGen, RouteGen, RouterError};
+
+/// routing table of [DynamicRouter]. See [xitca_router::Router] for detail.
+pub type RouteTable<S> = xitca_router::Router<S>;
+
+/// Router with routing table that can be swapped or patched at runtime through [RouterHandle].
+///
+/// Unlike [Router](super::Router) it routes to already constructed services and the routing table is
+/// shared by all router services produced from it. Changes made through handle are visible to every
+/// worker without restarting it.
+///
+/// Router service caches the routing table and only loads it from handle again after it's replaced.
+/// Routing request does not contend with other workers.
+///
+/// # Example
+/// ```rust
+/// # use std::{convert::Infallible, sync::Arc};
+/// # use xitca_http::{
+/// #   http::{Request, RequestExt, Response},
+/// #   util::service::router::{DynamicRouter, RouterHandle},
+/// # };
+/// # use xitca_service::{fn_service, object::BoxedSyncServiceObject, Service};
+/// # use xitca_unsafe_collection::futures::NowOrPanic;
+/// type Req = Request<RequestExt<()>>;
+/// type S = Arc<BoxedSyncServiceObject<Req, Response<()>, Infallible>>;
+///
+/// // construct service routed by DynamicRouter.
+/// fn service(status: u16) -> S {
+///     let service = fn_service(move |_: Req| async move {
+///         let mut res = Response::new(());
+///         *res.status_mut() = status.try_into().unwrap();
+///         Ok(res)
+///     })
+///     .call(())
+///     .now_or_panic()
+///     .unwrap();
+///     Arc::new(Box::new(service))
+/// }
+///
+/// let handle = RouterHandle::<S>::new();
+/// handle.insert("/users/:id", service(200)).unwrap();
+///
+/// // construct router service. the handle can be cloned and shared with other threads.
+/// let router = DynamicRouter::new(handle.clone()).call(()).now_or_panic().unwrap();
+///
+/// let req = || Request::builder().uri("/users/996").body(Default::default()).unwrap();
+/// assert_eq!(router.call(req()).now_or_panic().ok().unwrap().status(), 200);
+///
+/// // patch routing table at runtime.
+/// handle.remove("/users/:id").unwrap().unwrap();
+/// handle.insert("/users/:id", service(202)).unwrap();
+/// assert_eq!(router.call(req()).now_or_panic().ok().unwrap().status(), 202);
+/// ```
+pub struct DynamicRouter<S> {
+    prefix: Option<usize>,
+    handle: RouterHandle<S>,
+}
+
+impl<S> DynamicRouter<S> {
+    /// construct router with routing table of given handle.
+    pub fn new(handle: RouterHandle<S>) -> Self {
+        Self { prefix: None, handle }
+    }
+
+    /// handle of routing table of router.
+    pub fn handle(&self) -> &RouterHandle<S> {
+        &self.handle
+    }
+}
+
+/// Handle of shared routing table of [DynamicRouter].
+///
+/// All updates are atomic. Request being routed observes either the whole update or none of it and
+/// in-flight requests keep using the routing table they are matched against. Concurrent updates are
+/// serialized and each of them clones the routing table it modifies.
+pub struct RouterHandle<S> {
+    inner: Arc<Inner<S>>,
+}
+
+struct Inner<S> {
+    table: RwLock<Arc<RouteTable<S>>>,
+    // increased after routing table is replaced. router services compare it with the one their cached
+    // routing table is loaded at.
+    generation: AtomicUsize,
+    // serialize updates so that they don't overwrite each other.
+    update: Mutex<()>,
+}
+
+impl<S> Clone for RouterHandle<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S> Default for RouterHandle<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> RouterHandle<S> {
+    /// construct handle with empty routing table.
+    pub fn new() -> Self {
+        Self::from_table(RouteTable::new())
+    }
+
+    /// construct handle with given routing table.
+    pub fn from_table(table: RouteTable<S>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                table: RwLock::new(Arc::new(table)),
+                generation: AtomicUsize::new(0),
+                update: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// snapshot of current routing table.
+    pub fn load(&self) -> Arc<RouteTable<S>> {
+        self.inner.table.read().unwrap().clone()
+    }
+
+    /// replace routing table with a new one and return the old one.
+    pub fn swap(&self, table: RouteTable<S>) -> Arc<RouteTable<S>> {
+        let _guard = self.inner.update.lock().unwrap();
+        self.store(table)
+    }
+
+    /// modify a copy of current routing table with given function. the copy replaces current routing table
+    /// when function returns `Ok` and is discarded when it returns `Err`.
+    pub fn update<F, T, E>(&self, func: F) -> Result<T, E>
+    where
+        S: Clone,
+        F: FnOnce(&mut RouteTable<S>) -> Result<T, E>,
+    {
+        let _guard = self.inner.update.lock().unwrap();
+        let mut table = RouteTable::clone(&self.load());
+        let res = func(&mut table)?;
+        self.store(table);
+        Ok(res)
+    }
+
+    /// insert a service to given route. See [Router::insert](xitca_router::Router::insert).
+    pub fn insert(&self, route: impl Into<String>, service: S) -> Result<(), InsertError>
+    where
+        S: Clone,
+    {
+        self.update(|table| table.insert(route, service))
+    }
+
+    /// remove service of given route. See [Router::remove](xitca_router::Router::remove).
+    pub fn remove(&self, route: &str) -> Result<Option<S>, InsertError>
+    where
+        S: Clone,
+    {
+        self.update(|table| table.remove(route))
+    }
+
+    /// merge routes of given routing table. See [Router::merge](xitca_router::Router::merge).
+    pub fn merge(&self, other: RouteTable<S>) -> Result<(), InsertError>
+    where
+        S: Clone,
+    {
+        self.update(|table| table.merge(other))
+    }
+
+    fn store(&self, table: RouteTable<S>) -> Arc<RouteTable<S>> {
+        let old = core::mem::replace(&mut *self.inner.table.write().unwrap(), Arc::new(table));
+        self.inner.generation.fetch_add(1, Ordering::Release);
+        old
+    }
+
+    // generation is loaded before routing table. a table newer than the generation is loaded again later.
+    fn load_cached(&self) -> Cached<S> {
+        let generation = self.inner.generation.load(Ordering::Acquire);
+        Cached {
+            generation,
+            table: Rc::new(self.load()),
+        }
+    }
+}
+
+impl<S> PathGen for DynamicRouter<S> {
+    fn path_gen(&mut self, path: &str) -> String {
+        let mut path = String::from(path);
+        if path.ends_with("/*") {
+            path.pop();
+        }
+
+        if path.ends_with('/') {
+            path.pop();
+        }
+
+        let prefix = self.prefix.get_or_insert(0);
+        *prefix += path.len();
+
+        path.push_str("/*");
+
+        path
+    }
+}
+
+impl<S> RouteGen for DynamicRouter<S> {
+    type Route<R> = R;
+
+    fn route_gen<R>(route: R) -> Self::Route<R> {
+        route
+    }
+}
+
+impl<S, Arg> Service<Arg> for DynamicRouter<S> {
+    type Response = DynamicRouterService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, _: Arg) -> Result<Self::Response, Self::Error> {
+        Ok(DynamicRouterService {
+            prefix: self.prefix,
+            cache: RefCell::new(self.handle.load_cached()),
+            handle: self.handle.clone(),
+        })
+    }
+}
+
+pub struct DynamicRouterService<S> {
+    prefix: Option<usize>,
+    handle: RouterHandle<S>,
+    cache: RefCell<Cached<S>>,
+}
+
+// routing table cached by router service of one worker. requests clone the local Rc instead of the Arc
+// shared with other workers.
+#[allow(clippy::redundant_allocation)]
+struct Cached<S> {
+    generation: usize,
+    table: Rc<Arc<RouteTable<S>>>,
+}
+
+impl<S> DynamicRouterService<S> {
+    // cached routing table. in-flight requests keep their own reference when it's reloaded.
+    #[allow(clippy::redundant_allocation)]
+    fn table(&self) -> Rc<Arc<RouteTable<S>>> {
+        let mut cache = self.cache.borrow_mut();
+        if cache.generation != self.handle.inner.generation.load(Ordering::Acquire) {
+            *cache = self.handle.load_cached();
+        }
+        cache.table.clone()
+    }
+}
+
+impl<S, Req> Service<Req> for DynamicRouterService<S>
+where
+    S: Service<Req>,
+    Req: BorrowReq<Uri> + BorrowReqMut<Params>,
+{
+    type Response = S::Response;
+    type Error = RouterError<S::Error>;
+
+    async fn call(&self, mut req: Req) -> Result<Self::Response, Self::Error> {
+        let table = self.table();
+
+        let mut path = BorrowReq::<Uri>::borrow(&req).path();
+
+        if let Some(prefix) = self.prefix {
+            path = &path[prefix..];
+        }
+
+        let xitca_router::Match { value, params } = table.at(path).map_err(RouterError::Match)?;
+        req.borrow_mut().replace(params);
+        value.call(req).await.map_err(RouterError::Service)
+    }
+}
+
+impl<S> ReadyService for DynamicRouterService<S> {
+    type Ready = ();
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {}
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use xitca_service::fn_service;
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::http::{Request, RequestExt, Response};
+
+    use super::{super::Router, *};
+
+    type Req = Request<RequestExt<()>>;
+
+    // service responding with version of routing table it's inserted to.
+    #[derive(Clone)]
+    struct Version(usize);
+
+    impl Service<Req> for Version {
+        type Response = usize;
+        type Error = Infallible;
+
+        async fn call(&self, _: Req) -> Result<Self::Response, Self::Error> {
+            Ok(self.0)
+        }
+    }
+
+    fn req(uri: &str) -> Req {
+        Request::builder().uri(uri).body(Default::default()).unwrap()
+    }
+
+    fn table(version: usize) -> RouteTable<Version> {
+        let mut table = RouteTable::new();
+        table.insert("/a", Version(version)).unwrap();
+        table.insert("/b", Version(version)).unwrap();
+        table
+    }
+
+    #[test]
+    fn concurrent_swap() {
+        let handle = RouterHandle::from_table(table(0));
+        let service = DynamicRouter::new(handle.clone()).call(()).now_or_panic().unwrap();
+
+        let writers = (0..4)
+            .map(|i| {
+                let handle = handle.clone();
+                thread::spawn(move || {
+                    for n in 0..250 {
+                        handle.swap(table(i * 250 + n + 1));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        while writers.iter().any(|w| !w.is_finished()) {
+            let res = service.call(req("/a")).now_or_panic().ok().unwrap();
+            assert!(res <= 1000);
+        }
+
+        writers.into_iter().for_each(|w| w.join().unwrap());
+
+        // cached routing table is reloaded after the last swap.
+        let last = handle.load().at("/b").unwrap().value.0;
+        assert_eq!(service.call(req("/a")).now_or_panic().ok().unwrap(), last);
+        assert_eq!(service.call(req("/b")).now_or_panic().ok().unwrap(), last);
+    }
+
+    #[test]
+    fn concurrent_insert() {
+        let handle = RouterHandle::new();
+        let service = DynamicRouter::new(handle.clone()).call(()).now_or_panic().unwrap();
+
+        let writers = (0..4)
+            .map(|i| {
+                let handle = handle.clone();
+                thread::spawn(move || {
+                    for n in 0..50 {
+                        handle.insert(format!("/{i}/{n}"), Version(i)).unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        writers.into_iter().for_each(|w| w.join().unwrap());
+
+        // serialized updates do not overwrite each other.
+        for i in 0..4 {
+            for n in 0..50 {
+                let uri = format!("/{i}/{n}");
+                assert_eq!(service.call(req(&uri)).now_or_panic().ok().unwrap(), i);
+            }
+        }
+    }
+
+    #[test]
+    fn merge_conflict_rollback() {
+        let handle = RouterHandle::from_table(table(0));
+        let service = DynamicRouter::new(handle.clone()).call(()).now_or_panic().unwrap();
+        let snapshot = handle.load();
+
+        let mut other = RouteTable::new();
+        other.insert("/c", Version(1)).unwrap();
+        other.insert("/a", Version(1)).unwrap();
+
+        assert!(handle.merge(other).is_err());
+
+        // routing table is not replaced and routes merged before conflict are discarded.
+        assert!(Arc::ptr_eq(&snapshot, &handle.load()));
+        assert!(matches!(
+            service.call(req("/c")).now_or_panic(),
+            Err(RouterError::Match(_))
+        ));
+        assert_eq!(service.call(req("/a")).now_or_panic().ok().unwrap(), 0);
+
+        let mut other = RouteTable::new();
+        other.insert("/c", Version(1)).unwrap();
+        handle.merge(other).unwrap();
+        assert_eq!(service.call(req("/c")).now_or_panic().ok().unwrap(), 1);
+    }
+
+    #[test]
+    fn nested_prefix() {
+        async fn func(_: Req) -> Result<Response<()>, Infallible> {
+            Ok(Response::new(()))
+        }
+
+        let handle = RouterHandle::new();
+        handle
+            .insert("/users/:id", fn_service(func).call(()).now_or_panic().unwrap())
+            .unwrap();
+
+        let service = Router::new()
+            .insert("/api", Router::new().insert("/v1/", DynamicRouter::new(handle.clone())))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |uri| service.call(req(uri)).now_or_panic();
+
+        let res = call("/api/v1/users/996").ok().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+        assert!(matches!(call("/api/users/996"), Err(RouterError::Match(_))));
+        assert!(matches!(call("/v1/users/996"), Err(RouterError::Match(_))));
+    }
+}
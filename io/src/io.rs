@@ -10,7 +10,9 @@ use core::{
     task::{ready, Context, Poll},
 };
 
-use std::io;
+use std::{fs::File, io};
+
+use crate::bytes::Bytes;
 
 /// A wrapper trait for an [AsyncRead]/[AsyncWrite] tokio type with additional methods.
 pub trait AsyncIo: io::Read + io::Write + Unpin {
@@ -44,6 +46,33 @@ pub trait AsyncIo: io::Read + io::Write + Unpin {
     /// # Why:
     /// tokio's network Stream types do not expose other api for shutdown besides [AsyncWrite::poll_shutdown].
     fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>>;
+
+    /// try to write up to `count` bytes of given file starting from `offset` to Self without copying through
+    /// user space. `offset` is advanced by the number of bytes written.
+    ///
+    /// Like [std::io::Write::write] it returns [io::ErrorKind::WouldBlock] error when Self is not ready.
+    ///
+    /// # Default
+    /// [io::ErrorKind::Unsupported] error is returned and caller should fall back to plain write.
+    ///
+    /// # Why:
+    /// Types like tls streams must transform bytes before writing and can not take advantage of zero copy.
+    fn try_sendfile(&mut self, file: &File, offset: &mut u64, count: usize) -> io::Result<usize> {
+        let _ = (file, offset, count);
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// verified certificate chain of tls peer in DER encoding with end entity certificate first.
+    ///
+    /// # Default
+    /// None is returned. Plain connection types and tls stream types without peer verification use default.
+    ///
+    /// # Why:
+    /// Http services are generic over tls acceptor types and the io type is the only thing they can get the
+    /// negotiated tls session from.
+    fn peer_certificate_chain(&self) -> Option<Vec<Bytes>> {
+        None
+    }
 }
 
 /// adapter type for transforming a type impl [AsyncIo] trait to a type impl [AsyncRead] and [AsyncWrite] traits.
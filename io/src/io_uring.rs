@@ -2,14 +2,71 @@
 
 use core::future::Future;
 
-use std::{io, net::Shutdown};
+use std::{fs::File, io, net::Shutdown};
+
+use crate::bytes::Bytes;
+
+mod driver;
+mod probe;
 
 pub use tokio_uring::buf::{IoBuf, IoBufMut, Slice};
+pub use xitca_unsafe_collection::uring::{FixedBuf, FixedBufPool, ProvidedBuf};
+
+pub use self::probe::OpSupport;
+
+pub(crate) use self::driver::{is_unsupported, Driver};
+
+/// register memory of buffer pool to io-uring instance of current thread. buffers of registered pool are
+/// read into and written from without kernel mapping them for every operation with
+/// [AsyncBufRead::read_fixed] and [AsyncBufWrite::write_fixed].
+///
+/// A thread can only have one registered pool. Buffers of pool not registered to current thread still work
+/// with plain read and write.
+pub fn register_buffers(pool: &FixedBufPool) -> io::Result<()> {
+    match Driver::get() {
+        Some(driver) => driver.register_buffers(pool),
+        None => Err(io::ErrorKind::Unsupported.into()),
+    }
+}
 
 pub trait AsyncBufRead {
     fn read<B>(&self, buf: B) -> impl Future<Output = (io::Result<usize>, B)>
     where
         B: IoBufMut;
+
+    /// read into spare capacity of buffer taken from [FixedBufPool]. Length of buffer is advanced by bytes read.
+    ///
+    /// # Default
+    /// plain read. Types reading from socket directly use registered buffer when pool is registered to current
+    /// thread with [register_buffers] and fall back to plain read otherwise.
+    fn read_fixed(&self, buf: FixedBuf) -> impl Future<Output = (io::Result<usize>, FixedBuf)> {
+        async {
+            let len = buf.len();
+            let (res, slice) = self.read(buf.slice(len..)).await;
+            (res, slice.into_inner())
+        }
+    }
+
+    /// read into buffer selected by kernel from buffer ring of current thread when data arrives. Connections
+    /// waiting for data do not hold any buffer. Empty buffer is returned when Self is closed by peer.
+    ///
+    /// Received bytes can be lost when returned future is dropped before it resolves. Only drop it when Self
+    /// is about to be closed.
+    ///
+    /// # Default
+    /// [io::ErrorKind::Unsupported] error is returned and caller should fall back to plain read. This error
+    /// is also returned when kernel does not support buffer ring or all buffers are in use.
+    fn read_provided(&self) -> impl Future<Output = io::Result<ProvidedBuf>> {
+        async { Err(io::ErrorKind::Unsupported.into()) }
+    }
+
+    /// verified certificate chain of tls peer in DER encoding with end entity certificate first.
+    ///
+    /// # Default
+    /// None is returned. Plain connection types and tls stream types without peer verification use default.
+    fn peer_certificate_chain(&self) -> Option<Vec<Bytes>> {
+        None
+    }
 }
 
 pub trait AsyncBufWrite {
@@ -18,16 +75,78 @@ pub trait AsyncBufWrite {
         B: IoBuf;
 
     fn shutdown(&self, direction: Shutdown) -> io::Result<()>;
+
+    /// write initialized bytes of buffer to Self without copying them into kernel. Returned future resolves
+    /// after kernel is done with buffer which is a lot later than plain write, making it only beneficial for
+    /// large buffers.
+    ///
+    /// # Default
+    /// plain write. Tcp stream uses zero copy send (`IORING_OP_SEND_ZC`) and falls back to plain write when
+    /// kernel does not support it.
+    fn write_zc<B>(&self, buf: B) -> impl Future<Output = (io::Result<usize>, B)>
+    where
+        B: IoBuf,
+    {
+        self.write(buf)
+    }
+
+    /// write initialized bytes of buffer taken from [FixedBufPool].
+    ///
+    /// # Default
+    /// plain write. Types writing to socket directly use registered buffer when pool is registered to current
+    /// thread with [register_buffers] and fall back to plain write otherwise.
+    fn write_fixed(&self, buf: FixedBuf) -> impl Future<Output = (io::Result<usize>, FixedBuf)> {
+        self.write(buf)
+    }
+
+    /// write `count` bytes of given file starting from `offset` to Self without copying through user space.
+    ///
+    /// [io::ErrorKind::UnexpectedEof] error is returned when file ends before `count` bytes are written.
+    ///
+    /// # Default
+    /// [io::ErrorKind::Unsupported] error is returned before anything is written and caller should fall back
+    /// to plain write.
+    ///
+    /// # Why:
+    /// tokio-uring does not expose splice operation. Types like tls streams must transform bytes before writing
+    /// and can not take advantage of zero copy.
+    fn sendfile(&self, file: &File, offset: u64, count: u64) -> impl Future<Output = io::Result<()>> {
+        let _ = (file, offset, count);
+        async { Err(io::ErrorKind::Unsupported.into()) }
+    }
+}
+
+pub async fn write_all<Io, B>(io: &Io, buf: B) -> (io::Result<()>, B)
+where
+    Io: AsyncBufWrite,
+    B: IoBuf,
+{
+    _write_all(io, buf, false).await
+}
+
+/// [write_all] with [AsyncBufWrite::write_zc].
+pub async fn write_all_zc<Io, B>(io: &Io, buf: B) -> (io::Result<()>, B)
+where
+    Io: AsyncBufWrite,
+    B: IoBuf,
+{
+    _write_all(io, buf, true).await
 }
 
-pub async fn write_all<Io, B>(io: &Io, mut buf: B) -> (io::Result<()>, B)
+async fn _write_all<Io, B>(io: &Io, mut buf: B, zc: bool) -> (io::Result<()>, B)
 where
     Io: AsyncBufWrite,
     B: IoBuf,
 {
     let mut n = 0;
     while n < buf.bytes_init() {
-        match io.write(buf.slice(n..)).await {
+        let slice = buf.slice(n..);
+        let res = if zc {
+            io.write_zc(slice).await
+        } else {
+            io.write(slice).await
+        };
+        match res {
             (Ok(0), slice) => {
                 return (Err(io::ErrorKind::WriteZero.into()), slice.into_inner());
             }
@@ -0,0 +1,242 @@
+//! thread local io-uring instance for operations tokio-uring does not expose.
+//!
+//! tokio-uring does not give access to it's ring. a second ring is created lazily for every thread using these
+//! operations and it's file descriptor is registered to tokio reactor of tokio-uring runtime. operations are
+//! completed when the ring is readable.
+
+use core::{
+    cell::{OnceCell, RefCell},
+    future::poll_fn,
+    task::{ready, Context, Poll},
+};
+
+use std::{
+    io,
+    os::fd::{AsRawFd, OwnedFd, RawFd},
+    rc::Rc,
+};
+
+use tokio::io::{unix::AsyncFd, Interest};
+use xitca_unsafe_collection::uring::{FixedBuf, FixedBufPool, Key, ProvidedBuf, Ring};
+
+use super::{IoBuf, OpSupport};
+
+// submission queue size of ring. operations are submitted immediately and this only limits burst of them.
+const ENTRIES: u32 = 256;
+
+// provided buffers shared by all connections of thread. idle connections waiting for data do not hold any.
+const PROVIDED_ENTRIES: u16 = 64;
+const PROVIDED_SIZE: usize = 4096;
+
+pub(crate) struct Driver {
+    ring: RefCell<Ring>,
+    // registration of ring to tokio reactor. re-created when runtime of thread changes.
+    fd: RefCell<Option<AsyncFd<RingFd>>>,
+}
+
+struct RingFd(RawFd);
+
+impl AsRawFd for RingFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl Driver {
+    // driver of current thread. None when io-uring is not available.
+    pub(crate) fn get() -> Option<Rc<Self>> {
+        thread_local! {
+            static DRIVER: OnceCell<Option<Rc<Driver>>> = const { OnceCell::new() };
+        }
+
+        DRIVER.with(|driver| driver.get_or_init(|| Self::new().ok().map(Rc::new)).clone())
+    }
+
+    fn new() -> io::Result<Self> {
+        let mut ring = Ring::new(ENTRIES)?;
+
+        // buffer ring is optional (linux 5.19) and receiving falls back to plain read without it.
+        let _ = ring.register_buf_ring(PROVIDED_ENTRIES, PROVIDED_SIZE);
+
+        Ok(Self {
+            ring: RefCell::new(ring),
+            fd: RefCell::new(None),
+        })
+    }
+
+    pub(crate) fn register_buffers(&self, pool: &FixedBufPool) -> io::Result<()> {
+        self.ring.borrow_mut().register_buffers(pool)
+    }
+
+    pub(crate) async fn send_zc<B: IoBuf>(&self, fd: RawFd, buf: B) -> (io::Result<usize>, B) {
+        if !OpSupport::get().send_zc() {
+            return (Err(io::ErrorKind::Unsupported.into()), buf);
+        }
+
+        let res = self.ring.borrow_mut().send_zc(fd, buf);
+        match res {
+            Ok(key) => self.run(key, |ring, key, cx| ring.poll_send_zc(key, cx)).await,
+            Err((e, buf)) => (Err(e), buf),
+        }
+    }
+
+    pub(crate) async fn read_fixed(&self, fd: RawFd, buf: FixedBuf) -> (io::Result<usize>, FixedBuf) {
+        let res = self.ring.borrow_mut().read_fixed(fd, buf, 0);
+        match res {
+            Ok(key) => self.run(key, |ring, key, cx| ring.poll_fixed(key, cx)).await,
+            Err((e, buf)) => (Err(e), buf),
+        }
+    }
+
+    pub(crate) async fn write_fixed(&self, fd: RawFd, buf: FixedBuf) -> (io::Result<usize>, FixedBuf) {
+        let res = self.ring.borrow_mut().write_fixed(fd, buf, 0);
+        match res {
+            Ok(key) => self.run(key, |ring, key, cx| ring.poll_fixed(key, cx)).await,
+            Err((e, buf)) => (Err(e), buf),
+        }
+    }
+
+    pub(crate) async fn recv_provided(&self, fd: RawFd) -> io::Result<ProvidedBuf> {
+        let key = self.ring.borrow_mut().recv_provided(fd)?;
+        self.run(key, |ring, key, cx| ring.poll_recv_provided(key, cx))
+            .await
+            // every provided buffer is in use. nothing is received and caller can fall back to plain read.
+            .map_err(|e| match e.raw_os_error() {
+                Some(libc::ENOBUFS) => io::ErrorKind::Unsupported.into(),
+                _ => e,
+            })
+    }
+
+    pub(crate) fn accept_multi(&self, fd: RawFd) -> io::Result<Key> {
+        self.ring.borrow_mut().accept_multi(fd)
+    }
+
+    // poll multishot accept. key is taken when operation is ended or can not be waited for anymore.
+    pub(crate) fn poll_accept(&self, key: &mut Option<Key>, cx: &mut Context<'_>) -> Poll<Option<io::Result<OwnedFd>>> {
+        loop {
+            let res = self.ring.borrow_mut().poll_accept(key.as_ref().unwrap(), cx);
+            if let Poll::Ready(res) = res {
+                // key is invalid after operation is ended.
+                if !matches!(res, Some(Ok(_))) {
+                    *key = None;
+                }
+                self.ring.borrow_mut().wake_one();
+                return Poll::Ready(res);
+            }
+
+            match self.poll_ring(cx) {
+                Poll::Ready(Ok(_)) => {}
+                Poll::Ready(Err(e)) => {
+                    // release wakes another operation to observe the error.
+                    self.release(key.take().unwrap());
+                    return Poll::Ready(Some(Err(e)));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    pub(crate) fn cancel(&self, key: &Key) {
+        self.ring.borrow_mut().cancel(key);
+    }
+
+    // operation may be the one watching readiness of ring. hand it over to another one.
+    pub(crate) fn release(&self, key: Key) {
+        let mut ring = self.ring.borrow_mut();
+        ring.release(key);
+        ring.wake_one();
+    }
+
+    // wait for final result of single shot operation. operation is released when future is dropped before it.
+    async fn run<F, T>(&self, key: Key, mut poll: F) -> T
+    where
+        F: FnMut(&mut Ring, &Key, &mut Context<'_>) -> Poll<T>,
+    {
+        struct Guard<'a> {
+            driver: &'a Driver,
+            key: Option<Key>,
+        }
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                if let Some(key) = self.key.take() {
+                    self.driver.release(key);
+                }
+            }
+        }
+
+        let mut guard = Guard {
+            driver: self,
+            key: Some(key),
+        };
+
+        let res = poll_fn(|cx| self.poll_op(guard.key.as_ref().unwrap(), cx, &mut poll)).await;
+        // key is invalid after final result.
+        guard.key = None;
+        res
+    }
+
+    fn poll_op<F, T>(&self, key: &Key, cx: &mut Context<'_>, mut poll: F) -> Poll<T>
+    where
+        F: FnMut(&mut Ring, &Key, &mut Context<'_>) -> Poll<T>,
+    {
+        loop {
+            let res = poll(&mut self.ring.borrow_mut(), key, cx);
+            if let Poll::Ready(res) = res {
+                // readiness of ring is only watched by the last polled operation. hand it over to another one.
+                self.ring.borrow_mut().wake_one();
+                return Poll::Ready(res);
+            }
+
+            match self.poll_ring(cx) {
+                Poll::Ready(Ok(_)) => {}
+                // reactor is gone with it's runtime and ring can not be waited for. operation owns resource that
+                // must be given back. cancel it and poll until it's completion.
+                Poll::Ready(Err(_)) => {
+                    let mut ring = self.ring.borrow_mut();
+                    ring.cancel(key);
+                    ring.complete();
+                    // other operations are waiting for the same readiness and must observe the error too.
+                    ring.wake_one();
+                    let res = poll(&mut ring, key, cx);
+                    if res.is_pending() {
+                        cx.waker().wake_by_ref();
+                    }
+                    return res;
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    // wait for ring to be readable and complete operations.
+    fn poll_ring(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut fd = self.fd.borrow_mut();
+
+        let registered = match *fd {
+            Some(ref mut registered) => registered,
+            None => {
+                let raw = self.ring.borrow().as_raw_fd();
+                fd.insert(AsyncFd::with_interest(RingFd(raw), Interest::READABLE)?)
+            }
+        };
+
+        match ready!(registered.poll_read_ready(cx)) {
+            Ok(mut guard) => {
+                guard.clear_ready();
+                self.ring.borrow_mut().complete();
+                Poll::Ready(Ok(()))
+            }
+            // reactor is shutting down. ring is registered again to reactor of next runtime running on thread.
+            Err(e) => {
+                *fd = None;
+                Poll::Ready(Err(e))
+            }
+        }
+    }
+}
+
+// error of io-uring operation rejected by kernel or not available for given file descriptor.
+pub(crate) fn is_unsupported(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::Unsupported || matches!(e.raw_os_error(), Some(libc::EINVAL | libc::EOPNOTSUPP))
+}
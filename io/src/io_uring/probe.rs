@@ -0,0 +1,88 @@
+use std::sync::OnceLock;
+
+use io_uring::{opcode, IoUring, Probe};
+
+/// io-uring operations supported by running kernel.
+///
+/// Kernel support is probed once per process and cached. When probing fails (io-uring disabled by
+/// sysctl/seccomp or kernel too old) every operation is reported as unsupported and callers are
+/// expected to fall back to plain read/write.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OpSupport {
+    send_zc: bool,
+    fixed_buffers: bool,
+    provide_buffers: bool,
+}
+
+impl OpSupport {
+    /// probe kernel for supported operations. the result is cached for the rest of process lifetime.
+    pub fn get() -> &'static Self {
+        static SUPPORT: OnceLock<OpSupport> = OnceLock::new();
+        SUPPORT.get_or_init(|| Self::probe().unwrap_or_default())
+    }
+
+    fn probe() -> std::io::Result<Self> {
+        let ring = IoUring::new(2)?;
+        let mut probe = Probe::new();
+        ring.submitter().register_probe(&mut probe)?;
+
+        Ok(Self {
+            send_zc: probe.is_supported(opcode::SendZc::CODE),
+            fixed_buffers: probe.is_supported(opcode::ReadFixed::CODE) && probe.is_supported(opcode::WriteFixed::CODE),
+            provide_buffers: probe.is_supported(opcode::ProvideBuffers::CODE),
+        })
+    }
+
+    /// zero copy send (`IORING_OP_SEND_ZC`).
+    #[inline]
+    pub fn send_zc(&self) -> bool {
+        self.send_zc
+    }
+
+    /// read and write with registered buffers (`IORING_OP_READ_FIXED` and `IORING_OP_WRITE_FIXED`).
+    #[inline]
+    pub fn fixed_buffers(&self) -> bool {
+        self.fixed_buffers
+    }
+
+    /// kernel selected read buffers (`IORING_OP_PROVIDE_BUFFERS`).
+    #[inline]
+    pub fn provide_buffers(&self) -> bool {
+        self.provide_buffers
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cached() {
+        assert!(core::ptr::eq(OpSupport::get(), OpSupport::get()));
+    }
+
+    #[test]
+    fn probe() {
+        let support = OpSupport::get();
+
+        match OpSupport::probe() {
+            Ok(probed) => {
+                assert_eq!(support.send_zc(), probed.send_zc());
+                assert_eq!(support.fixed_buffers(), probed.fixed_buffers());
+                assert_eq!(support.provide_buffers(), probed.provide_buffers());
+
+                // SEND_ZC (linux 6.0) is introduced after registered and provided buffers (linux 5.7).
+                if support.send_zc() {
+                    assert!(support.fixed_buffers());
+                    assert!(support.provide_buffers());
+                }
+            }
+            // io-uring is not available and every operation falls back.
+            Err(_) => {
+                assert!(!support.send_zc());
+                assert!(!support.fixed_buffers());
+                assert!(!support.provide_buffers());
+            }
+        }
+    }
+}
@@ -9,3 +9,4 @@ pub mod io;
 pub mod io_uring;
 #[cfg(feature = "runtime")]
 pub mod net;
+pub mod shutdown;
@@ -43,6 +43,19 @@ macro_rules! default_aio_impl {
                 crate::io::AsyncWrite::is_write_vectored(&self.0)
             }
 
+            #[cfg(all(feature = "sendfile", target_os = "linux"))]
+            fn try_sendfile(
+                &mut self,
+                file: &::std::fs::File,
+                offset: &mut u64,
+                count: usize,
+            ) -> ::std::io::Result<usize> {
+                use ::std::os::fd::AsFd;
+                self.0.try_io(crate::io::Interest::WRITABLE, || {
+                    ::xitca_unsafe_collection::sendfile::sendfile(self.0.as_fd(), file, offset, count)
+                })
+            }
+
             fn poll_shutdown(
                 self: ::core::pin::Pin<&mut Self>,
                 cx: &mut ::core::task::Context<'_>,
@@ -116,6 +129,19 @@ pub enum Listener {
 }
 
 impl Listener {
+    /// Returns the local address of Tcp and Udp listener.
+    ///
+    /// Unix domain listener does not have internet address and [io::ErrorKind::Unsupported] error is returned.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        match *self {
+            Self::Tcp(ref tcp) => tcp.local_addr(),
+            #[cfg(feature = "http3")]
+            Self::Udp(ref udp) => udp.endpoint().local_addr(),
+            #[cfg(unix)]
+            Self::Unix(_) => Err(io::ErrorKind::Unsupported.into()),
+        }
+    }
+
     pub async fn accept(&self) -> io::Result<Stream> {
         match *self {
             Self::Tcp(ref tcp) => {
@@ -148,3 +174,18 @@ pub enum Stream {
     #[cfg(unix)]
     Unix(std::os::unix::net::UnixStream, std::os::unix::net::SocketAddr),
 }
+
+impl Stream {
+    /// Returns the peer address of Tcp and Udp stream.
+    ///
+    /// Unix domain stream does not have internet address and None is returned.
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        match *self {
+            Self::Tcp(_, addr) => Some(addr),
+            #[cfg(feature = "http3")]
+            Self::Udp(_, addr) => Some(addr),
+            #[cfg(unix)]
+            Self::Unix(..) => None,
+        }
+    }
+}
@@ -1,6 +1,11 @@
-use std::{io, net::SocketAddr};
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+};
 
-use quinn::{Connecting, Endpoint, ServerConfig};
+use quinn::{Connecting, Connection, Endpoint, EndpointConfig, ServerConfig};
+
+use crate::bytes::Bytes;
 
 use super::Stream;
 
@@ -34,7 +39,7 @@ impl UdpListener {
 
 /// Builder type for UdpListener.
 pub struct UdpListenerBuilder {
-    addr: SocketAddr,
+    bind: Bind,
     config: ServerConfig,
     /// An artificial backlog capacity reinforced by bounded channel.
     /// The channel is tasked with distribute [UdpStream] and can cache stream up most to
@@ -42,10 +47,24 @@ pub struct UdpListenerBuilder {
     backlog: u32,
 }
 
+enum Bind {
+    Addr(SocketAddr),
+    Socket(UdpSocket),
+}
+
 impl UdpListenerBuilder {
     pub fn new(addr: SocketAddr, config: ServerConfig) -> Self {
         Self {
-            addr,
+            bind: Bind::Addr(addr),
+            config,
+            backlog: 2048,
+        }
+    }
+
+    /// Construct from an already bound [UdpSocket]. Useful for socket passed from other process.
+    pub fn from_std(socket: UdpSocket, config: ServerConfig) -> Self {
+        Self {
+            bind: Bind::Socket(socket),
             config,
             backlog: 2048,
         }
@@ -57,8 +76,17 @@ impl UdpListenerBuilder {
     }
 
     pub fn build(self) -> io::Result<UdpListener> {
-        let Self { config, addr, .. } = self;
-        Endpoint::server(config, addr).map(|endpoint| UdpListener { endpoint })
+        let Self { config, bind, .. } = self;
+        let endpoint = match bind {
+            Bind::Addr(addr) => Endpoint::server(config, addr)?,
+            Bind::Socket(socket) => {
+                socket.set_nonblocking(true)?;
+                let runtime = quinn::default_runtime()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "no async runtime found"))?;
+                Endpoint::new(EndpointConfig::default(), Some(config), socket, runtime)?
+            }
+        };
+        Ok(UdpListener { endpoint })
     }
 }
 
@@ -91,6 +119,16 @@ impl UdpStream {
     }
 }
 
+/// Get verified certificate chain of quic peer in DER encoding with end entity certificate first.
+///
+/// None is returned when peer does not present certificate or it's not verified by [ServerConfig].
+pub fn peer_certificate_chain(conn: &Connection) -> Option<Vec<Bytes>> {
+    conn.peer_identity()?
+        .downcast::<Vec<rustls::Certificate>>()
+        .ok()
+        .map(|certs| certs.into_iter().map(|cert| Bytes::from(cert.0)).collect())
+}
+
 impl TryFrom<Stream> for UdpStream {
     type Error = io::Error;
 
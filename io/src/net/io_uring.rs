@@ -1,16 +1,128 @@
+use core::future::poll_fn;
+
 use std::{
     io,
     net::{Shutdown, SocketAddr},
+    os::fd::{AsRawFd, OwnedFd},
+    rc::Rc,
 };
 
+use xitca_unsafe_collection::uring::Key;
+
 pub use tokio_uring::net::TcpStream;
 
 #[cfg(unix)]
 pub use tokio_uring::net::UnixStream;
 
-use crate::io_uring::{AsyncBufRead, AsyncBufWrite, IoBuf, IoBufMut};
+use crate::io_uring::{is_unsupported, AsyncBufRead, AsyncBufWrite, Driver, FixedBuf, IoBuf, IoBufMut, ProvidedBuf};
+
+use super::{Listener, Stream};
+
+/// Accept connections from [Listener] with multishot io-uring accept (linux 5.19). A single operation keeps
+/// accepting connections until it's cancelled.
+///
+/// Plain accept of listener is used when io-uring or multishot accept is not available.
+#[derive(Default)]
+pub struct MultiAccept {
+    // None when falling back to plain accept.
+    driver: Option<Rc<Driver>>,
+    key: Option<Key>,
+    fallback: bool,
+}
+
+impl MultiAccept {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-use super::Stream;
+    /// Accept connection from listener. The same listener must be passed until [MultiAccept::stop] is called.
+    ///
+    /// Returned future can be dropped without losing connections. They are accepted and queued by kernel.
+    pub async fn accept(&mut self, listener: &Listener) -> io::Result<Stream> {
+        let fd = match *listener {
+            Listener::Tcp(ref tcp) => tcp.as_raw_fd(),
+            #[cfg(unix)]
+            Listener::Unix(ref unix) => unix.as_raw_fd(),
+            #[allow(unreachable_patterns)]
+            _ => return listener.accept().await,
+        };
+
+        if self.fallback {
+            return listener.accept().await;
+        }
+
+        let driver = match self.driver {
+            Some(ref driver) => driver.clone(),
+            None => match Driver::get() {
+                Some(driver) => self.driver.insert(driver).clone(),
+                None => {
+                    self.fallback = true;
+                    return listener.accept().await;
+                }
+            },
+        };
+
+        loop {
+            if self.key.is_none() {
+                match driver.accept_multi(fd) {
+                    Ok(key) => self.key = Some(key),
+                    // submission queue is full. try again on next accept.
+                    Err(_) => return listener.accept().await,
+                }
+            }
+
+            match poll_fn(|cx| driver.poll_accept(&mut self.key, cx)).await {
+                Some(Ok(fd)) => return accepted(listener, fd),
+                // operation is ended by cancellation or kernel. queued connections are all taken and it's
+                // submitted again.
+                None => {}
+                Some(Err(e)) if is_unsupported(&e) => {
+                    self.fallback = true;
+                    return listener.accept().await;
+                }
+                Some(Err(e)) => return Err(e),
+            }
+        }
+    }
+
+    /// Stop accepting connections. Connections already accepted by kernel are still returned by following
+    /// [MultiAccept::accept] before it starts accepting again.
+    pub fn stop(&mut self) {
+        if let (Some(driver), Some(key)) = (self.driver.as_ref(), self.key.as_ref()) {
+            driver.cancel(key);
+        }
+    }
+}
+
+impl Drop for MultiAccept {
+    fn drop(&mut self) {
+        if let (Some(driver), Some(key)) = (self.driver.as_ref(), self.key.take()) {
+            driver.release(key);
+        }
+    }
+}
+
+fn accepted(listener: &Listener, fd: OwnedFd) -> io::Result<Stream> {
+    // peer can be gone before address is queried. treat it the same as failed accept of such connection.
+    let aborted = |e: io::Error| match e.kind() {
+        io::ErrorKind::NotConnected => io::ErrorKind::ConnectionAborted.into(),
+        _ => e,
+    };
+
+    match *listener {
+        #[cfg(unix)]
+        Listener::Unix(_) => {
+            let stream = std::os::unix::net::UnixStream::from(fd);
+            let addr = stream.peer_addr().map_err(aborted)?;
+            Ok(Stream::Unix(stream, addr))
+        }
+        _ => {
+            let stream = std::net::TcpStream::from(fd);
+            let addr = stream.peer_addr().map_err(aborted)?;
+            Ok(Stream::Tcp(stream, addr))
+        }
+    }
+}
 
 impl TryFrom<Stream> for TcpStream {
     type Error = io::Error;
@@ -40,6 +152,14 @@ impl AsyncBufRead for TcpStream {
     {
         TcpStream::read(self, buf).await
     }
+
+    async fn read_fixed(&self, buf: FixedBuf) -> (io::Result<usize>, FixedBuf) {
+        read_fixed(self, buf).await
+    }
+
+    async fn read_provided(&self) -> io::Result<ProvidedBuf> {
+        read_provided(self).await
+    }
 }
 
 impl AsyncBufWrite for TcpStream {
@@ -51,10 +171,37 @@ impl AsyncBufWrite for TcpStream {
         TcpStream::write(self, buf).await
     }
 
+    async fn write_zc<B>(&self, buf: B) -> (io::Result<usize>, B)
+    where
+        B: IoBuf,
+    {
+        let Some(driver) = Driver::get() else {
+            return TcpStream::write(self, buf).await;
+        };
+        match driver.send_zc(self.as_raw_fd(), buf).await {
+            (Err(ref e), buf) if is_unsupported(e) => TcpStream::write(self, buf).await,
+            res => res,
+        }
+    }
+
+    async fn write_fixed(&self, buf: FixedBuf) -> (io::Result<usize>, FixedBuf) {
+        write_fixed(self, buf).await
+    }
+
     #[inline(always)]
     fn shutdown(&self, direction: Shutdown) -> io::Result<()> {
         TcpStream::shutdown(self, direction)
     }
+
+    #[cfg(all(feature = "sendfile", target_os = "linux"))]
+    fn sendfile(
+        &self,
+        file: &std::fs::File,
+        offset: u64,
+        count: u64,
+    ) -> impl core::future::Future<Output = io::Result<()>> {
+        sendfile::send::<std::net::TcpStream>(self, file, offset, count)
+    }
 }
 
 #[cfg(unix)]
@@ -91,6 +238,14 @@ mod unix {
         {
             UnixStream::read(self, buf).await
         }
+
+        async fn read_fixed(&self, buf: FixedBuf) -> (io::Result<usize>, FixedBuf) {
+            super::read_fixed(self, buf).await
+        }
+
+        async fn read_provided(&self) -> io::Result<ProvidedBuf> {
+            super::read_provided(self).await
+        }
     }
 
     impl AsyncBufWrite for UnixStream {
@@ -102,9 +257,240 @@ mod unix {
             UnixStream::write(self, buf).await
         }
 
+        async fn write_fixed(&self, buf: FixedBuf) -> (io::Result<usize>, FixedBuf) {
+            super::write_fixed(self, buf).await
+        }
+
         #[inline(always)]
         fn shutdown(&self, direction: Shutdown) -> io::Result<()> {
             UnixStream::shutdown(self, direction)
         }
+
+        #[cfg(all(feature = "sendfile", target_os = "linux"))]
+        fn sendfile(
+            &self,
+            file: &std::fs::File,
+            offset: u64,
+            count: u64,
+        ) -> impl core::future::Future<Output = io::Result<()>> {
+            super::sendfile::send::<std::os::unix::net::UnixStream>(self, file, offset, count)
+        }
+    }
+}
+
+// registered buffer falls back to plain read and write when it's pool is not registered to current thread.
+async fn read_fixed<Io>(io: &Io, buf: FixedBuf) -> (io::Result<usize>, FixedBuf)
+where
+    Io: AsyncBufRead + AsRawFd,
+{
+    let Some(driver) = Driver::get() else {
+        return plain_read_fixed(io, buf).await;
+    };
+    match driver.read_fixed(io.as_raw_fd(), buf).await {
+        (Err(ref e), buf) if is_unsupported(e) => plain_read_fixed(io, buf).await,
+        res => res,
+    }
+}
+
+async fn plain_read_fixed<Io>(io: &Io, buf: FixedBuf) -> (io::Result<usize>, FixedBuf)
+where
+    Io: AsyncBufRead,
+{
+    let len = buf.len();
+    let (res, slice) = io.read(buf.slice(len..)).await;
+    (res, slice.into_inner())
+}
+
+async fn write_fixed<Io>(io: &Io, buf: FixedBuf) -> (io::Result<usize>, FixedBuf)
+where
+    Io: AsyncBufWrite + AsRawFd,
+{
+    let Some(driver) = Driver::get() else {
+        return io.write(buf).await;
+    };
+    match driver.write_fixed(io.as_raw_fd(), buf).await {
+        (Err(ref e), buf) if is_unsupported(e) => io.write(buf).await,
+        res => res,
+    }
+}
+
+async fn read_provided(io: &impl AsRawFd) -> io::Result<ProvidedBuf> {
+    match Driver::get() {
+        Some(driver) => driver.recv_provided(io.as_raw_fd()).await,
+        None => Err(io::ErrorKind::Unsupported.into()),
+    }
+}
+
+#[cfg(all(feature = "sendfile", target_os = "linux"))]
+mod sendfile {
+    use std::{
+        fs::File,
+        io,
+        os::fd::{AsFd, AsRawFd, OwnedFd},
+    };
+
+    use tokio::io::{unix::AsyncFd, Interest};
+    use xitca_unsafe_collection::sendfile::{dup, sendfile};
+
+    pub(super) trait Socket: From<OwnedFd> + AsFd + AsRawFd {
+        fn set_nonblocking(&self) -> io::Result<()>;
+    }
+
+    impl Socket for std::net::TcpStream {
+        fn set_nonblocking(&self) -> io::Result<()> {
+            std::net::TcpStream::set_nonblocking(self, true)
+        }
+    }
+
+    impl Socket for std::os::unix::net::UnixStream {
+        fn set_nonblocking(&self) -> io::Result<()> {
+            std::os::unix::net::UnixStream::set_nonblocking(self, true)
+        }
+    }
+
+    // tokio-uring can not wait for socket readiness. a duplicate of socket is registered to tokio reactor
+    // of tokio-uring runtime and sendfile(2) is called on it whenever it's writable.
+    pub(super) async fn send<S>(io: &impl AsRawFd, file: &File, mut offset: u64, count: u64) -> io::Result<()>
+    where
+        S: Socket,
+    {
+        let end = offset + count;
+
+        let io = S::from(dup(io)?);
+        // sockets accepted by tokio listener are non-blocking already. this only affects sockets constructed
+        // elsewhere and io-uring operations on them work the same either way.
+        io.set_nonblocking()?;
+        let io = AsyncFd::with_interest(io, Interest::WRITABLE)?;
+
+        while offset < end {
+            let count = usize::try_from(end - offset).unwrap_or(usize::MAX);
+            let mut guard = io.writable().await?;
+            match guard.try_io(|io| sendfile(io.get_ref().as_fd(), file, &mut offset, count)) {
+                Ok(Ok(0)) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => return Err(e),
+                Err(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener as StdTcpListener,
+    };
+
+    use crate::io_uring::{register_buffers, write_all, FixedBufPool};
+
+    use super::*;
+
+    fn pair() -> (TcpStream, std::net::TcpStream) {
+        let listener = StdTcpListener::bind("127.0.0.1:0").unwrap();
+        let client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        (TcpStream::from_std(server), client)
+    }
+
+    #[test]
+    fn write_zc() {
+        tokio_uring::start(async {
+            let (stream, mut client) = pair();
+
+            let reader = std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                client.read_to_end(&mut buf).unwrap();
+                buf
+            });
+
+            let mut buf = vec![1u8; 256 * 1024];
+            let mut n = 0;
+            while n < buf.len() {
+                let (res, slice) = stream.write_zc(buf.slice(n..)).await;
+                n += res.unwrap();
+                buf = slice.into_inner();
+            }
+            // tokio-uring closes stream asynchronously on drop and blocking join would never observe it.
+            stream.shutdown(Shutdown::Write).unwrap();
+
+            assert_eq!(reader.join().unwrap(), buf);
+        })
+    }
+
+    #[test]
+    fn read_provided() {
+        tokio_uring::start(async {
+            let (stream, mut client) = pair();
+
+            client.write_all(b"hello").unwrap();
+            let buf = stream.read_provided().await.unwrap();
+            assert_eq!(&*buf, b"hello");
+
+            drop(client);
+            assert!(stream.read_provided().await.unwrap().is_empty());
+        })
+    }
+
+    #[test]
+    fn fixed_buffers() {
+        tokio_uring::start(async {
+            let (stream, mut client) = pair();
+
+            let pool = FixedBufPool::new(2, 8);
+            let other = FixedBufPool::new(1, 8);
+
+            // pool is registered once per thread. registered and unregistered buffers work the same.
+            register_buffers(&pool).unwrap();
+            assert!(register_buffers(&other).is_err());
+
+            for pool in [&pool, &other] {
+                let mut buf = pool.get().unwrap();
+                buf.put_slice(b"ping");
+                let (res, buf) = stream.write_fixed(buf).await;
+                assert_eq!(res.unwrap(), 4);
+                drop(buf);
+
+                let mut recv = [0; 4];
+                client.read_exact(&mut recv).unwrap();
+                assert_eq!(&recv, b"ping");
+
+                client.write_all(b"pong").unwrap();
+                let mut buf = pool.get().unwrap();
+                buf.put_slice(b">");
+                let (res, buf) = stream.read_fixed(buf).await;
+                assert_eq!(res.unwrap(), 4);
+                assert_eq!(&*buf, b">pong");
+            }
+        })
+    }
+
+    #[test]
+    fn multi_accept() {
+        tokio_uring::start(async {
+            let listener = crate::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let listener = Listener::Tcp(listener);
+
+            let mut accept = MultiAccept::new();
+
+            for _ in 0..2 {
+                let mut client = std::net::TcpStream::connect(addr).unwrap();
+                let stream = accept.accept(&listener).await.unwrap();
+                assert_eq!(stream.peer_addr(), Some(client.local_addr().unwrap()));
+
+                let stream = TcpStream::try_from(stream).unwrap();
+                let (res, _) = write_all(&stream, b"hi".to_vec()).await;
+                res.unwrap();
+                let mut buf = [0; 2];
+                client.read_exact(&mut buf).unwrap();
+                assert_eq!(&buf, b"hi");
+
+                // connection accepted by kernel after stop is still returned.
+                accept.stop();
+            }
+        })
     }
 }
@@ -0,0 +1,194 @@
+//! thread local signal for graceful shutdown of connections.
+//!
+//! Server triggers the signal on it's worker thread when it starts to shut down gracefully. Connection
+//! dispatchers running on the same thread observe it with [GracefulShutdown] and stop taking new requests
+//! while letting in-flight ones finish.
+
+use core::{
+    cell::{Cell, RefCell},
+    future::poll_fn,
+    task::{Context, Poll, Waker},
+};
+
+use std::rc::Rc;
+
+thread_local! {
+    static SIGNAL: RefCell<Rc<Signal>> = RefCell::new(Rc::new(Signal::default()));
+}
+
+#[derive(Default)]
+struct Signal {
+    triggered: Cell<bool>,
+    wakers: RefCell<Wakers>,
+}
+
+// waker slots of subscribers. a subscriber takes one slot when it's polled and releases it on drop so
+// memory is bound to the number of live subscribers.
+#[derive(Default)]
+struct Wakers {
+    slots: Vec<Option<Waker>>,
+    vacant: Vec<usize>,
+}
+
+impl Wakers {
+    fn insert(&mut self, waker: Waker) -> usize {
+        match self.vacant.pop() {
+            Some(key) => {
+                self.slots[key] = Some(waker);
+                key
+            }
+            None => {
+                self.slots.push(Some(waker));
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    fn remove(&mut self, key: usize) {
+        self.slots[key] = None;
+        self.vacant.push(key);
+    }
+}
+
+/// Graceful shutdown signal of current thread.
+pub struct GracefulShutdown {
+    signal: Rc<Signal>,
+    key: Cell<Option<usize>>,
+}
+
+impl Clone for GracefulShutdown {
+    fn clone(&self) -> Self {
+        Self::new(self.signal.clone())
+    }
+}
+
+impl Drop for GracefulShutdown {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.get() {
+            self.signal.wakers.borrow_mut().remove(key);
+        }
+    }
+}
+
+impl GracefulShutdown {
+    /// Subscribe to graceful shutdown signal of current thread.
+    pub fn current() -> Self {
+        SIGNAL.with(|signal| Self::new(signal.borrow().clone()))
+    }
+
+    fn new(signal: Rc<Signal>) -> Self {
+        Self {
+            signal,
+            key: Cell::new(None),
+        }
+    }
+
+    /// Check if graceful shutdown is triggered.
+    #[inline]
+    pub fn is_triggered(&self) -> bool {
+        self.signal.triggered.get()
+    }
+
+    /// Poll for graceful shutdown. Resolve when shutdown is triggered.
+    pub fn poll_triggered(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_triggered() {
+            return Poll::Ready(());
+        }
+
+        let mut wakers = self.signal.wakers.borrow_mut();
+        match self.key.get() {
+            Some(key) => match wakers.slots[key] {
+                Some(ref waker) if waker.will_wake(cx.waker()) => {}
+                ref mut waker => *waker = Some(cx.waker().clone()),
+            },
+            None => self.key.set(Some(wakers.insert(cx.waker().clone()))),
+        }
+
+        Poll::Pending
+    }
+
+    /// Wait for graceful shutdown to be triggered.
+    pub async fn triggered(&self) {
+        poll_fn(|cx| self.poll_triggered(cx)).await
+    }
+}
+
+/// Trigger graceful shutdown signal of current thread and wake up all subscribers.
+///
+/// Subscribers created after the call observe a fresh signal that is not triggered.
+pub fn trigger() {
+    let signal = SIGNAL.with(|signal| core::mem::take(&mut *signal.borrow_mut()));
+    signal.triggered.set(true);
+    // slots are kept for subscribers releasing them on drop.
+    let wakers = signal
+        .wakers
+        .borrow_mut()
+        .slots
+        .iter_mut()
+        .filter_map(Option::take)
+        .collect::<Vec<_>>();
+    wakers.into_iter().for_each(Waker::wake);
+}
+
+#[cfg(test)]
+mod test {
+    use core::{
+        future::Future,
+        pin::pin,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use std::{sync::Arc, task::Wake};
+
+    use super::*;
+
+    struct Counter(AtomicUsize);
+
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn slots(shutdown: &GracefulShutdown) -> usize {
+        let wakers = shutdown.signal.wakers.borrow();
+        wakers.slots.len() - wakers.vacant.len()
+    }
+
+    #[test]
+    fn release_on_drop() {
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let shutdown = GracefulShutdown::current();
+
+        for _ in 0..8 {
+            let sub = shutdown.clone();
+            assert!(sub.poll_triggered(&mut cx).is_pending());
+            // repeated polling of the same subscriber does not take new slot.
+            assert!(sub.poll_triggered(&mut cx).is_pending());
+            assert_eq!(slots(&shutdown), 1);
+        }
+        assert_eq!(slots(&shutdown), 0);
+        assert_eq!(shutdown.signal.wakers.borrow().slots.len(), 1);
+
+        {
+            let sub = shutdown.clone();
+            let mut fut = pin!(sub.triggered());
+            assert!(fut.as_mut().poll(&mut cx).is_pending());
+            assert!(shutdown.poll_triggered(&mut cx).is_pending());
+            assert_eq!(slots(&shutdown), 2);
+
+            trigger();
+            assert_eq!(counter.0.load(Ordering::Relaxed), 2);
+            assert!(fut.as_mut().poll(&mut cx).is_ready());
+            assert!(shutdown.poll_triggered(&mut cx).is_ready());
+        }
+        // subscriber dropped after trigger releases it's slot.
+        assert_eq!(slots(&shutdown), 1);
+
+        // new subscriber observes fresh signal.
+        assert!(!GracefulShutdown::current().is_triggered());
+    }
+}
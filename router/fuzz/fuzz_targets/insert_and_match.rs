@@ -1,21 +1,38 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
 
-fuzz_target!(|data: (Vec<(String, i32)>, String, Option<bool>)| {
-    let mut matcher = matchit::Node::new();
+// routes with parameter constraints and mid-segment parameters. their values are negative so they are
+// distinguishable from fuzzed routes.
+const ROUTES: &[(&str, i32)] = &[
+    ("/users/:id{uint}", -1),
+    ("/users/:name", -2),
+    ("/files/:name.:ext", -3),
+    ("/v:version{[0-9]+}/items", -4),
+];
+
+fuzz_target!(|data: (Vec<(String, i32)>, String)| {
+    let mut router = xitca_router::Router::new();
+
+    for (route, value) in ROUTES {
+        router.insert(*route, *value).unwrap();
+    }
 
     for (key, item) in data.0 {
-        if matcher.insert(key, item).is_err() {
+        if router.insert(key, item.max(0)).is_err() {
             return;
         }
     }
 
-    match data.2 {
-        None => {
-            let _ = matcher.at(&data.1);
-        }
-        Some(b) => {
-            let _ = matcher.path_ignore_case(&data.1, b);
+    if let Ok(matched) = router.at(&data.1) {
+        let params = matched.params;
+        match *matched.value {
+            -1 => assert!(params.get("id").unwrap().bytes().all(|b| b.is_ascii_digit())),
+            -3 => {
+                assert!(!params.get("name").unwrap().is_empty());
+                assert!(!params.get("ext").unwrap().contains('.'));
+            }
+            -4 => assert!(params.get("version").unwrap().bytes().all(|b| b.is_ascii_digit())),
+            _ => {}
         }
     }
 });
@@ -13,12 +13,18 @@ pub enum InsertError {
     },
     /// Route path is not in utf-8 format.
     Parse(Utf8Error),
-    /// Only one parameter per route segment is allowed.
+    /// Parameters in the same route segment must be separated by static text and can not be mixed with
+    /// catch-all parameter.
     TooManyParams,
     /// Parameters must be registered with a name.
     UnnamedParam,
     /// Catch-all parameters are only allowed at the end of a path.
     InvalidCatchAll,
+    /// Parameter constraint is malformed or unknown.
+    InvalidConstraint {
+        /// The constraint failed to parse.
+        constraint: String,
+    },
 }
 
 impl fmt::Display for InsertError {
@@ -31,9 +37,10 @@ impl fmt::Display for InsertError {
                 )
             }
             Self::Parse(ref e) => fmt::Display::fmt(e, f),
-            Self::TooManyParams => f.write_str("only one parameter is allowed per path segment"),
+            Self::TooManyParams => f.write_str("parameters in a path segment must be separated by static text"),
             Self::UnnamedParam => f.write_str("parameters must be registered with a name"),
             Self::InvalidCatchAll => f.write_str("catch-all parameters are only allowed at the end of a route"),
+            Self::InvalidConstraint { constraint } => write!(f, "invalid parameter constraint: {constraint}"),
         }
     }
 }
@@ -47,6 +54,12 @@ impl From<Utf8Error> for InsertError {
 }
 
 impl InsertError {
+    pub(crate) fn invalid_constraint(constraint: &[u8]) -> Self {
+        InsertError::InvalidConstraint {
+            constraint: String::from_utf8_lossy(constraint).into_owned(),
+        }
+    }
+
     pub(crate) fn conflict<T>(route: &[u8], prefix: &[u8], current: &Node<T>) -> Self {
         let mut route = route[..route.len() - prefix.len()].to_owned();
 
@@ -84,3 +97,36 @@ impl fmt::Display for MatchError {
 }
 
 impl std::error::Error for MatchError {}
+
+/// A recommended path for a path failed to match any route, returned by [`Router::recommend`](crate::Router::recommend).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Recommendation {
+    /// Path would match a route after adding or removing trailing slash.
+    TrailingSlash(String),
+    /// Path would match a route with ascii case insensitive matching. Trailing slash may also be fixed.
+    CaseInsensitive(String),
+}
+
+impl Recommendation {
+    /// The recommended path.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::TrailingSlash(path) | Self::CaseInsensitive(path) => path,
+        }
+    }
+
+    /// Mutable reference to the recommended path. Useful for adding prefix to path.
+    pub fn path_mut(&mut self) -> &mut String {
+        match self {
+            Self::TrailingSlash(path) | Self::CaseInsensitive(path) => path,
+        }
+    }
+}
+
+impl fmt::Display for Recommendation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "router error: route not found. recommended path: {}", self.path())
+    }
+}
+
+impl std::error::Error for Recommendation {}
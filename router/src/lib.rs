@@ -41,6 +41,47 @@
 //! # }
 //! ```
 //!
+//! ### Parameter Constraints
+//!
+//! Named parameters can be followed by a constraint in braces. The parameter only matches when the whole
+//! value satisfies the constraint. Built in constraints are `int`, `uint`, `alpha`, `alnum`, `hex` and `uuid`.
+//! With `regex` feature enabled any other constraint is treated as a regular expression:
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut m = xitca_router::Router::new();
+//! m.insert("/users/:id{uint}", "id")?;
+//! m.insert("/users/:name", "name")?;
+//!
+//! assert_eq!(*m.at("/users/1")?.value, "id");
+//! assert_eq!(*m.at("/users/foo")?.value, "name");
+//!
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ### Mid-segment Parameters
+//!
+//! A path segment can contain static text around named parameters and multiple parameters separated by
+//! static text. Parameter names consist of ascii alphanumeric characters and `_`. When static text appears
+//! more than once in a value the right most occurrence is used as separator:
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut m = xitca_router::Router::new();
+//! m.insert("/files/:name.:ext", true)?;
+//! m.insert("/v:version/items", true)?;
+//!
+//! let params = m.at("/files/archive.tar.gz")?.params;
+//! assert_eq!(params.get("name"), Some("archive.tar"));
+//! assert_eq!(params.get("ext"), Some("gz"));
+//!
+//! assert_eq!(m.at("/v2/items")?.params.get("version"), Some("2"));
+//!
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ### Catch-all Parameters
 //!
 //! Catch-all parameters start with `*` and match everything after the `/`.
@@ -90,14 +131,37 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! Parameter segments at the same position are tried in the following order and the next one is tried
+//! when the rest of the path fails to match:
+//! 1. segments with more static text. ex: `:name.json` before `:name.:ext` before `:name`.
+//! 2. segments with more constrained parameters. ex: `:id{uint}` before `:id`.
+//! 3. registration order.
+//!
+//! Parameter segments that only differ in parameter names are conflicting. Catch-all parameters can not
+//! share a position with any parameter segment:
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut m = xitca_router::Router::new();
+//! m.insert("/:name.:ext", true)?;
+//! m.insert("/:name{alpha}", true)?;
+//!
+//! assert!(m.insert("/:file.:extension", true).is_err());
+//! assert!(m.insert("/*rest", true).is_err());
+//!
+//! # Ok(())
+//! # }
+//! ```
 
 #![forbid(unsafe_code)]
 
 mod error;
 mod router;
+mod segment;
 mod tree;
 
 pub mod params;
 
-pub use error::{InsertError, MatchError};
+pub use error::{InsertError, MatchError, Recommendation};
 pub use router::{Match, Router};
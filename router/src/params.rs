@@ -41,6 +41,8 @@ impl Param {
 #[derive(Clone, Debug)]
 pub struct Params {
     inner: Vec<Param>,
+    // length of leading parameters kept by Params::replace.
+    retained: usize,
 }
 
 impl Default for Params {
@@ -77,11 +79,42 @@ impl Params {
             inner: self.inner.iter(),
         }
     }
+
+    /// Replace parameters with the ones from other route match. Parameters marked by [Params::retain] are
+    /// kept in front of the new parameters.
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut router = xitca_router::Router::new();
+    /// # router.insert("/:sub.example.com", true)?;
+    /// # router.insert("/users/:id", true)?;
+    /// let mut params = router.at("/api.example.com")?.params;
+    /// params.retain();
+    ///
+    /// params.replace(router.at("/users/1")?.params);
+    /// assert_eq!(params.get("sub"), Some("api"));
+    /// assert_eq!(params.get("id"), Some("1"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn replace(&mut self, other: Params) {
+        self.inner.truncate(self.retained);
+        self.inner.extend(other.inner);
+    }
+
+    /// Mark all current parameters as retained. See [Params::replace] for detail.
+    #[inline]
+    pub fn retain(&mut self) {
+        self.retained = self.inner.len();
+    }
 }
 
 impl Params {
     pub(super) const fn new() -> Self {
-        Self { inner: Vec::new() }
+        Self {
+            inner: Vec::new(),
+            retained: 0,
+        }
     }
 
     pub(super) fn truncate(&mut self, n: usize) {
@@ -1,4 +1,6 @@
-use super::{params::Params, tree::Node, InsertError, MatchError};
+use std::collections::HashSet;
+
+use super::{params::Params, tree::Node, InsertError, MatchError, Recommendation};
 
 /// A URL router.
 ///
@@ -38,6 +40,91 @@ impl<T> Router<T> {
         self.root.insert(route, value)
     }
 
+    /// Remove a route and return it's value. The route must be the same as it was inserted with,
+    /// parameter names and constraints included. Returns `Ok(None)` when route is not found.
+    ///
+    /// Removal rebuilds the tree from remaining routes and is meant for routing table that changes
+    /// occasionally. Remaining routes keep their priorities including the registration order of
+    /// parameter segments.
+    ///
+    /// # Errors
+    ///
+    /// When a remaining route fails to be inserted to rebuilt tree. Routes after the failed one are lost.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xitca_router::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/home", "Welcome!")?;
+    /// router.insert("/users/:id", "A User")?;
+    ///
+    /// assert_eq!(router.remove("/users/:id")?, Some("A User"));
+    /// assert_eq!(router.remove("/users/:id")?, None);
+    /// assert!(router.at("/users/996").is_err());
+    /// assert!(router.at("/home").is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove(&mut self, route: &str) -> Result<Option<T>, InsertError> {
+        let mut routes = self.take_routes();
+
+        let value = routes.iter().position(|(r, _)| r == route).map(|i| routes.remove(i).1);
+
+        self.extend(routes)?;
+
+        Ok(value)
+    }
+
+    /// Merge routes of another router into current one.
+    ///
+    /// Merging is atomic. When any route of other router conflicts with existing routes current router
+    /// is left unchanged and the error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xitca_router::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/home", "Welcome!")?;
+    ///
+    /// let mut other = Router::new();
+    /// other.insert("/users/:id", "A User")?;
+    /// router.merge(other)?;
+    ///
+    /// assert_eq!(*router.at("/users/996")?.value, "A User");
+    ///
+    /// let mut other = Router::new();
+    /// other.insert("/about", "About")?;
+    /// other.insert("/home", "Another Welcome!")?;
+    /// assert!(router.merge(other).is_err());
+    ///
+    /// assert_eq!(*router.at("/home")?.value, "Welcome!");
+    /// assert!(router.at("/about").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge(&mut self, other: Router<T>) -> Result<(), InsertError> {
+        let existing = self.take_routes();
+        let paths = existing.iter().map(|(route, _)| route.clone()).collect::<HashSet<_>>();
+
+        self.extend(existing)?;
+
+        for (route, value) in other.take_routes_owned() {
+            if let Err(e) = self.root.insert(route, value) {
+                // the tree may have been partially modified by failed insertion. rebuild it from existing routes.
+                let mut routes = self.take_routes();
+                routes.retain(|(route, _)| paths.contains(route));
+                self.extend(routes)?;
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Tries to find a value in the router matching the given path.
     ///
     /// # Examples
@@ -58,6 +145,77 @@ impl<T> Router<T> {
         self.root.at(path).map(|(value, params)| Match { value, params })
     }
 
+    /// Recommend a path that would match a route when the given path fails to match any.
+    ///
+    /// Adding or removing trailing slash is tried first. Then ascii case insensitive matching on static
+    /// segments with and without trailing slash fixed. Returns `None` when path already matches a route or
+    /// no recommendation can be made.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xitca_router::{Recommendation, Router};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/home/", "Welcome!")?;
+    /// router.insert("/users/:id", "A User")?;
+    ///
+    /// assert!(router.at("/home").is_err());
+    /// assert_eq!(
+    ///     router.recommend("/home"),
+    ///     Some(Recommendation::TrailingSlash("/home/".into()))
+    /// );
+    /// assert_eq!(
+    ///     router.recommend("/USERS/Foo/"),
+    ///     Some(Recommendation::CaseInsensitive("/users/Foo".into()))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn recommend(&self, path: &str) -> Option<Recommendation> {
+        if self.at(path).is_ok() {
+            return None;
+        }
+
+        let fixed = match path.strip_suffix('/') {
+            Some(p) if !p.is_empty() => String::from(p),
+            Some(_) => String::new(),
+            None => format!("{path}/"),
+        };
+
+        if !fixed.is_empty() && self.at(&fixed).is_ok() {
+            return Some(Recommendation::TrailingSlash(fixed));
+        }
+
+        let mut buf = String::with_capacity(path.len() + 1);
+
+        for path in [path, fixed.as_str()] {
+            buf.clear();
+            if !path.is_empty() && self.root.at_ignore_case(path, &mut buf) {
+                return Some(Recommendation::CaseInsensitive(buf));
+            }
+        }
+
+        None
+    }
+
+    fn take_routes(&mut self) -> Vec<(String, T)> {
+        core::mem::replace(self, Self::new()).take_routes_owned()
+    }
+
+    fn take_routes_owned(self) -> Vec<(String, T)> {
+        let mut routes = Vec::new();
+        self.root.drain_routes(&mut Vec::new(), &mut routes);
+        routes
+    }
+
+    // routes drained from a valid tree are expected to be inserted back without error.
+    fn extend(&mut self, routes: Vec<(String, T)>) -> Result<(), InsertError> {
+        routes
+            .into_iter()
+            .try_for_each(|(route, value)| self.root.insert(route, value))
+    }
+
     #[cfg(feature = "__test_helpers")]
     pub fn check_priorities(&self) -> Result<u32, (u32, u32)> {
         self.root.check_priorities()
@@ -0,0 +1,266 @@
+use core::ops::Range;
+
+use super::{params::Params, InsertError};
+
+/// A lexical token of a wildcard path segment.
+pub(crate) enum Token {
+    /// A named parameter, ex: `:id` or `:id{uint}`.
+    Param {
+        /// Range of parameter name including the leading `:`.
+        name: Range<usize>,
+        /// Range of constraint excluding the surrounding braces.
+        constraint: Option<Range<usize>>,
+    },
+    /// Static text between or after parameters, ex: the `.` in `:name.:ext`.
+    Static(Range<usize>),
+}
+
+/// Tokenize a parameter segment starting with `:` until the next `/` or the end of path.
+///
+/// Returns the tokens and the length of the segment.
+pub(crate) fn tokenize(path: &[u8]) -> Result<(Vec<Token>, usize), InsertError> {
+    debug_assert_eq!(path.first(), Some(&b':'));
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    loop {
+        match path.get(i) {
+            None | Some(b'/') => return Ok((tokens, i)),
+            Some(b':') => {
+                // "/:foo:bar" can not be split into separate parameters.
+                if let Some(Token::Param { .. }) = tokens.last() {
+                    return Err(InsertError::TooManyParams);
+                }
+
+                let mut end = i + 1;
+                while path.get(end).is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_') {
+                    end += 1;
+                }
+
+                if end == i + 1 {
+                    return Err(InsertError::UnnamedParam);
+                }
+
+                let name = i..end;
+                let mut constraint = None;
+
+                if path.get(end) == Some(&b'{') {
+                    let range = constraint_range(&path[end..])?;
+                    constraint = Some(end + range.start..end + range.end);
+                    end += range.end + 1;
+                }
+
+                tokens.push(Token::Param { name, constraint });
+                i = end;
+            }
+            // catch-all can not share a segment with parameters.
+            Some(b'*') => return Err(InsertError::TooManyParams),
+            Some(_) => {
+                let mut end = i + 1;
+                while path.get(end).is_some_and(|c| !matches!(c, b':' | b'*' | b'/')) {
+                    end += 1;
+                }
+                tokens.push(Token::Static(i..end));
+                i = end;
+            }
+        }
+    }
+}
+
+// find the range of a brace enclosed constraint. braces inside constraint must be balanced.
+fn constraint_range(path: &[u8]) -> Result<Range<usize>, InsertError> {
+    let mut depth = 0usize;
+
+    for (i, c) in path.iter().enumerate() {
+        match c {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    // empty constraint.
+                    if i == 1 {
+                        return Err(InsertError::invalid_constraint(&path[..=i]));
+                    }
+                    return Ok(1..i);
+                }
+            }
+            // unclosed constraint.
+            b'/' => return Err(InsertError::invalid_constraint(&path[..i])),
+            _ => {}
+        }
+    }
+
+    Err(InsertError::invalid_constraint(path))
+}
+
+/// Constraint applied to the value of a named parameter.
+#[derive(Clone)]
+pub(crate) enum Constraint {
+    /// `{int}`: optional `-` sign followed by ascii digits.
+    Int,
+    /// `{uint}`: ascii digits.
+    Uint,
+    /// `{alpha}`: ascii letters.
+    Alpha,
+    /// `{alnum}`: ascii letters and digits.
+    Alnum,
+    /// `{hex}`: ascii hex digits.
+    Hex,
+    /// `{uuid}`: hyphenated uuid in ascii hex digits.
+    Uuid,
+    /// any other constraint is treated as a regular expression that must match the whole value.
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Constraint {
+    pub(crate) fn parse(constraint: &str) -> Result<Self, InsertError> {
+        match constraint {
+            "int" => Ok(Self::Int),
+            "uint" => Ok(Self::Uint),
+            "alpha" => Ok(Self::Alpha),
+            "alnum" => Ok(Self::Alnum),
+            "hex" => Ok(Self::Hex),
+            "uuid" => Ok(Self::Uuid),
+            #[cfg(feature = "regex")]
+            _ => regex::Regex::new(&format!("^(?:{constraint})$"))
+                .map(Self::Regex)
+                .map_err(|_| InsertError::invalid_constraint(constraint.as_bytes())),
+            #[cfg(not(feature = "regex"))]
+            _ => Err(InsertError::invalid_constraint(constraint.as_bytes())),
+        }
+    }
+
+    fn is_match(&self, value: &str) -> bool {
+        fn all(value: &str, f: impl Fn(&u8) -> bool) -> bool {
+            !value.is_empty() && value.as_bytes().iter().all(f)
+        }
+
+        match self {
+            Self::Int => all(value.strip_prefix('-').unwrap_or(value), u8::is_ascii_digit),
+            Self::Uint => all(value, u8::is_ascii_digit),
+            Self::Alpha => all(value, u8::is_ascii_alphabetic),
+            Self::Alnum => all(value, u8::is_ascii_alphanumeric),
+            Self::Hex => all(value, u8::is_ascii_hexdigit),
+            Self::Uuid => {
+                value.len() == 36
+                    && value.bytes().enumerate().all(|(i, c)| match i {
+                        8 | 13 | 18 | 23 => c == b'-',
+                        _ => c.is_ascii_hexdigit(),
+                    })
+            }
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Part {
+    Param(Option<Constraint>),
+    Static(Box<str>),
+}
+
+/// A compiled parameter segment matching a single path segment.
+#[derive(Clone)]
+pub(crate) struct Segment {
+    parts: Vec<Part>,
+    statics: usize,
+    constraints: usize,
+}
+
+impl Segment {
+    pub(crate) fn parse(segment: &str) -> Result<Self, InsertError> {
+        let (tokens, _) = tokenize(segment.as_bytes())?;
+
+        let mut statics = 0;
+        let mut constraints = 0;
+
+        let parts = tokens
+            .into_iter()
+            .map(|token| match token {
+                Token::Param { constraint, .. } => {
+                    let constraint = constraint
+                        .map(|range| {
+                            constraints += 1;
+                            Constraint::parse(&segment[range])
+                        })
+                        .transpose()?;
+                    Ok(Part::Param(constraint))
+                }
+                Token::Static(range) => {
+                    statics += range.len();
+                    Ok(Part::Static(segment[range].into()))
+                }
+            })
+            .collect::<Result<_, InsertError>>()?;
+
+        Ok(Self {
+            parts,
+            statics,
+            constraints,
+        })
+    }
+
+    /// Matching priority of segment. Segments with more static text are matched first, then the ones
+    /// with more constrained parameters.
+    pub(crate) fn rank(&self) -> (usize, usize) {
+        (self.statics, self.constraints)
+    }
+
+    /// Match given path segment and push parameter values to params on success.
+    pub(crate) fn matches(&self, segment: &str, params: &mut Params) -> bool {
+        let len = params.len();
+        let matched = match_parts(&self.parts, segment, params);
+        if !matched {
+            params.truncate(len);
+        }
+        matched
+    }
+}
+
+fn match_parts(parts: &[Part], segment: &str, params: &mut Params) -> bool {
+    match parts {
+        [] => segment.is_empty(),
+        [Part::Static(s), rest @ ..] => segment
+            .strip_prefix(&**s)
+            .is_some_and(|segment| match_parts(rest, segment, params)),
+        [Part::Param(constraint), rest @ ..] => {
+            let Some((Part::Static(s), rest)) = rest.split_first() else {
+                // parameter at the end of segment takes everything left.
+                debug_assert!(rest.is_empty(), "adjacent parameters are rejected on insertion");
+                if is_match(constraint, segment) {
+                    params.push("", segment);
+                    return true;
+                }
+                return false;
+            };
+
+            // static text after parameter is searched from the right so parameter value is as long as
+            // possible. ex: `:name.:ext` matches `archive.tar.gz` with name = archive.tar and ext = gz.
+            for (i, _) in segment.rmatch_indices(&**s) {
+                let value = &segment[..i];
+
+                if !is_match(constraint, value) {
+                    continue;
+                }
+
+                let len = params.len();
+                params.push("", value);
+
+                if match_parts(rest, &segment[i + s.len()..], params) {
+                    return true;
+                }
+
+                params.truncate(len);
+            }
+
+            false
+        }
+    }
+}
+
+fn is_match(constraint: &Option<Constraint>, value: &str) -> bool {
+    !value.is_empty() && constraint.as_ref().is_none_or(|c| c.is_match(value))
+}
@@ -1,13 +1,17 @@
 use core::{cmp::min, mem, str::from_utf8};
 
-use super::{params::Params, InsertError, MatchError};
+use super::{
+    params::Params,
+    segment::{tokenize, Constraint, Segment, Token},
+    InsertError, MatchError,
+};
 
 /// The types of nodes the tree can hold
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
 pub(crate) enum NodeType {
     /// The root path
     Root,
-    /// A route parameter, ex: `/:id`.
+    /// A route parameter segment, ex: `/:id`, `/:id{uint}` or `/:name.:ext`.
     Param,
     /// A catchall parameter, ex: `/*file`
     CatchAll,
@@ -24,6 +28,7 @@ pub struct Node<T> {
     wild_child: bool,
     indices: Vec<u8>,
     value: Option<T>,
+    segment: Option<Segment>,
     pub(crate) param_remapping: ParamRemapping,
     pub(crate) node_type: NodeType,
     pub(crate) prefix: String,
@@ -43,6 +48,7 @@ impl<T> Node<T> {
             wild_child: false,
             indices: Vec::new(),
             value: None,
+            segment: None,
             param_remapping: ParamRemapping::new(),
             node_type: NodeType::Static,
             prefix: String::new(),
@@ -130,25 +136,39 @@ impl<T> Node<T> {
                     return Ok(());
                 }
 
-                // inserting a wildcard, and this node already has a wildcard child
+                // inserting a wildcard, and this node already has wildcard children
                 if current.wild_child {
+                    let (wildcard, _) = find_wildcard(prefix)?.expect("prefix must start with wildcard");
+
                     // wildcards are always at the end
-                    current = current.children.last_mut().unwrap();
-                    current.priority += 1;
+                    let wilds = current.children.len() - current.wild_children().len();
 
-                    // make sure the wildcard matches
-                    if prefix.len() < current.prefix.len()
-                        || current.prefix.as_bytes() != &prefix[..current.prefix.len()]
-                        // catch-alls cannot have children 
-                        || current.node_type == NodeType::CatchAll
-                        // check for longer wildcard, e.g. :name and :names
-                        || (current.prefix.len() < prefix.len()
-                            && prefix[current.prefix.len()] != b'/')
+                    match current.children[wilds..]
+                        .iter()
+                        .position(|child| child.prefix.as_bytes() == wildcard)
                     {
-                        return Err(InsertError::conflict(&route, prefix, current));
-                    }
+                        // the same wildcard segment, search deeper
+                        Some(i) => {
+                            current = &mut current.children[wilds + i];
+                            current.priority += 1;
+
+                            // catch-alls cannot have children
+                            if current.node_type == NodeType::CatchAll {
+                                return Err(InsertError::conflict(&route, prefix, current));
+                            }
 
-                    continue 'walk;
+                            continue 'walk;
+                        }
+                        // param segments with different pattern can co-exist but catch-all can not share
+                        // it's position with any other wildcard.
+                        None => {
+                            let last = current.children.last_mut().unwrap();
+                            if wildcard[0] == b'*' || last.node_type == NodeType::CatchAll {
+                                last.priority += 1;
+                                return Err(InsertError::conflict(&route, prefix, last));
+                            }
+                        }
+                    }
                 }
 
                 // otherwise, create the wildcard node
@@ -170,17 +190,42 @@ impl<T> Node<T> {
         }
     }
 
-    // add a child node, keeping wildcards at the end
+    // add a child node, keeping wildcards at the end.
+    //
+    // param segments are ordered by their rank and then insertion order. catch-all is always the last.
     fn add_child(&mut self, child: Node<T>) -> usize {
         let len = self.children.len();
+        let wilds = len - self.wild_children().len();
+
+        let i = match child.node_type {
+            NodeType::Param => {
+                let rank = child.rank();
+                self.children[wilds..]
+                    .iter()
+                    .position(|c| c.node_type == NodeType::CatchAll || c.rank() < rank)
+                    .map_or(len, |i| wilds + i)
+            }
+            NodeType::CatchAll => len,
+            _ => wilds,
+        };
 
-        if self.wild_child && len > 0 {
-            self.children.insert(len - 1, child);
-            len - 1
-        } else {
-            self.children.push(child);
-            len
-        }
+        self.children.insert(i, child);
+        i
+    }
+
+    // wildcard children are always at the end of children.
+    fn wild_children(&self) -> &[Self] {
+        let n = self
+            .children
+            .iter()
+            .rev()
+            .take_while(|c| matches!(c.node_type, NodeType::Param | NodeType::CatchAll))
+            .count();
+        &self.children[self.children.len() - n..]
+    }
+
+    fn rank(&self) -> (usize, usize) {
+        self.segment.as_ref().map(Segment::rank).unwrap_or_default()
     }
 
     // increments priority of the given child and reorders if necessary.
@@ -236,9 +281,12 @@ impl<T> Node<T> {
                     prefix = &prefix[wildcard_index..];
                 }
 
+                let wildcard = from_utf8(wildcard)?;
+
                 let child = Self {
                     node_type: NodeType::Param,
-                    prefix: from_utf8(wildcard)?.into(),
+                    segment: Some(Segment::parse(wildcard)?),
+                    prefix: wildcard.into(),
                     ..Self::default()
                 };
 
@@ -307,15 +355,38 @@ impl<T> Node<T> {
     }
 }
 
+impl<T> Node<T> {
+    // drain routes and their values from tree in depth first order. routes are restored to their original
+    // form with parameter names and wildcard nodes are visited in their matching order.
+    pub(crate) fn drain_routes(self, route: &mut Vec<u8>, routes: &mut Vec<(String, T)>) {
+        let len = route.len();
+        route.extend_from_slice(self.prefix.as_bytes());
+
+        if let Some(value) = self.value {
+            let mut route = route.clone();
+            denormalize_params(&mut route, &self.param_remapping);
+            routes.push((String::from_utf8(route).unwrap(), value));
+        }
+
+        for child in self.children {
+            child.drain_routes(route, routes);
+        }
+
+        route.truncate(len);
+    }
+}
+
 struct Skipped<'n, 'p, T> {
     path: &'p str,
     node: &'n Node<T>,
     params: usize,
+    // index of the next wildcard child to try.
+    wild: usize,
 }
 
 #[rustfmt::skip]
 macro_rules! backtracker {
-    ($skipped_nodes:ident, $path:ident, $current:ident, $params:ident, $backtracking:ident, $walk:lifetime) => {
+    ($skipped_nodes:ident, $path:ident, $current:ident, $params:ident, $backtracking:ident, $wild:ident, $walk:lifetime) => {
         macro_rules! try_backtrack {
             () => {
                 // try backtracking to any matching wildcard nodes we skipped while traversing
@@ -326,6 +397,7 @@ macro_rules! backtracker {
                         $current = &skipped.node;
                         $params.truncate(skipped.params);
                         $backtracking = true;
+                        $wild = skipped.wild;
                         continue $walk;
                     }
                 }
@@ -342,11 +414,12 @@ impl<T> Node<T> {
         let mut current = self;
         let mut path = full_path;
         let mut backtracking = false;
+        let mut wild = 0;
         let mut params = Params::new();
         let mut skipped_nodes = Vec::new();
 
         'walk: loop {
-            backtracker!(skipped_nodes, path, current, params, backtracking, 'walk);
+            backtracker!(skipped_nodes, path, current, params, backtracking, wild, 'walk);
 
             // the path is longer than this node's prefix, we are expecting a child node
             if path.len() > current.prefix.len() {
@@ -369,6 +442,7 @@ impl<T> Node<T> {
                                     path: consumed,
                                     node: current,
                                     params: params.len(),
+                                    wild: 0,
                                 });
                             }
 
@@ -386,64 +460,72 @@ impl<T> Node<T> {
                         break;
                     }
 
-                    // handle the wildcard child, which is always at the end of the list
-                    current = current.children.last().unwrap();
-
-                    match current.node_type {
-                        NodeType::Param => {
-                            // check if there are more segments in the path other than this parameter
-                            match path.chars().position(|c| c == '/') {
-                                Some(i) => {
-                                    let (param, rest) = path.split_at(i);
-
-                                    if let [child] = current.children.as_slice() {
-                                        // store the parameter value
-                                        params.push(&current.prefix[1..], param);
-
-                                        // continue with the child node
-                                        path = rest;
-                                        current = child;
-                                        backtracking = false;
-                                        continue 'walk;
-                                    }
+                    // handle the wildcard children, which are always at the end of the list
+                    let parent = current;
+                    let wilds = parent.wild_children();
+
+                    for (i, child) in wilds.iter().enumerate().skip(wild) {
+                        match child.node_type {
+                            NodeType::Param => {
+                                // parameter segment ends at the next `/` or the end of path
+                                let (segment, rest) = path.split_at(path.find('/').unwrap_or(path.len()));
+
+                                let len = params.len();
 
-                                    try_backtrack!();
+                                // store the parameter values
+                                if !child.segment.as_ref().unwrap().matches(segment, &mut params) {
+                                    continue;
                                 }
-                                // this is the last path segment
-                                None => {
-                                    // store the parameter value
-                                    params.push(&current.prefix[1..], path);
 
+                                // this is the last path segment
+                                if rest.is_empty() {
                                     // found the matching value
-                                    if let Some(ref value) = current.value {
+                                    if let Some(ref value) = child.value {
                                         // remap parameter keys
-                                        params
-                                            .for_each_key_mut(|(i, key)| *key = current.param_remapping[i][1..].into());
+                                        params.for_each_key_mut(|(i, key)| *key = child.param_remapping[i][1..].into());
 
                                         return Ok((value, params));
                                     }
+                                } else if let [next] = child.children.as_slice() {
+                                    // keep track of the wildcard siblings to backtrack to later if we don't
+                                    // find a match
+                                    if i + 1 < wilds.len() {
+                                        skipped_nodes.push(Skipped {
+                                            path: consumed,
+                                            node: parent,
+                                            params: len,
+                                            wild: i + 1,
+                                        });
+                                    }
 
-                                    try_backtrack!();
-
-                                    // this node doesn't have the value, no match
+                                    // continue with the child node
+                                    path = rest;
+                                    current = next;
+                                    backtracking = false;
+                                    wild = 0;
+                                    continue 'walk;
                                 }
+
+                                params.truncate(len);
                             }
-                        }
-                        NodeType::CatchAll => {
-                            // catch all segments are only allowed at the end of the route,
-                            // either this node has the value or there is no match
-                            if let Some(ref value) = current.value {
-                                // remap parameter keys
-                                params.for_each_key_mut(|(i, key)| *key = current.param_remapping[i][1..].into());
+                            NodeType::CatchAll => {
+                                // catch all segments are only allowed at the end of the route,
+                                // either this node has the value or there is no match
+                                if let Some(ref value) = child.value {
+                                    // remap parameter keys
+                                    params.for_each_key_mut(|(i, key)| *key = child.param_remapping[i][1..].into());
 
-                                // store the final catch-all parameter
-                                params.push(&current.prefix[1..], path);
+                                    // store the final catch-all parameter
+                                    params.push(&child.prefix[1..], path);
 
-                                return Ok((value, params));
+                                    return Ok((value, params));
+                                }
                             }
-                        }
-                        _ => unreachable!(),
-                    };
+                            _ => unreachable!(),
+                        };
+                    }
+
+                    try_backtrack!();
 
                     break;
                 }
@@ -477,6 +559,79 @@ impl<T> Node<T> {
         Err(MatchError)
     }
 
+    // match path with ascii case insensitive static segments. on success the path with the case of
+    // registered route is written to buf.
+    pub(crate) fn at_ignore_case(&self, path: &str, buf: &mut String) -> bool {
+        let len = buf.len();
+
+        let prefix = self.prefix.as_bytes();
+
+        if path.len() < prefix.len() || !path.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix) {
+            return false;
+        }
+
+        buf.push_str(&self.prefix);
+        let path = &path[prefix.len()..];
+
+        if path.is_empty() {
+            // the node itself or it's single * wildcard holds the value
+            if self.value.is_some()
+                || self
+                    .children
+                    .first()
+                    .is_some_and(|c| c.prefix == "*" && c.value.is_some())
+            {
+                return true;
+            }
+        } else {
+            let first = path.as_bytes()[0];
+
+            for (i, c) in self.indices.iter().enumerate() {
+                if c.eq_ignore_ascii_case(&first) && self.children[i].at_ignore_case(path, buf) {
+                    return true;
+                }
+            }
+
+            for child in self.wild_children() {
+                match child.node_type {
+                    NodeType::Param => {
+                        let (segment, rest) = path.split_at(path.find('/').unwrap_or(path.len()));
+
+                        // parameter values are matched as is.
+                        if !child.segment.as_ref().unwrap().matches(segment, &mut Params::new()) {
+                            continue;
+                        }
+
+                        let len = buf.len();
+                        buf.push_str(segment);
+
+                        if rest.is_empty() {
+                            if child.value.is_some() {
+                                return true;
+                            }
+                        } else if let [next] = child.children.as_slice() {
+                            if next.at_ignore_case(rest, buf) {
+                                return true;
+                            }
+                        }
+
+                        buf.truncate(len);
+                    }
+                    NodeType::CatchAll => {
+                        if child.value.is_some() {
+                            buf.push_str(path);
+                            return true;
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        buf.truncate(len);
+        false
+    }
+
     #[cfg(feature = "__test_helpers")]
     pub fn check_priorities(&self) -> Result<u32, (u32, u32)> {
         let mut priority: u32 = 0;
@@ -514,11 +669,6 @@ fn normalize_params(mut path: Vec<u8>) -> Result<(Vec<u8>, ParamRemapping), Inse
             None => return Ok((path, original)),
         };
 
-        // makes sure the param has a valid name unless it's * catch all
-        if wildcard.len() < 2 && wildcard.first().filter(|w| **w == b'*').is_none() {
-            return Err(InsertError::UnnamedParam);
-        }
-
         // don't need to normalize catch-all parameters
         if wildcard[0] == b'*' {
             start += wildcard_index + wildcard.len();
@@ -527,20 +677,39 @@ fn normalize_params(mut path: Vec<u8>) -> Result<(Vec<u8>, ParamRemapping), Inse
 
         wildcard_index += start;
 
-        // normalize the parameter
-        let removed = path.splice((wildcard_index)..(wildcard_index + wildcard.len()), vec![b':', next]);
+        let (tokens, len) = tokenize(wildcard)?;
 
-        // remember the original name for remappings
-        let removed = removed.collect::<Vec<u8>>();
-        original.push(from_utf8(&removed[..])?.into());
+        // normalize the parameters in segment and keep static text and constraints as is
+        let mut normalized = Vec::with_capacity(len);
 
-        // get the next key
-        next += 1;
-        if next > b'z' {
-            panic!("too many route parameters");
+        for token in tokens {
+            match token {
+                Token::Param { name, constraint } => {
+                    // remember the original name for remappings
+                    original.push(from_utf8(&wildcard[name])?.into());
+
+                    normalized.extend_from_slice(&[b':', next]);
+
+                    if let Some(constraint) = constraint {
+                        // make sure constraint is valid before modifying the tree.
+                        Constraint::parse(from_utf8(&wildcard[constraint.clone()])?)?;
+                        normalized.push(b'{');
+                        normalized.extend_from_slice(&wildcard[constraint]);
+                        normalized.push(b'}');
+                    }
+
+                    // get the next key
+                    next += 1;
+                    if next > b'z' {
+                        panic!("too many route parameters");
+                    }
+                }
+                Token::Static(range) => normalized.extend_from_slice(&wildcard[range]),
+            }
         }
 
-        start = wildcard_index + 2;
+        start = wildcard_index + normalized.len();
+        path.splice(wildcard_index..wildcard_index + len, normalized);
     }
 }
 
@@ -558,39 +727,63 @@ pub(crate) fn denormalize_params(route: &mut Vec<u8>, params: &ParamRemapping) {
 
         wildcard_index += start;
 
-        let next = match params.get(i) {
-            Some(param) => param.clone(),
-            None => return,
-        };
+        // catch-all parameters are not normalized
+        if wildcard[0] == b'*' {
+            start = wildcard_index + wildcard.len();
+            continue;
+        }
+
+        let (tokens, len) = tokenize(wildcard).unwrap();
+
+        let mut denormalized = Vec::with_capacity(len);
+
+        for token in tokens {
+            match token {
+                Token::Param { name, constraint } => {
+                    // denormalize this parameter
+                    match params.get(i) {
+                        Some(param) => denormalized.extend_from_slice(param.as_bytes()),
+                        None => denormalized.extend_from_slice(&wildcard[name]),
+                    }
+
+                    if let Some(constraint) = constraint {
+                        denormalized.extend_from_slice(&wildcard[constraint.start - 1..constraint.end + 1]);
+                    }
 
-        // denormalize this parameter
-        route.splice(
-            (wildcard_index)..(wildcard_index + wildcard.len()),
-            next.as_bytes().iter().copied(),
-        );
+                    i += 1;
+                }
+                Token::Static(range) => denormalized.extend_from_slice(&wildcard[range]),
+            }
+        }
 
-        i += 1;
-        start = wildcard_index + 2;
+        start = wildcard_index + denormalized.len();
+        route.splice(wildcard_index..wildcard_index + len, denormalized);
     }
 }
 
 // Searches for a wildcard segment and checks the path for invalid characters.
+//
+// A param segment starts with ':' and a catch-all starts with '*'. Both of them span to the next '/'.
 fn find_wildcard(path: &[u8]) -> Result<Option<(&[u8], usize)>, InsertError> {
     for (start, &c) in path.iter().enumerate() {
-        // a wildcard starts with ':' (param) or '*' (catch-all)
-        if c != b':' && c != b'*' {
-            continue;
-        }
+        match c {
+            b':' => {
+                let (_, len) = tokenize(&path[start..])?;
+                return Ok(Some((&path[start..start + len], start)));
+            }
+            b'*' => {
+                for (end, &c) in path[start + 1..].iter().enumerate() {
+                    match c {
+                        b'/' => return Ok(Some((&path[start..start + 1 + end], start))),
+                        b':' | b'*' => return Err(InsertError::TooManyParams),
+                        _ => {}
+                    }
+                }
 
-        for (end, &c) in path[start + 1..].iter().enumerate() {
-            match c {
-                b'/' => return Ok(Some((&path[start..start + 1 + end], start))),
-                b':' | b'*' => return Err(InsertError::TooManyParams),
-                _ => {}
+                return Ok(Some((&path[start..], start)));
             }
+            _ => {}
         }
-
-        return Ok(Some((&path[start..], start)));
     }
 
     Ok(None)
@@ -1,6 +1,6 @@
 use core::cell::RefCell;
 
-use xitca_router::{InsertError, MatchError, Router};
+use xitca_router::{InsertError, MatchError, Recommendation, Router};
 use xitca_unsafe_collection::small_str::SmallBoxedStr;
 
 #[test]
@@ -35,6 +35,161 @@ fn issue_31() {
     assert_eq!(router.at("/path/foo/myarg/bar/baz").map(|m| *m.value), Ok("wildcard"));
 }
 
+#[test]
+fn recommend_trailing_slash() {
+    let mut router = Router::new();
+    for route in [
+        "/hi",
+        "/b/",
+        "/search/:query",
+        "/cmd/:tool/",
+        "/src/*filepath",
+        "/x/y",
+        "/files/:name.:ext",
+    ] {
+        router.insert(route, route).unwrap();
+    }
+
+    let tsr = |path: &str| Some(Recommendation::TrailingSlash(path.into()));
+
+    assert_eq!(router.recommend("/hi/"), tsr("/hi"));
+    assert_eq!(router.recommend("/b"), tsr("/b/"));
+    assert_eq!(router.recommend("/search/rust/"), tsr("/search/rust"));
+    assert_eq!(router.recommend("/cmd/vet"), tsr("/cmd/vet/"));
+    assert_eq!(router.recommend("/files/a.txt/"), tsr("/files/a.txt"));
+    assert_eq!(router.recommend("/hi"), None);
+    assert_eq!(router.recommend("/x"), None);
+    assert_eq!(router.recommend("/"), None);
+    assert_eq!(router.recommend(""), None);
+}
+
+#[test]
+fn recommend_case_insensitive() {
+    let mut router = Router::new();
+    for route in [
+        "/Hello",
+        "/users/:id{uint}/Posts",
+        "/users/:name/posts/",
+        "/src/*filepath",
+        "/v:version/Items",
+        "/ʯ/about",
+    ] {
+        router.insert(route, route).unwrap();
+    }
+
+    let ci = |path: &str| Some(Recommendation::CaseInsensitive(path.into()));
+
+    assert_eq!(router.recommend("/hello"), ci("/Hello"));
+    assert_eq!(router.recommend("/HELLO/"), ci("/Hello"));
+    assert_eq!(router.recommend("/USERS/1/posts"), ci("/users/1/Posts"));
+    // parameter values keep their case and constraints are respected.
+    assert_eq!(router.recommend("/USERS/Bob/POSTS"), ci("/users/Bob/posts/"));
+    assert_eq!(router.recommend("/SRC/Foo/Bar"), ci("/src/Foo/Bar"));
+    assert_eq!(router.recommend("/V2/items"), ci("/v2/Items"));
+    assert_eq!(router.recommend("/ʯ/ABOUT"), ci("/ʯ/about"));
+    assert_eq!(router.recommend("/HELLO/world"), None);
+    assert_eq!(router.recommend("/users/1/posts/x"), None);
+}
+
+#[test]
+fn remove() {
+    let routes = [
+        "/",
+        "/home",
+        "/users/:id{uint}",
+        "/users/:name",
+        "/users/:id/posts",
+        "/files/:name.:ext",
+        "/src/*filepath",
+        "/api/*",
+    ];
+
+    let mut router = Router::new();
+    for route in routes {
+        router.insert(route, route).unwrap();
+    }
+
+    // parameter names must match.
+    assert_eq!(router.remove("/users/:uid{uint}"), Ok(None));
+    assert_eq!(router.remove("/users/:id{uint}"), Ok(Some("/users/:id{uint}")));
+    assert_eq!(router.remove("/users/:id{uint}"), Ok(None));
+    assert_eq!(router.check_priorities(), Ok(routes.len() as u32 - 1));
+
+    let m = router.at("/users/996").unwrap();
+    assert_eq!(*m.value, "/users/:name");
+    assert_eq!(m.params.get("name"), Some("996"));
+
+    let m = router.at("/users/996/posts").unwrap();
+    assert_eq!(m.params.get("id"), Some("996"));
+
+    let m = router.at("/files/foo.txt").unwrap();
+    assert_eq!(m.params.get("ext"), Some("txt"));
+
+    assert_eq!(router.at("/src/a/b").map(|m| *m.value), Ok("/src/*filepath"));
+    assert_eq!(router.at("/api/v1").map(|m| *m.value), Ok("/api/*"));
+
+    for route in &routes[1..] {
+        router.remove(route).unwrap();
+    }
+    assert_eq!(router.at("/").map(|m| *m.value), Ok("/"));
+    assert_eq!(router.remove("/"), Ok(Some("/")));
+    assert_eq!(router.at("/").unwrap_err(), MatchError);
+
+    // removed routes can be inserted again.
+    router.insert("/home", "/home").unwrap();
+    assert_eq!(router.at("/home").map(|m| *m.value), Ok("/home"));
+}
+
+#[test]
+fn rebuild_keeps_registration_order() {
+    // parameter segments of the same rank overlapping each other. the first registered one wins.
+    for routes in [["/n/:a{int}", "/n/:b{uint}"], ["/n/:b{uint}", "/n/:a{int}"]] {
+        let mut router = Router::new();
+        for route in routes {
+            router.insert(route, route).unwrap();
+        }
+        router.insert("/home", "/home").unwrap();
+
+        assert_eq!(router.at("/n/5").map(|m| *m.value), Ok(routes[0]));
+
+        router.remove("/home").unwrap();
+        assert_eq!(router.at("/n/5").map(|m| *m.value), Ok(routes[0]));
+
+        let mut other = Router::new();
+        other.insert("/about", "/about").unwrap();
+        router.merge(other).unwrap();
+        assert_eq!(router.at("/n/5").map(|m| *m.value), Ok(routes[0]));
+        assert_eq!(router.at("/n/-5").map(|m| *m.value), Ok("/n/:a{int}"));
+    }
+}
+
+#[test]
+fn merge() {
+    let mut router = Router::new();
+    router.insert("/home", "home").unwrap();
+    router.insert("/users/:id", "user").unwrap();
+
+    let mut other = Router::new();
+    other.insert("/users/:id/posts", "posts").unwrap();
+    other.insert("/src/*filepath", "src").unwrap();
+    router.merge(other).unwrap();
+
+    assert_eq!(router.at("/users/1/posts").map(|m| *m.value), Ok("posts"));
+    assert_eq!(router.at("/src/a").map(|m| *m.value), Ok("src"));
+    assert_eq!(router.check_priorities(), Ok(4));
+
+    let mut other = Router::new();
+    other.insert("/about", "about").unwrap();
+    other.insert("/users/:name", "conflict").unwrap();
+
+    assert!(matches!(router.merge(other), Err(InsertError::Conflict { .. })));
+
+    // router is unchanged on conflict.
+    assert_eq!(router.at("/about").unwrap_err(), MatchError);
+    assert_eq!(router.at("/users/1").map(|m| *m.value), Ok("user"));
+    assert_eq!(router.check_priorities(), Ok(4));
+}
+
 match_tests! {
     basic {
         routes = [
@@ -527,6 +682,85 @@ match_tests! {
         ],
         "/" :: "" => None,
     },
+    param_constraints {
+        routes = [
+            "/users/:id{uint}",
+            "/users/:name",
+            "/users/:id{uint}/posts",
+            "/items/:id{uuid}",
+            "/temp/:deg{int}",
+            "/color/:c{hex}",
+            "/tag/:t{alpha}",
+        ],
+        "/users/42"                                   :: "/users/:id{uint}"       => { "id" => "42" },
+        "/users/bob"                                  :: "/users/:name"           => { "name" => "bob" },
+        "/users/42/posts"                             :: "/users/:id{uint}/posts" => { "id" => "42" },
+        "/users/bob/posts"                            :: ""                       => None,
+        "/items/67e55044-10b1-426f-9247-bb680e5fe0c8" :: "/items/:id{uuid}"       => { "id" => "67e55044-10b1-426f-9247-bb680e5fe0c8" },
+        "/items/67e55044"                             :: ""                       => None,
+        "/temp/-12"                                   :: "/temp/:deg{int}"        => { "deg" => "-12" },
+        "/temp/-"                                     :: ""                       => None,
+        "/temp/12a"                                   :: ""                       => None,
+        "/color/ff00AA"                               :: "/color/:c{hex}"         => { "c" => "ff00AA" },
+        "/color/fg"                                   :: ""                       => None,
+        "/tag/rust"                                   :: "/tag/:t{alpha}"         => { "t" => "rust" },
+        "/tag/rust2"                                  :: ""                       => None,
+    },
+    mid_segment_params {
+        routes = [
+            "/files/:name.:ext",
+            "/files/:name",
+            "/files/:name.json",
+            "/v:version/items",
+            "/v:version{uint}/items",
+            "/date/:y-:m-:d",
+            "/assets/:file.:ext/raw",
+        ],
+        "/files/a.txt"          :: "/files/:name.:ext"      => { "name" => "a", "ext" => "txt" },
+        "/files/a.json"         :: "/files/:name.json"      => { "name" => "a" },
+        "/files/archive.tar.gz" :: "/files/:name.:ext"      => { "name" => "archive.tar", "ext" => "gz" },
+        "/files/readme"         :: "/files/:name"           => { "name" => "readme" },
+        "/files/.txt"           :: "/files/:name"           => { "name" => ".txt" },
+        "/files/a."             :: "/files/:name"           => { "name" => "a." },
+        "/v2/items"             :: "/v:version{uint}/items" => { "version" => "2" },
+        "/vbeta/items"          :: "/v:version/items"       => { "version" => "beta" },
+        "/v/items"              :: ""                       => None,
+        "/date/2024-01-02"      :: "/date/:y-:m-:d"         => { "y" => "2024", "m" => "01", "d" => "02" },
+        "/date/2024-01"         :: ""                       => None,
+        "/assets/a.b/raw"       :: "/assets/:file.:ext/raw" => { "file" => "a", "ext" => "b" },
+        "/assets/a/raw"         :: ""                       => None,
+    },
+    backtracking_param_segments {
+        routes = [
+            "/:a{uint}/foo",
+            "/:b/bar",
+            "/:c.:d/baz",
+        ],
+        "/1/foo"   :: "/:a{uint}/foo" => { "a" => "1" },
+        "/1/bar"   :: "/:b/bar"       => { "b" => "1" },
+        "/1.2/baz" :: "/:c.:d/baz"    => { "c" => "1", "d" => "2" },
+        "/1.2/bar" :: "/:b/bar"       => { "b" => "1.2" },
+        "/x/foo"   :: ""              => None,
+        "/1/baz"   :: ""              => None,
+    },
+}
+
+#[cfg(feature = "regex")]
+match_tests! {
+    regex_constraints {
+        routes = [
+            "/users/:id{[0-9]+}",
+            "/users/:name",
+            "/posts/:slug{[a-z]+(?:-[a-z]+)*}.html",
+            "/code/:c{[A-Z]{3}}",
+        ],
+        "/users/978"              :: "/users/:id{[0-9]+}"                    => { "id" => "978" },
+        "/users/abc"              :: "/users/:name"                          => { "name" => "abc" },
+        "/posts/hello-world.html" :: "/posts/:slug{[a-z]+(?:-[a-z]+)*}.html" => { "slug" => "hello-world" },
+        "/posts/hello-.html"      :: ""                                      => None,
+        "/code/ABC"               :: "/code/:c{[A-Z]{3}}"                    => { "c" => "ABC" },
+        "/code/ABCD"              :: ""                                      => None,
+    },
 }
 
 // https://github.com/ibraheemdev/matchit/issues/12
@@ -632,6 +866,32 @@ insert_tests! {
         "/:foo:bar/" => Err(InsertError::TooManyParams),
         "/:foo*bar/" => Err(InsertError::TooManyParams),
     },
+    mid_segment_double_params {
+        "/:foo.:bar:baz" => Err(InsertError::TooManyParams),
+        "/:foo.*bar"     => Err(InsertError::TooManyParams),
+        "/:foo{uint}*"   => Err(InsertError::TooManyParams),
+        "/:foo.:bar"     => Ok(()),
+    },
+    param_segment_conflict {
+        "/users/:id{uint}"        => Ok(()),
+        "/users/:name"            => Ok(()),
+        "/users/:user{uint}"      => Err(InsertError::Conflict { with: "/users/:id{uint}".into() }),
+        "/users/:name{int}"       => Ok(()),
+        "/users/*rest"            => Err(InsertError::Conflict { with: "/users/:name".into() }),
+        "/files/:name.:ext"       => Ok(()),
+        "/files/:file.:extension" => Err(InsertError::Conflict { with: "/files/:name.:ext".into() }),
+        "/files/:name.json"       => Ok(()),
+        "/files/*rest"            => Err(InsertError::Conflict { with: "/files/:name.:ext".into() }),
+        "/static/*rest"           => Ok(()),
+        "/static/:name.:ext"      => Err(InsertError::Conflict { with: "/static/*rest".into() }),
+    },
+    invalid_constraint {
+        "/users/:id{}"           => Err(InsertError::InvalidConstraint { constraint: "{}".into() }),
+        "/users/:id{uint/posts"  => Err(InsertError::InvalidConstraint { constraint: "{uint".into() }),
+        "/users/:id{[0-9}"       => Err(InsertError::InvalidConstraint { constraint: "[0-9".into() }),
+        "/users/:{uint}"         => Err(InsertError::UnnamedParam),
+        "/users/:id{uint}"       => Ok(()),
+    },
     normalized_conflict {
         "/x/:foo/bar"  => Ok(()),
         "/x/:bar/bar"  => Err(InsertError::Conflict { with: "/x/:foo/bar".into() }),
@@ -6,6 +6,7 @@ use std::io;
 use xitca_io::net::Stream;
 
 use crate::{
+    limit::ConnectionLimits,
     net::AsListener,
     server::{IntoServiceObj, Server, ServerFuture, ServiceObj},
 };
@@ -16,9 +17,18 @@ pub struct Builder {
     pub(crate) worker_max_blocking_threads: usize,
     pub(crate) listeners: HashMap<String, Vec<Box<dyn AsListener>>>,
     pub(crate) factories: HashMap<String, ServiceObj>,
+    pub(crate) limits: HashMap<String, ConnectionLimits>,
     pub(crate) enable_signal: bool,
     pub(crate) shutdown_timeout: Duration,
     pub(crate) on_worker_start: Box<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>,
+    #[cfg(unix)]
+    pub(crate) upgrade_on_hup: bool,
+    #[cfg(unix)]
+    pub(crate) upgrade_timeout: Duration,
+    #[cfg(feature = "http3")]
+    activated_udp: Vec<(String, net::UdpSocket)>,
+    #[cfg(feature = "http3")]
+    h3_configs: HashMap<String, xitca_io::net::H3ServerConfig>,
     backlog: u32,
 }
 
@@ -37,9 +47,18 @@ impl Builder {
             worker_max_blocking_threads: 512,
             listeners: HashMap::new(),
             factories: HashMap::new(),
+            limits: HashMap::new(),
             enable_signal: true,
             shutdown_timeout: Duration::from_secs(30),
             on_worker_start: Box::new(|| Box::pin(async {})),
+            #[cfg(unix)]
+            upgrade_on_hup: false,
+            #[cfg(unix)]
+            upgrade_timeout: Duration::from_secs(30),
+            #[cfg(feature = "http3")]
+            activated_udp: Vec::new(),
+            #[cfg(feature = "http3")]
+            h3_configs: HashMap::new(),
             backlog: 2048,
         }
     }
@@ -107,6 +126,43 @@ impl Builder {
         self
     }
 
+    #[cfg(unix)]
+    /// Upgrade server to a new process of current executable when receiving SIGHUP signal.
+    ///
+    /// Listeners created by [Builder::bind] and [Builder::bind_unix] are inherited by the new process
+    /// and it reuses them when binding with the same name and address. Current process is gracefully
+    /// stopped after the new one finishes building server. Udp listeners for http/3 are not inherited.
+    ///
+    /// Without this SIGHUP is ignored. Upgrade can also be triggered by [ServerHandle::upgrade].
+    ///
+    /// [ServerHandle::upgrade]: crate::ServerHandle::upgrade
+    pub fn upgrade_on_hup(mut self) -> Self {
+        self.upgrade_on_hup = true;
+        self
+    }
+
+    #[cfg(unix)]
+    /// Timeout for upgraded process to report readiness in seconds.
+    ///
+    /// Current process keeps running when the new process fails to be ready in time.
+    ///
+    /// By default upgrade timeout sets to 30 seconds.
+    pub fn upgrade_timeout(mut self, secs: u64) -> Self {
+        self.upgrade_timeout = Duration::from_secs(secs);
+        self
+    }
+
+    /// Set [ConnectionLimits] for listeners of service with given name.
+    ///
+    /// Limits apply to listeners added at runtime through [ServerHandle](crate::ServerHandle) too.
+    pub fn connection_limits<N>(mut self, name: N, limits: ConnectionLimits) -> Self
+    where
+        N: AsRef<str>,
+    {
+        self.limits.insert(name.as_ref().to_string(), limits);
+        self
+    }
+
     pub fn backlog(mut self, num: u32) -> Self {
         self.backlog = num;
         self
@@ -140,7 +196,27 @@ impl Builder {
         self._listen(name, Some(listener), service)
     }
 
-    pub fn build(self) -> ServerFuture {
+    /// Register service with given name without binding listener.
+    ///
+    /// Service handles listeners with the same name and is used together with listeners passed from
+    /// other process. See [Builder::listen_from_env] for example.
+    pub fn service<N, F, St>(mut self, name: N, service: F) -> Self
+    where
+        N: AsRef<str>,
+        F: IntoServiceObj<St>,
+        St: TryFrom<Stream> + 'static,
+    {
+        self.factories.insert(name.as_ref().to_string(), service.into_object());
+        self
+    }
+
+    #[allow(unused_mut)]
+    pub fn build(mut self) -> ServerFuture {
+        #[cfg(feature = "http3")]
+        if let Err(e) = self.resolve_activated_udp() {
+            return ServerFuture::Error(e);
+        }
+
         let enable_signal = self.enable_signal;
         match Server::new(self) {
             Ok(server) => ServerFuture::Init { server, enable_signal },
@@ -189,6 +265,11 @@ impl Builder {
         F: IntoServiceObj<St>,
         St: TryFrom<Stream> + 'static,
     {
+        #[cfg(unix)]
+        if let Some(listener) = crate::upgrade::take_tcp(name.as_ref(), &addr) {
+            return Ok(self.listen(name, listener, service));
+        }
+
         let listener = net::TcpListener::bind(addr)?;
         listener.set_nonblocking(true)?;
 
@@ -202,6 +283,60 @@ impl Builder {
 
 #[cfg(unix)]
 impl Builder {
+    /// Take listeners passed by systemd socket activation.
+    ///
+    /// Sockets are described by `LISTEN_FDS` and `LISTEN_FDNAMES` environment variables and each one
+    /// is served by service registered with the same name as it's `FileDescriptorName`. Tcp and Unix
+    /// stream sockets are supported. Udp sockets are supported for http/3 with `http3` feature and
+    /// [Builder::h3_config] must be set for their names. Listeners inherited from upgrading process
+    /// are taken too. See [Builder::upgrade_on_hup] for detail.
+    ///
+    /// When `NOTIFY_SOCKET` environment variable is set server reports `READY=1` after it starts,
+    /// `STOPPING=1` when it's shutting down and `WATCHDOG=1` periodically when `WATCHDOG_USEC` is set.
+    /// Notification does not depend on this method.
+    ///
+    /// When current process is not activated by systemd no listener is taken.
+    ///
+    /// # Examples:
+    /// ```no_run
+    /// # use xitca_io::net::TcpStream;
+    /// # use xitca_server::Builder;
+    /// # use xitca_service::fn_service;
+    /// # fn main() -> std::io::Result<()> {
+    /// // systemd socket unit with FileDescriptorName=http
+    /// Builder::new()
+    ///     .listen_from_env()?
+    ///     .service("http", fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }))
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn listen_from_env(mut self) -> io::Result<Self> {
+        use crate::systemd::Activated;
+
+        let fds = crate::systemd::listen_fds()
+            .into_iter()
+            .chain(crate::upgrade::take_all());
+
+        for (name, fd) in fds {
+            match Activated::from_fd(fd)? {
+                Activated::Tcp(listener) => self.listeners.entry(name).or_default().push(Box::new(Some(listener))),
+                Activated::Unix(listener) => self.listeners.entry(name).or_default().push(Box::new(Some(listener))),
+                #[cfg(feature = "http3")]
+                Activated::Udp(socket) => self.activated_udp.push((name, socket)),
+                #[cfg(not(feature = "http3"))]
+                Activated::Udp(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        format!("udp socket: {name} requires http3 feature"),
+                    ))
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
     pub fn bind_unix<N, P, F, St>(self, name: N, path: P, service: F) -> io::Result<Self>
     where
         N: AsRef<str>,
@@ -209,6 +344,11 @@ impl Builder {
         F: IntoServiceObj<St>,
         St: TryFrom<Stream> + 'static,
     {
+        // listener inherited from upgrading process is still bound to the path.
+        if let Some(listener) = crate::upgrade::take_unix(name.as_ref(), path.as_ref()) {
+            return Ok(self.listen_unix(name, listener, service));
+        }
+
         // The path must not exist when we try to bind.
         // Try to remove it to avoid bind error.
         if let Err(e) = std::fs::remove_file(path.as_ref()) {
@@ -235,6 +375,32 @@ impl Builder {
 
 #[cfg(feature = "http3")]
 impl Builder {
+    /// Set http/3 config for udp sockets with given name taken by [Builder::listen_from_env].
+    pub fn h3_config<N>(mut self, name: N, config: xitca_io::net::H3ServerConfig) -> Self
+    where
+        N: AsRef<str>,
+    {
+        self.h3_configs.insert(name.as_ref().to_string(), config);
+        self
+    }
+
+    fn resolve_activated_udp(&mut self) -> io::Result<()> {
+        for (name, socket) in std::mem::take(&mut self.activated_udp) {
+            let config = self.h3_configs.get(&name).cloned().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("http/3 config for udp socket: {name} is not set"),
+                )
+            })?;
+
+            let builder = xitca_io::net::UdpListenerBuilder::from_std(socket, config).backlog(self.backlog);
+
+            self.listeners.entry(name).or_default().push(Box::new(Some(builder)));
+        }
+
+        Ok(())
+    }
+
     /// Bind to both Tcp and Udp of the same address to enable http/1/2/3 handling
     /// with single service.
     pub fn bind_all<N, A, F>(
@@ -1,16 +1,22 @@
 //! Multi-threaded server for Tcp/Udp/UnixDomain handling.
 
-#![forbid(unsafe_code)]
+#![deny(unsafe_code)]
 
 mod builder;
+mod limit;
 mod server;
 mod signals;
+#[cfg(unix)]
+mod systemd;
+#[cfg(unix)]
+mod upgrade;
 mod worker;
 
 pub mod net;
 
 pub use builder::Builder;
-pub use server::{ServerFuture, ServerHandle};
+pub use limit::ConnectionLimits;
+pub use server::{ListenerInfo, ServerFuture, ServerHandle};
 
 #[cfg(all(not(target_os = "linux"), feature = "io-uring"))]
 compile_error!("io_uring can only be used on linux system");
@@ -27,4 +33,94 @@ mod test {
             .listen("test", listener, fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }))
             .build();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn runtime_listener() {
+        use std::time::Duration;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(2)
+            .disable_signal()
+            .shutdown_timeout(1)
+            .listen(
+                "test",
+                listener,
+                fn_service(|_: TcpStream| async {
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                    Ok::<_, ()>(())
+                }),
+            )
+            .build();
+
+        let handle = server.handle().unwrap();
+        let shutdown = handle.on_shutdown();
+        let server = tokio::spawn(server);
+
+        assert_eq!(handle.listeners().len(), 1);
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let id = handle.add_listener("test", listener).unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(handle.add_listener("none", listener).is_err());
+
+        let _stream = TcpStream::connect(addr).await.unwrap();
+
+        let mut connections = 0;
+        for _ in 0..50 {
+            connections = handle.worker_connections().iter().sum::<usize>();
+            if connections == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(connections, 1);
+
+        let info = handle.listeners().into_iter().find(|info| info.id == id).unwrap();
+        assert_eq!(info.name, "test");
+        assert_eq!(info.local_addr, addr.to_string());
+        assert_eq!(info.connections.len(), 2);
+
+        handle.pause();
+        assert!(handle.is_paused());
+        handle.resume();
+        assert!(!handle.is_paused());
+
+        assert!(handle.remove_listener(id));
+        assert!(!handle.remove_listener(id));
+        assert_eq!(handle.listeners().len(), 1);
+
+        handle.stop(true);
+        shutdown.await;
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pause_then_stop() {
+        use std::time::Duration;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(2)
+            .disable_signal()
+            .shutdown_timeout(1)
+            .listen("test", listener, fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }))
+            .build();
+
+        let handle = server.handle().unwrap();
+        let server = tokio::spawn(server);
+
+        handle.pause();
+        // give workers a chance to observe pause state.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        handle.stop(false);
+
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("paused server must stop")
+            .unwrap()
+            .unwrap();
+    }
 }
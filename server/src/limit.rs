@@ -0,0 +1,261 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    pin::pin,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Notify;
+
+/// Limits of connections accepted from listeners of a service.
+///
+/// Limits are shared by all workers and apply to each listener separately.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_server::{Builder, ConnectionLimits};
+/// let limits = ConnectionLimits::new()
+///     // at most 10k concurrent connections.
+///     .max_connections(10_000)
+///     // at most 64 concurrent connections from one client ip.
+///     .max_connections_per_ip(64)
+///     // accept 1k connections per second with a burst of 200 connections.
+///     .accept_rate(1_000, 200);
+///
+/// let builder = Builder::new().connection_limits("http", limits);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConnectionLimits {
+    max_connections: Option<usize>,
+    max_connections_per_ip: Option<usize>,
+    accept_rate: Option<(u32, u32)>,
+}
+
+impl ConnectionLimits {
+    /// Construct limits with nothing limited.
+    pub const fn new() -> Self {
+        Self {
+            max_connections: None,
+            max_connections_per_ip: None,
+            accept_rate: None,
+        }
+    }
+
+    /// Max number of concurrent connections.
+    ///
+    /// When reached workers stop accepting until an active connection is closed. New connections are
+    /// queued in listener's backlog instead of being reset.
+    ///
+    /// # Panics:
+    /// When receive 0 as max connections.
+    pub fn max_connections(mut self, max: usize) -> Self {
+        assert_ne!(max, 0, "max connections must be higher than 0");
+        self.max_connections = Some(max);
+        self
+    }
+
+    /// Max number of concurrent connections from the same client ip address.
+    ///
+    /// Connections exceeding the limit are closed immediately after they are accepted. Ip address is
+    /// the peer address of connection. Unix domain socket connections are not limited.
+    ///
+    /// # Panics:
+    /// When receive 0 as max connections.
+    pub fn max_connections_per_ip(mut self, max: usize) -> Self {
+        assert_ne!(max, 0, "max connections per ip must be higher than 0");
+        self.max_connections_per_ip = Some(max);
+        self
+    }
+
+    /// Max number of accepted connections per second with given burst size.
+    ///
+    /// When exceeded workers delay accepting until the rate falls back.
+    ///
+    /// # Panics:
+    /// When receive 0 as rate or burst.
+    pub fn accept_rate(mut self, per_second: u32, burst: u32) -> Self {
+        assert_ne!(per_second, 0, "accept rate must be higher than 0");
+        assert_ne!(burst, 0, "accept burst must be higher than 0");
+        self.accept_rate = Some((per_second, burst));
+        self
+    }
+}
+
+/// limit state of a listener.
+pub(crate) struct LimitState {
+    limits: ConnectionLimits,
+    active: AtomicUsize,
+    released: Notify,
+    per_ip: Mutex<HashMap<IpAddr, usize>>,
+    bucket: Mutex<Bucket>,
+    rejected: AtomicU64,
+}
+
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl LimitState {
+    pub(crate) fn new(limits: ConnectionLimits) -> Self {
+        Self {
+            limits,
+            active: AtomicUsize::new(0),
+            released: Notify::new(),
+            per_ip: Mutex::new(HashMap::new()),
+            bucket: Mutex::new(Bucket {
+                tokens: limits.accept_rate.map(|(_, burst)| burst as f64).unwrap_or_default(),
+                last: Instant::now(),
+            }),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// number of connections closed for exceeding per ip limit.
+    pub(crate) fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn is_per_ip_limited(&self) -> bool {
+        self.limits.max_connections_per_ip.is_some()
+    }
+
+    /// wait for accept rate and a free connection slot.
+    pub(crate) async fn acquire(self: &Arc<Self>) -> Slot {
+        if let Some((rate, _)) = self.limits.accept_rate {
+            while let Some(dur) = self.take_token(rate) {
+                tokio::time::sleep(dur).await;
+            }
+        }
+
+        loop {
+            let mut released = pin!(self.released.notified());
+            released.as_mut().enable();
+
+            let res = self.active.fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                match self.limits.max_connections {
+                    Some(max) if active >= max => None,
+                    _ => Some(active + 1),
+                }
+            });
+
+            if res.is_ok() {
+                return Slot(self.clone());
+            }
+
+            released.await;
+        }
+    }
+
+    // take a token from bucket. return duration to wait when bucket is empty.
+    fn take_token(&self, rate: u32) -> Option<Duration> {
+        let burst = self
+            .limits
+            .accept_rate
+            .map(|(_, burst)| burst as f64)
+            .unwrap_or_default();
+        let rate = rate as f64;
+
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        bucket.tokens = (bucket.tokens + now.duration_since(bucket.last).as_secs_f64() * rate).min(burst);
+        bucket.last = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+
+    /// enter connection from given ip. return false when per ip limit is exceeded.
+    pub(crate) fn enter_ip(&self, ip: IpAddr) -> bool {
+        let Some(max) = self.limits.max_connections_per_ip else {
+            return true;
+        };
+
+        let mut per_ip = self.per_ip.lock().unwrap();
+        let count = per_ip.entry(ip).or_default();
+        if *count >= max {
+            drop(per_ip);
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    pub(crate) fn leave_ip(&self, ip: IpAddr) {
+        let mut per_ip = self.per_ip.lock().unwrap();
+        if let Some(count) = per_ip.get_mut(&ip) {
+            *count -= 1;
+            if *count == 0 {
+                per_ip.remove(&ip);
+            }
+        }
+    }
+}
+
+/// a reserved connection slot. released on drop.
+pub(crate) struct Slot(Arc<LimitState>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::AcqRel);
+        self.0.released.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::future::Future;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    #[test]
+    fn max_connections() {
+        let state = Arc::new(LimitState::new(ConnectionLimits::new().max_connections(1)));
+
+        let slot = state.acquire().now_or_panic();
+
+        let mut next = pin!(state.acquire());
+        let waker = std::task::Waker::noop();
+        let mut cx = std::task::Context::from_waker(waker);
+        assert!(next.as_mut().poll(&mut cx).is_pending());
+
+        drop(slot);
+        assert!(next.as_mut().poll(&mut cx).is_ready());
+    }
+
+    #[test]
+    fn per_ip() {
+        let state = LimitState::new(ConnectionLimits::new().max_connections_per_ip(2));
+        let ip = IpAddr::from([127, 0, 0, 1]);
+
+        assert!(state.enter_ip(ip));
+        assert!(state.enter_ip(ip));
+        assert!(!state.enter_ip(ip));
+        assert!(state.enter_ip(IpAddr::from([127, 0, 0, 2])));
+        assert_eq!(state.rejected(), 1);
+
+        state.leave_ip(ip);
+        assert!(state.enter_ip(ip));
+    }
+
+    #[test]
+    fn accept_rate() {
+        let state = LimitState::new(ConnectionLimits::new().accept_rate(10, 2));
+
+        assert!(state.take_token(10).is_none());
+        assert!(state.take_token(10).is_none());
+        let dur = state.take_token(10).unwrap();
+        assert!(dur <= Duration::from_millis(100));
+    }
+}
@@ -7,7 +7,7 @@ use std::{
 
 use crate::signals::{self, Signal, SignalFuture};
 
-use super::{handle::ServerHandle, Command, Server};
+use super::{Command, Server, ServerHandle};
 
 #[must_use = "ServerFuture must be .await/ spawn as task / consumed with ServerFuture::wait."]
 pub enum ServerFuture {
@@ -45,12 +45,8 @@ impl ServerFuture {
     /// ```
     pub fn handle(&mut self) -> io::Result<ServerHandle> {
         match *self {
-            Self::Init { ref server, .. } => Ok(ServerHandle {
-                tx: server.tx_cmd.clone(),
-            }),
-            Self::Running(ref inner) => Ok(ServerHandle {
-                tx: inner.server.tx_cmd.clone(),
-            }),
+            Self::Init { ref server, .. } => Ok(server.handle()),
+            Self::Running(ref inner) => Ok(inner.server.handle()),
             Self::Error(_) => match mem::take(self) {
                 Self::Error(e) => Err(e),
                 _ => unreachable!(),
@@ -125,26 +121,35 @@ impl ServerFutureInner {
 
     #[inline(never)]
     fn poll_cmd(&mut self, cx: &mut Context<'_>) -> Poll<Command> {
-        if let Some(signals) = self.signals.as_mut() {
-            if let Poll::Ready(sig) = Pin::new(signals).poll(cx) {
-                tracing::info!("Signal {:?} received.", sig);
-                let cmd = match sig {
-                    Signal::Int | Signal::Quit => Command::ForceStop,
-                    Signal::Term => Command::GracefulStop,
-                    // Remove signal listening and keep Server running when
-                    // terminal closed which xitca-server process belong.
-                    Signal::Hup => {
-                        self.signals = None;
-                        return Poll::Pending;
-                    }
-                };
-                return Poll::Ready(cmd);
+        loop {
+            if let Some(signals) = self.signals.as_mut() {
+                if let Poll::Ready(sig) = Pin::new(signals).poll(cx) {
+                    tracing::info!("Signal {:?} received.", sig);
+                    let cmd = match sig {
+                        Signal::Int | Signal::Quit => Command::ForceStop,
+                        Signal::Term => Command::GracefulStop,
+                        #[cfg(unix)]
+                        Signal::Hup if self.server.upgrader.on_hup => {
+                            self.server.upgrade();
+                            continue;
+                        }
+                        // Remove signal listening and keep Server running when
+                        // terminal closed which xitca-server process belong.
+                        Signal::Hup => {
+                            self.signals = None;
+                            return Poll::Pending;
+                        }
+                    };
+                    return Poll::Ready(cmd);
+                }
             }
-        }
 
-        match ready!(Pin::new(&mut self.server.rx_cmd).poll_recv(cx)) {
-            Some(cmd) => Poll::Ready(cmd),
-            None => Poll::Pending,
+            match ready!(Pin::new(&mut self.server.rx_cmd).poll_recv(cx)) {
+                #[cfg(unix)]
+                Some(Command::Upgrade) => self.server.upgrade(),
+                Some(cmd) => return Poll::Ready(cmd),
+                None => return Poll::Pending,
+            }
         }
     }
 
@@ -157,6 +162,8 @@ impl ServerFutureInner {
             Command::GracefulStop => {
                 self.server.stop(true);
             }
+            #[cfg(unix)]
+            Command::Upgrade => unreachable!("upgrade command is handled when polling"),
         }
     }
 }
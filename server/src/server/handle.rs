@@ -1,10 +1,40 @@
+use std::{
+    future::Future,
+    io, net,
+    sync::{atomic::Ordering, Arc},
+};
+
 use tokio::sync::mpsc::UnboundedSender;
 
-use super::Command;
+use super::{state::Shared, Command};
 
 #[derive(Clone)]
 pub struct ServerHandle {
     pub(super) tx: UnboundedSender<Command>,
+    pub(super) shared: Arc<Shared>,
+}
+
+/// Information of listener served by xitca-server.
+#[derive(Clone, Debug)]
+pub struct ListenerInfo {
+    /// id of listener. can be used for [ServerHandle::remove_listener].
+    pub id: usize,
+    /// name of service the listener is bound to.
+    pub name: String,
+    pub local_addr: String,
+    /// active connections accepted from listener. indexed by worker.
+    pub connections: Vec<usize>,
+    /// number of connections closed for exceeding per ip limit of [ConnectionLimits].
+    ///
+    /// [ConnectionLimits]: crate::ConnectionLimits
+    pub rejected: u64,
+}
+
+impl ListenerInfo {
+    /// Sum of active connections of all workers.
+    pub fn total_connections(&self) -> usize {
+        self.connections.iter().sum()
+    }
 }
 
 impl ServerHandle {
@@ -18,4 +48,99 @@ impl ServerHandle {
 
         let _ = self.tx.send(cmd);
     }
+
+    /// Add Tcp listener to service registered with given name at runtime. Return id of the listener.
+    ///
+    /// # Errors:
+    /// When no service registered with given name or server is shutting down.
+    pub fn add_listener<N>(&self, name: N, listener: net::TcpListener) -> io::Result<usize>
+    where
+        N: AsRef<str>,
+    {
+        self.shared.add_listener(name.as_ref(), Some(listener))
+    }
+
+    #[cfg(unix)]
+    /// Unix domain socket version of [ServerHandle::add_listener].
+    pub fn add_unix_listener<N>(&self, name: N, listener: std::os::unix::net::UnixListener) -> io::Result<usize>
+    where
+        N: AsRef<str>,
+    {
+        self.shared.add_listener(name.as_ref(), Some(listener))
+    }
+
+    /// Stop accepting from listener with given id. Connections already accepted are not affected.
+    ///
+    /// Return false when no listener is found.
+    pub fn remove_listener(&self, id: usize) -> bool {
+        self.shared.remove_listener(id)
+    }
+
+    /// Pause accepting new connections from all listeners. Connections already accepted are not affected.
+    ///
+    /// New connections are queued in listeners' backlog until [ServerHandle::resume] is called.
+    pub fn pause(&self) {
+        self.shared.paused.send_replace(true);
+    }
+
+    /// Resume accepting new connections.
+    pub fn resume(&self) {
+        self.shared.paused.send_replace(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.shared.paused.borrow()
+    }
+
+    /// Information of all listeners.
+    pub fn listeners(&self) -> Vec<ListenerInfo> {
+        self.shared
+            .listeners
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|entry| ListenerInfo {
+                id: entry.id,
+                name: entry.name.clone(),
+                local_addr: entry.local_addr.clone(),
+                connections: entry.connections.iter().map(|c| c.load(Ordering::Relaxed)).collect(),
+                rejected: entry.limit.rejected(),
+            })
+            .collect()
+    }
+
+    /// Active connections of each worker. indexed by worker.
+    pub fn worker_connections(&self) -> Vec<usize> {
+        let mut connections = vec![0; self.shared.worker_threads()];
+        for entry in self.shared.listeners.lock().unwrap().iter() {
+            for (total, c) in connections.iter_mut().zip(entry.connections.iter()) {
+                *total += c.load(Ordering::Relaxed);
+            }
+        }
+        connections
+    }
+
+    /// A future resolves when server starts shutting down.
+    ///
+    /// It resolves before connections are drained which is useful for flipping readiness probe.
+    /// The future must not be polled on the same thread server is running on as server blocks
+    /// it's thread when draining connections.
+    pub fn on_shutdown(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut rx = self.shared.shutdown.subscribe();
+        async move {
+            let _ = rx.wait_for(|shutdown| *shutdown).await;
+        }
+    }
+
+    #[cfg(unix)]
+    /// Upgrade xitca-server to a new process of current executable without dropping connections.
+    ///
+    /// The new process inherits listeners of current one and current process is gracefully stopped
+    /// after the new one reports readiness. The new process must build server with the same
+    /// listener names and addresses. When upgrade fails current process keeps running.
+    ///
+    /// See [Builder::upgrade_on_hup](crate::Builder::upgrade_on_hup) for detail.
+    pub fn upgrade(&self) {
+        let _ = self.tx.send(Command::Upgrade);
+    }
 }
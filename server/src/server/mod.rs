@@ -1,12 +1,20 @@
 mod future;
 mod handle;
 mod service;
+mod state;
 
-pub use self::{future::ServerFuture, handle::ServerHandle};
+pub use self::{
+    future::ServerFuture,
+    handle::{ListenerInfo, ServerHandle},
+};
 
-pub(crate) use self::service::{IntoServiceObj, ServiceObj};
+pub(crate) use self::{
+    service::{IntoServiceObj, ServiceObj},
+    state::{ListenerEntry, WorkerCommand},
+};
 
 use std::{
+    collections::HashMap,
     io, mem,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -20,7 +28,12 @@ use tokio::{
     sync::mpsc::{UnboundedReceiver, UnboundedSender},
 };
 
-use crate::{builder::Builder, worker};
+use crate::{
+    builder::Builder,
+    worker::{self, WorkerContext},
+};
+
+use self::state::Shared;
 
 pub struct Server {
     is_graceful_shutdown: Arc<AtomicBool>,
@@ -28,6 +41,9 @@ pub struct Server {
     rx_cmd: UnboundedReceiver<Command>,
     rt: Option<Runtime>,
     worker_join_handles: Vec<thread::JoinHandle<io::Result<()>>>,
+    shared: Arc<Shared>,
+    #[cfg(unix)]
+    upgrader: Arc<crate::upgrade::Upgrader>,
 }
 
 impl Server {
@@ -36,6 +52,7 @@ impl Server {
         let Builder {
             listeners,
             factories,
+            limits,
             shutdown_timeout,
             on_worker_start,
             ..
@@ -49,7 +66,7 @@ impl Server {
                 .flat_map(|(name, listeners)| {
                     listeners.into_iter().map(move |mut l| {
                         let l = l.as_listener()?;
-                        Ok((name.to_owned(), Arc::new(l)))
+                        Ok((name.to_owned(), l))
                     })
                 })
                 .collect::<Result<Vec<_>, io::Error>>()
@@ -57,6 +74,15 @@ impl Server {
 
         let listeners = rt.block_on(fut)?;
 
+        let shared = Shared::new(
+            listeners,
+            factories.keys().cloned().collect(),
+            limits,
+            1,
+            rt.handle().clone(),
+        );
+        let listeners = shared.listeners.lock().unwrap().clone();
+
         let is_graceful_shutdown = Arc::new(AtomicBool::new(false));
 
         let on_start_fut = on_worker_start();
@@ -64,19 +90,36 @@ impl Server {
         let fut = async {
             on_start_fut.await;
 
+            let ctx = WorkerContext {
+                idx: 0,
+                paused: shared.paused.subscribe(),
+                shutdown: shared.shutdown.subscribe(),
+            };
+
+            // runtime listener management is not supported and command channel is closed immediately.
+            let (_, rx_cmd) = tokio::sync::mpsc::unbounded_channel();
+
             let mut handles = Vec::new();
             let mut services = Vec::new();
 
             for (name, factory) in factories.iter() {
-                let (h, s) = factory
-                    .call((name, &listeners))
+                let (h, s, _) = factory
+                    .call((name, &listeners, &ctx))
                     .await
                     .map_err(|_| io::Error::from(io::ErrorKind::Other))?;
                 handles.extend(h);
                 services.push(s);
             }
 
-            worker::wait_for_stop(handles, services, shutdown_timeout, &is_graceful_shutdown).await;
+            worker::wait_for_stop(
+                handles,
+                services,
+                HashMap::new(),
+                rx_cmd,
+                shutdown_timeout,
+                &is_graceful_shutdown,
+            )
+            .await;
 
             Ok::<_, io::Error>(())
         };
@@ -94,8 +137,13 @@ impl Server {
             worker_max_blocking_threads,
             listeners,
             factories,
+            limits,
             shutdown_timeout,
             on_worker_start,
+            #[cfg(unix)]
+            upgrade_on_hup,
+            #[cfg(unix)]
+            upgrade_timeout,
             ..
         } = builder;
 
@@ -112,7 +160,7 @@ impl Server {
                 .flat_map(|(name, listeners)| {
                     listeners.into_iter().map(move |mut l| {
                         let l = l.as_listener()?;
-                        Ok((name.to_owned(), Arc::new(l)))
+                        Ok((name.to_owned(), l))
                     })
                 })
                 .collect::<Result<Vec<_>, io::Error>>()
@@ -122,6 +170,30 @@ impl Server {
         // *. Server::new is most likely already inside a tokio runtime.
         let listeners = thread::scope(|s| s.spawn(|| rt.block_on(fut)).join()).unwrap()?;
 
+        let shared = Arc::new(Shared::new(
+            listeners,
+            factories.keys().cloned().collect(),
+            limits,
+            worker_threads,
+            rt.handle().clone(),
+        ));
+        let listeners = shared.listeners.lock().unwrap().clone();
+
+        let mut rx_cmds = Vec::with_capacity(worker_threads);
+        {
+            let mut workers = shared.workers.lock().unwrap();
+            for _ in 0..worker_threads {
+                let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+                workers.push(tx);
+                rx_cmds.push(rx);
+            }
+        }
+
+        #[cfg(unix)]
+        let upgrader = Arc::new(crate::upgrade::Upgrader::new(upgrade_on_hup, upgrade_timeout));
+
+        let shared2 = shared.clone();
+
         let is_graceful_shutdown = Arc::new(AtomicBool::new(false));
         let is_graceful_shutdown2 = is_graceful_shutdown.clone();
 
@@ -129,30 +201,52 @@ impl Server {
             .name(String::from("xitca-server-worker-shared-scope"))
             .spawn(move || {
                 let is_graceful_shutdown = is_graceful_shutdown2;
+                let shared = shared2;
 
                 // TODO: wait for startup error(including panic) and return as io::Error on call site.
                 // currently the error only show when shared scope thread is joined with handle.
                 thread::scope(|scope| {
-                    for idx in 0..worker_threads {
+                    let factories = &factories;
+                    let listeners = &listeners;
+                    let on_worker_start = &on_worker_start;
+                    let is_graceful_shutdown = &*is_graceful_shutdown;
+
+                    for (idx, rx_cmd) in rx_cmds.into_iter().enumerate() {
                         let thread = thread::Builder::new().name(format!("xitca-server-worker-{idx}"));
 
-                        let task = || async {
+                        let ctx = WorkerContext {
+                            idx,
+                            paused: shared.paused.subscribe(),
+                            shutdown: shared.shutdown.subscribe(),
+                        };
+
+                        let task = move || async move {
                             on_worker_start().await;
 
                             let mut handles = Vec::new();
                             let mut services = Vec::new();
+                            let mut starters = HashMap::new();
 
                             for (name, factory) in factories.iter() {
-                                match factory.call((name, &listeners)).await {
-                                    Ok((h, s)) => {
+                                match factory.call((name, listeners, &ctx)).await {
+                                    Ok((h, s, starter)) => {
                                         handles.extend(h);
                                         services.push(s);
+                                        starters.insert(name.as_str(), starter);
                                     }
                                     Err(_) => return,
                                 }
                             }
 
-                            worker::wait_for_stop(handles, services, shutdown_timeout, &is_graceful_shutdown).await;
+                            worker::wait_for_stop(
+                                handles,
+                                services,
+                                starters,
+                                rx_cmd,
+                                shutdown_timeout,
+                                is_graceful_shutdown,
+                            )
+                            .await;
                         };
 
                         #[cfg(not(feature = "io-uring"))]
@@ -182,18 +276,61 @@ impl Server {
 
         let (tx_cmd, rx_cmd) = tokio::sync::mpsc::unbounded_channel();
 
+        // report readiness to parent process when current one is spawned from upgrading.
+        #[cfg(unix)]
+        crate::upgrade::notify_ready();
+
+        #[cfg(unix)]
+        {
+            crate::systemd::notify_ready();
+            if let Some(interval) = crate::systemd::watchdog_interval() {
+                rt.spawn(crate::systemd::watchdog(interval));
+            }
+        }
+
         Ok(Self {
             is_graceful_shutdown,
             tx_cmd,
             rx_cmd,
             rt: Some(rt),
             worker_join_handles: vec![worker_handles],
+            shared,
+            #[cfg(unix)]
+            upgrader,
         })
     }
 
+    #[cfg(unix)]
+    pub(crate) fn upgrade(&self) {
+        let listeners = match crate::upgrade::dup_listeners(&self.shared.listeners.lock().unwrap()) {
+            Ok(listeners) => listeners,
+            Err(e) => {
+                tracing::error!("Failed to duplicate listeners for upgrading: {e}");
+                return;
+            }
+        };
+
+        let tx = self.tx_cmd.clone();
+        self.upgrader.upgrade(listeners, move || {
+            let _ = tx.send(Command::GracefulStop);
+        });
+    }
+
+    pub(crate) fn handle(&self) -> ServerHandle {
+        ServerHandle {
+            tx: self.tx_cmd.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+
     pub(crate) fn stop(&mut self, graceful: bool) {
         if let Some(rt) = self.rt.take() {
+            self.shared.shutdown.send_replace(true);
             self.is_graceful_shutdown.store(graceful, Ordering::SeqCst);
+            // closing command channels notifies workers to stop.
+            self.shared.workers.lock().unwrap().clear();
+            #[cfg(unix)]
+            crate::systemd::notify_stopping();
             rt.shutdown_background();
             mem::take(&mut self.worker_join_handles).into_iter().for_each(|handle| {
                 let _ = handle.join().unwrap();
@@ -205,4 +342,6 @@ impl Server {
 enum Command {
     GracefulStop,
     ForceStop,
+    #[cfg(unix)]
+    Upgrade,
 }
@@ -1,15 +1,17 @@
 use std::{marker::PhantomData, rc::Rc, sync::Arc};
 
 use tokio::task::JoinHandle;
-use xitca_io::net::{Listener, Stream};
+use xitca_io::net::Stream;
 use xitca_service::{ready::ReadyService, Service};
 
-use crate::worker::{self, ServiceAny};
+use crate::worker::{self, ServiceAny, Starter, WorkerContext};
+
+use super::ListenerEntry;
 
 pub type ServiceObj = Box<
     dyn for<'a> xitca_service::object::ServiceObject<
-            (&'a str, &'a [(String, Arc<Listener>)]),
-            Response = (Vec<JoinHandle<()>>, ServiceAny),
+            (&'a str, &'a [Arc<ListenerEntry>], &'a WorkerContext),
+            Response = (Vec<(usize, JoinHandle<()>)>, ServiceAny, Starter),
             Error = (),
         > + Send
         + Sync,
@@ -20,28 +22,34 @@ struct Container<F, Req> {
     _t: PhantomData<fn(Req)>,
 }
 
-impl<'a, F, Req> Service<(&'a str, &'a [(String, Arc<Listener>)])> for Container<F, Req>
+impl<'a, F, Req> Service<(&'a str, &'a [Arc<ListenerEntry>], &'a WorkerContext)> for Container<F, Req>
 where
     F: IntoServiceObj<Req>,
     Req: TryFrom<Stream> + 'static,
 {
-    type Response = (Vec<JoinHandle<()>>, ServiceAny);
+    type Response = (Vec<(usize, JoinHandle<()>)>, ServiceAny, Starter);
     type Error = ();
 
     async fn call(
         &self,
-        (name, listeners): (&'a str, &'a [(String, Arc<Listener>)]),
+        (name, listeners, ctx): (&'a str, &'a [Arc<ListenerEntry>], &'a WorkerContext),
     ) -> Result<Self::Response, Self::Error> {
         let service = self.inner.call(()).await.map_err(|_| ())?;
         let service = Rc::new(service);
 
         let handles = listeners
             .iter()
-            .filter(|(n, _)| n == name)
-            .map(|(_, listener)| worker::start(listener, &service))
+            .filter(|entry| entry.name == name)
+            .map(|entry| (entry.id, worker::start(entry, ctx, &service)))
             .collect::<Vec<_>>();
 
-        Ok((handles, service as _))
+        let starter = {
+            let service = service.clone();
+            let ctx = ctx.clone();
+            Box::new(move |entry: &Arc<ListenerEntry>| worker::start(entry, &ctx, &service)) as Starter
+        };
+
+        Ok((handles, service as _, starter))
     }
 }
 
@@ -0,0 +1,169 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::{
+    runtime::Handle,
+    sync::{mpsc::UnboundedSender, watch},
+};
+use xitca_io::net::Listener;
+
+use crate::{
+    limit::{ConnectionLimits, LimitState},
+    net::AsListener,
+};
+
+/// listener shared by all workers.
+pub struct ListenerEntry {
+    pub(crate) id: usize,
+    pub(crate) name: String,
+    pub(crate) listener: Listener,
+    pub(crate) local_addr: String,
+    // active connections accepted from listener. indexed by worker.
+    pub(crate) connections: Box<[AtomicUsize]>,
+    pub(crate) limit: Arc<LimitState>,
+}
+
+impl ListenerEntry {
+    pub(crate) fn new(
+        id: usize,
+        name: String,
+        listener: Listener,
+        worker_threads: usize,
+        limits: ConnectionLimits,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            local_addr: local_addr(&listener),
+            listener,
+            connections: (0..worker_threads).map(|_| AtomicUsize::new(0)).collect(),
+            limit: Arc::new(LimitState::new(limits)),
+        }
+    }
+}
+
+fn local_addr(listener: &Listener) -> String {
+    // variants of Listener are decided by features of xitca-io. match them through it's api.
+    let addr = match *listener {
+        #[cfg(unix)]
+        Listener::Unix(ref unix) => unix.local_addr().map(|addr| match addr.as_pathname() {
+            Some(path) => path.display().to_string(),
+            None => String::from("(unnamed)"),
+        }),
+        ref listener => listener.local_addr().map(|addr| addr.to_string()),
+    };
+    addr.unwrap_or_default()
+}
+
+/// command sent from server to worker.
+pub(crate) enum WorkerCommand {
+    Add(Arc<ListenerEntry>),
+    Remove(usize),
+}
+
+/// state shared between server, it's workers and handles.
+pub(crate) struct Shared {
+    pub(crate) listeners: Mutex<Vec<Arc<ListenerEntry>>>,
+    pub(crate) workers: Mutex<Vec<UnboundedSender<WorkerCommand>>>,
+    pub(crate) paused: watch::Sender<bool>,
+    pub(crate) shutdown: watch::Sender<bool>,
+    services: HashSet<String>,
+    limits: HashMap<String, ConnectionLimits>,
+    worker_threads: usize,
+    next_id: AtomicUsize,
+    rt: Handle,
+}
+
+impl Shared {
+    pub(crate) fn new(
+        listeners: Vec<(String, Listener)>,
+        services: HashSet<String>,
+        limits: HashMap<String, ConnectionLimits>,
+        worker_threads: usize,
+        rt: Handle,
+    ) -> Self {
+        let listeners = listeners
+            .into_iter()
+            .enumerate()
+            .map(|(id, (name, listener))| {
+                let limits = limits.get(&name).copied().unwrap_or_default();
+                Arc::new(ListenerEntry::new(id, name, listener, worker_threads, limits))
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            next_id: AtomicUsize::new(listeners.len()),
+            listeners: Mutex::new(listeners),
+            workers: Mutex::new(Vec::new()),
+            paused: watch::channel(false).0,
+            shutdown: watch::channel(false).0,
+            services,
+            limits,
+            worker_threads,
+            rt,
+        }
+    }
+
+    pub(crate) fn worker_threads(&self) -> usize {
+        self.worker_threads
+    }
+
+    pub(crate) fn add_listener(&self, name: &str, mut listener: impl AsListener) -> io::Result<usize> {
+        if !self.services.contains(name) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("service: {name} is not registered"),
+            ));
+        }
+
+        if *self.shutdown.borrow() {
+            return Err(io::Error::other("server is shutting down"));
+        }
+
+        // listener must be registered to server's runtime where it's polled.
+        let listener = {
+            let _guard = self.rt.enter();
+            listener.as_listener()?
+        };
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let limits = self.limits.get(name).copied().unwrap_or_default();
+        let entry = Arc::new(ListenerEntry::new(
+            id,
+            name.to_owned(),
+            listener,
+            self.worker_threads,
+            limits,
+        ));
+
+        self.listeners.lock().unwrap().push(entry.clone());
+
+        for worker in self.workers.lock().unwrap().iter() {
+            let _ = worker.send(WorkerCommand::Add(entry.clone()));
+        }
+
+        Ok(id)
+    }
+
+    pub(crate) fn remove_listener(&self, id: usize) -> bool {
+        let mut listeners = self.listeners.lock().unwrap();
+
+        let Some(idx) = listeners.iter().position(|entry| entry.id == id) else {
+            return false;
+        };
+
+        listeners.remove(idx);
+
+        for worker in self.workers.lock().unwrap().iter() {
+            let _ = worker.send(WorkerCommand::Remove(id));
+        }
+
+        true
+    }
+}
@@ -0,0 +1,223 @@
+//! systemd socket activation and service notification.
+//!
+//! Sockets passed by systemd are described with `LISTEN_PID`, `LISTEN_FDS` and `LISTEN_FDNAMES`
+//! environment variables and start from file descriptor 3. Service state is reported with datagram
+//! written to unix socket described by `NOTIFY_SOCKET` environment variable.
+
+use std::{
+    env,
+    ffi::OsStr,
+    io, net,
+    os::{
+        fd::{OwnedFd, RawFd},
+        unix::{
+            ffi::OsStrExt,
+            net::{UnixDatagram, UnixListener},
+        },
+    },
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use socket2::{SockRef, Type};
+use tracing::{debug, error, warn};
+
+const LISTEN_FDS_START: RawFd = 3;
+// upper bound of passed sockets. guard against bogus value taking over unrelated fds.
+const LISTEN_FDS_MAX: RawFd = 1024;
+
+const LISTEN_PID: &str = "LISTEN_PID";
+const LISTEN_FDS: &str = "LISTEN_FDS";
+const LISTEN_FDNAMES: &str = "LISTEN_FDNAMES";
+const NOTIFY_SOCKET: &str = "NOTIFY_SOCKET";
+
+/// socket passed from systemd or upgrading process.
+pub(crate) enum Activated {
+    Tcp(net::TcpListener),
+    Unix(UnixListener),
+    #[cfg_attr(not(feature = "http3"), allow(dead_code))]
+    Udp(net::UdpSocket),
+}
+
+impl Activated {
+    pub(crate) fn from_fd(fd: OwnedFd) -> io::Result<Self> {
+        let sock = SockRef::from(&fd);
+
+        // prevent socket from leaking into spawned processes.
+        sock.set_cloexec(true)?;
+
+        let is_unix = sock.local_addr()?.as_socket().is_none();
+
+        match sock.r#type()? {
+            Type::STREAM if is_unix => Ok(Self::Unix(UnixListener::from(fd))),
+            Type::STREAM => Ok(Self::Tcp(net::TcpListener::from(fd))),
+            Type::DGRAM if !is_unix => Ok(Self::Udp(net::UdpSocket::from(fd))),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "activated socket must be tcp/unix stream or udp datagram",
+            )),
+        }
+    }
+}
+
+/// take sockets passed from systemd socket activation.
+///
+/// sockets are only taken by the first call. environment variables are left untouched as processes
+/// spawned by current one would not pass `LISTEN_PID` check.
+#[allow(unsafe_code)]
+pub(crate) fn listen_fds() -> Vec<(String, OwnedFd)> {
+    static TAKEN: AtomicBool = AtomicBool::new(false);
+
+    if TAKEN.swap(true, Ordering::SeqCst) {
+        return Vec::new();
+    }
+
+    let pid = env::var(LISTEN_PID).ok();
+    let fds = env::var(LISTEN_FDS).ok();
+    let names = env::var(LISTEN_FDNAMES).ok();
+
+    let (Some(pid), Some(fds)) = (pid, fds) else {
+        debug!("{LISTEN_FDS} environment variable is not set");
+        return Vec::new();
+    };
+
+    if pid.parse() != Ok(process::id()) {
+        warn!("{LISTEN_PID}: {pid} does not match current process");
+        return Vec::new();
+    }
+
+    let Some(fds) = parse_count(&fds) else {
+        warn!("{LISTEN_FDS}: {fds} is not a valid number in range of 0..={LISTEN_FDS_MAX}");
+        return Vec::new();
+    };
+
+    // SAFETY: LISTEN_PID matches current process so fds are passed by systemd to it. nothing in current process
+    // owns them and they are only taken by the first call.
+    fd_names(names.as_deref(), fds as usize)
+        .zip(LISTEN_FDS_START..LISTEN_FDS_START + fds)
+        .filter_map(|(name, fd)| unsafe { crate::upgrade::owned_fd(fd) }.map(|fd| (name, fd)))
+        .collect()
+}
+
+fn parse_count(fds: &str) -> Option<RawFd> {
+    fds.parse().ok().filter(|fds| (0..=LISTEN_FDS_MAX).contains(fds))
+}
+
+// names default to "unknown" when LISTEN_FDNAMES is absent or shorter than LISTEN_FDS.
+fn fd_names(names: Option<&str>, fds: usize) -> impl Iterator<Item = String> + '_ {
+    names
+        .into_iter()
+        .flat_map(|names| names.split(':'))
+        .chain(std::iter::repeat("unknown"))
+        .take(fds)
+        .map(String::from)
+}
+
+/// send state to service manager. return false when current process is not managed by systemd.
+pub(crate) fn notify(state: &str) -> io::Result<bool> {
+    match env::var_os(NOTIFY_SOCKET) {
+        Some(path) => send(&path, state).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+fn send(path: &OsStr, state: &str) -> io::Result<()> {
+    let sock = UnixDatagram::unbound()?;
+
+    match path.as_bytes() {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        [b'@', name @ ..] => {
+            #[cfg(target_os = "android")]
+            use std::os::android::net::SocketAddrExt;
+            #[cfg(target_os = "linux")]
+            use std::os::linux::net::SocketAddrExt;
+
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            sock.send_to_addr(state.as_bytes(), &addr)?;
+        }
+        _ => {
+            sock.send_to(state.as_bytes(), path)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn notify_state(state: &str) {
+    if let Err(e) = notify(state) {
+        error!("Failed to notify systemd with {state:?}: {e}");
+    }
+}
+
+pub(crate) fn notify_ready() {
+    notify_state(&format!("READY=1\nMAINPID={}", process::id()));
+}
+
+pub(crate) fn notify_stopping() {
+    notify_state("STOPPING=1");
+}
+
+/// interval of keep alive ping to service manager. half of `WATCHDOG_USEC` as systemd suggested.
+pub(crate) fn watchdog_interval() -> Option<Duration> {
+    let usec = env::var("WATCHDOG_USEC").ok()?.parse::<u64>().ok()?;
+
+    if let Ok(pid) = env::var("WATCHDOG_PID") {
+        if pid.parse() != Ok(process::id()) {
+            return None;
+        }
+    }
+
+    (usec > 0).then(|| Duration::from_micros(usec) / 2)
+}
+
+pub(crate) async fn watchdog(interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        notify_state("WATCHDOG=1");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn names() {
+        let names = fd_names(Some("http:h3"), 3).collect::<Vec<_>>();
+        assert_eq!(names, ["http", "h3", "unknown"]);
+
+        let names = fd_names(None, 2).collect::<Vec<_>>();
+        assert_eq!(names, ["unknown", "unknown"]);
+    }
+
+    #[test]
+    fn count() {
+        assert_eq!(parse_count("2"), Some(2));
+        assert_eq!(parse_count("0"), Some(0));
+        assert_eq!(parse_count("-1"), None);
+        assert_eq!(parse_count("1025"), None);
+        assert_eq!(parse_count(&RawFd::MAX.to_string()), None);
+        assert_eq!(parse_count("nah"), None);
+    }
+
+    #[test]
+    fn fake_notify_socket() {
+        let path = env::temp_dir().join(format!("xitca-server-notify-{}.sock", process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let sock = UnixDatagram::bind(&path).unwrap();
+
+        send(path.as_os_str(), "READY=1").unwrap();
+        send(path.as_os_str(), "STOPPING=1").unwrap();
+
+        let mut buf = [0; 64];
+        let n = sock.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+        let n = sock.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"STOPPING=1");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
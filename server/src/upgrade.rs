@@ -0,0 +1,311 @@
+//! zero downtime binary upgrade through listener file descriptor inheritance.
+//!
+//! Upgrading process spawns the current executable with listener file descriptors left open and
+//! describes them with environment variables:
+//!
+//! - `XITCA_LISTEN_FDS`: newline separated `<fd> <name>` pairs of inherited listeners.
+//! - `XITCA_READY_FD`: file descriptor of a unix stream child writes to when it's ready to serve.
+//! - `XITCA_UPGRADE_PID`: process id of upgrading process. Variables are ignored when it does not match
+//!   parent of current process so processes spawned by child would not pick up stale descriptors.
+//!
+//! Child [Builder](crate::Builder) reuses inherited listeners when binding the same name and address.
+//! Parent gracefully shuts down after child reports readiness.
+
+use std::{
+    env,
+    io::{self, Read, Write},
+    net::{self, SocketAddr},
+    os::{
+        fd::{AsFd, AsRawFd, OwnedFd, RawFd},
+        unix::net::{UnixListener, UnixStream},
+    },
+    path::Path,
+    process::{self, Command},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use socket2::SockRef;
+use tracing::{error, info, warn};
+use xitca_io::net::Listener;
+
+use crate::server::ListenerEntry;
+
+const LISTEN_FDS: &str = "XITCA_LISTEN_FDS";
+const READY_FD: &str = "XITCA_READY_FD";
+const UPGRADE_PID: &str = "XITCA_UPGRADE_PID";
+
+struct Inherited {
+    listeners: Vec<(String, OwnedFd)>,
+    ready: Option<OwnedFd>,
+}
+
+#[allow(unsafe_code)]
+fn inherited() -> &'static Mutex<Inherited> {
+    static INHERITED: OnceLock<Mutex<Inherited>> = OnceLock::new();
+
+    INHERITED.get_or_init(|| {
+        // environment variables are inherited by every process spawned by child. only the direct child
+        // of upgrading process takes the descriptors.
+        if !is_child_of(env::var(UPGRADE_PID).ok().as_deref()) {
+            return Mutex::new(Inherited {
+                listeners: Vec::new(),
+                ready: None,
+            });
+        }
+
+        // SAFETY: fds in environment variables are passed from upgrading parent process. nothing in current
+        // process owns them and they are only taken once when inherited state is initialized.
+        let listeners = env::var(LISTEN_FDS)
+            .map(|fds| {
+                parse_fds(&fds)
+                    .filter_map(|(fd, name)| unsafe { owned_fd(fd) }.map(|fd| (name.to_owned(), fd)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let ready = env::var(READY_FD)
+            .ok()
+            .and_then(|fd| fd.parse().ok())
+            .and_then(|fd| unsafe { owned_fd(fd) });
+
+        Mutex::new(Inherited { listeners, ready })
+    })
+}
+
+fn is_child_of(pid: Option<&str>) -> bool {
+    pid.and_then(|pid| pid.parse().ok())
+        .is_some_and(|pid: u32| pid == std::os::unix::process::parent_id())
+}
+
+/// take ownership of inherited socket. invalid fd is skipped with warning.
+///
+/// # Safety
+/// fd must not be owned by anything else in current process.
+/// See [take_socket](xitca_unsafe_collection::fd::take_socket).
+#[allow(unsafe_code)]
+pub(crate) unsafe fn owned_fd(fd: RawFd) -> Option<OwnedFd> {
+    unsafe { xitca_unsafe_collection::fd::take_socket(fd) }
+        .map_err(|e| warn!("Inherited file descriptor is skipped: {e}"))
+        .ok()
+}
+
+fn parse_fds(fds: &str) -> impl Iterator<Item = (RawFd, &str)> {
+    fds.lines().filter_map(|line| {
+        let (fd, name) = line.split_once(' ')?;
+        fd.parse().ok().map(|fd| (fd, name))
+    })
+}
+
+fn take(name: &str, matches: impl Fn(SockRef<'_>) -> bool) -> Option<OwnedFd> {
+    let mut inherited = inherited().lock().unwrap();
+    let idx = inherited
+        .listeners
+        .iter()
+        .position(|(n, fd)| n == name && matches(SockRef::from(fd)))?;
+    Some(inherited.listeners.swap_remove(idx).1)
+}
+
+/// take inherited tcp listener with given name and local address.
+pub(crate) fn take_tcp(name: &str, addr: &SocketAddr) -> Option<net::TcpListener> {
+    take(name, |sock| {
+        sock.local_addr().ok().and_then(|a| a.as_socket()).as_ref() == Some(addr)
+    })
+    .map(|fd| {
+        info!("Inherited Tcp listener on: {addr:?}");
+        net::TcpListener::from(fd)
+    })
+}
+
+/// take inherited unix listener with given name and path.
+pub(crate) fn take_unix(name: &str, path: &Path) -> Option<UnixListener> {
+    take(name, |sock| {
+        sock.local_addr()
+            .ok()
+            .and_then(|a| a.as_pathname().map(Path::to_path_buf))
+            .as_deref()
+            == Some(path)
+    })
+    .map(|fd| {
+        info!("Inherited Unix listener on: {path:?}");
+        UnixListener::from(fd)
+    })
+}
+
+/// take all inherited listeners.
+pub(crate) fn take_all() -> Vec<(String, OwnedFd)> {
+    inherited().lock().unwrap().listeners.drain(..).collect()
+}
+
+/// close inherited listeners not taken by builder and report readiness to parent process.
+pub(crate) fn notify_ready() {
+    let mut inherited = inherited().lock().unwrap();
+
+    for (name, _) in inherited.listeners.drain(..) {
+        warn!("Inherited listener: {name} is not used and closed");
+    }
+
+    if let Some(fd) = inherited.ready.take() {
+        if let Err(e) = UnixStream::from(fd).write_all(&[1]) {
+            error!("Failed to report readiness to parent process: {e}");
+        }
+    }
+}
+
+/// duplicate listeners for upgrading. udp listeners are skipped as they are owned by quic endpoint.
+pub(crate) fn dup_listeners(listeners: &[Arc<ListenerEntry>]) -> io::Result<Vec<(String, OwnedFd)>> {
+    listeners
+        .iter()
+        .filter_map(|entry| {
+            let fd = match entry.listener {
+                Listener::Tcp(ref tcp) => tcp.as_fd(),
+                Listener::Unix(ref unix) => unix.as_fd(),
+                #[allow(unreachable_patterns)]
+                _ => return None,
+            };
+            Some(fd.try_clone_to_owned().map(|fd| (entry.name.clone(), fd)))
+        })
+        .collect()
+}
+
+/// state for upgrading running server.
+pub(crate) struct Upgrader {
+    timeout: Duration,
+    pub(crate) on_hup: bool,
+    running: AtomicBool,
+}
+
+impl Upgrader {
+    pub(crate) fn new(on_hup: bool, timeout: Duration) -> Self {
+        Self {
+            timeout,
+            on_hup,
+            running: AtomicBool::new(false),
+        }
+    }
+
+    /// spawn upgraded process in a background thread and call on_ready when it's ready to serve.
+    pub(crate) fn upgrade<F>(self: &Arc<Self>, listeners: Vec<(String, OwnedFd)>, on_ready: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if self.running.swap(true, Ordering::SeqCst) {
+            warn!("Server upgrade is already in progress");
+            return;
+        }
+
+        let this = self.clone();
+
+        let res = thread::Builder::new()
+            .name(String::from("xitca-server-upgrade"))
+            .spawn(move || {
+                let listeners = listeners.iter().map(|(name, fd)| (name.as_str(), fd));
+                match spawn(listeners, this.timeout) {
+                    Ok(_) => {
+                        info!("Upgraded process is ready. Shutting down current process gracefully");
+                        on_ready();
+                    }
+                    Err(e) => error!("Failed to upgrade server: {e}"),
+                }
+                this.running.store(false, Ordering::SeqCst);
+            });
+
+        if let Err(e) = res {
+            error!("Failed to spawn server upgrade thread: {e}");
+            self.running.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+/// spawn current executable with given listeners and wait for it to report readiness.
+///
+/// This call blocks current thread.
+fn spawn<'a>(listeners: impl Iterator<Item = (&'a str, &'a OwnedFd)>, timeout: Duration) -> io::Result<()> {
+    let mut fds = Vec::new();
+    let mut env_fds = String::new();
+
+    for (name, fd) in listeners {
+        // duplicated fd is closed in this process after child is spawned.
+        let fd = fd.as_fd().try_clone_to_owned()?;
+        SockRef::from(&fd).set_cloexec(false)?;
+        env_fds.push_str(&format!("{} {name}\n", fd.as_raw_fd()));
+        fds.push(fd);
+    }
+
+    let (mut ready, ready_child) = UnixStream::pair()?;
+    SockRef::from(&ready_child).set_cloexec(false)?;
+
+    let mut cmd = Command::new(executable()?);
+    cmd.args(env::args_os().skip(1))
+        .env(LISTEN_FDS, env_fds)
+        .env(READY_FD, ready_child.as_raw_fd().to_string())
+        .env(UPGRADE_PID, process::id().to_string());
+
+    let child = cmd.spawn()?;
+
+    // close fds for child so the read below observes eof when child exits without being ready.
+    drop(fds);
+    drop(ready_child);
+
+    info!("Spawned upgraded process: {}", child.id());
+
+    ready.set_read_timeout(Some(timeout))?;
+
+    let mut buf = [0];
+    match ready.read(&mut buf) {
+        Ok(1) => Ok(()),
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "upgraded process exited before ready",
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+fn executable() -> io::Result<std::path::PathBuf> {
+    let exe = env::current_exe()?;
+
+    // on linux path of executable replaced on disk is suffixed with " (deleted)".
+    match exe.to_str().and_then(|exe| exe.strip_suffix(" (deleted)")) {
+        Some(exe) => Ok(exe.into()),
+        None => Ok(exe),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let fds = parse_fds("3 http\n4 h3 udp\ninvalid\n5 \n").collect::<Vec<_>>();
+        assert_eq!(fds, vec![(3, "http"), (4, "h3 udp"), (5, "")]);
+    }
+
+    #[test]
+    fn child_of_upgrading_process() {
+        let parent = std::os::unix::process::parent_id().to_string();
+        assert!(is_child_of(Some(&parent)));
+        assert!(!is_child_of(Some(&process::id().to_string())));
+        assert!(!is_child_of(Some("invalid")));
+        assert!(!is_child_of(None));
+    }
+
+    #[allow(unsafe_code)]
+    #[test]
+    fn skip_invalid_fd() {
+        // SAFETY: none of the fds is a socket so ownership is never taken.
+        unsafe {
+            // standard io and not opened fd must never be taken.
+            assert!(owned_fd(0).is_none());
+            assert!(owned_fd(RawFd::MAX).is_none());
+
+            let file = std::fs::File::open(env::current_exe().unwrap()).unwrap();
+            assert!(owned_fd(file.as_raw_fd()).is_none());
+        }
+    }
+}
@@ -1,39 +1,98 @@
 mod shutdown;
 
-use core::{any::Any, sync::atomic::AtomicBool, time::Duration};
+use core::{
+    any::Any,
+    future::{poll_fn, Future},
+    pin::pin,
+    sync::atomic::{AtomicBool, Ordering},
+    task::Poll,
+    time::Duration,
+};
 
-use std::{io, rc::Rc, sync::Arc, thread};
+use std::{collections::HashMap, io, net::IpAddr, rc::Rc, sync::Arc, thread};
 
-use tokio::{task::JoinHandle, time::sleep};
+use tokio::{
+    sync::{mpsc::UnboundedReceiver, watch},
+    task::JoinHandle,
+    time::sleep,
+};
 use tracing::{error, info};
 use xitca_io::net::{Listener, Stream};
 use xitca_service::{ready::ReadyService, Service};
 
+use crate::{
+    limit::Slot,
+    server::{ListenerEntry, WorkerCommand},
+};
+
 use self::shutdown::ShutdownHandle;
 
 // erase Rc<S: ReadyService<_>> type and only use it for counting the reference counter of Rc.
 pub(crate) type ServiceAny = Rc<dyn Any>;
 
-pub(crate) fn start<S, Req>(listener: &Arc<Listener>, service: &Rc<S>) -> JoinHandle<()>
+// erase Rc<S: ReadyService<_>> type and start accepting from listener added at runtime.
+pub(crate) type Starter = Box<dyn Fn(&Arc<ListenerEntry>) -> JoinHandle<()>>;
+
+#[derive(Clone)]
+pub struct WorkerContext {
+    pub(crate) idx: usize,
+    pub(crate) paused: watch::Receiver<bool>,
+    pub(crate) shutdown: watch::Receiver<bool>,
+}
+
+pub(crate) fn start<S, Req>(entry: &Arc<ListenerEntry>, ctx: &WorkerContext, service: &Rc<S>) -> JoinHandle<()>
 where
     S: ReadyService + Service<Req> + 'static,
     S::Ready: 'static,
     Req: TryFrom<Stream> + 'static,
 {
-    let listener = listener.clone();
+    let entry = entry.clone();
     let service = service.clone();
+    let idx = ctx.idx;
+    let mut paused = ctx.paused.clone();
+    let mut shutdown = ctx.shutdown.clone();
 
     tokio::task::spawn_local(async move {
+        let mut acceptor = Acceptor::new();
+
         loop {
-            let ready = service.ready().await;
+            // server is gone when pause state is dropped.
+            match or_shutdown(paused.wait_for(|paused| !*paused), &mut shutdown).await {
+                Some(Ok(_)) => {}
+                _ => return,
+            }
+
+            let ready = or_stop(service.ready(), &mut acceptor).await;
+
+            // wait for connection limits. slot is released when connection is closed.
+            let Some(slot) = or_shutdown(or_stop(entry.limit.acquire(), &mut acceptor), &mut shutdown).await else {
+                return;
+            };
 
-            match listener.accept().await {
+            let accept = accept_or_pause(&mut acceptor, &entry.listener, &mut paused);
+            let res = match or_shutdown(accept, &mut shutdown).await {
+                Some(Some(res)) => res,
+                // connections accepted by kernel are kept and returned after accepting is resumed.
+                Some(None) => {
+                    acceptor.stop();
+                    continue;
+                }
+                None => return,
+            };
+
+            match res {
                 Ok(stream) => {
+                    let Some(guard) = ConnectionGuard::new(entry.clone(), idx, slot, &stream) else {
+                        // drop connection exceeding per ip limit.
+                        continue;
+                    };
+
                     if let Ok(req) = TryFrom::try_from(stream) {
                         let service = service.clone();
                         tokio::task::spawn_local(async move {
                             let _ = service.call(req).await;
                             drop(ready);
+                            drop(guard);
                         });
                     }
                 }
@@ -50,9 +109,126 @@ where
     })
 }
 
+// run future until server is shutting down. paused accept loop must not block server from stopping.
+async fn or_shutdown<F>(fut: F, shutdown: &mut watch::Receiver<bool>) -> Option<F::Output>
+where
+    F: Future,
+{
+    let mut fut = pin!(fut);
+    // server is gone when shutdown state is dropped.
+    let mut stop = pin!(shutdown.wait_for(|shutdown| *shutdown));
+
+    poll_fn(|cx| {
+        if stop.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+        fut.as_mut().poll(cx).map(Some)
+    })
+    .await
+}
+
+// multishot io-uring accept keeps accepting connections with one operation.
+#[cfg(feature = "io-uring")]
+use xitca_io::net::io_uring::MultiAccept as Acceptor;
+
+#[cfg(not(feature = "io-uring"))]
+struct Acceptor;
+
+#[cfg(not(feature = "io-uring"))]
+impl Acceptor {
+    fn new() -> Self {
+        Self
+    }
+
+    async fn accept(&mut self, listener: &Listener) -> io::Result<Stream> {
+        listener.accept().await
+    }
+
+    fn stop(&mut self) {}
+}
+
+// stop acceptor when future can not resolve immediately. connections are left in listen backlog of kernel
+// instead of being accepted while worker can not take them.
+async fn or_stop<F>(fut: F, acceptor: &mut Acceptor) -> F::Output
+where
+    F: Future,
+{
+    let mut fut = pin!(fut);
+
+    if let Poll::Ready(res) = poll_fn(|cx| Poll::Ready(fut.as_mut().poll(cx))).await {
+        return res;
+    }
+
+    acceptor.stop();
+    fut.await
+}
+
+// accept from listener until accepting is paused.
+async fn accept_or_pause(
+    acceptor: &mut Acceptor,
+    listener: &Listener,
+    paused: &mut watch::Receiver<bool>,
+) -> Option<io::Result<Stream>> {
+    let mut accept = pin!(acceptor.accept(listener));
+    let mut pause = pin!(paused.wait_for(|paused| *paused));
+
+    poll_fn(|cx| {
+        if let Poll::Ready(res) = accept.as_mut().poll(cx) {
+            return Poll::Ready(Some(res));
+        }
+        pause.as_mut().poll(cx).map(|_| None)
+    })
+    .await
+}
+
+// track active connections of listener.
+struct ConnectionGuard {
+    entry: Arc<ListenerEntry>,
+    idx: usize,
+    ip: Option<IpAddr>,
+    _slot: Slot,
+}
+
+impl ConnectionGuard {
+    fn new(entry: Arc<ListenerEntry>, idx: usize, slot: Slot, stream: &Stream) -> Option<Self> {
+        let ip = entry.limit.is_per_ip_limited().then(|| peer_ip(stream)).flatten();
+
+        if let Some(ip) = ip {
+            if !entry.limit.enter_ip(ip) {
+                return None;
+            }
+        }
+
+        entry.connections[idx].fetch_add(1, Ordering::Relaxed);
+
+        Some(Self {
+            entry,
+            idx,
+            ip,
+            _slot: slot,
+        })
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.entry.connections[self.idx].fetch_sub(1, Ordering::Relaxed);
+        if let Some(ip) = self.ip {
+            self.entry.limit.leave_ip(ip);
+        }
+    }
+}
+
+// variants of Stream are decided by features of xitca-io. match them through it's api.
+fn peer_ip(stream: &Stream) -> Option<IpAddr> {
+    stream.peer_addr().map(|addr| addr.ip())
+}
+
 pub(crate) async fn wait_for_stop(
-    handles: Vec<JoinHandle<()>>,
+    mut handles: Vec<(usize, JoinHandle<()>)>,
     services: Vec<ServiceAny>,
+    starters: HashMap<&str, Starter>,
+    mut rx_cmd: UnboundedReceiver<WorkerCommand>,
     shutdown_timeout: Duration,
     is_graceful_shutdown: &AtomicBool,
 ) {
@@ -60,7 +236,28 @@ pub(crate) async fn wait_for_stop(
 
     let shutdown_handle = ShutdownHandle::new(shutdown_timeout, services, is_graceful_shutdown);
 
-    for handle in handles {
+    // command channel is closed when server is stopping.
+    while let Some(cmd) = rx_cmd.recv().await {
+        match cmd {
+            WorkerCommand::Add(entry) => {
+                if let Some(start) = starters.get(entry.name.as_str()) {
+                    handles.push((entry.id, start(&entry)));
+                }
+            }
+            WorkerCommand::Remove(id) => handles.retain(|(i, handle)| {
+                let remove = *i == id;
+                if remove {
+                    handle.abort();
+                }
+                !remove
+            }),
+        }
+    }
+
+    // starters hold references of services and must be dropped before counting active connections.
+    drop(starters);
+
+    for (_, handle) in handles {
         handle
             .await
             .unwrap_or_else(|e| with_worker_name_str(|name| error!("{name} exit on error: {e}")));
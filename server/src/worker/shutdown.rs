@@ -45,6 +45,9 @@ impl<'a> ShutdownHandle<'a> {
 
     pub(super) async fn shutdown(mut self) {
         if self.is_graceful_shutdown.load(Ordering::SeqCst) {
+            // notify connections accepted by current worker to finish in-flight requests and close.
+            xitca_io::shutdown::trigger();
+
             let start = Instant::now();
             let mut interval = tokio::time::interval(Duration::from_millis(500));
             while start.elapsed() < self.shutdown_timeout {
@@ -1,13 +1,17 @@
-use futures_util::StreamExt;
+use futures_util::{Stream, StreamExt};
 use std::{
+    fs::File,
     io::{Read, Write},
     net::TcpStream,
+    path::PathBuf,
+    pin::Pin,
+    task::{Context, Poll},
     time::Duration,
 };
 
 use xitca_client::Client;
 use xitca_http::{
-    body::{BoxBody, ResponseBody},
+    body::{BoxBody, ResponseBody, SendFile},
     bytes::{Bytes, BytesMut},
     h1,
     http::{
@@ -93,6 +97,34 @@ async fn h1_post() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h1_sendfile() -> Result<(), Error> {
+    let content = (0..2 * 1024 * 1024).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    std::fs::write(sendfile_path(), &content)?;
+
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/sendfile", handle.ip_port_string());
+
+    let c = Client::new();
+
+    for _ in 0..3 {
+        let mut res = c.get(&server_url)?.send().await?;
+        assert_eq!(res.status().as_u16(), 200);
+        assert!(!res.can_close_connection());
+        let body = res.limit::<{ 4 * 1024 * 1024 }>().body().await?;
+        assert_eq!(body, &content[SENDFILE_OFFSET as usize..]);
+    }
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    std::fs::remove_file(sendfile_path())?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn h1_drop_body_read() -> Result<(), Error> {
     let mut handle = test_h1_server(fn_service(handle))?;
@@ -243,9 +275,70 @@ async fn h1_keepalive() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h1_graceful_shutdown() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.set_read_timeout(Some(Duration::from_secs(3)))?;
+
+    let mut buf = [0; 128];
+    stream.write_all(SIMPLE_GET_REQ)?;
+
+    loop {
+        let n = stream.read(&mut buf)?;
+        if buf[..n].ends_with(b"GET Response") {
+            break;
+        }
+    }
+
+    handle.try_handle()?.stop(true);
+
+    // idle keep-alive connection is closed by server without waiting for keep alive timeout.
+    let read = tokio::task::spawn_blocking(move || stream.read(&mut buf));
+
+    handle.await?;
+
+    assert_eq!(read.await??, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_graceful_shutdown_in_flight() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    stream.write_all(b"GET /slow HTTP/1.1\r\ncontent-length: 0\r\n\r\n")?;
+
+    let read = tokio::task::spawn_blocking(move || {
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).map(|_| buf)
+    });
+
+    // shut down server while request is still being handled.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    // in-flight response is finished and connection is closed after it.
+    let res = String::from_utf8(read.await??)?;
+    assert!(res.starts_with("HTTP/1.1 200 OK"));
+    assert!(res.contains("connection: close"));
+    assert!(res.ends_with("GET Response"));
+
+    Ok(())
+}
+
 async fn handle(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/") | (&Method::HEAD, "/") => Ok(Response::new(Bytes::from("GET Response").into())),
+        (&Method::GET, "/slow") => {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            Ok(Response::new(Bytes::from("GET Response").into()))
+        }
         (&Method::POST, "/") => {
             let length = req.headers().get(header::CONTENT_LENGTH).unwrap().clone();
             let ty = req.headers().get(header::CONTENT_TYPE).unwrap().clone();
@@ -277,6 +370,15 @@ async fn handle(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<Re
 
             Ok(Response::new(Bytes::new().into()))
         }
+        // file range is written with sendfile(2) and the response body must not be polled.
+        (&Method::GET, "/sendfile") => {
+            let file = File::open(sendfile_path())?;
+            let len = file.metadata()?.len() - SENDFILE_OFFSET;
+
+            let mut res = Response::new(ResponseBody::box_stream(Unpolled(len as usize)));
+            res.extensions_mut().insert(SendFile::new(file, SENDFILE_OFFSET, len));
+            Ok(res)
+        }
         (&Method::GET, "/close_connection") => {
             let mut res = Response::new(Bytes::new().into());
             res.headers_mut().insert(CONNECTION, HeaderValue::from_static("close"));
@@ -286,4 +388,25 @@ async fn handle(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<Re
     }
 }
 
+const SENDFILE_OFFSET: u64 = 7;
+
+fn sendfile_path() -> PathBuf {
+    std::env::temp_dir().join(format!("xitca-test-sendfile-{}", std::process::id()))
+}
+
+// response body with exact size that fails when polled.
+struct Unpolled(usize);
+
+impl Stream for Unpolled {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(Some(Err("response body is polled instead of sending file".into())))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0, Some(self.0))
+    }
+}
+
 const SIMPLE_GET_REQ: &[u8] = b"GET / HTTP/1.1\r\ncontent-length: 0\r\n\r\n";
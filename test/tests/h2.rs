@@ -107,6 +107,51 @@ async fn h2_keepalive() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn h2_graceful_shutdown() -> Result<(), Error> {
+    let mut handle = test_h2_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/slow", handle.ip_port_string());
+    let tcp = tokio::net::TcpStream::connect(handle.addr()).await?;
+
+    let server_handle = handle.try_handle()?;
+    let server = tokio::spawn(handle);
+
+    let (mut client, conn) = ::h2::client::handshake(tcp).await?;
+    let conn = tokio::spawn(conn);
+
+    let (res, _) = client.send_request(Request::get(&server_url).body(())?, true)?;
+
+    // shut down server while request is still being handled.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    server_handle.stop(true);
+
+    // in-flight stream is finished after GOAWAY is sent.
+    let res = res.await?;
+    assert_eq!(res.status().as_u16(), 200);
+
+    let mut body = res.into_body();
+    let mut buf = BytesMut::new();
+    while let Some(bytes) = body.data().await {
+        buf.extend_from_slice(&bytes?);
+    }
+    assert_eq!(buf, "GET Response");
+
+    // connection is closed gracefully by server after draining.
+    conn.await??;
+
+    // no new stream can be opened on drained connection.
+    let res = match client.send_request(Request::get(&server_url).body(())?, true) {
+        Ok((res, _)) => res.await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+    assert!(res.is_err());
+
+    server.await??;
+
+    Ok(())
+}
+
 async fn handle(req: Request<RequestExt<h2::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
     // Some yield for testing h2 dispatcher's concurrent future handling.
     tokio::task::yield_now().await;
@@ -115,6 +160,10 @@ async fn handle(req: Request<RequestExt<h2::RequestBody>>) -> Result<Response<Re
 
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/") => Ok(Response::new(Bytes::from("GET Response").into())),
+        (&Method::GET, "/slow") => {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            Ok(Response::new(Bytes::from("GET Response").into()))
+        }
         (&Method::POST, "/") => {
             let (parts, mut body) = req.into_parts();
 
@@ -0,0 +1,139 @@
+use std::{net::SocketAddr, pin::Pin};
+
+use openssl::{
+    asn1::Asn1Time,
+    bn::BigNum,
+    ec::{EcGroup, EcKey},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+    ssl::{SslAcceptor, SslConnector, SslFiletype, SslMethod, SslVerifyMode},
+    x509::{X509NameBuilder, X509},
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_openssl::SslStream;
+use xitca_http::{
+    body::ResponseBody,
+    bytes::{Bytes, BytesMut},
+    h1, h2,
+    http::{Request, RequestExt, Response},
+    HttpServiceBuilder,
+};
+use xitca_io::net::TcpStream;
+use xitca_service::{fn_service, ServiceExt};
+use xitca_test::{test_server, Error};
+
+#[tokio::test]
+async fn h1_peer_certificate() -> Result<(), Error> {
+    let (cert, key) = client_cert();
+
+    let service = fn_service(handle::<h1::RequestBody>).enclosed(HttpServiceBuilder::h1().openssl(acceptor(&cert)));
+    let mut handle = test_server::<_, (TcpStream, SocketAddr)>(service)?;
+
+    let mut stream = connect(handle.addr(), &cert, &key).await?;
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+        .await?;
+
+    let mut buf = Vec::new();
+    stream.read_to_end(&mut buf).await?;
+    let res = String::from_utf8(buf)?;
+    assert!(res.starts_with("HTTP/1.1 200 OK"));
+    assert!(res.ends_with("xitca-client"));
+
+    handle.try_handle()?.stop(false);
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h2_peer_certificate() -> Result<(), Error> {
+    let (cert, key) = client_cert();
+
+    let service = fn_service(handle::<h2::RequestBody>).enclosed(HttpServiceBuilder::h2().openssl(acceptor(&cert)));
+    let mut handle = test_server::<_, (TcpStream, SocketAddr)>(service)?;
+
+    let stream = connect(handle.addr(), &cert, &key).await?;
+
+    let (mut client, conn) = ::h2::client::handshake(stream).await?;
+    tokio::spawn(conn);
+
+    let (res, _) = client.send_request(Request::get("https://localhost/").body(())?, true)?;
+    let res = res.await?;
+    assert_eq!(res.status().as_u16(), 200);
+
+    let mut body = res.into_body();
+    let mut buf = BytesMut::new();
+    while let Some(bytes) = body.data().await {
+        buf.extend_from_slice(&bytes?);
+    }
+    assert_eq!(buf, "xitca-client");
+
+    handle.try_handle()?.stop(false);
+    handle.await?;
+
+    Ok(())
+}
+
+async fn handle<B>(req: Request<RequestExt<B>>) -> Result<Response<ResponseBody>, Error> {
+    let name = req
+        .body()
+        .peer_certificate()
+        .and_then(|cert| cert.common_name())
+        .unwrap_or("none")
+        .to_owned();
+    Ok(Response::new(Bytes::from(name).into()))
+}
+
+// server trusting given self signed client certificate.
+fn acceptor(client_cert: &X509) -> SslAcceptor {
+    let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+    builder.set_certificate_chain_file("../examples/cert/cert.pem").unwrap();
+    builder
+        .set_private_key_file("../examples/cert/key.pem", SslFiletype::PEM)
+        .unwrap();
+    builder.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+    builder.cert_store_mut().add_cert(client_cert.clone()).unwrap();
+    builder.build()
+}
+
+async fn connect(
+    addr: SocketAddr,
+    cert: &X509,
+    key: &PKey<Private>,
+) -> Result<SslStream<tokio::net::TcpStream>, Error> {
+    let mut builder = SslConnector::builder(SslMethod::tls())?;
+    builder.set_verify(SslVerifyMode::NONE);
+    builder.set_certificate(cert)?;
+    builder.set_private_key(key)?;
+
+    let ssl = builder.build().configure()?.into_ssl("localhost")?;
+    let tcp = tokio::net::TcpStream::connect(addr).await?;
+    let mut stream = SslStream::new(ssl, tcp)?;
+    Pin::new(&mut stream).connect().await?;
+
+    Ok(stream)
+}
+
+fn client_cert() -> (X509, PKey<Private>) {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, "xitca-client").unwrap();
+    let name = name.build();
+
+    let mut cert = X509::builder().unwrap();
+    cert.set_version(2).unwrap();
+    cert.set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+        .unwrap();
+    cert.set_subject_name(&name).unwrap();
+    cert.set_issuer_name(&name).unwrap();
+    cert.set_pubkey(&key).unwrap();
+    cert.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+    cert.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+    cert.sign(&key, MessageDigest::sha256()).unwrap();
+
+    (cert.build(), key)
+}
@@ -1,3 +1,8 @@
+#[cfg(any(feature = "rustls", feature = "openssl"))]
+mod sni;
+
+#[cfg(feature = "openssl")]
+pub mod openssl;
 #[cfg(feature = "rustls")]
 pub mod rustls;
 #[cfg(feature = "rustls-uring")]
@@ -0,0 +1,6 @@
+mod client_auth;
+mod sni;
+
+pub use openssl::*;
+
+pub use self::{client_auth::set_client_cert_verifier_from_pem, sni::SniResolver};
@@ -0,0 +1,137 @@
+use std::{fs, io, path::Path};
+
+use openssl::{
+    ssl::{SslContextBuilder, SslVerifyMode},
+    x509::{store::X509StoreBuilder, X509},
+};
+
+/// Enable client certificate verification on given builder trusting CA certificates from given pem file.
+/// CA certificates are also sent to client as acceptable issuers of it's certificate.
+///
+/// When `optional` is true client is allowed to skip presenting certificate and the connection is treated as
+/// unauthenticated. Certificates presented by client are always verified.
+///
+/// # Examples
+/// ```rust,no_run
+/// use xitca_tls::openssl::{
+///     set_client_cert_verifier_from_pem,
+///     ssl::{SslAcceptor, SslFiletype, SslMethod},
+/// };
+///
+/// # fn acceptor() -> std::io::Result<()> {
+/// let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).map_err(std::io::Error::other)?;
+/// builder.set_certificate_chain_file("cert.pem").map_err(std::io::Error::other)?;
+/// builder.set_private_key_file("key.pem", SslFiletype::PEM).map_err(std::io::Error::other)?;
+/// set_client_cert_verifier_from_pem(&mut builder, "ca.pem", false)?;
+/// let acceptor = builder.build();
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_client_cert_verifier_from_pem(
+    builder: &mut SslContextBuilder,
+    ca: impl AsRef<Path>,
+    optional: bool,
+) -> io::Result<()> {
+    let certs = X509::stack_from_pem(&fs::read(ca)?).map_err(io::Error::other)?;
+
+    if certs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no CA certificate found in pem file",
+        ));
+    }
+
+    let mut store = X509StoreBuilder::new().map_err(io::Error::other)?;
+    for cert in certs {
+        builder.add_client_ca(&cert).map_err(io::Error::other)?;
+        store.add_cert(cert).map_err(io::Error::other)?;
+    }
+    builder.set_verify_cert_store(store.build()).map_err(io::Error::other)?;
+
+    let mode = if optional {
+        SslVerifyMode::PEER
+    } else {
+        SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT
+    };
+    builder.set_verify(mode);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
+    use openssl::{
+        pkey::PKey,
+        ssl::{SslAcceptor, SslConnector, SslMethod},
+    };
+
+    use crate::sni::test::{self_signed, temp_dir, write};
+
+    use super::*;
+
+    // handshake with optional client certificate and return if it succeeded on both sides.
+    fn handshake(ca: &Path, optional: bool, client: Option<&(Vec<u8>, Vec<u8>)>) -> bool {
+        let (cert, key) = self_signed("server");
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        builder.set_certificate(&X509::from_pem(&cert).unwrap()).unwrap();
+        builder
+            .set_private_key(&PKey::private_key_from_pem(&key).unwrap())
+            .unwrap();
+        set_client_cert_verifier_from_pem(&mut builder, ca, optional).unwrap();
+        let acceptor = builder.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            acceptor.accept(stream).is_ok()
+        });
+
+        let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+        connector.set_verify(SslVerifyMode::NONE);
+        if let Some((cert, key)) = client {
+            connector.set_certificate(&X509::from_pem(cert).unwrap()).unwrap();
+            connector
+                .set_private_key(&PKey::private_key_from_pem(key).unwrap())
+                .unwrap();
+        }
+        let client = connector
+            .build()
+            .connect("localhost", TcpStream::connect(addr).unwrap())
+            .is_ok();
+
+        server.join().unwrap() && client
+    }
+
+    #[test]
+    fn verify_client() {
+        let dir = temp_dir("openssl-client-auth");
+        let ca_path = dir.join("ca.pem");
+
+        // self signed client certificate is it's own CA.
+        let trusted = self_signed("client");
+        let untrusted = self_signed("client");
+        write(&ca_path, &trusted.0);
+
+        assert!(handshake(&ca_path, false, Some(&trusted)));
+        assert!(!handshake(&ca_path, false, Some(&untrusted)));
+        assert!(!handshake(&ca_path, false, None));
+
+        assert!(handshake(&ca_path, true, None));
+        assert!(!handshake(&ca_path, true, Some(&untrusted)));
+
+        write(&ca_path, b"");
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        let err = set_client_cert_verifier_from_pem(&mut builder, &ca_path, false).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
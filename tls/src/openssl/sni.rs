@@ -0,0 +1,355 @@
+use core::{fmt, time::Duration};
+
+use std::{
+    io,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use openssl::{
+    error::ErrorStack,
+    ssl::{NameType, SniError, SslAcceptor, SslAcceptorBuilder, SslContext, SslFiletype, SslMethod},
+};
+
+use crate::sni::{self, normalize, Certs, Reload, Watchers};
+
+/// Server certificate resolver switching [SslContext] with server name indication(SNI) of client.
+///
+/// Resolver is installed to a [SslAcceptorBuilder] with [SniResolver::install]. Handshake with matched host
+/// name continues with the context of it and otherwise with the context of acceptor itself. Openssl negotiates
+/// ALPN with the switched context so contexts must be configured with the same ALPN callback as the acceptor.
+///
+/// Resolver is cheap to clone and all clones share the same contexts. A clone can be kept as handle for
+/// updating contexts at runtime. Updates are observed by following tls handshakes while established
+/// connections are not affected.
+///
+/// # Examples:
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use xitca_tls::openssl::{
+///     ssl::{AlpnError, SslAcceptor, SslMethod},
+///     SniResolver,
+/// };
+///
+/// # fn resolve() -> std::io::Result<()> {
+/// // template of contexts loaded from pem files.
+/// let resolver = SniResolver::new(|| {
+///     let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls())?;
+///     builder.set_alpn_select_callback(|_, protocols| {
+///         xitca_tls::openssl::ssl::select_next_proto(b"\x08http/1.1", protocols).ok_or(AlpnError::NOACK)
+///     });
+///     Ok(builder)
+/// });
+///
+/// // certificate for exact host name.
+/// resolver.load_pem("example.com", "example.com/cert.pem", "example.com/key.pem")?;
+/// // certificate for any sub domain of example.com and reload it when files are changed.
+/// resolver.watch_pem("*.example.com", "wildcard/cert.pem", "wildcard/key.pem", Duration::from_secs(60))?;
+///
+/// let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls())?;
+/// resolver.install(&mut builder);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SniResolver {
+    inner: Arc<Inner>,
+}
+
+type Template = Box<dyn Fn() -> Result<SslAcceptorBuilder, ErrorStack> + Send + Sync>;
+
+struct Inner {
+    template: Template,
+    certs: RwLock<Certs<SslContext>>,
+    watchers: Watchers,
+}
+
+impl Default for SniResolver {
+    fn default() -> Self {
+        Self::new(|| SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()))
+    }
+}
+
+impl SniResolver {
+    /// Construct an empty resolver with given template for building contexts loaded from pem files.
+    ///
+    /// Certificate and private key are set to the builder template returned.
+    pub fn new<F>(template: F) -> Self
+    where
+        F: Fn() -> Result<SslAcceptorBuilder, ErrorStack> + Send + Sync + 'static,
+    {
+        Self {
+            inner: Arc::new(Inner {
+                template: Box::new(template),
+                certs: RwLock::new(Certs::default()),
+                watchers: Watchers::default(),
+            }),
+        }
+    }
+
+    /// Install resolver as server name callback of given acceptor builder.
+    pub fn install(&self, builder: &mut SslAcceptorBuilder) {
+        let inner = self.inner.clone();
+        builder.set_servername_callback(move |ssl, _| {
+            let ctx = inner.certs.read().unwrap().resolve(ssl.servername(NameType::HOST_NAME));
+            match ctx {
+                Some(ctx) => ssl.set_ssl_context(&ctx).map_err(|_| SniError::ALERT_FATAL),
+                None => Ok(()),
+            }
+        });
+    }
+
+    /// Insert context for given host name and return the context it replaced.
+    ///
+    /// Host name starting with `*.` is a wildcard matching exactly one more label. For example
+    /// `*.example.com` matches `www.example.com` but not `example.com` or `a.www.example.com`.
+    /// Exact host name always takes priority over wildcard.
+    pub fn insert(&self, name: &str, ctx: SslContext) -> Option<SslContext> {
+        self.inner.insert(name, ctx)
+    }
+
+    /// Remove context of given host name and stop watching it's pem files.
+    pub fn remove(&self, name: &str) -> Option<SslContext> {
+        self.inner.watchers.stop(name);
+        self.inner.certs.write().unwrap().names.remove(&normalize(name))
+    }
+
+    /// Set context used when client does not send SNI or no context matches it. When not set handshake
+    /// continues with the context of acceptor.
+    pub fn set_fallback(&self, ctx: Option<SslContext>) -> Option<SslContext> {
+        core::mem::replace(&mut self.inner.certs.write().unwrap().fallback, ctx)
+    }
+
+    /// Replace all host name contexts at once. Handshakes observe either the old or the new set
+    /// and never a mix of them. Fallback context and pem file watchers are not affected.
+    pub fn replace<I, N>(&self, ctxs: I)
+    where
+        I: IntoIterator<Item = (N, SslContext)>,
+        N: AsRef<str>,
+    {
+        let names = ctxs
+            .into_iter()
+            .map(|(name, ctx)| (normalize(name.as_ref()), ctx))
+            .collect();
+        self.inner.certs.write().unwrap().names = names;
+    }
+
+    /// Load pem encoded certificate chain and private key from files and insert them for given host name.
+    pub fn load_pem(&self, name: &str, cert: impl AsRef<Path>, key: impl AsRef<Path>) -> io::Result<()> {
+        self.inner.load_pem(name, cert.as_ref(), key.as_ref())
+    }
+
+    /// Load pem encoded certificate chain and private key from files and reload them when any file is
+    /// modified. Files are checked in given interval from one background thread shared by all watched
+    /// host names of resolver. The thread exits when all clones of resolver are dropped or no host name
+    /// is watched.
+    ///
+    /// Calling it again for the same host name replaces the previous watcher. Watching can be stopped
+    /// with [SniResolver::unwatch] or [SniResolver::remove].
+    ///
+    /// When reloading fails previous context is kept and error is logged. A certificate not matching
+    /// it's private key is treated as failure so certificate and key files can be replaced one by one.
+    pub fn watch_pem(
+        &self,
+        name: &str,
+        cert: impl AsRef<Path>,
+        key: impl AsRef<Path>,
+        interval: Duration,
+    ) -> io::Result<()> {
+        sni::watch_pem(&self.inner, name, cert.as_ref(), key.as_ref(), interval)
+    }
+
+    /// Stop watching pem files of given host name. Return false when host name is not watched.
+    /// Context already loaded is kept.
+    pub fn unwatch(&self, name: &str) -> bool {
+        self.inner.watchers.stop(name)
+    }
+
+    /// Build context from resolver's template with pem encoded certificate chain and private key from files.
+    ///
+    /// Return error when private key does not match the end entity certificate.
+    pub fn context_from_pem(&self, cert: impl AsRef<Path>, key: impl AsRef<Path>) -> io::Result<SslContext> {
+        self.inner.context_from_pem(cert.as_ref(), key.as_ref())
+    }
+}
+
+impl Inner {
+    fn insert(&self, name: &str, ctx: SslContext) -> Option<SslContext> {
+        self.certs.write().unwrap().names.insert(normalize(name), ctx)
+    }
+
+    fn context_from_pem(&self, cert: &Path, key: &Path) -> io::Result<SslContext> {
+        let mut builder = (self.template)()?;
+        builder.set_certificate_chain_file(cert)?;
+        builder
+            .set_private_key_file(key, SslFiletype::PEM)
+            .and_then(|_| builder.check_private_key())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(builder.build().into_context())
+    }
+}
+
+impl Reload for Inner {
+    fn watchers(&self) -> &Watchers {
+        &self.watchers
+    }
+
+    fn load_pem(&self, name: &str, cert: &Path, key: &Path) -> io::Result<()> {
+        let ctx = self.context_from_pem(cert, key)?;
+        self.insert(name, ctx);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SniResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let certs = self.inner.certs.read().unwrap();
+        f.debug_struct("SniResolver")
+            .field("names", &certs.names.keys().collect::<Vec<_>>())
+            .field("fallback", &certs.fallback.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        net::{TcpListener, TcpStream},
+        thread,
+        time::Instant,
+    };
+
+    use openssl::{
+        nid::Nid,
+        ssl::{AlpnError, SslConnector, SslVerifyMode},
+        x509::X509Ref,
+    };
+
+    use crate::sni::test::{self_signed, temp_dir, write};
+
+    use super::*;
+
+    fn template() -> Result<SslAcceptorBuilder, ErrorStack> {
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls())?;
+        builder.set_alpn_select_callback(|_, _| Ok(b"h2"));
+        Ok(builder)
+    }
+
+    fn common_name(cert: &X509Ref) -> String {
+        let name = cert.subject_name().entries_by_nid(Nid::COMMONNAME).next().unwrap();
+        name.data().to_string().unwrap()
+    }
+
+    // handshake with given sni and return common name of server certificate and selected alpn protocol.
+    fn handshake(resolver: &SniResolver, default: (&Path, &Path), sni: &str) -> (String, Option<Vec<u8>>) {
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        builder.set_certificate_chain_file(default.0).unwrap();
+        builder.set_private_key_file(default.1, SslFiletype::PEM).unwrap();
+        builder.set_alpn_select_callback(|_, _| Err(AlpnError::NOACK));
+        resolver.install(&mut builder);
+        let acceptor = builder.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let _ = acceptor.accept(stream);
+        });
+
+        let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+        connector.set_verify(SslVerifyMode::NONE);
+        connector.set_alpn_protos(b"\x02h2").unwrap();
+        let stream = connector
+            .build()
+            .connect(sni, TcpStream::connect(addr).unwrap())
+            .unwrap();
+
+        let ssl = stream.ssl();
+        let name = common_name(&ssl.peer_certificate().unwrap());
+        let alpn = ssl.selected_alpn_protocol().map(<[u8]>::to_vec);
+
+        drop(stream);
+        server.join().unwrap();
+
+        (name, alpn)
+    }
+
+    #[test]
+    fn select_by_sni() {
+        let dir = temp_dir("openssl-sni");
+
+        for name in ["default", "exact", "wildcard"] {
+            let (cert, key) = self_signed(name);
+            write(&dir.join(format!("{name}.cert.pem")), &cert);
+            write(&dir.join(format!("{name}.key.pem")), &key);
+        }
+
+        let path = |name: &str, ext: &str| dir.join(format!("{name}.{ext}.pem"));
+
+        let resolver = SniResolver::new(template);
+        resolver
+            .load_pem("www.example.com", path("exact", "cert"), path("exact", "key"))
+            .unwrap();
+        resolver
+            .load_pem("*.example.com", path("wildcard", "cert"), path("wildcard", "key"))
+            .unwrap();
+
+        let default_cert = path("default", "cert");
+        let default_key = path("default", "key");
+        let default = (default_cert.as_path(), default_key.as_path());
+
+        let (name, alpn) = handshake(&resolver, default, "www.example.com");
+        assert_eq!(name, "exact");
+        // alpn is negotiated with switched context.
+        assert_eq!(alpn.as_deref(), Some(&b"h2"[..]));
+
+        assert_eq!(handshake(&resolver, default, "api.example.com").0, "wildcard");
+        assert_eq!(handshake(&resolver, default, "example.com").0, "default");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reload() {
+        let dir = temp_dir("openssl-reload");
+        let (cert_path, key_path) = (dir.join("cert.pem"), dir.join("key.pem"));
+
+        let (cert, key) = self_signed("first");
+        write(&cert_path, &cert);
+        write(&key_path, &key);
+
+        let resolver = SniResolver::new(template);
+        let interval = Duration::from_millis(10);
+        resolver.watch_pem("a.com", &cert_path, &key_path, interval).unwrap();
+
+        let default = (cert_path.as_path(), key_path.as_path());
+        let (name, _) = handshake(&resolver, default, "a.com");
+        assert_eq!(name, "first");
+
+        // certificate replaced before private key. mismatched pair must not be installed.
+        let (cert, key) = self_signed("second");
+        write(&cert_path, &cert);
+        assert!(resolver.context_from_pem(&cert_path, &key_path).is_err());
+        thread::sleep(interval * 10);
+
+        write(&key_path, &key);
+
+        let now = Instant::now();
+        loop {
+            let ctx = resolver.inner.certs.read().unwrap().lookup("a.com").unwrap();
+            if common_name(ctx.certificate().unwrap()) == "second" {
+                break;
+            }
+            assert!(now.elapsed() < Duration::from_secs(5), "certificate is not reloaded");
+            thread::sleep(interval);
+        }
+
+        assert!(resolver.unwatch("a.com"));
+        assert!(!resolver.unwatch("a.com"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -7,9 +7,17 @@ use core::{
 
 use std::io;
 
+mod client_auth;
+mod sni;
+
 pub use rustls::*;
 
-use xitca_io::io::{AsyncIo, Interest, Ready};
+pub use self::{client_auth::client_cert_verifier_from_pem, sni::SniResolver};
+
+use xitca_io::{
+    bytes::Bytes,
+    io::{AsyncIo, Interest, Ready},
+};
 
 /// A stream managed by `rustls` crate for tls read/write.
 pub struct TlsStream<C, Io> {
@@ -111,6 +119,16 @@ where
     fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         AsyncIo::poll_shutdown(Pin::new(&mut self.get_mut().io), cx)
     }
+
+    fn peer_certificate_chain(&self) -> Option<Vec<Bytes>> {
+        peer_certificate_chain(&self.conn)
+    }
+}
+
+// rustls only keeps peer certificates when they are verified. e.g. by client cert verifier of ServerConfig.
+pub(crate) fn peer_certificate_chain<S>(conn: &ConnectionCommon<S>) -> Option<Vec<Bytes>> {
+    conn.peer_certificates()
+        .map(|certs| certs.iter().map(|cert| Bytes::copy_from_slice(cert)).collect())
 }
 
 impl<C, Io, S> io::Read for TlsStream<C, Io>
@@ -0,0 +1,53 @@
+use std::{io, path::Path, sync::Arc};
+
+use rustls::{
+    server::{danger::ClientCertVerifier, WebPkiClientVerifier},
+    RootCertStore,
+};
+use rustls_pki_types::{pem::PemObject, CertificateDer};
+
+/// Construct a client certificate verifier trusting CA certificates from given pem file.
+///
+/// When `optional` is true client is allowed to skip presenting certificate and the connection is treated as
+/// unauthenticated. Certificates presented by client are always verified.
+///
+/// # Examples
+/// ```rust,no_run
+/// use xitca_tls::rustls::{
+///     client_cert_verifier_from_pem,
+///     pki_types::{CertificateDer, PrivateKeyDer},
+///     ServerConfig,
+/// };
+///
+/// # fn config(cert: Vec<CertificateDer<'static>>, key: PrivateKeyDer<'static>) -> std::io::Result<()> {
+/// let verifier = client_cert_verifier_from_pem("ca.pem", false)?;
+/// let config = ServerConfig::builder()
+///     .with_client_cert_verifier(verifier)
+///     .with_single_cert(cert, key)
+///     .map_err(std::io::Error::other)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn client_cert_verifier_from_pem(ca: impl AsRef<Path>, optional: bool) -> io::Result<Arc<dyn ClientCertVerifier>> {
+    let mut roots = RootCertStore::empty();
+
+    for cert in CertificateDer::pem_file_iter(ca).map_err(io::Error::other)? {
+        roots.add(cert.map_err(io::Error::other)?).map_err(io::Error::other)?;
+    }
+
+    if roots.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no CA certificate found in pem file",
+        ));
+    }
+
+    let builder = WebPkiClientVerifier::builder(Arc::new(roots));
+    let builder = if optional {
+        builder.allow_unauthenticated()
+    } else {
+        builder
+    };
+
+    builder.build().map_err(io::Error::other)
+}
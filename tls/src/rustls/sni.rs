@@ -0,0 +1,308 @@
+use core::{fmt, time::Duration};
+
+use std::{
+    io,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use rustls::{
+    crypto::CryptoProvider,
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+    Error, InconsistentKeys, ServerConfig,
+};
+use rustls_pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer};
+
+use crate::sni::{self, normalize, Certs, Reload, Watchers};
+
+/// Server certificate resolver selecting certificate with server name indication(SNI) of client.
+///
+/// Resolver is cheap to clone and all clones share the same certificates. A clone can be kept as
+/// handle for updating certificates at runtime. Updates are observed by following tls handshakes
+/// while established connections are not affected.
+///
+/// # Examples:
+/// ```rust,no_run
+/// use std::{sync::Arc, time::Duration};
+///
+/// use xitca_tls::rustls::{ServerConfig, SniResolver};
+///
+/// # fn resolve() -> std::io::Result<()> {
+/// let resolver = SniResolver::new();
+///
+/// // certificate for exact host name.
+/// resolver.load_pem("example.com", "example.com/cert.pem", "example.com/key.pem")?;
+/// // certificate for any sub domain of example.com and reload it when files are changed.
+/// resolver.watch_pem("*.example.com", "wildcard/cert.pem", "wildcard/key.pem", Duration::from_secs(60))?;
+///
+/// let config = ServerConfig::builder()
+///     .with_no_client_auth()
+///     .with_cert_resolver(Arc::new(resolver.clone()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SniResolver {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    provider: Arc<CryptoProvider>,
+    certs: RwLock<Certs<Arc<CertifiedKey>>>,
+    watchers: Watchers,
+}
+
+impl Default for SniResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SniResolver {
+    /// Construct an empty resolver with process default [CryptoProvider] for loading private keys.
+    ///
+    /// # Panics:
+    /// When process default [CryptoProvider] can not be determined. See [ServerConfig::builder] for detail.
+    pub fn new() -> Self {
+        Self::with_crypto_provider(ServerConfig::builder().crypto_provider().clone())
+    }
+
+    /// Construct an empty resolver with given [CryptoProvider] for loading private keys.
+    pub fn with_crypto_provider(provider: Arc<CryptoProvider>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                provider,
+                certs: RwLock::new(Certs::default()),
+                watchers: Watchers::default(),
+            }),
+        }
+    }
+
+    /// Insert certificate for given host name and return the certificate it replaced.
+    ///
+    /// Host name starting with `*.` is a wildcard matching exactly one more label. For example
+    /// `*.example.com` matches `www.example.com` but not `example.com` or `a.www.example.com`.
+    /// Exact host name always takes priority over wildcard.
+    pub fn insert(&self, name: &str, key: Arc<CertifiedKey>) -> Option<Arc<CertifiedKey>> {
+        self.inner.insert(name, key)
+    }
+
+    /// Remove certificate of given host name and stop watching it's pem files.
+    pub fn remove(&self, name: &str) -> Option<Arc<CertifiedKey>> {
+        self.inner.watchers.stop(name);
+        self.inner.certs.write().unwrap().names.remove(&normalize(name))
+    }
+
+    /// Set certificate used when client does not send SNI or no certificate matches it.
+    pub fn set_fallback(&self, key: Option<Arc<CertifiedKey>>) -> Option<Arc<CertifiedKey>> {
+        core::mem::replace(&mut self.inner.certs.write().unwrap().fallback, key)
+    }
+
+    /// Replace all host name certificates at once. Handshakes observe either the old or the new set
+    /// and never a mix of them. Fallback certificate and pem file watchers are not affected.
+    pub fn replace<I, N>(&self, certs: I)
+    where
+        I: IntoIterator<Item = (N, Arc<CertifiedKey>)>,
+        N: AsRef<str>,
+    {
+        let names = certs
+            .into_iter()
+            .map(|(name, key)| (normalize(name.as_ref()), key))
+            .collect();
+        self.inner.certs.write().unwrap().names = names;
+    }
+
+    /// Load pem encoded certificate chain and private key from files and insert them for given host name.
+    pub fn load_pem(&self, name: &str, cert: impl AsRef<Path>, key: impl AsRef<Path>) -> io::Result<()> {
+        self.inner.load_pem(name, cert.as_ref(), key.as_ref())
+    }
+
+    /// Load pem encoded certificate chain and private key from files and reload them when any file is
+    /// modified. Files are checked in given interval from one background thread shared by all watched
+    /// host names of resolver. The thread exits when all clones of resolver are dropped or no host name
+    /// is watched.
+    ///
+    /// Calling it again for the same host name replaces the previous watcher. Watching can be stopped
+    /// with [SniResolver::unwatch] or [SniResolver::remove].
+    ///
+    /// When reloading fails previous certificate is kept and error is logged. A certificate not matching
+    /// it's private key is treated as failure so certificate and key files can be replaced one by one.
+    pub fn watch_pem(
+        &self,
+        name: &str,
+        cert: impl AsRef<Path>,
+        key: impl AsRef<Path>,
+        interval: Duration,
+    ) -> io::Result<()> {
+        sni::watch_pem(&self.inner, name, cert.as_ref(), key.as_ref(), interval)
+    }
+
+    /// Stop watching pem files of given host name. Return false when host name is not watched.
+    /// Certificate already loaded is kept.
+    pub fn unwatch(&self, name: &str) -> bool {
+        self.inner.watchers.stop(name)
+    }
+
+    /// Load pem encoded certificate chain and private key from files with resolver's [CryptoProvider].
+    ///
+    /// Return error when private key does not match the end entity certificate.
+    pub fn certified_key_from_pem(&self, cert: impl AsRef<Path>, key: impl AsRef<Path>) -> io::Result<CertifiedKey> {
+        self.inner.certified_key_from_pem(cert.as_ref(), key.as_ref())
+    }
+}
+
+impl Inner {
+    fn insert(&self, name: &str, key: Arc<CertifiedKey>) -> Option<Arc<CertifiedKey>> {
+        self.certs.write().unwrap().names.insert(normalize(name), key)
+    }
+
+    fn certified_key_from_pem(&self, cert: &Path, key: &Path) -> io::Result<CertifiedKey> {
+        let chain = CertificateDer::pem_file_iter(cert)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(io::Error::other)?;
+
+        if chain.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no certificate found in pem file",
+            ));
+        }
+
+        let key = PrivateKeyDer::from_pem_file(key).map_err(io::Error::other)?;
+        let key = self
+            .provider
+            .key_provider
+            .load_private_key(key)
+            .map_err(io::Error::other)?;
+
+        let key = CertifiedKey::new(chain, key);
+
+        match key.keys_match() {
+            // key provider not able to expose public key can not be checked.
+            Ok(_) | Err(Error::InconsistentKeys(InconsistentKeys::Unknown)) => Ok(key),
+            Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        }
+    }
+}
+
+impl Reload for Inner {
+    fn watchers(&self) -> &Watchers {
+        &self.watchers
+    }
+
+    fn load_pem(&self, name: &str, cert: &Path, key: &Path) -> io::Result<()> {
+        let key = self.certified_key_from_pem(cert, key)?;
+        self.insert(name, Arc::new(key));
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for SniResolver {
+    fn resolve(&self, hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        self.inner.certs.read().unwrap().resolve(hello.server_name())
+    }
+}
+
+impl fmt::Debug for SniResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let certs = self.inner.certs.read().unwrap();
+        f.debug_struct("SniResolver")
+            .field("names", &certs.names.keys().collect::<Vec<_>>())
+            .field("fallback", &certs.fallback.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, thread, time::Instant};
+
+    use crate::sni::test::{self_signed, temp_dir, write};
+
+    use super::*;
+
+    fn lookup(resolver: &SniResolver, name: &str) -> Option<CertificateDer<'static>> {
+        resolver
+            .inner
+            .certs
+            .read()
+            .unwrap()
+            .lookup(name)
+            .map(|key| key.cert[0].clone())
+    }
+
+    fn der(pem: &[u8]) -> CertificateDer<'static> {
+        CertificateDer::from_pem_slice(pem).unwrap()
+    }
+
+    // wait for background watcher to observe expected certificate.
+    fn wait(resolver: &SniResolver, name: &str, expect: &CertificateDer<'_>) -> bool {
+        let now = Instant::now();
+        while now.elapsed() < Duration::from_secs(5) {
+            if lookup(resolver, name).as_ref() == Some(expect) {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        false
+    }
+
+    #[test]
+    fn key_mismatch() {
+        let dir = temp_dir("rustls-mismatch");
+        let (cert, _) = self_signed("a.com");
+        let (_, key) = self_signed("a.com");
+        write(&dir.join("cert.pem"), &cert);
+        write(&dir.join("key.pem"), &key);
+
+        let resolver = SniResolver::new();
+        let err = resolver
+            .load_pem("a.com", dir.join("cert.pem"), dir.join("key.pem"))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(lookup(&resolver, "a.com").is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reload() {
+        let dir = temp_dir("rustls-reload");
+        let (cert_path, key_path) = (dir.join("cert.pem"), dir.join("key.pem"));
+
+        let (cert1, key1) = self_signed("a.com");
+        write(&cert_path, &cert1);
+        write(&key_path, &key1);
+
+        let resolver = SniResolver::new();
+        let interval = Duration::from_millis(10);
+        resolver.watch_pem("a.com", &cert_path, &key_path, interval).unwrap();
+        assert_eq!(lookup(&resolver, "a.com"), Some(der(&cert1)));
+
+        // certificate replaced before private key. mismatched pair must not be installed.
+        let (cert2, key2) = self_signed("a.com");
+        write(&cert_path, &cert2);
+        thread::sleep(interval * 10);
+        assert_eq!(lookup(&resolver, "a.com"), Some(der(&cert1)));
+
+        write(&key_path, &key2);
+        assert!(wait(&resolver, "a.com", &der(&cert2)));
+
+        // watching the same name again replaces the previous watcher.
+        resolver.watch_pem("a.com", &cert_path, &key_path, interval).unwrap();
+
+        // no reload after watching is stopped.
+        assert!(resolver.unwatch("a.com"));
+        assert!(!resolver.unwatch("a.com"));
+        thread::sleep(interval * 2);
+
+        let (cert3, key3) = self_signed("a.com");
+        write(&cert_path, &cert3);
+        write(&key_path, &key3);
+        thread::sleep(interval * 10);
+        assert_eq!(lookup(&resolver, "a.com"), Some(der(&cert2)));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
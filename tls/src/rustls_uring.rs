@@ -10,7 +10,7 @@ use std::{io, net::Shutdown};
 
 use rustls::{ConnectionCommon, SideData};
 use xitca_io::{
-    bytes::{Buf, BytesMut},
+    bytes::{Buf, Bytes, BytesMut},
     io_uring::{AsyncBufRead, AsyncBufWrite, IoBuf, IoBufMut},
 };
 
@@ -241,6 +241,10 @@ where
             };
         }
     }
+
+    fn peer_certificate_chain(&self) -> Option<Vec<Bytes>> {
+        crate::rustls::peer_certificate_chain(&self.session.borrow().session)
+    }
 }
 
 impl<C, S, Io> AsyncBufWrite for TlsStream<C, Io>
@@ -0,0 +1,348 @@
+//! host name lookup and pem file watching shared by sni resolvers of different tls backends.
+
+use core::time::Duration;
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, Mutex, Weak},
+    thread,
+    time::{Instant, SystemTime},
+};
+
+use tracing::{error, info};
+
+pub(crate) struct Certs<K> {
+    pub(crate) names: HashMap<String, K>,
+    pub(crate) fallback: Option<K>,
+}
+
+impl<K> Default for Certs<K> {
+    fn default() -> Self {
+        Self {
+            names: HashMap::new(),
+            fallback: None,
+        }
+    }
+}
+
+impl<K: Clone> Certs<K> {
+    // exact host name first and then wildcard of it's parent domain.
+    pub(crate) fn lookup(&self, name: &str) -> Option<K> {
+        if let Some(key) = self.names.get(name) {
+            return Some(key.clone());
+        }
+
+        let (_, parent) = name.split_once('.')?;
+        self.names.get(&format!("*.{parent}")).cloned()
+    }
+
+    pub(crate) fn resolve(&self, name: Option<&str>) -> Option<K> {
+        name.and_then(|name| self.lookup(&normalize(name)))
+            .or_else(|| self.fallback.clone())
+    }
+}
+
+// host names are case insensitive and may end with a dot.
+pub(crate) fn normalize(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Background watcher of pem files keyed by host name. All watched host names of a resolver are checked from one
+/// thread which is started on demand and exits when no host name is watched or the resolver is dropped.
+/// A host name has at most one active entry and watching it again replaces the previous.
+#[derive(Default)]
+pub(crate) struct Watchers {
+    state: Mutex<State>,
+    notify: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    entries: HashMap<String, Entry>,
+    running: bool,
+}
+
+#[derive(Clone)]
+struct Entry {
+    id: u64,
+    cert: PathBuf,
+    key: PathBuf,
+    interval: Duration,
+    modified: (SystemTime, SystemTime),
+    deadline: Instant,
+}
+
+impl Watchers {
+    // insert entry for host name and return true when watcher thread must be spawned.
+    fn insert(&self, name: String, mut entry: Entry) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        entry.id = state.next_id;
+        state.entries.insert(name, entry);
+        self.notify.notify_one();
+        !core::mem::replace(&mut state.running, true)
+    }
+
+    pub(crate) fn stop(&self, name: &str) -> bool {
+        let removed = self.state.lock().unwrap().entries.remove(&normalize(name)).is_some();
+        self.notify.notify_one();
+        removed
+    }
+
+    // block until any entry is due and return copies of them. return None when nothing is watched and
+    // watcher thread must exit.
+    fn wait_due(&self) -> Option<Vec<(String, Entry)>> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let Some(deadline) = state.entries.values().map(|e| e.deadline).min() else {
+                state.running = false;
+                return None;
+            };
+
+            let now = Instant::now();
+            if deadline <= now {
+                let due = state
+                    .entries
+                    .iter()
+                    .filter(|(_, e)| e.deadline <= now)
+                    .map(|(name, e)| (name.clone(), e.clone()))
+                    .collect();
+                return Some(due);
+            }
+
+            state = self.notify.wait_timeout(state, deadline - now).unwrap().0;
+        }
+    }
+
+    // schedule next check of entry when it's not replaced or stopped in the mean time.
+    fn reschedule(&self, name: &str, id: u64, modified: (SystemTime, SystemTime)) {
+        if let Some(entry) = self.state.lock().unwrap().entries.get_mut(name) {
+            if entry.id == id {
+                entry.modified = modified;
+                entry.deadline = Instant::now() + entry.interval;
+            }
+        }
+    }
+}
+
+/// Shared state of a sni resolver that can be reloaded from pem files.
+pub(crate) trait Reload: Send + Sync + 'static {
+    fn watchers(&self) -> &Watchers;
+
+    /// load certificate chain and private key from files, check they match and insert them for host name.
+    fn load_pem(&self, name: &str, cert: &Path, key: &Path) -> io::Result<()>;
+}
+
+pub(crate) fn watch_pem<R>(inner: &Arc<R>, name: &str, cert: &Path, key: &Path, interval: Duration) -> io::Result<()>
+where
+    R: Reload,
+{
+    let name = normalize(name);
+
+    let modified = modified(cert, key)?;
+    inner.load_pem(&name, cert, key)?;
+
+    let entry = Entry {
+        id: 0,
+        cert: cert.to_path_buf(),
+        key: key.to_path_buf(),
+        interval,
+        modified,
+        deadline: Instant::now() + interval,
+    };
+
+    if inner.watchers().insert(name.clone(), entry) {
+        let weak = Arc::downgrade(inner);
+        if let Err(e) = thread::Builder::new()
+            .name(String::from("xitca-tls-watch"))
+            .spawn(move || run(weak))
+        {
+            let mut state = inner.watchers().state.lock().unwrap();
+            state.entries.remove(&name);
+            state.running = false;
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+fn run<R>(inner: Weak<R>)
+where
+    R: Reload,
+{
+    loop {
+        // strong reference is only held for one round so dropping all clones of resolver ends the thread.
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+
+        let Some(due) = inner.watchers().wait_due() else {
+            return;
+        };
+
+        for (name, entry) in due {
+            let modified = match self::modified(&entry.cert, &entry.key) {
+                Ok(m) if m == entry.modified => m,
+                // when only one of the files is replaced so far the pair does not match and loading fails.
+                // modified time is not updated so loading is retried in next interval.
+                Ok(m) => match inner.load_pem(&name, &entry.cert, &entry.key) {
+                    Ok(_) => {
+                        info!("Reloaded certificate for: {name}");
+                        m
+                    }
+                    Err(e) => {
+                        error!("Failed to reload certificate for: {name}: {e}");
+                        entry.modified
+                    }
+                },
+                Err(e) => {
+                    error!("Failed to check certificate files for: {name}: {e}");
+                    entry.modified
+                }
+            };
+
+            inner.watchers().reschedule(&name, entry.id, modified);
+        }
+    }
+}
+
+fn modified(cert: &Path, key: &Path) -> io::Result<(SystemTime, SystemTime)> {
+    Ok((fs::metadata(cert)?.modified()?, fs::metadata(key)?.modified()?))
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::{
+        fs::File,
+        path::PathBuf,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use openssl::{
+        asn1::Asn1Time,
+        bn::BigNum,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::PKey,
+        x509::{X509NameBuilder, X509},
+    };
+
+    use super::*;
+
+    // self signed certificate and private key in pem format.
+    pub(crate) fn self_signed(name: &str) -> (Vec<u8>, Vec<u8>) {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+        let mut subject = X509NameBuilder::new().unwrap();
+        subject.append_entry_by_nid(Nid::COMMONNAME, name).unwrap();
+        let subject = subject.build();
+
+        let mut cert = X509::builder().unwrap();
+        cert.set_version(2).unwrap();
+        cert.set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        cert.set_subject_name(&subject).unwrap();
+        cert.set_issuer_name(&subject).unwrap();
+        cert.set_pubkey(&key).unwrap();
+        cert.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        cert.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+        cert.sign(&key, MessageDigest::sha256()).unwrap();
+
+        (cert.build().to_pem().unwrap(), key.private_key_to_pem_pkcs8().unwrap())
+    }
+
+    // write pem to file and move it's modified time forward so change is observed regardless of file system
+    // time resolution.
+    pub(crate) fn write(path: &Path, pem: &[u8]) {
+        static TICK: AtomicUsize = AtomicUsize::new(1);
+
+        fs::write(path, pem).unwrap();
+        let time = SystemTime::now() + Duration::from_secs(TICK.fetch_add(1, Ordering::Relaxed) as _);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xitca-tls-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn lookup_priority() {
+        let mut certs = Certs::default();
+        certs.names.insert(normalize("*.example.com"), 1);
+        certs.names.insert(normalize("www.example.com"), 2);
+
+        assert_eq!(certs.lookup("www.example.com"), Some(2));
+        assert_eq!(certs.lookup("api.example.com"), Some(1));
+        assert_eq!(certs.lookup("example.com"), None);
+        assert_eq!(certs.lookup("a.api.example.com"), None);
+
+        assert_eq!(certs.resolve(Some("WWW.Example.COM.")), Some(2));
+        assert_eq!(certs.resolve(None), None);
+
+        certs.fallback = Some(3);
+        assert_eq!(certs.resolve(Some("example.com")), Some(3));
+        assert_eq!(certs.resolve(None), Some(3));
+    }
+
+    #[test]
+    fn watcher_replace() {
+        let entry = || Entry {
+            id: 0,
+            cert: PathBuf::new(),
+            key: PathBuf::new(),
+            interval: Duration::from_secs(60),
+            modified: (SystemTime::UNIX_EPOCH, SystemTime::UNIX_EPOCH),
+            deadline: Instant::now(),
+        };
+
+        let id = |watchers: &Watchers, name: &str| watchers.state.lock().unwrap().entries.get(name).map(|e| e.id);
+
+        let watchers = Watchers::default();
+
+        // all host names share one watcher thread.
+        assert!(watchers.insert("a.com".into(), entry()));
+        assert!(!watchers.insert("b.com".into(), entry()));
+
+        let first = id(&watchers, "a.com").unwrap();
+        assert!(!watchers.insert("a.com".into(), entry()));
+        let second = id(&watchers, "a.com").unwrap();
+        assert_ne!(first, second);
+
+        // result of replaced entry is ignored.
+        watchers.reschedule("a.com", first, (SystemTime::now(), SystemTime::now()));
+        assert_eq!(
+            watchers.state.lock().unwrap().entries["a.com"].modified,
+            (SystemTime::UNIX_EPOCH, SystemTime::UNIX_EPOCH)
+        );
+
+        assert_eq!(watchers.wait_due().unwrap().len(), 2);
+
+        assert!(watchers.stop("A.com."));
+        assert!(!watchers.stop("a.com"));
+        assert!(watchers.stop("b.com"));
+
+        // watcher thread exits when nothing is watched and next watch spawns a new one.
+        assert!(watchers.wait_due().is_none());
+        assert!(watchers.insert("a.com".into(), entry()));
+    }
+
+    #[test]
+    fn normalize_name() {
+        assert_eq!(normalize("Example.COM"), "example.com");
+        assert_eq!(normalize("example.com."), "example.com");
+        assert_eq!(normalize("*.Example.com."), "*.example.com");
+    }
+}
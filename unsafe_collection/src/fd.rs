@@ -0,0 +1,105 @@
+//! take ownership of socket file descriptor inherited from parent process.
+
+use std::{
+    collections::HashSet,
+    io, mem,
+    os::fd::{FromRawFd, OwnedFd, RawFd},
+    sync::Mutex,
+};
+
+/// take ownership of socket file descriptor passed from parent process. (systemd socket activation,
+/// process upgrading etc)
+///
+/// Given fd is validated before ownership is taken. It must not be one of standard io (0, 1 and 2), it
+/// must be open in current process and it must refer to a socket. [io::ErrorKind::InvalidInput] error
+/// is returned when validation fails and the fd is left untouched.
+///
+/// The same fd can only be taken once for the lifetime of process. Later calls with it return error
+/// even when the taken [OwnedFd] is already closed.
+///
+/// # Safety
+///
+/// Caller must guarantee fd is not owned by anything else in current process. Validation can only tell
+/// fd is an open socket and it can not tell if the fd is already owned by types like
+/// [TcpListener](std::net::TcpListener). Taking an owned fd results in it being closed twice.
+pub unsafe fn take_socket(fd: RawFd) -> io::Result<OwnedFd> {
+    static TAKEN: Mutex<Option<HashSet<RawFd>>> = Mutex::new(None);
+
+    if fd < 3 {
+        return Err(invalid(fd, "is standard io or negative"));
+    }
+
+    let mut taken = TAKEN.lock().unwrap();
+
+    if taken.as_ref().is_some_and(|taken| taken.contains(&fd)) {
+        return Err(invalid(fd, "is already taken"));
+    }
+
+    // SAFETY: fcntl with F_GETFD only reads fd flags and fails with EBADF on closed fd.
+    if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(invalid(fd, "is not open"));
+    }
+
+    let mut ty: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>() as libc::socklen_t;
+
+    // SAFETY: ty and len are valid pointers to stack allocated values with matching size.
+    let res = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_TYPE,
+            &mut ty as *mut libc::c_int as *mut libc::c_void,
+            &mut len,
+        )
+    };
+
+    if res == -1 {
+        return Err(invalid(fd, "is not a socket"));
+    }
+
+    taken.get_or_insert_with(HashSet::new).insert(fd);
+
+    // SAFETY: fd is validated as an open socket that is not standard io and it's only handed out once.
+    // caller guarantees it's not owned by anything else.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+fn invalid(fd: RawFd, reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("file descriptor {fd} {reason}"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs::File,
+        os::{fd::IntoRawFd, unix::net::UnixStream},
+    };
+
+    use super::*;
+
+    #[test]
+    fn validate() {
+        unsafe {
+            assert!(take_socket(-1).is_err());
+            assert!(take_socket(1).is_err());
+
+            // regular file is not a socket.
+            let file = File::open(std::env::current_exe().unwrap()).unwrap();
+            let fd = file.into_raw_fd();
+            assert!(take_socket(fd).is_err());
+            // fd is not taken on error.
+            drop(File::from_raw_fd(fd));
+
+            // fd not opened.
+            assert!(take_socket(RawFd::MAX).is_err());
+
+            // ownership of fd is released from stream before taking it.
+            let (a, _b) = UnixStream::pair().unwrap();
+            let fd = a.into_raw_fd();
+            let owned = take_socket(fd).unwrap();
+            assert!(take_socket(fd).is_err());
+            drop(owned);
+        }
+    }
+}
@@ -12,3 +12,9 @@ pub mod uninit;
 
 #[cfg(feature = "bytes")]
 pub mod bytes;
+#[cfg(all(feature = "fd", unix))]
+pub mod fd;
+#[cfg(all(feature = "sendfile", target_os = "linux"))]
+pub mod sendfile;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+pub mod uring;
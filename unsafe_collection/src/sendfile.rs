@@ -0,0 +1,104 @@
+//! zero copy file transfer with linux `sendfile(2)` syscall.
+
+use std::{
+    fs::File,
+    io,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd},
+};
+
+/// copy up to `count` bytes from given file starting at `offset` to `out` file descriptor without passing
+/// through user space. `offset` is advanced by the number of bytes sent and file's own cursor is not
+/// touched.
+///
+/// Return the number of bytes sent. `Ok(0)` is returned when offset has reached file's end.
+///
+/// When `out` is in non-blocking mode [io::ErrorKind::WouldBlock] error is returned like
+/// [std::io::Write::write] does.
+pub fn sendfile(out: BorrowedFd<'_>, file: &File, offset: &mut u64, count: usize) -> io::Result<usize> {
+    let mut off = libc::off_t::try_from(*offset).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+
+    // linux transfers at most 0x7ffff000 bytes per call.
+    let count = count.min(0x7fff_f000);
+
+    // SAFETY:
+    // both file descriptors are borrowed and valid for the duration of call. off is a valid pointer to
+    // stack allocated off_t.
+    let n = unsafe { libc::sendfile(out.as_raw_fd(), file.as_fd().as_raw_fd(), &mut off, count) };
+
+    if n < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    *offset = off as u64;
+
+    Ok(n as usize)
+}
+
+/// duplicate file descriptor of io types that only expose [AsRawFd]. The duplicate has close-on-exec flag set.
+///
+/// Useful for sending from a handle that can be registered to other reactor or moved to other thread.
+pub fn dup(fd: &impl AsRawFd) -> io::Result<OwnedFd> {
+    // SAFETY: fcntl with F_DUPFD_CLOEXEC fails with EBADF on invalid fd and only reads it's flags otherwise.
+    let fd = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0) };
+
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: fd is a newly allocated file descriptor that is not owned by anything else.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        os::unix::net::UnixStream,
+    };
+
+    use super::*;
+
+    #[test]
+    fn sendfile_range() {
+        let path = std::env::temp_dir().join(format!("xitca-sendfile-{}", std::process::id()));
+        File::create(&path).unwrap().write_all(b"hello, world!").unwrap();
+        let file = File::open(&path).unwrap();
+
+        let (tx, mut rx) = UnixStream::pair().unwrap();
+
+        let mut offset = 2;
+        let mut remaining = 10;
+
+        while remaining > 0 {
+            let n = sendfile(tx.as_fd(), &file, &mut offset, remaining).unwrap();
+            assert_ne!(n, 0);
+            remaining -= n;
+        }
+
+        assert_eq!(offset, 12);
+        assert_eq!(sendfile(tx.as_fd(), &file, &mut 13, 4).unwrap(), 0);
+
+        drop(tx);
+        let mut buf = String::new();
+        rx.read_to_string(&mut buf).unwrap();
+        assert_eq!(buf, "llo, world");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn dup_fd() {
+        let (tx, mut rx) = UnixStream::pair().unwrap();
+
+        let mut dup = UnixStream::from(dup(&tx).unwrap());
+        assert_ne!(dup.as_raw_fd(), tx.as_raw_fd());
+
+        drop(tx);
+        dup.write_all(b"hello").unwrap();
+        drop(dup);
+
+        let mut buf = String::new();
+        rx.read_to_string(&mut buf).unwrap();
+        assert_eq!(buf, "hello");
+    }
+}
@@ -0,0 +1,1045 @@
+//! io-uring instance for operations not exposed by tokio-uring. linux only.
+//!
+//! [Ring] is runtime agnostic. Operations are submitted through it's methods and identified by returned
+//! [Key]. Owner of ring is responsible for calling [Ring::complete] when ring's file descriptor is readable
+//! and polling operations with their keys afterwards.
+//!
+//! Resources referenced by in flight operations (buffers and registered memory) are owned by ring until
+//! kernel is done with them. Dropping ring cancels all operations and blocks until their completions are
+//! received.
+
+use core::{
+    alloc::Layout,
+    cell::{Cell, RefCell},
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+    sync::atomic::{AtomicU16, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+use std::{
+    alloc,
+    any::Any,
+    collections::VecDeque,
+    io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    rc::Rc,
+};
+
+use io_uring::{
+    cqueue, opcode, squeue,
+    types::{BufRingEntry, Fd},
+    IoUring,
+};
+use tokio_uring::buf::{IoBuf, IoBufMut};
+
+// user data of cancel requests. their completions are ignored.
+const CANCEL: u64 = u64::MAX;
+
+// IORING_CQE_F_NOTIF. zero copy send notification of buffer release.
+const NOTIF: u32 = 1 << 3;
+
+/// Key of operation submitted to [Ring].
+///
+/// Key is invalid after it's operation returns final result from polling or is passed to [Ring::release].
+/// Using an invalid key panics.
+#[derive(Debug)]
+pub struct Key(usize);
+
+/// io-uring instance with bookkeeping of in flight operations.
+pub struct Ring {
+    // ring is dropped before memory referenced by it's registrations.
+    ring: IoUring,
+    slots: Slots,
+    fixed: Option<Rc<FixedInner>>,
+    provided: Option<Rc<BufRingInner>>,
+}
+
+struct Slots {
+    entries: Vec<Option<Slot>>,
+    vacant: Vec<usize>,
+    // number of slots in use. including operations whose owners are gone.
+    len: usize,
+}
+
+struct Slot {
+    state: State,
+    // final completion of operation is received.
+    done: bool,
+    // owner of operation is gone and slot is released after final completion.
+    orphan: bool,
+    waker: Option<Waker>,
+    // resource referenced by in flight operation. kept until final completion.
+    hold: Option<Box<dyn Any>>,
+}
+
+enum State {
+    // read or write with registered buffer.
+    Fixed { res: i32, read: bool },
+    // single shot receive with buffer selected from provided buffer ring.
+    Provided(i32, u32),
+    // zero copy send. result arrives first and buffer is released by kernel with a following notification.
+    SendZc(i32),
+    // multishot accept. accepted sockets not taken yet and terminal result of operation.
+    Accept(VecDeque<OwnedFd>, Option<i32>),
+}
+
+impl Slots {
+    fn insert(&mut self, state: State, hold: Option<Box<dyn Any>>) -> usize {
+        let slot = Slot {
+            state,
+            done: false,
+            orphan: false,
+            waker: None,
+            hold,
+        };
+
+        self.len += 1;
+
+        match self.vacant.pop() {
+            Some(idx) => {
+                self.entries[idx] = Some(slot);
+                idx
+            }
+            None => {
+                self.entries.push(Some(slot));
+                self.entries.len() - 1
+            }
+        }
+    }
+
+    fn remove(&mut self, idx: usize) -> Slot {
+        let slot = self.entries[idx].take().expect("io-uring operation key is invalid");
+        self.vacant.push(idx);
+        self.len -= 1;
+        slot
+    }
+
+    fn get(&mut self, idx: usize) -> &mut Slot {
+        self.entries
+            .get_mut(idx)
+            .and_then(Option::as_mut)
+            .expect("io-uring operation key is invalid")
+    }
+
+    fn complete(&mut self, idx: usize, res: i32, flags: u32, provided: Option<&BufRingInner>) {
+        let slot = self.get(idx);
+
+        slot.done = match slot.state {
+            State::Fixed { res: ref mut r, .. } => {
+                *r = res;
+                true
+            }
+            State::Provided(ref mut r, ref mut f) => {
+                *r = res;
+                *f = flags;
+                true
+            }
+            // notification has no meaningful result.
+            State::SendZc(_) if flags & NOTIF != 0 => true,
+            State::SendZc(ref mut r) => {
+                *r = res;
+                !cqueue::more(flags)
+            }
+            State::Accept(ref mut queue, ref mut end) => {
+                if res >= 0 {
+                    // SAFETY: accepted socket is a new file descriptor not owned by anything else.
+                    queue.push_back(unsafe { OwnedFd::from_raw_fd(res) });
+                }
+                let more = cqueue::more(flags);
+                if !more {
+                    *end = Some(res.min(0));
+                }
+                !more
+            }
+        };
+
+        if slot.done && slot.orphan {
+            let slot = self.remove(idx);
+            recycle(slot, provided);
+        } else if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+
+    // take slot when operation is done or register waker.
+    fn poll_done(&mut self, idx: usize, cx: &mut Context<'_>) -> Poll<Slot> {
+        let slot = self.get(idx);
+        if slot.done {
+            return Poll::Ready(self.remove(idx));
+        }
+        match slot.waker {
+            Some(ref waker) if waker.will_wake(cx.waker()) => {}
+            _ => slot.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+// buffer selected for receive nobody is waiting for goes back to buffer ring.
+fn recycle(slot: Slot, provided: Option<&BufRingInner>) {
+    if let (State::Provided(res, flags), Some(provided)) = (slot.state, provided) {
+        if let Some(bid) = cqueue::buffer_select(flags) {
+            debug_assert!(res >= 0);
+            provided.push(bid);
+        }
+    }
+}
+
+fn result(res: i32) -> io::Result<usize> {
+    if res < 0 {
+        Err(io::Error::from_raw_os_error(-res))
+    } else {
+        Ok(res as usize)
+    }
+}
+
+fn take_hold<B: 'static>(hold: Option<Box<dyn Any>>) -> B {
+    *hold
+        .expect("io-uring operation does not hold resource")
+        .downcast()
+        .expect("io-uring operation key is used for wrong operation")
+}
+
+impl Ring {
+    /// Construct a ring with given number of submission queue entries.
+    pub fn new(entries: u32) -> io::Result<Self> {
+        Ok(Self {
+            ring: IoUring::new(entries)?,
+            slots: Slots {
+                entries: Vec::new(),
+                vacant: Vec::new(),
+                len: 0,
+            },
+            fixed: None,
+            provided: None,
+        })
+    }
+
+    /// Number of operations not completed yet. Including operations already released.
+    pub fn in_flight(&self) -> usize {
+        self.slots.len
+    }
+
+    /// Process completions received so far and wake operations completed by them.
+    pub fn complete(&mut self) {
+        let Self {
+            ring, slots, provided, ..
+        } = self;
+
+        for cqe in ring.completion() {
+            if cqe.user_data() != CANCEL {
+                slots.complete(cqe.user_data() as usize, cqe.result(), cqe.flags(), provided.as_deref());
+            }
+        }
+    }
+
+    /// Wake one operation that is waiting for completion.
+    ///
+    /// Runtime integration usually registers readiness interest of ring's file descriptor for the last polled
+    /// operation only. When that operation is completed another waiting one must be woken to take over.
+    pub fn wake_one(&mut self) {
+        if let Some(waker) = self
+            .slots
+            .entries
+            .iter_mut()
+            .flatten()
+            .find_map(|slot| slot.waker.take())
+        {
+            waker.wake();
+        }
+    }
+
+    /// Release operation whose owner is no longer interested in it's result. Receiving and accepting
+    /// operations are cancelled. Resources of operation are freed after it's final completion.
+    pub fn release(&mut self, key: Key) {
+        let slot = self.slots.get(key.0);
+
+        if slot.done {
+            let slot = self.slots.remove(key.0);
+            recycle(slot, self.provided.as_deref());
+            return;
+        }
+
+        slot.orphan = true;
+        slot.waker = None;
+
+        if matches!(
+            slot.state,
+            State::Accept(..) | State::Provided(..) | State::Fixed { read: true, .. }
+        ) {
+            self.cancel(&key);
+        }
+    }
+
+    /// Cancel in flight operation. It's final completion is still delivered through polling.
+    pub fn cancel(&mut self, key: &Key) {
+        let entry = opcode::AsyncCancel::new(key.0 as u64).build().user_data(CANCEL);
+        // cancel is best effort. operation completes normally when it can not be submitted.
+        // SAFETY: cancel request does not reference any memory.
+        let _ = unsafe { self.push(&entry) };
+    }
+
+    // push entry to submission queue and submit it. error is returned only when entry is not queued.
+    //
+    // SAFETY: caller must make sure resources referenced by entry outlive it's final completion.
+    unsafe fn push(&mut self, entry: &squeue::Entry) -> io::Result<()> {
+        if self.ring.submission().push(entry).is_err() {
+            // submission queue is full. submit queued entries to make room.
+            self.submit()?;
+            self.ring
+                .submission()
+                .push(entry)
+                .map_err(|_| io::Error::new(io::ErrorKind::WouldBlock, "io-uring submission queue is full"))?;
+        }
+
+        // entry is queued and failed submission is retried by following ones.
+        let _ = self.submit();
+
+        Ok(())
+    }
+
+    fn submit(&mut self) -> io::Result<()> {
+        loop {
+            match self.ring.submit() {
+                Ok(_) => return Ok(()),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // allocate slot and submit entry with it's key. resource is given back when entry can not be queued.
+    fn submit_entry(
+        &mut self,
+        entry: squeue::Entry,
+        state: State,
+        hold: Option<Box<dyn Any>>,
+    ) -> Result<Key, (io::Error, Option<Box<dyn Any>>)> {
+        let idx = self.slots.insert(state, hold);
+        let entry = entry.user_data(idx as u64);
+        // SAFETY: resources referenced by entry are owned by slot until it's final completion. slot is only
+        // removed here when entry never reached kernel.
+        match unsafe { self.push(&entry) } {
+            Ok(_) => Ok(Key(idx)),
+            Err(e) => Err((e, self.slots.remove(idx).hold)),
+        }
+    }
+
+    /// Send initialized bytes of buffer to socket without copying them into kernel (`IORING_OP_SEND_ZC`).
+    ///
+    /// Buffer is given back when operation can not be submitted.
+    pub fn send_zc<B: IoBuf>(&mut self, fd: RawFd, buf: B) -> Result<Key, (io::Error, B)> {
+        let len = u32::try_from(buf.bytes_init()).unwrap_or(u32::MAX);
+        let entry = opcode::SendZc::new(Fd(fd), buf.stable_ptr(), len).build();
+        self.submit_entry(entry, State::SendZc(0), Some(Box::new(buf)))
+            .map_err(|(e, hold)| (e, take_hold(hold)))
+    }
+
+    /// Poll result of [Ring::send_zc]. Buffer is given back after kernel releases it.
+    pub fn poll_send_zc<B: IoBuf>(&mut self, key: &Key, cx: &mut Context<'_>) -> Poll<(io::Result<usize>, B)> {
+        self.slots.poll_done(key.0, cx).map(|slot| match slot.state {
+            State::SendZc(res) => (result(res), take_hold(slot.hold)),
+            _ => unreachable!("io-uring operation key is used for wrong operation"),
+        })
+    }
+
+    /// Register memory of buffer pool to ring so it's buffers can be used with [Ring::read_fixed] and
+    /// [Ring::write_fixed]. A ring can only have one registered pool.
+    pub fn register_buffers(&mut self, pool: &FixedBufPool) -> io::Result<()> {
+        if self.fixed.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "io-uring already has registered buffers",
+            ));
+        }
+
+        let iovecs = (0..pool.0.count)
+            .map(|idx| libc::iovec {
+                iov_base: pool.0.ptr(idx).cast(),
+                iov_len: pool.0.size,
+            })
+            .collect::<Vec<_>>();
+
+        // registered memory is owned by pool which is kept alive by ring for as long as it's registered.
+        self.ring.submitter().register_buffers(&iovecs)?;
+
+        self.fixed = Some(pool.0.clone());
+
+        Ok(())
+    }
+
+    /// Check if buffer belongs to pool registered to ring.
+    pub fn is_registered(&self, buf: &FixedBuf) -> bool {
+        self.fixed.as_ref().is_some_and(|fixed| Rc::ptr_eq(fixed, &buf.pool))
+    }
+
+    /// Read from file at offset into spare capacity of registered buffer (`IORING_OP_READ_FIXED`). Sockets
+    /// and other non seekable files ignore offset.
+    ///
+    /// Buffer is given back when operation can not be submitted or buffer is not registered to ring.
+    pub fn read_fixed(&mut self, fd: RawFd, buf: FixedBuf, offset: u64) -> Result<Key, (io::Error, FixedBuf)> {
+        if !self.is_registered(&buf) {
+            return Err((io::ErrorKind::Unsupported.into(), buf));
+        }
+
+        let spare = buf.pool.size - buf.len;
+        // SAFETY: pointer is within buffer's region of registered memory.
+        let ptr = unsafe { buf.pool.ptr(buf.idx).add(buf.len) };
+        let entry = opcode::ReadFixed::new(Fd(fd), ptr, spare as u32, buf.idx)
+            .offset(offset as _)
+            .build();
+        self.submit_entry(entry, State::Fixed { res: 0, read: true }, Some(Box::new(buf)))
+            .map_err(|(e, hold)| (e, take_hold(hold)))
+    }
+
+    /// Write initialized bytes of registered buffer to file at offset (`IORING_OP_WRITE_FIXED`). Sockets
+    /// and other non seekable files ignore offset.
+    ///
+    /// Buffer is given back when operation can not be submitted or buffer is not registered to ring.
+    pub fn write_fixed(&mut self, fd: RawFd, buf: FixedBuf, offset: u64) -> Result<Key, (io::Error, FixedBuf)> {
+        if !self.is_registered(&buf) {
+            return Err((io::ErrorKind::Unsupported.into(), buf));
+        }
+
+        let entry = opcode::WriteFixed::new(Fd(fd), buf.pool.ptr(buf.idx), buf.len as u32, buf.idx)
+            .offset(offset as _)
+            .build();
+        self.submit_entry(entry, State::Fixed { res: 0, read: false }, Some(Box::new(buf)))
+            .map_err(|(e, hold)| (e, take_hold(hold)))
+    }
+
+    /// Poll result of [Ring::read_fixed] and [Ring::write_fixed]. Length of buffer is advanced by bytes read.
+    pub fn poll_fixed(&mut self, key: &Key, cx: &mut Context<'_>) -> Poll<(io::Result<usize>, FixedBuf)> {
+        self.slots.poll_done(key.0, cx).map(|slot| match slot.state {
+            State::Fixed { res, read } => {
+                let mut buf = take_hold::<FixedBuf>(slot.hold);
+                if read && res > 0 {
+                    buf.len += res as usize;
+                }
+                (result(res), buf)
+            }
+            _ => unreachable!("io-uring operation key is used for wrong operation"),
+        })
+    }
+
+    /// Register a ring of provided buffers with given number of entries (power of two) and size of each buffer.
+    /// Kernel selects buffer from it for [Ring::recv_provided] when data arrives. A ring can only have one
+    /// registered buffer ring.
+    pub fn register_buf_ring(&mut self, entries: u16, size: usize) -> io::Result<()> {
+        if self.provided.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "io-uring already has registered buffer ring",
+            ));
+        }
+
+        let inner = BufRingInner::new(entries, size)?;
+
+        // SAFETY: ring memory is page aligned and kept alive by ring for as long as it's registered.
+        self.ring
+            .submitter()
+            .register_buf_ring(inner.ring.as_ptr() as u64, entries, BGID)?;
+
+        for bid in 0..entries {
+            inner.push(bid);
+        }
+
+        self.provided = Some(Rc::new(inner));
+
+        Ok(())
+    }
+
+    /// Receive from socket into buffer selected by kernel from registered buffer ring.
+    ///
+    /// [io::ErrorKind::Unsupported] error is returned when ring has no registered buffer ring.
+    pub fn recv_provided(&mut self, fd: RawFd) -> io::Result<Key> {
+        let Some(ref provided) = self.provided else {
+            return Err(io::ErrorKind::Unsupported.into());
+        };
+
+        let entry = opcode::Recv::new(Fd(fd), core::ptr::null_mut(), provided.size as u32)
+            .buf_group(BGID)
+            .build()
+            .flags(squeue::Flags::BUFFER_SELECT);
+
+        self.submit_entry(entry, State::Provided(0, 0), None)
+            .map_err(|(e, _)| e)
+    }
+
+    /// Poll result of [Ring::recv_provided]. Empty buffer is returned when socket is closed by peer.
+    ///
+    /// `ENOBUFS` os error is returned when all buffers of buffer ring are in use.
+    pub fn poll_recv_provided(&mut self, key: &Key, cx: &mut Context<'_>) -> Poll<io::Result<ProvidedBuf>> {
+        self.slots.poll_done(key.0, cx).map(|slot| match slot.state {
+            State::Provided(res, flags) => {
+                let ring = self.provided.clone().expect("io-uring buffer ring is not registered");
+                let len = result(res)?;
+                Ok(ProvidedBuf {
+                    buf: cqueue::buffer_select(flags).map(|bid| (ring, bid)),
+                    len,
+                })
+            }
+            _ => unreachable!("io-uring operation key is used for wrong operation"),
+        })
+    }
+
+    /// Accept connections from listening socket continuously (multishot `IORING_OP_ACCEPT`). Accepted sockets
+    /// are non-blocking and have close-on-exec flag set.
+    pub fn accept_multi(&mut self, fd: RawFd) -> io::Result<Key> {
+        let entry = opcode::AcceptMulti::new(Fd(fd))
+            .flags(libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK)
+            .build();
+        self.submit_entry(entry, State::Accept(VecDeque::new(), None), None)
+            .map_err(|(e, _)| e)
+    }
+
+    /// Number of accepted sockets of [Ring::accept_multi] not taken yet.
+    pub fn accept_queued(&mut self, key: &Key) -> usize {
+        match self.slots.get(key.0).state {
+            State::Accept(ref queue, _) => queue.len(),
+            _ => unreachable!("io-uring operation key is used for wrong operation"),
+        }
+    }
+
+    /// Poll next accepted socket of [Ring::accept_multi]. None is returned when operation is ended by
+    /// cancellation or kernel and it should be submitted again.
+    ///
+    /// Key is invalid after None or error is returned.
+    pub fn poll_accept(&mut self, key: &Key, cx: &mut Context<'_>) -> Poll<Option<io::Result<OwnedFd>>> {
+        let slot = self.slots.get(key.0);
+        let State::Accept(ref mut queue, end) = slot.state else {
+            unreachable!("io-uring operation key is used for wrong operation")
+        };
+
+        if let Some(fd) = queue.pop_front() {
+            return Poll::Ready(Some(Ok(fd)));
+        }
+
+        match end {
+            Some(end) => {
+                self.slots.remove(key.0);
+                Poll::Ready(match end {
+                    0 => None,
+                    end if -end == libc::ECANCELED => None,
+                    end => Some(Err(io::Error::from_raw_os_error(-end))),
+                })
+            }
+            None => {
+                match slot.waker {
+                    Some(ref waker) if waker.will_wake(cx.waker()) => {}
+                    _ => slot.waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl AsRawFd for Ring {
+    fn as_raw_fd(&self) -> RawFd {
+        self.ring.as_raw_fd()
+    }
+}
+
+impl Drop for Ring {
+    fn drop(&mut self) {
+        // kernel may access resources of in flight operations until their final completions. cancel them and
+        // wait for completions before resources are freed.
+        for idx in 0..self.slots.entries.len() {
+            if self.slots.entries[idx].as_ref().is_some_and(|slot| !slot.done) {
+                self.cancel(&Key(idx));
+            }
+        }
+
+        while self.slots.entries.iter().flatten().any(|slot| !slot.done) {
+            match self.ring.submit_and_wait(1) {
+                Ok(_) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                // can not wait for completions. leak resources instead of freeing memory kernel may still use.
+                Err(_) => {
+                    core::mem::forget(core::mem::take(&mut self.slots.entries));
+                    core::mem::forget(self.fixed.take());
+                    core::mem::forget(self.provided.take());
+                    return;
+                }
+            }
+
+            for slot in self.slots.entries.iter_mut().flatten() {
+                slot.orphan = true;
+            }
+
+            self.complete();
+        }
+    }
+}
+
+/// Pool of equal sized buffers that can be registered to [Ring] with [Ring::register_buffers].
+///
+/// Buffers are usable with any io operation when pool is not registered. Pool is cheap to clone and all
+/// clones share the same buffers.
+#[derive(Clone)]
+pub struct FixedBufPool(Rc<FixedInner>);
+
+struct FixedInner {
+    base: NonNull<u8>,
+    count: u16,
+    size: usize,
+    free: RefCell<Vec<u16>>,
+}
+
+impl FixedInner {
+    fn layout(count: u16, size: usize) -> Layout {
+        Layout::from_size_align(count as usize * size, 4096).expect("buffer pool is too large")
+    }
+
+    fn ptr(&self, idx: u16) -> *mut u8 {
+        // SAFETY: idx is always less than count and pointer stays within allocation.
+        unsafe { self.base.as_ptr().add(idx as usize * self.size) }
+    }
+}
+
+impl Drop for FixedInner {
+    fn drop(&mut self) {
+        // SAFETY: memory is allocated in FixedBufPool::new with the same layout.
+        unsafe { alloc::dealloc(self.base.as_ptr(), Self::layout(self.count, self.size)) }
+    }
+}
+
+impl FixedBufPool {
+    /// Construct pool of given number of buffers and size of each buffer.
+    ///
+    /// # Panics
+    /// When count or size is zero or total size overflows.
+    pub fn new(count: u16, size: usize) -> Self {
+        assert!(count > 0 && size > 0, "buffer pool can not be empty");
+        let layout = FixedInner::layout(count, size);
+        // SAFETY: layout has non zero size.
+        let base = unsafe { alloc::alloc_zeroed(layout) };
+        let base = NonNull::new(base).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+
+        Self(Rc::new(FixedInner {
+            base,
+            count,
+            size,
+            free: RefCell::new((0..count).rev().collect()),
+        }))
+    }
+
+    /// Take a buffer from pool. None is returned when all buffers are in use.
+    pub fn get(&self) -> Option<FixedBuf> {
+        let idx = self.0.free.borrow_mut().pop()?;
+        Some(FixedBuf {
+            pool: self.0.clone(),
+            idx,
+            len: 0,
+        })
+    }
+
+    /// Size of each buffer.
+    pub fn buf_size(&self) -> usize {
+        self.0.size
+    }
+}
+
+/// Buffer taken from [FixedBufPool]. Buffer goes back to pool on drop.
+pub struct FixedBuf {
+    pool: Rc<FixedInner>,
+    idx: u16,
+    len: usize,
+}
+
+impl FixedBuf {
+    /// Total size of buffer.
+    pub fn capacity(&self) -> usize {
+        self.pool.size
+    }
+
+    /// Copy as many bytes of given slice as spare capacity of buffer can hold. Return number of bytes copied.
+    pub fn put_slice(&mut self, src: &[u8]) -> usize {
+        let n = src.len().min(self.pool.size - self.len);
+        // SAFETY: destination is spare capacity of buffer and it does not overlap with src borrowed from elsewhere.
+        unsafe {
+            core::ptr::copy_nonoverlapping(src.as_ptr(), self.pool.ptr(self.idx).add(self.len), n);
+        }
+        self.len += n;
+        n
+    }
+
+    /// Shorten buffer to given length. Nothing happens when it's already shorter.
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    /// Clear buffer.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Deref for FixedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: buffer is exclusively owned by self and bytes up to len are initialized.
+        unsafe { core::slice::from_raw_parts(self.pool.ptr(self.idx), self.len) }
+    }
+}
+
+impl DerefMut for FixedBuf {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: buffer is exclusively owned by self and bytes up to len are initialized.
+        unsafe { core::slice::from_raw_parts_mut(self.pool.ptr(self.idx), self.len) }
+    }
+}
+
+impl Drop for FixedBuf {
+    fn drop(&mut self) {
+        self.pool.free.borrow_mut().push(self.idx);
+    }
+}
+
+// SAFETY: buffer memory is owned by pool and it's address does not change when FixedBuf is moved.
+unsafe impl IoBuf for FixedBuf {
+    fn stable_ptr(&self) -> *const u8 {
+        self.pool.ptr(self.idx)
+    }
+
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+
+    fn bytes_total(&self) -> usize {
+        self.pool.size
+    }
+}
+
+// SAFETY: same as IoBuf. pool memory is zero initialized so set_init never exposes uninitialized bytes.
+unsafe impl IoBufMut for FixedBuf {
+    fn stable_mut_ptr(&mut self) -> *mut u8 {
+        self.pool.ptr(self.idx)
+    }
+
+    unsafe fn set_init(&mut self, pos: usize) {
+        if self.len < pos {
+            self.len = pos;
+        }
+    }
+}
+
+// buffer group id of registered buffer ring. a ring only has one.
+const BGID: u16 = 0;
+
+struct BufRingInner {
+    // ring entries shared with kernel.
+    ring: NonNull<BufRingEntry>,
+    entries: u16,
+    bufs: NonNull<u8>,
+    size: usize,
+    tail: Cell<u16>,
+}
+
+impl BufRingInner {
+    fn layouts(entries: u16, size: usize) -> io::Result<(Layout, Layout)> {
+        if !entries.is_power_of_two() || entries > 1 << 15 || size == 0 || size > u32::MAX as usize {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+        let ring = Layout::from_size_align(entries as usize * core::mem::size_of::<BufRingEntry>(), 4096);
+        let bufs = Layout::from_size_align(entries as usize * size, 64);
+        match (ring, bufs) {
+            (Ok(ring), Ok(bufs)) => Ok((ring, bufs)),
+            _ => Err(io::ErrorKind::InvalidInput.into()),
+        }
+    }
+
+    fn new(entries: u16, size: usize) -> io::Result<Self> {
+        let (ring_layout, bufs_layout) = Self::layouts(entries, size)?;
+
+        // SAFETY: layouts have non zero size.
+        let (ring, bufs) = unsafe { (alloc::alloc_zeroed(ring_layout), alloc::alloc_zeroed(bufs_layout)) };
+        let ring = NonNull::new(ring.cast()).unwrap_or_else(|| alloc::handle_alloc_error(ring_layout));
+        let bufs = NonNull::new(bufs).unwrap_or_else(|| alloc::handle_alloc_error(bufs_layout));
+
+        Ok(Self {
+            ring,
+            entries,
+            bufs,
+            size,
+            tail: Cell::new(0),
+        })
+    }
+
+    fn buf(&self, bid: u16) -> *mut u8 {
+        // SAFETY: bid is always less than entries and pointer stays within allocation.
+        unsafe { self.bufs.as_ptr().add(bid as usize * self.size) }
+    }
+
+    // give buffer to kernel by adding it at the tail of ring.
+    fn push(&self, bid: u16) {
+        let tail = self.tail.get();
+        let mask = self.entries - 1;
+
+        // SAFETY: entry at tail is not visible to kernel until tail is advanced. tail field lives in the first
+        // entry and is not touched by setters of entry.
+        unsafe {
+            let entry = &mut *self.ring.as_ptr().add((tail & mask) as usize);
+            entry.set_addr(self.buf(bid) as u64);
+            entry.set_len(self.size as u32);
+            entry.set_bid(bid);
+
+            let tail_ptr = BufRingEntry::tail(self.ring.as_ptr()) as *const AtomicU16;
+            (*tail_ptr).store(tail.wrapping_add(1), Ordering::Release);
+        }
+
+        self.tail.set(tail.wrapping_add(1));
+    }
+}
+
+impl Drop for BufRingInner {
+    fn drop(&mut self) {
+        let (ring, bufs) = Self::layouts(self.entries, self.size).unwrap();
+        // SAFETY: memory is allocated in BufRingInner::new with the same layouts.
+        unsafe {
+            alloc::dealloc(self.ring.as_ptr().cast(), ring);
+            alloc::dealloc(self.bufs.as_ptr(), bufs);
+        }
+    }
+}
+
+/// Bytes received into buffer selected by kernel from buffer ring. Buffer goes back to ring on drop.
+pub struct ProvidedBuf {
+    buf: Option<(Rc<BufRingInner>, u16)>,
+    len: usize,
+}
+
+impl Deref for ProvidedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self.buf {
+            // SAFETY: buffer is taken out of ring by kernel and not visible to it until pushed back on drop.
+            // bytes up to len are written by kernel.
+            Some((ref ring, bid)) => unsafe { core::slice::from_raw_parts(ring.buf(bid), self.len) },
+            None => &[],
+        }
+    }
+}
+
+impl Drop for ProvidedBuf {
+    fn drop(&mut self) {
+        if let Some((ref ring, bid)) = self.buf {
+            ring.push(bid);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+
+    // drive ring until operation is ready.
+    fn wait<T>(ring: &mut Ring, mut poll: impl FnMut(&mut Ring, &mut Context<'_>) -> Poll<T>) -> T {
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(res) = poll(ring, &mut cx) {
+                return res;
+            }
+            ring.ring.submit_and_wait(1).unwrap();
+            ring.complete();
+        }
+    }
+
+    fn pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        (client, server)
+    }
+
+    #[test]
+    fn send_zc() {
+        let mut ring = Ring::new(8).unwrap();
+        let (client, mut server) = pair();
+
+        let buf = vec![7u8; 256 * 1024];
+        let key = ring.send_zc(client.as_raw_fd(), buf).map_err(|(e, _)| e).unwrap();
+
+        let reader = std::thread::spawn(move || {
+            let mut recv = Vec::new();
+            server.read_to_end(&mut recv).unwrap();
+            recv
+        });
+
+        let mut sent = 0;
+        let (res, buf) = wait(&mut ring, |ring, cx| ring.poll_send_zc::<Vec<u8>>(&key, cx));
+        sent += res.unwrap();
+        assert_ne!(sent, 0);
+        (&client).write_all(&buf[sent..]).unwrap();
+        drop(client);
+
+        let recv = reader.join().unwrap();
+        assert_eq!(recv.len(), buf.len());
+        assert!(recv.iter().all(|b| *b == 7));
+        assert_eq!(ring.in_flight(), 0);
+    }
+
+    #[test]
+    fn fixed_buffers() {
+        let mut ring = Ring::new(8).unwrap();
+        let pool = FixedBufPool::new(2, 16);
+        let (client, server) = pair();
+
+        let mut buf = pool.get().unwrap();
+        assert_eq!(buf.put_slice(b"hello, world! hello, world!"), 16);
+
+        // buffer of other pool is rejected.
+        let other = FixedBufPool::new(1, 16).get().unwrap();
+        let (e, other) = ring.write_fixed(client.as_raw_fd(), other, 0).err().unwrap();
+        assert_eq!(e.kind(), io::ErrorKind::Unsupported);
+        drop(other);
+
+        ring.register_buffers(&pool).unwrap();
+        assert!(ring.register_buffers(&pool).is_err());
+
+        let key = ring
+            .write_fixed(client.as_raw_fd(), buf, 0)
+            .map_err(|(e, _)| e)
+            .unwrap();
+        let (res, buf) = wait(&mut ring, |ring, cx| ring.poll_fixed(&key, cx));
+        assert_eq!(res.unwrap(), 16);
+        drop(buf);
+
+        let mut buf = pool.get().unwrap();
+        buf.put_slice(b"re");
+        let key = ring.read_fixed(server.as_raw_fd(), buf, 0).map_err(|(e, _)| e).unwrap();
+        let (res, buf) = wait(&mut ring, |ring, cx| ring.poll_fixed(&key, cx));
+        assert_eq!(res.unwrap(), 14);
+        assert_eq!(&*buf, b"rehello, world! ");
+
+        // both buffers are back to pool.
+        drop(buf);
+        let bufs = (pool.get(), pool.get(), pool.get());
+        assert!(bufs.0.is_some() && bufs.1.is_some() && bufs.2.is_none());
+    }
+
+    #[test]
+    fn provided_buffers() {
+        let mut ring = Ring::new(8).unwrap();
+        let (mut client, server) = pair();
+
+        assert_eq!(
+            ring.recv_provided(server.as_raw_fd()).err().unwrap().kind(),
+            io::ErrorKind::Unsupported
+        );
+
+        ring.register_buf_ring(2, 8).unwrap();
+
+        client.write_all(b"hello, world!").unwrap();
+
+        let key = ring.recv_provided(server.as_raw_fd()).unwrap();
+        let first = wait(&mut ring, |ring, cx| ring.poll_recv_provided(&key, cx)).unwrap();
+        assert_eq!(&*first, b"hello, w");
+
+        let key = ring.recv_provided(server.as_raw_fd()).unwrap();
+        let second = wait(&mut ring, |ring, cx| ring.poll_recv_provided(&key, cx)).unwrap();
+        assert_eq!(&*second, b"orld!");
+
+        // every buffer is in use.
+        client.write_all(b"again").unwrap();
+        let key = ring.recv_provided(server.as_raw_fd()).unwrap();
+        let err = wait(&mut ring, |ring, cx| ring.poll_recv_provided(&key, cx))
+            .err()
+            .unwrap();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOBUFS));
+
+        // buffer goes back to ring on drop.
+        drop(first);
+        let key = ring.recv_provided(server.as_raw_fd()).unwrap();
+        let third = wait(&mut ring, |ring, cx| ring.poll_recv_provided(&key, cx)).unwrap();
+        assert_eq!(&*third, b"again");
+        drop((second, third));
+
+        // closed by peer.
+        drop(client);
+        let key = ring.recv_provided(server.as_raw_fd()).unwrap();
+        let eof = wait(&mut ring, |ring, cx| ring.poll_recv_provided(&key, cx)).unwrap();
+        assert!(eof.is_empty());
+    }
+
+    #[test]
+    fn accept_multi() {
+        let mut ring = Ring::new(8).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let key = ring.accept_multi(listener.as_raw_fd()).unwrap();
+
+        let clients = (0..3).map(|_| TcpStream::connect(addr).unwrap()).collect::<Vec<_>>();
+
+        for client in clients.iter() {
+            let fd = wait(&mut ring, |ring, cx| ring.poll_accept(&key, cx)).unwrap().unwrap();
+            let stream = TcpStream::from(fd);
+            assert_eq!(stream.peer_addr().unwrap(), client.local_addr().unwrap());
+        }
+
+        // cancelled operation ends with None after queued sockets are taken.
+        let _client = TcpStream::connect(addr).unwrap();
+        ring.cancel(&key);
+        let mut accepted = 0;
+        while let Some(fd) = wait(&mut ring, |ring, cx| ring.poll_accept(&key, cx)) {
+            fd.unwrap();
+            accepted += 1;
+        }
+        assert!(accepted <= 1);
+        assert_eq!(ring.in_flight(), 0);
+    }
+
+    #[test]
+    fn release_in_flight() {
+        let mut ring = Ring::new(8).unwrap();
+        let (_client, server) = pair();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+
+        ring.register_buf_ring(1, 8).unwrap();
+
+        let pool = FixedBufPool::new(1, 8);
+        ring.register_buffers(&pool).unwrap();
+
+        // operations waiting for data and connections that would never come.
+        let recv = ring.recv_provided(server.as_raw_fd()).unwrap();
+        let accept = ring.accept_multi(listener.as_raw_fd()).unwrap();
+        let read = ring
+            .read_fixed(server.as_raw_fd(), pool.get().unwrap(), 0)
+            .map_err(|(e, _)| e)
+            .unwrap();
+        assert_eq!(ring.in_flight(), 3);
+
+        ring.release(recv);
+        ring.release(accept);
+        ring.release(read);
+
+        // released operations are cancelled and their slots freed on completion.
+        while ring.in_flight() != 0 {
+            ring.ring.submit_and_wait(1).unwrap();
+            ring.complete();
+        }
+
+        // registered buffers are back to pool and ring.
+        assert!(pool.get().is_some());
+    }
+
+    #[test]
+    fn drop_in_flight() {
+        let mut ring = Ring::new(8).unwrap();
+        let (_client, server) = pair();
+        ring.register_buf_ring(1, 8).unwrap();
+        let _recv = ring.recv_provided(server.as_raw_fd()).unwrap();
+        // dropping ring cancels pending receive and waits for it.
+        drop(ring);
+    }
+}
@@ -9,12 +9,12 @@ use core::{
     pin::Pin,
 };
 
-use std::error;
+use std::{collections::HashMap, error};
 
 use futures_core::stream::Stream;
 use xitca_http::util::{
     middleware::context::{Context, ContextBuilder},
-    service::router::{IntoObject, PathGen, RouteGen, RouteObject, TypedRoute},
+    service::router::{IntoObject, PathGen, RouteGen, RouteInfo, RouteObject, TypedRoute},
 };
 
 use crate::{
@@ -29,6 +29,20 @@ use crate::{
 
 use self::{object::WebObject, router::AppRouter};
 
+#[cfg(feature = "openapi")]
+use {
+    crate::{
+        handler::json::Json,
+        openapi::Value,
+        route::{get, Route},
+    },
+    xitca_http::util::service::route::MethodNotAllowedBuilder,
+};
+
+#[cfg(feature = "openapi")]
+pub(crate) use self::router::split_constraint;
+pub(crate) use self::router::RouteNames;
+
 /// composed application type with router, stateful context and default middlewares.
 pub struct App<R = (), CF = ()> {
     router: R,
@@ -297,6 +311,96 @@ impl<Obj, CF> App<AppRouter<Obj>, CF> {
         self
     }
 
+    /// insert routed service with given name and string literal as route path to application. the name
+    /// can be used to generate url of the route with [WebContext::url_for]. See [App::at] for routing rules.
+    ///
+    /// names registered to nested App are accessible from it's parent and their paths are prefixed with the
+    /// path nested App is inserted to.
+    ///
+    /// # Panic:
+    ///
+    /// When multiple routes registered with the same name.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use xitca_web::{error::Error, handler::handler_service, route::get, App, WebContext};
+    /// App::new()
+    ///     .at_named("user", "/users/:id", get(handler_service(|| async { "user" })))
+    ///     .at("/", get(handler_service(index)));
+    ///
+    /// async fn index(ctx: &WebContext<'_>) -> Result<String, Error> {
+    ///     // generate "/users/996" from route named "user".
+    ///     Ok(ctx.url_for("user", &[("id", "996")])?)
+    /// }
+    /// ```
+    pub fn at_named<F, C, B>(mut self, name: &'static str, path: &'static str, builder: F) -> Self
+    where
+        F: RouteGen + Service + Send + Sync,
+        F::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<F::Route<F>, (), Object = Obj>,
+    {
+        self.router = self.router.insert_named(name, path, builder);
+        self
+    }
+
+    /// insert routed service with given host pattern to application. request with matched `Host` header
+    /// (or `:authority` pseudo header for http/2 and http/3) is dispatched to the service before path routing
+    /// of current App. request with unmatched host falls back to path routing of current App.
+    ///
+    /// host is matched with port stripped and in ascii lowercase. host pattern can be an exact host or a
+    /// wildcard subdomain with captured parameter. captured parameters are accessible from
+    /// [Params](crate::handler::params::Params) extractor together with path parameters.
+    ///
+    /// routes of host service are matched against the same request path as routes of current App and
+    /// middlewares can be applied to host service with nested App's [App::enclosed] and alike APIs. named
+    /// routes of host service are registered to current App and can be used with [WebContext::url_for].
+    ///
+    /// # Panic:
+    ///
+    /// When multiple services inserted to the same host pattern, host pattern is invalid or conflicting with
+    /// existing one or multiple routes registered with the same name.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use xitca_web::{handler::handler_service, route::get, service::Service, App, NestApp, WebContext};
+    /// fn api() -> NestApp<()> {
+    ///     App::new().at("/users", get(handler_service(|| async { "api users" })))
+    /// }
+    ///
+    /// fn tenant() -> NestApp<()> {
+    ///     App::new().at("/", get(handler_service(index)))
+    /// }
+    ///
+    /// async fn index(ctx: &WebContext<'_>) -> String {
+    ///     // subdomain captured from host pattern.
+    ///     format!("hello {}", ctx.req().body().params().get("tenant").unwrap())
+    /// }
+    ///
+    /// async fn middleware<S, C, B, Res, Err>(s: &S, ctx: WebContext<'_, C, B>) -> Result<Res, Err>
+    /// where
+    ///     S: for<'r> Service<WebContext<'r, C, B>, Response = Res, Error = Err>,
+    /// {
+    ///     s.call(ctx).await
+    /// }
+    ///
+    /// App::new()
+    ///     // exact host with it's own middleware stack.
+    ///     .host("api.example.com", api().enclosed_fn(middleware))
+    ///     // wildcard subdomain with captured parameter.
+    ///     .host(":tenant.example.com", tenant())
+    ///     // fallback for all other hosts.
+    ///     .at("/", get(handler_service(|| async { "example.com" })));
+    /// ```
+    pub fn host<F, C, B>(mut self, host: &'static str, builder: F) -> Self
+    where
+        F: RouteGen + Service + Send + Sync,
+        F::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<F::Route<F>, (), Object = Obj>,
+    {
+        self.router = self.router.insert_host(host, builder);
+        self
+    }
+
     /// insert typed route service with given path to application.
     pub fn at_typed<T, C>(mut self, typed: T) -> Self
     where
@@ -305,6 +409,82 @@ impl<Obj, CF> App<AppRouter<Obj>, CF> {
         self.router = self.router.insert_typed(typed);
         self
     }
+
+    /// redirect request failed to match any route to a recommended path. trailing slash fixed path is
+    /// tried first and then ascii case insensitive fixed path. GET and HEAD requests are redirected with
+    /// 301 Moved Permanently and other methods with 308 Permanent Redirect. uri query is preserved.
+    ///
+    /// only routes registered to current App are considered. nested App must opt-in separately.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use xitca_unsafe_collection::futures::NowOrPanic;
+    /// # use xitca_web::{handler::handler_service, http::{header::LOCATION, Request}, service::Service, App};
+    /// let app = App::new()
+    ///     .at("/users/", handler_service(|| async { "users" }))
+    ///     .redirect_fixed_path()
+    ///     .finish()
+    ///     .call(())
+    ///     .now_or_panic()
+    ///     .unwrap();
+    ///
+    /// let req = Request::builder().uri("/Users?page=1").body(Default::default()).unwrap();
+    /// let res = app.call(req).now_or_panic().unwrap();
+    /// assert_eq!(res.status().as_u16(), 301);
+    /// assert_eq!(res.headers().get(LOCATION).unwrap(), "/users/?page=1");
+    /// ```
+    pub fn redirect_fixed_path(mut self) -> Self {
+        self.router = self.router.enable_recommend();
+        self
+    }
+
+    /// generate OpenAPI document from routes registered to application and serve it as json with GET
+    /// method at given path. See [openapi](crate::openapi) module for detail.
+    ///
+    /// document is generated when this method is called. routes inserted after it are not included. routes of
+    /// virtual hosts are described by separate documents. See [OpenApi::host](crate::openapi::OpenApi::host).
+    #[cfg(feature = "openapi")]
+    pub fn openapi<C, B>(self, path: &'static str, api: crate::openapi::OpenApi) -> Self
+    where
+        Obj: PathGen,
+        for<'r> WebContext<'r, C, B>:
+            IntoObject<Route<Json<Value>, MethodNotAllowedBuilder<Json<Value>>, 1>, (), Object = Obj>,
+    {
+        let doc = api.document(&self.routes());
+        self.at(path, get(Json(doc)))
+    }
+}
+
+impl<R, CF> App<R, CF>
+where
+    R: PathGen,
+{
+    /// List information of routes registered to application, including routes of nested App and host
+    /// services. Routes are sorted by host and path.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use xitca_web::{handler::handler_service, http::Method, route::get, App, NestApp};
+    /// let api: NestApp<()> = App::new()
+    ///     .at("/users/:id", get(handler_service(|| async { "" })).post(handler_service(|| async { "" })));
+    ///
+    /// let app: NestApp<()> = App::new()
+    ///     .at_named("index", "/", get(handler_service(|| async { "" })))
+    ///     .at("/api", api);
+    ///
+    /// let routes = app.routes();
+    ///
+    /// assert_eq!(routes[0].path(), "/");
+    /// assert_eq!(routes[0].name(), Some("index"));
+    /// assert_eq!(routes[1].path(), "/api/users/:id");
+    /// assert_eq!(routes[1].methods(), [Method::GET, Method::POST]);
+    /// ```
+    pub fn routes(&self) -> Vec<RouteInfo> {
+        let mut routes = Vec::new();
+        self.router.route_info(RouteInfo::default(), &mut routes);
+        routes.sort_by(|a, b| (a.host(), a.path()).cmp(&(b.host(), b.path())));
+        routes
+    }
 }
 
 impl<R, CF> App<R, CF> {
@@ -522,6 +702,14 @@ where
     fn path_gen(&mut self, prefix: &str) -> String {
         self.router.path_gen(prefix)
     }
+
+    fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+        self.router.named_path_gen()
+    }
+
+    fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+        self.router.route_info(info, routes)
+    }
 }
 
 impl<R, F> RouteGen for App<R, F>
@@ -586,7 +774,11 @@ mod test {
             extension::ExtensionRef, extension::ExtensionsRef, handler_service, path::PathRef, state::StateRef,
             uri::UriRef,
         },
-        http::{const_header_value::TEXT_UTF8, header::CONTENT_TYPE, request, Method},
+        http::{
+            const_header_value::TEXT_UTF8,
+            header::{ACCEPT, CONTENT_TYPE, HOST, LOCATION},
+            request, Method, StatusCode,
+        },
         middleware::UncheckedReady,
         route::get,
     };
@@ -701,6 +893,254 @@ mod test {
         assert_eq!(res.status().as_u16(), 405);
     }
 
+    #[test]
+    fn app_redirect_fixed_path() {
+        fn app() -> NestApp<()> {
+            App::new()
+                .at("/nest/", get(handler_service(|| async { "" })))
+                .redirect_fixed_path()
+        }
+
+        let service = App::new()
+            .at("/Root", get(handler_service(|| async { "" })))
+            .at("/scope", app())
+            .redirect_fixed_path()
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |method, uri| {
+            let req = request::Builder::default()
+                .method(method)
+                .uri(uri)
+                .body(Default::default())
+                .unwrap();
+            let res = service.call(req).now_or_panic().unwrap();
+            let location = res.headers().get(LOCATION).map(|v| v.to_str().unwrap().to_owned());
+            (res.status().as_u16(), location)
+        };
+
+        assert_eq!(call(Method::GET, "/root"), (301, Some("/Root".into())));
+        assert_eq!(call(Method::GET, "/Root/?q=1"), (301, Some("/Root?q=1".into())));
+        assert_eq!(call(Method::POST, "/root"), (308, Some("/Root".into())));
+        assert_eq!(call(Method::GET, "/scope/NEST"), (301, Some("/scope/nest/".into())));
+        assert_eq!(call(Method::GET, "/scope/nest/"), (200, None));
+        assert_eq!(call(Method::GET, "/foo"), (404, None));
+
+        let service = App::new()
+            .at("/root", get(handler_service(|| async { "" })))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let req = request::Builder::default()
+            .uri("/root/")
+            .body(Default::default())
+            .unwrap();
+        assert_eq!(service.call(req).now_or_panic().unwrap().status().as_u16(), 404);
+    }
+
+    #[test]
+    fn app_url_for() {
+        async fn handler(ctx: &WebContext<'_>) -> &'static str {
+            assert_eq!(ctx.url_for("index", &[]).unwrap(), "/");
+            assert_eq!(
+                ctx.url_for("file", &[("id", "1"), ("path", "a b/ä.txt")]).unwrap(),
+                "/scope/users/1/files/a%20b/%C3%A4.txt"
+            );
+            assert_eq!(
+                ctx.url_for("user", &[("name", "foo/bar"), ("ext", "json")]).unwrap(),
+                "/scope/nest/foo%2Fbar.json"
+            );
+            assert!(matches!(
+                ctx.url_for("user", &[("name", "foo")]),
+                Err(crate::error::UrlForError::MissingParam(name)) if &*name == "ext"
+            ));
+            assert!(matches!(
+                ctx.url_for("foo", &[]),
+                Err(crate::error::UrlForError::UnknownName(_))
+            ));
+            ""
+        }
+
+        fn nest() -> NestApp<()> {
+            App::new().at_named("user", "/:name.:ext{alpha}", get(handler_service(|| async { "" })))
+        }
+
+        fn app() -> NestApp<()> {
+            App::new()
+                .at_named(
+                    "file",
+                    "/users/:id{uint}/files/*path",
+                    get(handler_service(|| async { "" })),
+                )
+                .at("/nest", nest())
+        }
+
+        let service = App::new()
+            .at_named("index", "/", get(handler_service(handler)))
+            .at("/scope", app())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let req = request::Builder::default().uri("/").body(Default::default()).unwrap();
+        assert_eq!(service.call(req).now_or_panic().unwrap().status().as_u16(), 200);
+    }
+
+    #[test]
+    fn app_url_for_middleware() {
+        async fn handler(ctx: &WebContext<'_>) -> &'static str {
+            assert_eq!(ctx.url_for("index", &[]).unwrap(), "/");
+            assert_eq!(ctx.url_for("nest", &[]).unwrap(), "/nest/");
+            ""
+        }
+
+        // middleware reconstructing WebContext with new request body type must keep names of router.
+        let nest = App::new()
+            .at_named("nest", "/", get(handler_service(handler)))
+            .enclosed(TypeEraser::request_body());
+
+        let service = App::new()
+            .at_named("index", "/", get(handler_service(handler)))
+            .at("/nest", nest)
+            .enclosed(TypeEraser::request_body())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for uri in ["/", "/nest/"] {
+            let req = request::Builder::default().uri(uri).body(Default::default()).unwrap();
+            assert_eq!(service.call(req).now_or_panic().unwrap().status().as_u16(), 200);
+        }
+    }
+
+    #[test]
+    fn app_host() {
+        async fn tenant(ctx: &WebContext<'_>) -> StatusCode {
+            let params = ctx.req().body().params();
+            assert_eq!(params.get("tenant"), Some("foo"));
+            assert_eq!(params.get("id"), Some("996"));
+            StatusCode::ACCEPTED
+        }
+
+        async fn api_middleware<S, C, B, Err>(s: &S, ctx: WebContext<'_, C, B>) -> Result<WebResponse, Err>
+        where
+            S: for<'r> Service<WebContext<'r, C, B>, Response = WebResponse, Error = Err>,
+        {
+            let mut res = s.call(ctx).await?;
+            res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+            Ok(res)
+        }
+
+        fn api() -> NestApp<()> {
+            App::new().at("/users", get(handler_service(|| async { StatusCode::CREATED })))
+        }
+
+        fn nest() -> NestApp<()> {
+            App::new()
+                .host(
+                    ":tenant.example.org",
+                    App::new().at("/users/:id", get(handler_service(tenant))),
+                )
+                .at("/users/:id", get(handler_service(|| async { StatusCode::OK })))
+        }
+
+        let service = App::new()
+            .host("api.example.com", api().enclosed_fn(api_middleware))
+            .host(
+                ":tenant.example.com",
+                App::new().at("/users/:id", get(handler_service(tenant))),
+            )
+            .at("/scope", nest())
+            .at("/users", get(handler_service(|| async { StatusCode::OK })))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |uri, host: Option<&str>| {
+            let mut req = request::Builder::default().uri(uri);
+            if let Some(host) = host {
+                req = req.header(HOST, host);
+            }
+            let res = service
+                .call(req.body(Default::default()).unwrap())
+                .now_or_panic()
+                .unwrap();
+            (res.status().as_u16(), res.headers().contains_key(CONTENT_TYPE))
+        };
+
+        assert_eq!(call("/users", Some("api.example.com")), (201, true));
+        assert_eq!(call("/users", Some("API.Example.com:8080")), (201, true));
+        assert_eq!(call("http://api.example.com/users", None), (201, true));
+        assert_eq!(call("/users/996", Some("foo.example.com")), (202, false));
+        // host routing of current App takes precedence over path routing.
+        assert_eq!(call("/scope/users/996", Some("foo.example.com")), (404, false));
+        assert_eq!(call("/scope/users/996", Some("foo.example.org")), (202, false));
+        assert_eq!(call("/scope/users/996", Some("example.org")), (200, false));
+        assert_eq!(call("/users", Some("example.com")), (200, false));
+        assert_eq!(call("/users", None), (200, false));
+        assert_eq!(call("/users/996", Some("api.example.com")), (404, false));
+    }
+
+    #[test]
+    fn app_route_guard() {
+        use crate::route::{
+            guard::{accept, content_type},
+            post,
+        };
+
+        let service = App::new()
+            .at(
+                "/",
+                get(handler_service(|| async { StatusCode::OK }))
+                    .guard(accept("application/json"))
+                    .next(
+                        post(handler_service(|| async { StatusCode::CREATED })).guard(content_type("application/json")),
+                    ),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |method, header: Option<(_, &'static str)>| {
+            let mut req = request::Builder::default().method(method).uri("/");
+            if let Some((name, value)) = header {
+                req = req.header(name, value);
+            }
+            let res = service
+                .call(req.body(Default::default()).unwrap())
+                .now_or_panic()
+                .unwrap();
+            res.status().as_u16()
+        };
+
+        assert_eq!(call(Method::GET, None), 200);
+        assert_eq!(call(Method::GET, Some((ACCEPT, "application/*"))), 200);
+        assert_eq!(call(Method::GET, Some((ACCEPT, "text/html"))), 406);
+        assert_eq!(call(Method::POST, Some((CONTENT_TYPE, "application/json"))), 201);
+        assert_eq!(call(Method::POST, Some((CONTENT_TYPE, "text/plain"))), 415);
+        assert_eq!(call(Method::PUT, None), 405);
+    }
+
+    #[test]
+    #[should_panic]
+    fn app_url_for_duplicate_name() {
+        fn app() -> NestApp<()> {
+            App::new().at_named("index", "/", get(handler_service(|| async { "" })))
+        }
+
+        let _ = App::new()
+            .at_named("index", "/", get(handler_service(|| async { "" })))
+            .at("/scope", app());
+    }
+
     #[derive(Clone)]
     struct Foo;
 
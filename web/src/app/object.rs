@@ -1,6 +1,8 @@
 use core::marker::PhantomData;
 
-use xitca_http::util::service::router::{IntoObject, PathGen, RouteGen, RouteObject};
+use std::collections::HashMap;
+
+use xitca_http::util::service::router::{IntoObject, PathGen, RouteGen, RouteInfo, RouteObject};
 use xitca_service::{object::ServiceObject, Service};
 
 use crate::context::WebContext;
@@ -26,6 +28,14 @@ where
             fn path_gen(&mut self, prefix: &str) -> String {
                 self.0.path_gen(prefix)
             }
+
+            fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+                self.0.named_path_gen()
+            }
+
+            fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+                self.0.route_info(info, routes)
+            }
         }
 
         impl<I, C, B> RouteGen for Builder<I, C, B>
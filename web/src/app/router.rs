@@ -1,7 +1,11 @@
-use xitca_http::util::service::router::{IntoObject, PathGen, RouteGen, Router, RouterError, RouterMapErr, TypedRoute};
+use std::{collections::HashMap, sync::Arc};
+
+use xitca_http::util::service::router::{
+    IntoObject, PathGen, RouteGen, RouteInfo, Router, RouterError, RouterMapErr, TypedRoute,
+};
 
 use crate::{
-    error::Error,
+    error::{Error, UrlForError},
     service::{ready::ReadyService, Service},
     WebContext,
 };
@@ -24,6 +28,31 @@ impl<Obj> AppRouter<Obj> {
         self
     }
 
+    pub(super) fn insert_named<F, Arg, Req>(mut self, name: &'static str, path: &'static str, builder: F) -> Self
+    where
+        F: Service<Arg> + RouteGen + Send + Sync,
+        F::Response: Service<Req>,
+        Req: IntoObject<F::Route<F>, Arg, Object = Obj>,
+    {
+        self.0 = self.0.insert_named(name, path, builder);
+        self
+    }
+
+    pub(super) fn insert_host<F, Arg, Req>(mut self, host: &'static str, builder: F) -> Self
+    where
+        F: Service<Arg> + RouteGen + Send + Sync,
+        F::Response: Service<Req>,
+        Req: IntoObject<F::Route<F>, Arg, Object = Obj>,
+    {
+        self.0 = self.0.insert_host(host, builder);
+        self
+    }
+
+    pub(super) fn enable_recommend(mut self) -> Self {
+        self.0 = self.0.enable_recommend();
+        self
+    }
+
     pub(super) fn insert_typed<T, M>(mut self, t: T) -> Self
     where
         T: TypedRoute<M, Route = Obj>,
@@ -40,6 +69,14 @@ where
     fn path_gen(&mut self, prefix: &str) -> String {
         self.0.path_gen(prefix)
     }
+
+    fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+        self.0.named_path_gen()
+    }
+
+    fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+        self.0.route_info(info, routes)
+    }
 }
 
 impl<Obj> RouteGen for AppRouter<Obj>
@@ -61,11 +98,17 @@ where
     type Error = <Router<Obj> as Service<Arg>>::Error;
 
     async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
-        self.0.call(arg).await.map(RouterService)
+        // names of nested router are taken by it's parent. only the outermost router has them.
+        let names = self.0.named_paths().map(|(name, path)| (name, Box::from(path)));
+        let names = Some(RouteNames(Arc::new(names.collect()))).filter(|names| !names.0.is_empty());
+        self.0.call(arg).await.map(|service| RouterService { service, names })
     }
 }
 
-pub struct RouterService<S>(S);
+pub struct RouterService<S> {
+    service: S,
+    names: Option<RouteNames>,
+}
 
 impl<'r, S, C, B, Res, E> Service<WebContext<'r, C, B>> for RouterService<S>
 where
@@ -76,8 +119,13 @@ where
     type Error = Error<C>;
 
     #[inline]
-    async fn call(&self, req: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
-        self.0.call(req).await.map_err(Into::into)
+    async fn call(&self, mut req: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        // nested router has no names and keeps the ones from it's parent.
+        let names = self.names.as_ref().or(req.names);
+        self.service
+            .call(req.reborrow().with_names(names))
+            .await
+            .map_err(Into::into)
     }
 }
 
@@ -89,6 +137,89 @@ where
 
     #[inline]
     async fn ready(&self) -> Self::Ready {
-        self.0.ready().await
+        self.service.ready().await
+    }
+}
+
+/// named route paths of application. See [WebContext::url_for] for detail.
+#[derive(Clone)]
+pub(crate) struct RouteNames(Arc<HashMap<&'static str, Box<str>>>);
+
+impl RouteNames {
+    pub(crate) fn url_for(&self, name: &str, params: &[(&str, &str)]) -> Result<String, UrlForError> {
+        let path = self.0.get(name).ok_or_else(|| UrlForError::UnknownName(name.into()))?;
+
+        let param = |name: &str| {
+            params
+                .iter()
+                .find_map(|(k, v)| (*k == name).then_some(*v))
+                .ok_or_else(|| UrlForError::MissingParam(name.into()))
+        };
+
+        let mut url = String::with_capacity(path.len());
+        let mut path = &**path;
+
+        while let Some(i) = path.find([':', '*']) {
+            url.push_str(&path[..i]);
+
+            let wildcard = &path[i + 1..];
+
+            // catch all parameter takes the rest of path.
+            if path.as_bytes()[i] == b'*' {
+                push_encoded(&mut url, param(wildcard)?, true);
+                return Ok(url);
+            }
+
+            let end = wildcard
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(wildcard.len());
+            push_encoded(&mut url, param(&wildcard[..end])?, false);
+            path = split_constraint(&wildcard[end..]).1;
+        }
+
+        url.push_str(path);
+
+        Ok(url)
+    }
+}
+
+// split brace enclosed parameter constraint from the rest of path. braces inside constraint are balanced.
+// constraint is empty when path does not start with one.
+pub(crate) fn split_constraint(path: &str) -> (&str, &str) {
+    if !path.starts_with('{') {
+        return ("", path);
+    }
+
+    let mut depth = 0usize;
+    for (i, c) in path.bytes().enumerate() {
+        match c {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (&path[1..i], &path[i + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (&path[1..], "")
+}
+
+// percent encode everything except unreserved characters. catch all parameter keeps path separator.
+fn push_encoded(url: &mut String, value: &str, keep_slash: bool) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => url.push(b as char),
+            b'/' if keep_slash => url.push('/'),
+            _ => {
+                url.push('%');
+                url.push(HEX[(b >> 4) as usize] as char);
+                url.push(HEX[(b & 0xf) as usize] as char);
+            }
+        }
     }
 }
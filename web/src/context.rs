@@ -6,7 +6,9 @@ use core::{
 };
 
 use super::{
+    app::RouteNames,
     body::{RequestBody, ResponseBody},
+    error::UrlForError,
     http::{BorrowReq, BorrowReqMut, IntoResponse, Request, RequestExt, WebRequest, WebResponse},
 };
 
@@ -15,11 +17,24 @@ pub struct WebContext<'a, C = (), B = RequestBody> {
     pub(crate) req: &'a mut WebRequest<()>,
     pub(crate) body: &'a mut RefCell<B>,
     pub(crate) ctx: &'a C,
+    // named route paths of application router. set by router and passed down to it's routes.
+    pub(crate) names: Option<&'a RouteNames>,
 }
 
 impl<'a, C, B> WebContext<'a, C, B> {
     pub(crate) fn new(req: &'a mut WebRequest<()>, body: &'a mut RefCell<B>, ctx: &'a C) -> Self {
-        Self { req, body, ctx }
+        Self {
+            req,
+            body,
+            ctx,
+            names: None,
+        }
+    }
+
+    // keep named route paths when Self is reconstructed with different request body type.
+    pub(crate) fn with_names(mut self, names: Option<&'a RouteNames>) -> Self {
+        self.names = names;
+        self
     }
 
     /// Reborrow Self so the ownership of WebRequest is not lost.
@@ -51,6 +66,7 @@ impl<'a, C, B> WebContext<'a, C, B> {
             req: self.req,
             body: self.body,
             ctx: self.ctx,
+            names: self.names,
         }
     }
 
@@ -92,6 +108,32 @@ impl<'a, C, B> WebContext<'a, C, B> {
         self.body.get_mut()
     }
 
+    /// Generate url path of a named route with given route parameters. Parameter values are percent
+    /// encoded. See [App::at_named](crate::App::at_named) for registering named route.
+    ///
+    /// parameters not found in route path are ignored.
+    ///
+    /// # Errors:
+    ///
+    /// When no route is registered with the given name or a parameter of route path is not provided.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use xitca_web::{error::Error, WebContext};
+    /// // App::new().at_named("file", "/users/:id/files/*path", ..)
+    /// fn handler(ctx: &WebContext<'_>) -> Result<(), Error> {
+    ///     let url = ctx.url_for("file", &[("id", "996"), ("path", "foo/bar baz")])?;
+    ///     assert_eq!(url, "/users/996/files/foo/bar%20baz");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn url_for(&self, name: &str, params: &[(&str, &str)]) -> Result<String, UrlForError> {
+        match self.names {
+            Some(names) => names.url_for(name, params),
+            None => Err(UrlForError::UnknownName(name.into())),
+        }
+    }
+
     pub fn take_request(&mut self) -> WebRequest<B>
     where
         B: Default,
@@ -164,6 +206,7 @@ impl<C> TestWebContext<C> {
             req: &mut self.req,
             body: &mut self.body,
             ctx: &self.ctx,
+            names: None,
         }
     }
 }
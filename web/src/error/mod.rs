@@ -145,11 +145,7 @@ use self::service_impl::ErrorService;
 ///     assert_eq!(res.status().as_u16(), 200);
 ///
 ///     // upcast and downcast to concrete error type again.
-///     // *. trait upcast is a nightly feature.
-///     // see https://github.com/rust-lang/rust/issues/65991 for detail
-///     
-///     // let e = &*e as &dyn error::Error;
-///     // assert!(e.downcast_ref::<Foo>().is_some());
+///     assert!(e.upcast().downcast_ref::<Foo>().is_some());
 /// }
 /// ```
 pub struct Error<C = ()>(Box<dyn for<'r> ErrorService<WebContext<'r, C>>>);
@@ -166,6 +162,11 @@ impl<C> Error<C> {
     {
         Self(Box::new(s))
     }
+
+    /// upcast error object to [std::error::Error] trait object for downcasting to concrete error type.
+    pub fn upcast(&self) -> &(dyn error::Error + 'static) {
+        &*self.0
+    }
 }
 
 impl<C> fmt::Debug for Error<C> {
@@ -1,22 +1,81 @@
 pub use xitca_http::util::service::{
+    guard::Rejection,
     route::MethodNotAllowed,
-    router::{MatchError, RouterError},
+    router::{MatchError, Recommendation, RouterError},
 };
 
-use core::convert::Infallible;
+use core::{convert::Infallible, fmt};
+
+use std::error;
 
 use crate::{
     body::ResponseBody,
-    http::{header::ALLOW, StatusCode, WebResponse},
+    handler::{redirect::Redirect, Responder},
+    http::{
+        header::{HeaderValue, ALLOW},
+        Method, StatusCode, WebResponse,
+    },
     service::Service,
     WebContext,
 };
 
-use super::{blank_error_service, error_from_service, Error};
+use super::{blank_error_service, error_from_service, forward_blank_internal, Error};
 
 error_from_service!(MatchError);
 blank_error_service!(MatchError, StatusCode::NOT_FOUND);
 
+error_from_service!(Recommendation);
+
+impl<'r, C, B> Service<WebContext<'r, C, B>> for Recommendation {
+    type Response = WebResponse;
+    type Error = Infallible;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let mut location = String::from(self.path());
+
+        if let Some(query) = ctx.req().uri().query() {
+            location.push('?');
+            location.push_str(query);
+        }
+
+        let Ok(location) = HeaderValue::try_from(location) else {
+            return MatchError.call(ctx).await;
+        };
+
+        // method and body are preserved with 308 for non GET/HEAD request.
+        let redirect = match *ctx.req().method() {
+            Method::GET | Method::HEAD => Redirect::moved_permanently(location),
+            _ => Redirect::permanent(location),
+        };
+
+        let res = ctx.into_response(ResponseBody::empty());
+        Ok(Responder::<WebContext<'r, C, B>>::map(redirect, res).expect("location header value is checked"))
+    }
+}
+
+/// error type for url generation of named route. See [WebContext::url_for] for detail.
+#[derive(Debug)]
+pub enum UrlForError {
+    /// no route is registered with the name.
+    UnknownName(Box<str>),
+    /// value of named parameter in route path is not provided.
+    MissingParam(Box<str>),
+}
+
+impl fmt::Display for UrlForError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::UnknownName(ref name) => write!(f, "route with name: {name} can't be found"),
+            Self::MissingParam(ref name) => write!(f, "value of route parameter: {name} is not provided"),
+        }
+    }
+}
+
+impl error::Error for UrlForError {}
+
+error_from_service!(UrlForError);
+forward_blank_internal!(UrlForError);
+
 error_from_service!(MethodNotAllowed);
 
 impl<'r, C, B> Service<WebContext<'r, C, B>> for MethodNotAllowed {
@@ -45,6 +104,22 @@ impl<'r, C, B> Service<WebContext<'r, C, B>> for MethodNotAllowed {
     }
 }
 
+error_from_service!(Rejection);
+
+impl<'r, C, B> Service<WebContext<'r, C, B>> for Rejection {
+    type Response = WebResponse;
+    type Error = Infallible;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let status = match *self {
+            Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
+            Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::Unmatched => StatusCode::NOT_FOUND,
+        };
+        status.call(ctx).await
+    }
+}
+
 impl<E, C> From<RouterError<E>> for Error<C>
 where
     E: Into<Self>,
@@ -52,7 +127,9 @@ where
     fn from(e: RouterError<E>) -> Self {
         match e {
             RouterError::Match(e) => e.into(),
+            RouterError::Recommend(e) => e.into(),
             RouterError::NotAllowed(e) => e.into(),
+            RouterError::Rejected(e) => e.into(),
             RouterError::Service(e) => e.into(),
         }
     }
@@ -4,7 +4,7 @@ use crate::{
     body::ResponseBody,
     context::WebContext,
     error::{Error, ErrorStatus},
-    http::{Method, RequestExt, StatusCode, WebRequest, WebResponse},
+    http::{Method, PeerCertificate, RequestExt, StatusCode, WebRequest, WebResponse},
 };
 
 use super::{FromRequest, Responder};
@@ -109,6 +109,22 @@ impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for SocketAddr {
     }
 }
 
+/// Extract verified certificate chain of tls peer. [StatusCode::UNAUTHORIZED] error is returned when peer
+/// didn't present a verified client certificate. `Option<&PeerCertificate>` can be used when client
+/// certificate is optional.
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for &'a PeerCertificate {
+    type Type<'b> = &'b PeerCertificate;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        ctx.req()
+            .body()
+            .peer_certificate()
+            .ok_or_else(|| Error::from(StatusCode::UNAUTHORIZED))
+    }
+}
+
 impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for &'a Method {
     type Type<'b> = &'b Method;
     type Error = Error<C>;
@@ -228,6 +244,11 @@ mod test {
         <&WebContext<'_>>::from_request(&req).now_or_panic().unwrap();
 
         <()>::from_request(&req).now_or_panic().unwrap();
+
+        assert!(Option::<&PeerCertificate>::from_request(&req)
+            .now_or_panic()
+            .unwrap()
+            .is_none());
     }
 
     #[test]
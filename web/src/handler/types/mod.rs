@@ -20,9 +20,15 @@ pub mod form;
 #[cfg(feature = "json")]
 pub mod json;
 
+#[cfg(feature = "negotiate")]
+pub mod negotiate;
+
 #[cfg(feature = "cookie")]
 pub mod cookie;
 
+#[cfg(feature = "typed-header")]
+pub mod typed_header;
+
 #[cfg(feature = "multipart")]
 pub mod multipart;
 
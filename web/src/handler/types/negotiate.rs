@@ -0,0 +1,493 @@
+//! type extractor and response generator with content negotiation.
+
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{de::Deserialize, ser::Serialize};
+
+use crate::{
+    body::BodyStream,
+    bytes::{BufMutWriter, Bytes, BytesMut},
+    context::WebContext,
+    error::{Error, Rejection},
+    handler::{FromRequest, Responder},
+    http::{
+        const_header_value::TEXT_HTML_UTF8,
+        header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, VARY},
+        WebResponse,
+    },
+};
+
+use super::body::Limit;
+
+pub const DEFAULT_LIMIT: usize = 1024 * 1024;
+
+/// Response generator and extractor for a single value where wire format is negotiated with client.
+///
+/// As response generator the format is selected from request's `Accept` header with respect to quality
+/// values. Supported formats are json, html (when renderer is provided by [Negotiate::html]) and
+/// formats enabled by crate features: urlencoded (`urlencoded`), MessagePack (`msgpack`) and CBOR
+/// (`cbor`). Json is used when request has no `Accept` header and `406 Not Acceptable` response is
+/// generated when none of the formats is acceptable. Format can only be negotiated when [Negotiate]
+/// is used as standalone responder or the first item of responder tuple. Otherwise it's always json.
+///
+/// As extractor the format is selected from request's `Content-Type` header and `415 Unsupported
+/// Media Type` response is generated when the format is not supported. Request body larger than
+/// [DEFAULT_LIMIT] in bytes would be treated as error.
+///
+/// # Example
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{handler_service, negotiate::Negotiate},
+/// #   App, WebContext,
+/// # };
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// // receive user in the format of request's Content-Type header and respond in the format client
+/// // accepts. html is rendered with given renderer when client prefers it.
+/// async fn handler(user: Negotiate<User>) -> Negotiate<User, impl FnOnce(&User) -> String> {
+///     Negotiate::new(user.into_inner()).html(|user| format!("<h1>{}</h1>", user.name))
+/// }
+///
+/// App::new()
+///     .at("/users", handler_service(handler))
+///     # .at("/", handler_service(|_: &WebContext<'_>| async { "used for infer type" }));
+/// ```
+pub struct Negotiate<T, F = fn(&T) -> String> {
+    value: T,
+    html: Option<F>,
+}
+
+impl<T> Negotiate<T> {
+    pub fn new(value: T) -> Self {
+        Self { value, html: None }
+    }
+}
+
+impl<T, F> Negotiate<T, F> {
+    /// provide html renderer for value. html becomes one of the negotiable formats.
+    pub fn html<F1>(self, func: F1) -> Negotiate<T, F1>
+    where
+        F1: FnOnce(&T) -> String,
+    {
+        Negotiate {
+            value: self.value,
+            html: Some(func),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, F> fmt::Debug for Negotiate<T, F>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Negotiate")
+            .field("value", &self.value)
+            .field("html", &self.html.is_some())
+            .finish()
+    }
+}
+
+impl<T, F> Deref for Negotiate<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, F> DerefMut for Negotiate<T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<'a, 'r, C, B, T> FromRequest<'a, WebContext<'r, C, B>> for Negotiate<T>
+where
+    B: BodyStream + Default,
+    T: for<'de> Deserialize<'de>,
+{
+    type Type<'b> = Negotiate<T>;
+    type Error = Error<C>;
+
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        let format = ctx
+            .req()
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Format::from_content_type)
+            .ok_or(Rejection::UnsupportedMediaType)?;
+        let (bytes, _) = <(BytesMut, Limit<DEFAULT_LIMIT>)>::from_request(ctx).await?;
+        format.decode(&bytes).map(Negotiate::new)
+    }
+}
+
+impl<'r, C, B, T, F> Responder<WebContext<'r, C, B>> for Negotiate<T, F>
+where
+    T: Serialize,
+    F: FnOnce(&T) -> String,
+{
+    type Response = WebResponse;
+    type Error = Error<C>;
+
+    async fn respond(self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let format = Format::negotiate(ctx.req().headers(), self.html.is_some()).ok_or(Rejection::NotAcceptable)?;
+        let (bytes, content_type) = self.encode(format)?;
+        let mut res = ctx.into_response(bytes);
+        res.headers_mut().insert(CONTENT_TYPE, content_type);
+        res.headers_mut().append(VARY, HeaderValue::from_static("accept"));
+        Ok(res)
+    }
+
+    fn map(self, mut res: Self::Response) -> Result<Self::Response, Self::Error> {
+        let (bytes, content_type) = self.encode(Format::Json)?;
+        res.headers_mut().insert(CONTENT_TYPE, content_type);
+        Ok(res.map(|_| bytes.into()))
+    }
+}
+
+impl<T, F> Negotiate<T, F> {
+    fn encode<C>(self, format: Format) -> Result<(Bytes, HeaderValue), Error<C>>
+    where
+        T: Serialize,
+        F: FnOnce(&T) -> String,
+    {
+        let mut bytes = BytesMut::new();
+        match format {
+            Format::Json => serde_json::to_writer(BufMutWriter(&mut bytes), &self.value)?,
+            Format::Html => {
+                // html format is only negotiated when renderer is provided.
+                let html = self.html.expect("html renderer must be provided")(&self.value);
+                return Ok((Bytes::from(html), TEXT_HTML_UTF8));
+            }
+            #[cfg(feature = "urlencoded")]
+            Format::UrlEncoded => {
+                let string = serde_urlencoded::to_string(&self.value)?;
+                return Ok((Bytes::from(string), format.content_type()));
+            }
+            #[cfg(feature = "msgpack")]
+            Format::MsgPack => rmp_serde::encode::write_named(&mut BufMutWriter(&mut bytes), &self.value)?,
+            #[cfg(feature = "cbor")]
+            Format::Cbor => ciborium::into_writer(&self.value, BufMutWriter(&mut bytes))?,
+        }
+        Ok((bytes.freeze(), format.content_type()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Json,
+    Html,
+    #[cfg(feature = "urlencoded")]
+    UrlEncoded,
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+impl Format {
+    // negotiable formats in the order of server preference.
+    const ALL: &'static [Self] = &[
+        Self::Json,
+        Self::Html,
+        #[cfg(feature = "msgpack")]
+        Self::MsgPack,
+        #[cfg(feature = "cbor")]
+        Self::Cbor,
+        #[cfg(feature = "urlencoded")]
+        Self::UrlEncoded,
+    ];
+
+    // media types of format. the first one is used as Content-Type of response.
+    fn media_types(&self) -> &'static [&'static str] {
+        match self {
+            Self::Json => &["application/json"],
+            Self::Html => &["text/html"],
+            #[cfg(feature = "urlencoded")]
+            Self::UrlEncoded => &["application/x-www-form-urlencoded"],
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => &[
+                "application/msgpack",
+                "application/x-msgpack",
+                "application/vnd.msgpack",
+            ],
+            #[cfg(feature = "cbor")]
+            Self::Cbor => &["application/cbor"],
+        }
+    }
+
+    fn content_type(&self) -> HeaderValue {
+        match self {
+            Self::Html => TEXT_HTML_UTF8,
+            format => HeaderValue::from_static(format.media_types()[0]),
+        }
+    }
+
+    // html is excluded as it can not be deserialized.
+    fn from_content_type(value: &str) -> Option<Self> {
+        let mime = value.split(';').next().unwrap_or_default().trim();
+        Self::ALL
+            .iter()
+            .filter(|f| **f != Self::Html)
+            .find(|f| f.media_types().iter().any(|m| m.eq_ignore_ascii_case(mime)))
+            .copied()
+    }
+
+    // select format with highest quality in Accept header. earlier format in Self::ALL wins when quality
+    // is equal.
+    fn negotiate(headers: &HeaderMap, html: bool) -> Option<Self> {
+        let ranges = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(MediaRange::parse)
+            .collect::<Vec<_>>();
+
+        if ranges.is_empty() {
+            return Some(Self::Json);
+        }
+
+        let mut selected = None;
+        let mut max = 0.0;
+
+        for format in Self::ALL.iter().filter(|f| html || **f != Self::Html) {
+            let q = format
+                .media_types()
+                .iter()
+                .map(|m| MediaRange::quality(&ranges, m))
+                .fold(0.0, f32::max);
+
+            if q > max {
+                max = q;
+                selected = Some(*format);
+            }
+        }
+
+        selected
+    }
+
+    fn decode<T, C>(&self, bytes: &[u8]) -> Result<T, Error<C>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(Into::into),
+            Self::Html => unreachable!("html format can not be deserialized"),
+            #[cfg(feature = "urlencoded")]
+            Self::UrlEncoded => serde_urlencoded::from_bytes(bytes).map_err(Into::into),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => rmp_serde::from_slice(bytes).map_err(Into::into),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => ciborium::from_reader(bytes).map_err(Into::into),
+        }
+    }
+}
+
+struct MediaRange<'a> {
+    ty: &'a str,
+    sub_ty: &'a str,
+    q: f32,
+}
+
+impl<'a> MediaRange<'a> {
+    fn parse(range: &'a str) -> Option<Self> {
+        let mut params = range.split(';');
+        let (ty, sub_ty) = params.next()?.trim().split_once('/')?;
+        let q = params
+            .find_map(|p| p.trim().strip_prefix("q="))
+            .map(|q| q.trim().parse().unwrap_or(0.0))
+            .unwrap_or(1.0);
+        Some(Self { ty, sub_ty, q })
+    }
+
+    // quality of media type is decided by the most specific media range matching it.
+    fn quality(ranges: &[Self], media_type: &str) -> f32 {
+        let (ty, sub_ty) = media_type.split_once('/').unwrap();
+        ranges
+            .iter()
+            .filter_map(|r| {
+                let specificity = match (r.ty, r.sub_ty) {
+                    ("*", "*") => 0,
+                    (t, "*") if t.eq_ignore_ascii_case(ty) => 1,
+                    (t, s) if t.eq_ignore_ascii_case(ty) && s.eq_ignore_ascii_case(sub_ty) => 2,
+                    _ => return None,
+                };
+                Some((specificity, r.q))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map(|(_, q)| q)
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+mod msgpack {
+    use crate::error::{error_from_service, forward_blank_bad_request, forward_blank_internal};
+
+    error_from_service!(rmp_serde::encode::Error);
+    forward_blank_internal!(rmp_serde::encode::Error);
+
+    error_from_service!(rmp_serde::decode::Error);
+    forward_blank_bad_request!(rmp_serde::decode::Error);
+}
+
+#[cfg(feature = "cbor")]
+mod cbor {
+    use std::io;
+
+    use crate::error::{error_from_service, forward_blank_bad_request, forward_blank_internal};
+
+    error_from_service!(ciborium::ser::Error<io::Error>);
+    forward_blank_internal!(ciborium::ser::Error<io::Error>);
+
+    error_from_service!(ciborium::de::Error<io::Error>);
+    forward_blank_bad_request!(ciborium::de::Error<io::Error>);
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        http::const_header_value::JSON,
+        http::{header::CONTENT_LENGTH, StatusCode},
+        service::Service,
+        test::collect_string_body,
+    };
+
+    use super::*;
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    struct Gacha {
+        credit_card: String,
+    }
+
+    fn gacha() -> Gacha {
+        Gacha {
+            credit_card: String::from("declined"),
+        }
+    }
+
+    #[test]
+    fn respond() {
+        let respond = |accept: Option<&'static str>| {
+            let mut ctx = WebContext::new_test(());
+            let mut ctx = ctx.as_web_ctx();
+            if let Some(accept) = accept {
+                ctx.req_mut()
+                    .headers_mut()
+                    .insert(ACCEPT, HeaderValue::from_static(accept));
+            }
+
+            let res = Negotiate::new(gacha())
+                .html(|g| format!("<p>{}</p>", g.credit_card))
+                .respond(ctx)
+                .now_or_panic();
+
+            res.map(|res| {
+                let content_type = res.headers().get(CONTENT_TYPE).unwrap().clone();
+                let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+                (content_type, body)
+            })
+        };
+
+        let json = (JSON, String::from(r#"{"credit_card":"declined"}"#));
+        let html = (TEXT_HTML_UTF8, String::from("<p>declined</p>"));
+
+        assert_eq!(respond(None).unwrap(), json);
+        assert_eq!(respond(Some("*/*")).unwrap(), json);
+        assert_eq!(respond(Some("text/html")).unwrap(), html);
+        assert_eq!(respond(Some("text/*;q=0.9, application/json;q=0.5")).unwrap(), html);
+        assert_eq!(respond(Some("text/html;q=0.5, */*;q=0.8")).unwrap(), json);
+        assert_eq!(respond(Some("text/html;q=0, */*")).unwrap(), json);
+
+        let mut ctx = WebContext::new_test(());
+        let res = respond(Some("image/png, application/json;q=0")).unwrap_err();
+        let res = Service::call(&res, ctx.as_web_ctx()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+
+        // html is not negotiable without renderer.
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+        ctx.req_mut()
+            .headers_mut()
+            .insert(ACCEPT, HeaderValue::from_static("text/html"));
+        assert!(Negotiate::new(gacha()).respond(ctx).now_or_panic().is_err());
+    }
+
+    #[test]
+    fn extract() {
+        let extract = |content_type: Option<&'static str>, body: &'static str| {
+            let mut ctx = WebContext::new_test(());
+            let mut ctx = ctx.as_web_ctx();
+            if let Some(content_type) = content_type {
+                let headers = ctx.req_mut().headers_mut();
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+                headers.insert(CONTENT_LENGTH, body.len().into());
+            }
+            *ctx.body_borrow_mut() = body.as_bytes().into();
+            let res = Negotiate::<Gacha>::from_request(&ctx).now_or_panic();
+            res.map(Negotiate::into_inner)
+        };
+
+        assert_eq!(
+            extract(Some("application/json; charset=utf-8"), r#"{"credit_card":"declined"}"#).unwrap(),
+            gacha()
+        );
+
+        #[cfg(feature = "urlencoded")]
+        assert_eq!(
+            extract(Some("application/x-www-form-urlencoded"), "credit_card=declined").unwrap(),
+            gacha()
+        );
+
+        let mut ctx = WebContext::new_test(());
+        let err = extract(Some("text/html"), "<p>declined</p>").unwrap_err();
+        let res = Service::call(&err, ctx.as_web_ctx()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        assert!(extract(None, "").is_err());
+    }
+
+    #[cfg(all(feature = "msgpack", feature = "cbor"))]
+    #[test]
+    fn binary_format() {
+        for (accept, content_type) in [
+            ("application/msgpack", "application/msgpack"),
+            ("application/x-msgpack", "application/msgpack"),
+            ("application/cbor", "application/cbor"),
+        ] {
+            let mut ctx = WebContext::new_test(());
+            let mut ctx = ctx.as_web_ctx();
+            ctx.req_mut()
+                .headers_mut()
+                .insert(ACCEPT, HeaderValue::from_static(accept));
+            let res = Negotiate::new(gacha()).respond(ctx).now_or_panic().unwrap();
+            assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), content_type);
+
+            let body = crate::test::collect_body(res.into_body()).now_or_panic().unwrap();
+
+            let mut ctx = WebContext::new_test(());
+            let mut ctx = ctx.as_web_ctx();
+            let headers = ctx.req_mut().headers_mut();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+            headers.insert(CONTENT_LENGTH, body.len().into());
+            *ctx.body_borrow_mut() = body.into();
+            let value = Negotiate::<Gacha>::from_request(&ctx).now_or_panic().unwrap();
+            assert_eq!(value.into_inner(), gacha());
+        }
+    }
+}
@@ -32,6 +32,7 @@ macro_rules! variants {
 }
 
 impl Redirect {
+    variants!(moved_permanently, MOVED_PERMANENTLY);
     variants!(found, FOUND);
     variants!(see_other, SEE_OTHER);
     variants!(temporary, TEMPORARY_REDIRECT);
@@ -0,0 +1,1201 @@
+//! typed header extractor and response generator.
+
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+use base64::engine::{general_purpose::STANDARD, Engine};
+
+use crate::{
+    body::ResponseBody,
+    context::WebContext,
+    error::{Error, HeaderNotFound, InvalidHeaderValue},
+    handler::{FromRequest, Responder},
+    http::{
+        header::{self, HeaderName, HeaderValue},
+        StatusCode, WebResponse,
+    },
+};
+
+/// trait for header that can be parsed from and serialized to [HeaderValue].
+///
+/// # Example
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{handler_service, typed_header::{Header, TypedHeader}},
+/// #   http::header::{HeaderName, HeaderValue, InvalidHeaderValue},
+/// #   App, WebContext,
+/// # };
+/// // user defined header.
+/// struct RequestId(u64);
+///
+/// impl Header for RequestId {
+///     fn name() -> HeaderName {
+///         HeaderName::from_static("x-request-id")
+///     }
+///
+///     fn decode<'a, I>(mut values: I) -> Option<Self>
+///     where
+///         I: Iterator<Item = &'a HeaderValue>,
+///     {
+///         values.next()?.to_str().ok()?.parse().ok().map(RequestId)
+///     }
+///
+///     fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+///         Ok(HeaderValue::from(self.0))
+///     }
+/// }
+///
+/// // request without valid x-request-id header would be rejected with 400 response. and the header
+/// // is echoed back in response.
+/// async fn handler(TypedHeader(id): TypedHeader<RequestId>) -> TypedHeader<RequestId> {
+///     TypedHeader(id)
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "for type infer" }));
+/// ```
+pub trait Header: Sized {
+    /// name of header.
+    fn name() -> HeaderName;
+
+    /// parse header from all values of it in request. `None` is returned when values can not be parsed.
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>;
+
+    /// serialize header to value.
+    fn encode(&self) -> Result<HeaderValue, header::InvalidHeaderValue>;
+}
+
+/// typed header extractor and response generator.
+///
+/// on failure [HeaderNotFound] error would be returned when header is missing and [InvalidHeaderValue]
+/// error would be returned when header can not be parsed. Both generate "400 BadRequest" http response.
+///
+/// # Example
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{handler_service, typed_header::{Authorization, CacheControl, TypedHeader}},
+/// #   App, WebContext,
+/// # };
+/// async fn handler(TypedHeader(auth): TypedHeader<Authorization>) -> (String, TypedHeader<CacheControl>) {
+///     let name = match auth {
+///         Authorization::Basic { username, .. } => username,
+///         _ => String::from("anonymous"),
+///     };
+///
+///     let cache = CacheControl {
+///         no_store: true,
+///         ..Default::default()
+///     };
+///
+///     (name, TypedHeader(cache))
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "for type infer" }));
+/// ```
+#[derive(Clone)]
+pub struct TypedHeader<H>(pub H);
+
+impl<H> fmt::Debug for TypedHeader<H>
+where
+    H: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedHeader").field(&self.0).finish()
+    }
+}
+
+impl<H> Deref for TypedHeader<H> {
+    type Target = H;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<H> DerefMut for TypedHeader<H> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, 'r, C, B, H> FromRequest<'a, WebContext<'r, C, B>> for TypedHeader<H>
+where
+    H: Header,
+{
+    type Type<'b> = TypedHeader<H>;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        let name = H::name();
+        let mut values = ctx.req().headers().get_all(&name).iter().peekable();
+
+        if values.peek().is_none() {
+            return Err(Error::from_service(HeaderNotFound(name)));
+        }
+
+        H::decode(values)
+            .map(TypedHeader)
+            .ok_or_else(|| Error::from_service(InvalidHeaderValue(name)))
+    }
+}
+
+impl<'r, C, B, H> Responder<WebContext<'r, C, B>> for TypedHeader<H>
+where
+    H: Header,
+{
+    type Response = WebResponse;
+    type Error = Error<C>;
+
+    async fn respond(self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let res = ctx.into_response(ResponseBody::empty());
+        Responder::<WebContext<'r, C, B>>::map(self, res)
+    }
+
+    fn map(self, mut res: Self::Response) -> Result<Self::Response, Self::Error> {
+        // header is produced by server and failing to encode it is not client's fault.
+        let value = self
+            .0
+            .encode()
+            .map_err(|_| Error::from(StatusCode::INTERNAL_SERVER_ERROR))?;
+        res.headers_mut().insert(H::name(), value);
+        Ok(res)
+    }
+}
+
+/// `Authorization` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Authorization {
+    /// `Basic` scheme with decoded user credentials.
+    Basic { username: String, password: String },
+    /// `Bearer` scheme with token.
+    Bearer(String),
+    /// other schemes with raw credentials.
+    Other { scheme: String, credentials: String },
+}
+
+impl Header for Authorization {
+    fn name() -> HeaderName {
+        header::AUTHORIZATION
+    }
+
+    fn decode<'a, I>(mut values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let (scheme, credentials) = values.next()?.to_str().ok()?.trim().split_once(' ')?;
+        let credentials = credentials.trim();
+
+        if scheme.eq_ignore_ascii_case("basic") {
+            let decoded = STANDARD.decode(credentials).ok()?;
+            let decoded = String::from_utf8(decoded).ok()?;
+            let (username, password) = decoded.split_once(':')?;
+            Some(Self::Basic {
+                username: username.to_owned(),
+                password: password.to_owned(),
+            })
+        } else if credentials.is_empty() {
+            None
+        } else if scheme.eq_ignore_ascii_case("bearer") {
+            Some(Self::Bearer(credentials.to_owned()))
+        } else {
+            Some(Self::Other {
+                scheme: scheme.to_owned(),
+                credentials: credentials.to_owned(),
+            })
+        }
+    }
+
+    fn encode(&self) -> Result<HeaderValue, header::InvalidHeaderValue> {
+        let value = match self {
+            Self::Basic { username, password } => {
+                format!("Basic {}", STANDARD.encode(format!("{username}:{password}")))
+            }
+            Self::Bearer(token) => format!("Bearer {token}"),
+            Self::Other { scheme, credentials } => format!("{scheme} {credentials}"),
+        };
+        HeaderValue::try_from(value)
+    }
+}
+
+/// `Range` header with `bytes` unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Range(Vec<ByteRange>);
+
+/// a single range of [Range] header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// inclusive range of first and last byte position. ex: `0-499`
+    FromTo(u64, u64),
+    /// range starts from byte position to end. ex: `500-`
+    From(u64),
+    /// last number of bytes. ex: `-500`
+    Last(u64),
+}
+
+impl Range {
+    pub fn new(ranges: impl IntoIterator<Item = ByteRange>) -> Self {
+        Self(ranges.into_iter().collect())
+    }
+
+    pub fn ranges(&self) -> &[ByteRange] {
+        &self.0
+    }
+
+    /// inclusive ranges of first and last byte position that are satisfiable for content with given
+    /// length. unsatisfiable ranges are skipped.
+    pub fn satisfiable(&self, len: u64) -> Vec<(u64, u64)> {
+        self.0.iter().filter_map(|range| range.satisfiable(len)).collect()
+    }
+}
+
+impl ByteRange {
+    /// inclusive range of first and last byte position for content with given length. `None` is
+    /// returned when range is not satisfiable.
+    pub fn satisfiable(&self, len: u64) -> Option<(u64, u64)> {
+        match *self {
+            Self::FromTo(first, last) if first < len => Some((first, last.min(len - 1))),
+            Self::From(first) if first < len => Some((first, len - 1)),
+            Self::Last(n) if n > 0 && len > 0 => Some((len.saturating_sub(n), len - 1)),
+            _ => None,
+        }
+    }
+
+    fn parse(range: &str) -> Option<Self> {
+        let (first, last) = range.trim().split_once('-')?;
+        match (first.trim(), last.trim()) {
+            ("", last) => last.parse().ok().map(Self::Last),
+            (first, "") => first.parse().ok().map(Self::From),
+            (first, last) => {
+                let (first, last) = (first.parse().ok()?, last.parse().ok()?);
+                (first <= last).then_some(Self::FromTo(first, last))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ByteRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::FromTo(first, last) => write!(f, "{first}-{last}"),
+            Self::From(first) => write!(f, "{first}-"),
+            Self::Last(n) => write!(f, "-{n}"),
+        }
+    }
+}
+
+impl Header for Range {
+    fn name() -> HeaderName {
+        header::RANGE
+    }
+
+    fn decode<'a, I>(mut values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let value = values.next()?.to_str().ok()?.trim();
+        let (unit, ranges) = value.split_once('=')?;
+
+        if !unit.trim().eq_ignore_ascii_case("bytes") {
+            return None;
+        }
+
+        let ranges = ranges
+            .split(',')
+            .filter(|range| !range.trim().is_empty())
+            .map(ByteRange::parse)
+            .collect::<Option<Vec<_>>>()?;
+
+        (!ranges.is_empty()).then_some(Self(ranges))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, header::InvalidHeaderValue> {
+        let ranges = self.0.iter().map(ToString::to_string).collect::<Vec<_>>();
+        HeaderValue::try_from(format!("bytes={}", ranges.join(", ")))
+    }
+}
+
+/// entity tag used by [ETag] and [IfNoneMatch] headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityTag {
+    weak: bool,
+    tag: String,
+}
+
+impl EntityTag {
+    /// construct strong entity tag. given tag must not contain double quote.
+    pub fn strong(tag: impl Into<String>) -> Self {
+        Self {
+            weak: false,
+            tag: tag.into(),
+        }
+    }
+
+    /// construct weak entity tag. given tag must not contain double quote.
+    pub fn weak(tag: impl Into<String>) -> Self {
+        Self {
+            weak: true,
+            tag: tag.into(),
+        }
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// opaque tag without quotes and weak indicator.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// strong comparison. both tags must be strong and equal.
+    pub fn strong_eq(&self, other: &Self) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// weak comparison. tags are equal regardless of their weakness.
+    pub fn weak_eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (weak, tag) = match value.strip_prefix("W/") {
+            Some(tag) => (true, tag),
+            None => (false, value),
+        };
+        let tag = tag.strip_prefix('"')?.strip_suffix('"')?;
+
+        if tag.bytes().any(|b| b == b'"' || b < 0x21 || b == 0x7f) {
+            return None;
+        }
+
+        Some(Self {
+            weak,
+            tag: tag.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weak {
+            f.write_str("W/")?;
+        }
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+/// `ETag` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ETag(pub EntityTag);
+
+impl Header for ETag {
+    fn name() -> HeaderName {
+        header::ETAG
+    }
+
+    fn decode<'a, I>(mut values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        EntityTag::parse(values.next()?.to_str().ok()?).map(Self)
+    }
+
+    fn encode(&self) -> Result<HeaderValue, header::InvalidHeaderValue> {
+        HeaderValue::try_from(self.0.to_string())
+    }
+}
+
+/// `If-None-Match` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfNoneMatch {
+    /// `*` matches any current representation.
+    Any,
+    Tags(Vec<EntityTag>),
+}
+
+impl IfNoneMatch {
+    /// check if given entity tag of current representation matches the header with weak comparison.
+    /// `304 Not Modified` is expected to be responded for `GET` and `HEAD` request when it matches.
+    pub fn matches(&self, etag: &EntityTag) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Tags(tags) => tags.iter().any(|tag| tag.weak_eq(etag)),
+        }
+    }
+}
+
+impl Header for IfNoneMatch {
+    fn name() -> HeaderName {
+        header::IF_NONE_MATCH
+    }
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let mut tags = Vec::new();
+
+        for value in values {
+            let value = value.to_str().ok()?;
+            if value.trim() == "*" {
+                return Some(Self::Any);
+            }
+            for tag in split_quoted(value, ',').filter(|tag| !tag.trim().is_empty()) {
+                tags.push(EntityTag::parse(tag)?);
+            }
+        }
+
+        (!tags.is_empty()).then_some(Self::Tags(tags))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, header::InvalidHeaderValue> {
+        match self {
+            Self::Any => Ok(HeaderValue::from_static("*")),
+            Self::Tags(tags) => {
+                let tags = tags.iter().map(ToString::to_string).collect::<Vec<_>>();
+                HeaderValue::try_from(tags.join(", "))
+            }
+        }
+    }
+}
+
+/// `Cache-Control` header. unknown directives are ignored.
+///
+/// `max-stale` directive without value is represented as [Duration::MAX].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    pub no_cache: bool,
+    pub no_store: bool,
+    pub no_transform: bool,
+    pub only_if_cached: bool,
+    pub must_revalidate: bool,
+    pub proxy_revalidate: bool,
+    pub must_understand: bool,
+    pub public: bool,
+    pub private: bool,
+    pub immutable: bool,
+    pub max_age: Option<Duration>,
+    pub s_maxage: Option<Duration>,
+    pub max_stale: Option<Duration>,
+    pub min_fresh: Option<Duration>,
+    pub stale_while_revalidate: Option<Duration>,
+    pub stale_if_error: Option<Duration>,
+}
+
+impl Header for CacheControl {
+    fn name() -> HeaderName {
+        header::CACHE_CONTROL
+    }
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let mut cache = Self::default();
+
+        for value in values {
+            for directive in split_quoted(value.to_str().ok()?, ',') {
+                let (name, arg) = match directive.split_once('=') {
+                    Some((name, arg)) => (name.trim(), Some(unquote(arg)?)),
+                    None => (directive.trim(), None),
+                };
+
+                let secs = || -> Option<Option<Duration>> {
+                    let secs = arg.as_deref()?.parse().ok()?;
+                    Some(Some(Duration::from_secs(secs)))
+                };
+
+                match name.to_ascii_lowercase().as_str() {
+                    "" => {}
+                    "no-cache" => cache.no_cache = true,
+                    "no-store" => cache.no_store = true,
+                    "no-transform" => cache.no_transform = true,
+                    "only-if-cached" => cache.only_if_cached = true,
+                    "must-revalidate" => cache.must_revalidate = true,
+                    "proxy-revalidate" => cache.proxy_revalidate = true,
+                    "must-understand" => cache.must_understand = true,
+                    "public" => cache.public = true,
+                    "private" => cache.private = true,
+                    "immutable" => cache.immutable = true,
+                    "max-age" => cache.max_age = secs()?,
+                    "s-maxage" => cache.s_maxage = secs()?,
+                    "max-stale" if arg.is_none() => cache.max_stale = Some(Duration::MAX),
+                    "max-stale" => cache.max_stale = secs()?,
+                    "min-fresh" => cache.min_fresh = secs()?,
+                    "stale-while-revalidate" => cache.stale_while_revalidate = secs()?,
+                    "stale-if-error" => cache.stale_if_error = secs()?,
+                    _ => {}
+                }
+            }
+        }
+
+        Some(cache)
+    }
+
+    fn encode(&self) -> Result<HeaderValue, header::InvalidHeaderValue> {
+        let flags = [
+            (self.no_cache, "no-cache"),
+            (self.no_store, "no-store"),
+            (self.no_transform, "no-transform"),
+            (self.only_if_cached, "only-if-cached"),
+            (self.must_revalidate, "must-revalidate"),
+            (self.proxy_revalidate, "proxy-revalidate"),
+            (self.must_understand, "must-understand"),
+            (self.public, "public"),
+            (self.private, "private"),
+            (self.immutable, "immutable"),
+        ];
+
+        let secs = [
+            (self.max_age, "max-age"),
+            (self.s_maxage, "s-maxage"),
+            (self.max_stale, "max-stale"),
+            (self.min_fresh, "min-fresh"),
+            (self.stale_while_revalidate, "stale-while-revalidate"),
+            (self.stale_if_error, "stale-if-error"),
+        ];
+
+        let directives = flags
+            .into_iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, name)| name.to_owned())
+            .chain(secs.into_iter().filter_map(|(secs, name)| match secs? {
+                Duration::MAX => Some(name.to_owned()),
+                secs => Some(format!("{name}={}", secs.as_secs())),
+            }))
+            .collect::<Vec<_>>();
+
+        HeaderValue::try_from(directives.join(", "))
+    }
+}
+
+/// `Accept-Language` header. languages are sorted by quality in descending order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptLanguage(Vec<(String, f32)>);
+
+impl AcceptLanguage {
+    pub fn new<S>(languages: impl IntoIterator<Item = (S, f32)>) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut languages = languages
+            .into_iter()
+            .map(|(lang, q)| (lang.into(), q))
+            .collect::<Vec<_>>();
+        languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        Self(languages)
+    }
+
+    /// iterate over language ranges and their quality in order of preference.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.0.iter().map(|(lang, q)| (lang.as_str(), *q))
+    }
+
+    /// select the most preferred language from given available language tags. language range matches
+    /// tag that equals to it or starts with it followed by `-`. ex: `en` matches `en-US`.
+    pub fn preferred<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        self.iter().filter(|(_, q)| *q > 0.0).find_map(|(range, _)| {
+            available.iter().copied().find(|tag| {
+                range == "*"
+                    || tag.eq_ignore_ascii_case(range)
+                    || (tag.len() > range.len()
+                        && tag.as_bytes()[range.len()] == b'-'
+                        && tag[..range.len()].eq_ignore_ascii_case(range))
+            })
+        })
+    }
+}
+
+impl Header for AcceptLanguage {
+    fn name() -> HeaderName {
+        header::ACCEPT_LANGUAGE
+    }
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let mut languages = Vec::new();
+
+        for value in values {
+            for item in value.to_str().ok()?.split(',').filter(|item| !item.trim().is_empty()) {
+                let mut params = item.split(';');
+                let lang = params.next()?.trim();
+
+                if lang.is_empty()
+                    || !lang
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'*')
+                {
+                    return None;
+                }
+
+                let q = match params.find_map(|p| p.trim().strip_prefix("q=")) {
+                    Some(q) => q.trim().parse().ok().filter(|q| (0.0..=1.0).contains(q))?,
+                    None => 1.0,
+                };
+
+                languages.push((lang, q));
+            }
+        }
+
+        (!languages.is_empty()).then(|| Self::new(languages))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, header::InvalidHeaderValue> {
+        let languages = self
+            .iter()
+            .map(|(lang, q)| {
+                if q == 1.0 {
+                    lang.to_owned()
+                } else {
+                    format!("{lang};q={q}")
+                }
+            })
+            .collect::<Vec<_>>();
+        HeaderValue::try_from(languages.join(", "))
+    }
+}
+
+/// `Forwarded` header. elements are in the order of proxies appending them and the first one is
+/// closest to client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Forwarded(Vec<ForwardedElement>);
+
+/// a single element of [Forwarded] header added by one proxy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForwardedElement(Vec<(String, String)>);
+
+impl Forwarded {
+    pub fn new(elements: impl IntoIterator<Item = ForwardedElement>) -> Self {
+        Self(elements.into_iter().collect())
+    }
+
+    pub fn elements(&self) -> &[ForwardedElement] {
+        &self.0
+    }
+
+    /// `for` parameter of the first element. it's the address of client when all proxies are trusted.
+    pub fn client(&self) -> Option<&str> {
+        self.0.first().and_then(ForwardedElement::forwarded_for)
+    }
+}
+
+impl ForwardedElement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add parameter to element.
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.0.push((name.into(), value.into()));
+        self
+    }
+
+    /// value of parameter with given name. name is case insensitive.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// `for` parameter. the node making request to proxy.
+    pub fn forwarded_for(&self) -> Option<&str> {
+        self.get("for")
+    }
+
+    /// `by` parameter. the interface where request came in to proxy.
+    pub fn by(&self) -> Option<&str> {
+        self.get("by")
+    }
+
+    /// `host` parameter. the `Host` header received by proxy.
+    pub fn host(&self) -> Option<&str> {
+        self.get("host")
+    }
+
+    /// `proto` parameter. the protocol used to make request to proxy.
+    pub fn proto(&self) -> Option<&str> {
+        self.get("proto")
+    }
+}
+
+impl Header for Forwarded {
+    fn name() -> HeaderName {
+        header::FORWARDED
+    }
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let mut elements = Vec::new();
+
+        for value in values {
+            for element in split_quoted(value.to_str().ok()?, ',') {
+                let mut params = Vec::new();
+                for pair in split_quoted(element, ';').filter(|pair| !pair.trim().is_empty()) {
+                    let (name, value) = pair.split_once('=')?;
+                    let name = name.trim();
+                    if !is_token(name) {
+                        return None;
+                    }
+                    params.push((name.to_owned(), unquote(value)?));
+                }
+                if !params.is_empty() {
+                    elements.push(ForwardedElement(params));
+                }
+            }
+        }
+
+        (!elements.is_empty()).then_some(Self(elements))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, header::InvalidHeaderValue> {
+        let elements = self
+            .0
+            .iter()
+            .map(|element| {
+                let params = element
+                    .0
+                    .iter()
+                    .map(|(name, value)| format!("{name}={}", quote(value)))
+                    .collect::<Vec<_>>();
+                params.join(";")
+            })
+            .collect::<Vec<_>>();
+        HeaderValue::try_from(elements.join(", "))
+    }
+}
+
+/// `Content-Disposition` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentDisposition {
+    disposition: DispositionType,
+    params: Vec<(String, String)>,
+}
+
+/// disposition type of [ContentDisposition] header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DispositionType {
+    Inline,
+    Attachment,
+    FormData,
+    Other(String),
+}
+
+impl ContentDisposition {
+    pub fn new(disposition: DispositionType) -> Self {
+        Self {
+            disposition,
+            params: Vec::new(),
+        }
+    }
+
+    /// `attachment` disposition with given file name.
+    pub fn attachment(filename: impl Into<String>) -> Self {
+        Self::new(DispositionType::Attachment).param("filename", filename)
+    }
+
+    /// add parameter to header. non ascii value is encoded with `UTF-8` charset as extended parameter.
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn disposition(&self) -> &DispositionType {
+        &self.disposition
+    }
+
+    /// value of parameter with given name. name is case insensitive. extended parameter (ex: `filename*`)
+    /// is decoded and takes precedence over regular one.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// `filename` parameter.
+    pub fn filename(&self) -> Option<&str> {
+        self.get("filename")
+    }
+
+    /// `name` parameter of `form-data` disposition.
+    pub fn form_name(&self) -> Option<&str> {
+        self.get("name")
+    }
+}
+
+impl Header for ContentDisposition {
+    fn name() -> HeaderName {
+        header::CONTENT_DISPOSITION
+    }
+
+    fn decode<'a, I>(mut values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let value = values.next()?;
+        // file name can be utf-8 encoded in the wild.
+        let value = core::str::from_utf8(value.as_bytes()).ok()?;
+        let mut items = split_quoted(value, ';');
+
+        let disposition = items.next()?.trim();
+        let disposition = match disposition.to_ascii_lowercase().as_str() {
+            "inline" => DispositionType::Inline,
+            "attachment" => DispositionType::Attachment,
+            "form-data" => DispositionType::FormData,
+            _ if is_token(disposition) => DispositionType::Other(disposition.to_owned()),
+            _ => return None,
+        };
+
+        let mut params = Vec::<(String, String)>::new();
+
+        for item in items.filter(|item| !item.trim().is_empty()) {
+            let (name, value) = item.split_once('=')?;
+            let name = name.trim();
+
+            match name.strip_suffix('*') {
+                Some(name) => {
+                    let value = decode_ext_value(value.trim())?;
+                    // extended parameter takes precedence.
+                    params.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+                    params.push((name.to_owned(), value));
+                }
+                None if params.iter().any(|(n, _)| n.eq_ignore_ascii_case(name)) => {}
+                None => params.push((name.to_owned(), unquote(value)?)),
+            }
+        }
+
+        Some(Self { disposition, params })
+    }
+
+    fn encode(&self) -> Result<HeaderValue, header::InvalidHeaderValue> {
+        let mut value = String::from(match self.disposition {
+            DispositionType::Inline => "inline",
+            DispositionType::Attachment => "attachment",
+            DispositionType::FormData => "form-data",
+            DispositionType::Other(ref other) => other,
+        });
+
+        for (name, v) in self.params.iter() {
+            if v.is_ascii() {
+                value.push_str(&format!("; {name}={}", quote(v)));
+            } else {
+                value.push_str(&format!("; {name}*=UTF-8''{}", encode_ext_value(v)));
+            }
+        }
+
+        HeaderValue::try_from(value)
+    }
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+// split by separator that is not in quoted string.
+fn split_quoted(s: &str, sep: char) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+    let mut escaped = false;
+    s.split(move |c: char| {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == sep && !quoted => return true,
+            _ => {}
+        }
+        false
+    })
+}
+
+// parse token or quoted string.
+fn unquote(s: &str) -> Option<String> {
+    let s = s.trim();
+
+    let Some(quoted) = s.strip_prefix('"') else {
+        return is_token(s).then(|| s.to_owned());
+    };
+
+    let mut res = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+
+    loop {
+        match chars.next()? {
+            '\\' => res.push(chars.next()?),
+            '"' => return chars.as_str().is_empty().then_some(res),
+            c => res.push(c),
+        }
+    }
+}
+
+// produce token or quoted string.
+fn quote(s: &str) -> String {
+    if is_token(s) {
+        return s.to_owned();
+    }
+
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res.push('"');
+    res
+}
+
+// decode extended parameter value in the form of charset'language'percent-encoded. only UTF-8 and
+// ISO-8859-1 charsets are supported.
+fn decode_ext_value(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let (charset, _, encoded) = (parts.next()?, parts.next()?, parts.next()?);
+
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut iter = encoded.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(core::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+
+    if charset.eq_ignore_ascii_case("utf-8") {
+        String::from_utf8(bytes).ok()
+    } else if charset.eq_ignore_ascii_case("iso-8859-1") {
+        Some(bytes.into_iter().map(char::from).collect())
+    } else {
+        None
+    }
+}
+
+fn encode_ext_value(value: &str) -> String {
+    let mut res = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+            res.push(char::from(b));
+        } else {
+            res.push_str(&format!("%{b:02X}"));
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::service::Service;
+
+    use super::*;
+
+    fn decode<H: Header>(values: &[&'static str]) -> Option<H> {
+        let values = values.iter().copied().map(HeaderValue::from_static).collect::<Vec<_>>();
+        H::decode(values.iter())
+    }
+
+    fn round_trip<H: Header + fmt::Debug + PartialEq>(header: H) {
+        let value = header.encode().unwrap();
+        assert_eq!(H::decode([value].iter()).unwrap(), header);
+    }
+
+    #[test]
+    fn authorization() {
+        assert_eq!(
+            decode::<Authorization>(&["Basic Zm9vOmJhcjpiYXo="]).unwrap(),
+            Authorization::Basic {
+                username: String::from("foo"),
+                password: String::from("bar:baz")
+            }
+        );
+        assert_eq!(
+            decode::<Authorization>(&["bearer  token"]).unwrap(),
+            Authorization::Bearer(String::from("token"))
+        );
+        assert!(decode::<Authorization>(&["Basic !!!"]).is_none());
+        assert!(decode::<Authorization>(&["Bearer"]).is_none());
+
+        round_trip(Authorization::Basic {
+            username: String::from("996"),
+            password: String::from("251"),
+        });
+        round_trip(Authorization::Other {
+            scheme: String::from("Digest"),
+            credentials: String::from("username=\"foo\""),
+        });
+    }
+
+    #[test]
+    fn range() {
+        let range = decode::<Range>(&["bytes=0-499, 500-, -200"]).unwrap();
+        assert_eq!(
+            range.ranges(),
+            [ByteRange::FromTo(0, 499), ByteRange::From(500), ByteRange::Last(200)]
+        );
+        assert_eq!(range.satisfiable(300), [(0, 299), (100, 299)]);
+        assert_eq!(range.satisfiable(1000), [(0, 499), (500, 999), (800, 999)]);
+
+        assert!(decode::<Range>(&["bytes=5-1"]).is_none());
+        assert!(decode::<Range>(&["items=0-1"]).is_none());
+        assert!(decode::<Range>(&["bytes="]).is_none());
+
+        round_trip(range);
+    }
+
+    #[test]
+    fn etag() {
+        let tags = decode::<IfNoneMatch>(&[r#""a,b", W/"c""#, r#""d""#]).unwrap();
+        assert_eq!(
+            tags,
+            IfNoneMatch::Tags(vec![
+                EntityTag::strong("a,b"),
+                EntityTag::weak("c"),
+                EntityTag::strong("d")
+            ])
+        );
+        assert!(tags.matches(&EntityTag::strong("c")));
+        assert!(!tags.matches(&EntityTag::strong("e")));
+        assert!(decode::<IfNoneMatch>(&["*"]).unwrap().matches(&EntityTag::weak("e")));
+        assert!(decode::<IfNoneMatch>(&["a"]).is_none());
+
+        assert!(!EntityTag::weak("a").strong_eq(&EntityTag::weak("a")));
+        assert!(EntityTag::weak("a").weak_eq(&EntityTag::strong("a")));
+
+        round_trip(tags);
+        round_trip(ETag(EntityTag::weak("996")));
+    }
+
+    #[test]
+    fn cache_control() {
+        let cache =
+            decode::<CacheControl>(&["no-cache, max-age=\"60\"", "Private, max-stale, community=\"UCI\""]).unwrap();
+        assert_eq!(
+            cache,
+            CacheControl {
+                no_cache: true,
+                private: true,
+                max_age: Some(Duration::from_secs(60)),
+                max_stale: Some(Duration::MAX),
+                ..Default::default()
+            }
+        );
+        assert!(decode::<CacheControl>(&["max-age=abc"]).is_none());
+
+        assert_eq!(cache.encode().unwrap(), "no-cache, private, max-age=60, max-stale");
+        round_trip(cache);
+    }
+
+    #[test]
+    fn accept_language() {
+        let lang = decode::<AcceptLanguage>(&["fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5"]).unwrap();
+        assert_eq!(lang.iter().next(), Some(("fr-CH", 1.0)));
+        assert_eq!(lang.preferred(&["en-US", "fr"]), Some("fr"));
+        assert_eq!(lang.preferred(&["de", "en-GB"]), Some("en-GB"));
+        assert_eq!(lang.preferred(&["zh"]), Some("zh"));
+
+        let lang = decode::<AcceptLanguage>(&["en;q=0.5, de"]).unwrap();
+        assert_eq!(lang.iter().collect::<Vec<_>>(), [("de", 1.0), ("en", 0.5)]);
+        assert_eq!(lang.preferred(&["zh"]), None);
+
+        assert!(decode::<AcceptLanguage>(&["en;q=2"]).is_none());
+        assert!(decode::<AcceptLanguage>(&["en us"]).is_none());
+
+        round_trip(lang);
+    }
+
+    #[test]
+    fn forwarded() {
+        let forwarded = decode::<Forwarded>(&[
+            r#"for="[2001:db8:cafe::17]:4711";proto=https, For=192.0.2.43"#,
+            "by=203.0.113.43",
+        ])
+        .unwrap();
+        assert_eq!(forwarded.client(), Some("[2001:db8:cafe::17]:4711"));
+        assert_eq!(forwarded.elements()[0].proto(), Some("https"));
+        assert_eq!(forwarded.elements()[1].forwarded_for(), Some("192.0.2.43"));
+        assert_eq!(forwarded.elements()[2].by(), Some("203.0.113.43"));
+        assert!(decode::<Forwarded>(&["for"]).is_none());
+        assert!(decode::<Forwarded>(&["for=\"unclosed"]).is_none());
+
+        round_trip(forwarded);
+        round_trip(Forwarded::new([ForwardedElement::new()
+            .param("for", "unknown")
+            .param("host", "example.com")]));
+    }
+
+    #[test]
+    fn content_disposition() {
+        let disposition = decode::<ContentDisposition>(&[
+            r#"attachment; filename="EURO rates.txt"; filename*=UTF-8''%e2%82%ac%20rates.txt"#,
+        ])
+        .unwrap();
+        assert_eq!(disposition.disposition(), &DispositionType::Attachment);
+        assert_eq!(disposition.filename(), Some("€ rates.txt"));
+
+        let disposition = decode::<ContentDisposition>(&[r#"form-data; name="field\"1""#]).unwrap();
+        assert_eq!(disposition.disposition(), &DispositionType::FormData);
+        assert_eq!(disposition.form_name(), Some("field\"1"));
+
+        assert!(decode::<ContentDisposition>(&["attachment; filename"]).is_none());
+
+        let disposition = ContentDisposition::attachment("€ rates.txt");
+        assert_eq!(
+            disposition.encode().unwrap(),
+            "attachment; filename*=UTF-8''%E2%82%AC%20rates.txt"
+        );
+        round_trip(disposition);
+    }
+
+    #[test]
+    fn extract() {
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+
+        let status = |ctx: &WebContext<'_>| {
+            let err = TypedHeader::<Authorization>::from_request(ctx)
+                .now_or_panic()
+                .unwrap_err();
+            let mut ctx = WebContext::new_test(());
+            let res = Service::call(&err, ctx.as_web_ctx()).now_or_panic().unwrap();
+            res.status()
+        };
+
+        assert_eq!(status(&ctx), StatusCode::BAD_REQUEST);
+
+        ctx.req_mut()
+            .headers_mut()
+            .insert(header::AUTHORIZATION, HeaderValue::from_static("Basic ???"));
+        assert_eq!(status(&ctx), StatusCode::BAD_REQUEST);
+
+        ctx.req_mut()
+            .headers_mut()
+            .insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer 996"));
+        let TypedHeader(auth) = TypedHeader::<Authorization>::from_request(&ctx).now_or_panic().unwrap();
+        assert_eq!(auth, Authorization::Bearer(String::from("996")));
+
+        let res = TypedHeader(ETag(EntityTag::strong("251")))
+            .respond(ctx)
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.headers().get(header::ETAG).unwrap(), "\"251\"");
+    }
+}
@@ -274,6 +274,12 @@ pub mod middleware;
 pub mod service;
 pub mod test;
 
+#[cfg(feature = "openapi")]
+pub mod openapi;
+
+#[cfg(feature = "validate")]
+pub mod validate;
+
 #[cfg(feature = "codegen")]
 pub mod codegen {
     //! macro code generation module.
@@ -320,9 +326,43 @@ pub mod codegen {
     /// a hidden module for macro to access public types that are not framework user facing.
     pub mod __private {
         pub use xitca_http::util::service::router::{IntoObject, RouteObject, RouterMapErr, TypedRoute};
+
+        pub use crate::__route_document as document;
     }
 }
 
+// describe operation of route generated by route macro. route is passed through as is when openapi feature
+// is not enabled.
+#[cfg(all(feature = "codegen", feature = "openapi"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __route_document {
+    ($route: expr, $id: expr, $summary: expr, [$($arg: ty),*], $res: ty) => {{
+        #[allow(unused_imports)]
+        use $crate::openapi::__private::{
+            DescribeInput as _, DescribeInputFallback as _, DescribeOutput as _, DescribeOutputFallback as _, Probe,
+        };
+
+        let mut op = $crate::openapi::Operation::new().operation_id($id);
+        if let ::core::option::Option::Some(summary) = $summary {
+            op = op.summary(summary);
+        }
+        $((&Probe::<$arg>::new()).describe_input(&mut op);)*
+        (&Probe::<$res>::new()).describe_output(&mut op);
+
+        $crate::openapi::Documented::new($route, op)
+    }};
+}
+
+#[cfg(all(feature = "codegen", not(feature = "openapi")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __route_document {
+    ($route: expr, $($rest: tt)*) => {
+        $route
+    };
+}
+
 pub mod http {
     //! http types
 
@@ -360,7 +400,11 @@ pub mod route {
     //! # Ok(())
     //! # }
     //! ```
-    pub use xitca_http::util::service::route::{connect, delete, get, head, options, patch, post, put, trace, Route};
+    pub use xitca_http::util::service::{
+        guard,
+        route::{connect, delete, get, head, options, patch, post, put, trace, Route},
+        router::RouteInfo,
+    };
 }
 
 pub use app::{App, AppObject, NestApp};
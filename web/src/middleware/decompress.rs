@@ -63,7 +63,7 @@ mod service {
             let mut req = req.map(|_| ext);
 
             self.0
-                .call(WebContext::new(&mut req, &mut body, state))
+                .call(WebContext::new(&mut req, &mut body, state).with_names(ctx.names))
                 .await
                 .map_err(|e| {
                     // restore original body as error path of other services may have use of it.
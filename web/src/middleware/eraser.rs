@@ -138,8 +138,11 @@ mod service {
         async fn call(&self, mut ctx: WebContext<'r, C, ReqB>) -> Result<Self::Response, Self::Error> {
             let body = ctx.take_body_mut();
             let mut body = RefCell::new(RequestBody::Unknown(BoxBody::new(body)));
-            let WebContext { req, ctx, .. } = ctx;
-            let res = self.service.call(WebContext::new(req, &mut body, ctx)).await?;
+            let WebContext { req, ctx, names, .. } = ctx;
+            let res = self
+                .service
+                .call(WebContext::new(req, &mut body, ctx).with_names(names))
+                .await?;
             Ok(res.map(ResponseBody::box_stream))
         }
     }
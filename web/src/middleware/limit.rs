@@ -80,7 +80,7 @@ where
         let mut req = Request::from_parts(parts, ext);
 
         self.service
-            .call(WebContext::new(&mut req, &mut body, state))
+            .call(WebContext::new(&mut req, &mut body, state).with_names(ctx.names))
             .await
             .map_err(|e| {
                 let body = body.into_inner().into_inner();
@@ -92,6 +92,7 @@ mod service {
         async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
             let func = self.func.clone();
             let state = ctx.state().clone();
+            let names = ctx.names.cloned();
             let mut req = mem::take(ctx.req_mut());
 
             let (tx, mut rx) = unbounded_channel();
@@ -100,7 +101,7 @@ mod service {
             let mut next = Next { tx, rx: rx2 };
             let handle = tokio::task::spawn_blocking(move || {
                 let mut body = RefCell::new(RequestBody::None);
-                let ctx = WebContext::new(&mut req, &mut body, &state);
+                let ctx = WebContext::new(&mut req, &mut body, &state).with_names(names.as_ref());
                 func(&mut next, ctx)
             });
 
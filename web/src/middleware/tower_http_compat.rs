@@ -103,13 +103,14 @@ mod compat_layer {
         fn call(&mut self, req: Request<CompatReqBody<RequestExt<ReqB>, C>>) -> Self::Future {
             let service = self.0.clone();
             Box::pin(async move {
-                let (parts, body) = req.into_parts();
+                let (parts, mut body) = req.into_parts();
+                let names = body.names.take();
                 let (body, ctx) = body.into_parts();
                 let (ext, body) = body.replace_body(());
 
                 let mut req = Request::from_parts(parts, ext);
                 let mut body = RefCell::new(body);
-                let req = WebContext::new(&mut req, &mut body, &ctx);
+                let req = WebContext::new(&mut req, &mut body, &ctx).with_names(names.as_ref());
 
                 service.call(req).await.map(|res| res.map(CompatResBody::new))
             })
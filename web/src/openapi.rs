@@ -0,0 +1,849 @@
+//! OpenAPI 3 document generation.
+//!
+//! Document is generated from information of routes registered to [App](crate::App). Operation of route
+//! is described by wrapping route service with [Documented] or by using [route](crate::codegen::route)
+//! macro where request and response schemas are derived from handler function's extractor and responder
+//! types.
+//!
+//! # Example
+//! ```rust
+//! # use xitca_web::{
+//! #   handler::{handler_service, json::Json},
+//! #   openapi::{object, Documented, OpenApi, Operation, Schema, Value},
+//! #   route::post,
+//! #   App, NestApp,
+//! # };
+//! struct User {
+//!     name: String,
+//!     age: Option<u8>,
+//! }
+//!
+//! // describe schema of type. with codegen feature it can be derived by #[derive(Schema)].
+//! impl Schema for User {
+//!     fn schema() -> Value {
+//!         object([("name", String::schema(), true), ("age", u8::schema(), false)])
+//!     }
+//! }
+//!
+//! let mut op = Operation::new().operation_id("create_user");
+//! op.request_body("application/json", User::schema());
+//! op.response(201, None, None);
+//!
+//! let app: NestApp<()> = App::new()
+//!     .at("/users", Documented::new(post(handler_service(|| async { "" })), op))
+//!     // serve generated document as json at given path.
+//!     .openapi("/openapi.json", OpenApi::new("users", "1.0.0"));
+//! ```
+
+use core::marker::PhantomData;
+
+use std::collections::{BTreeMap, HashMap};
+
+use xitca_http::util::service::router::{PathGen, RouteGen, RouteInfo};
+
+use crate::{
+    app::split_constraint,
+    handler::{html::Html, json::Json, text::Text},
+    http::Method,
+    service::Service,
+};
+
+pub use serde_json::{Map, Value};
+
+#[cfg(feature = "codegen")]
+/// Derive macro for [Schema] trait. Struct with named fields and enum with only unit variants are supported.
+/// Fields with [Option] type are not required in generated schema.
+///
+/// # Example
+/// ```rust
+/// # use xitca_web::openapi::Schema;
+/// #[derive(Schema)]
+/// struct User {
+///     name: String,
+///     role: Role,
+///     nick_name: Option<String>,
+/// }
+///
+/// #[derive(Schema)]
+/// enum Role {
+///     Admin,
+///     Guest,
+/// }
+///
+/// let schema = User::schema();
+/// assert_eq!(schema["required"], xitca_web::openapi::Value::from(["name", "role"]));
+/// ```
+pub use xitca_codegen::Schema;
+
+/// trait for types that can be described by a JSON schema object of OpenAPI document.
+pub trait Schema {
+    /// schema object of type.
+    fn schema() -> Value;
+}
+
+/// helper function for constructing schema of object with iterator of (field name, schema, required) tuple.
+pub fn object<I>(fields: I) -> Value
+where
+    I: IntoIterator<Item = (&'static str, Value, bool)>,
+{
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+
+    for (name, schema, req) in fields {
+        if req {
+            required.push(Value::from(name));
+        }
+        properties.insert(name.into(), schema);
+    }
+
+    let mut obj = Map::new();
+    obj.insert("type".into(), "object".into());
+    obj.insert("properties".into(), Value::Object(properties));
+    if !required.is_empty() {
+        obj.insert("required".into(), Value::Array(required));
+    }
+    Value::Object(obj)
+}
+
+/// helper function for constructing schema of string with enumerated values.
+pub fn string_enum(variants: &[&'static str]) -> Value {
+    serde_json::json!({ "type": "string", "enum": variants })
+}
+
+fn typed(ty: &str) -> Value {
+    serde_json::json!({ "type": ty })
+}
+
+macro_rules! schema_impl {
+    ($ty: literal, $($t: ty),*) => {
+        $(
+            impl Schema for $t {
+                fn schema() -> Value {
+                    typed($ty)
+                }
+            }
+        )*
+    };
+}
+
+schema_impl!("boolean", bool);
+schema_impl!("integer", i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+schema_impl!("number", f32, f64);
+schema_impl!("string", String, str, char);
+
+impl<T> Schema for &T
+where
+    T: Schema + ?Sized,
+{
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T> Schema for Box<T>
+where
+    T: Schema + ?Sized,
+{
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T> Schema for Option<T>
+where
+    T: Schema,
+{
+    fn schema() -> Value {
+        let mut schema = T::schema();
+        if let Value::Object(ref mut obj) = schema {
+            obj.insert("nullable".into(), true.into());
+        }
+        schema
+    }
+}
+
+impl<T> Schema for [T]
+where
+    T: Schema,
+{
+    fn schema() -> Value {
+        serde_json::json!({ "type": "array", "items": T::schema() })
+    }
+}
+
+impl<T> Schema for Vec<T>
+where
+    T: Schema,
+{
+    fn schema() -> Value {
+        <[T]>::schema()
+    }
+}
+
+impl<T> Schema for HashMap<String, T>
+where
+    T: Schema,
+{
+    fn schema() -> Value {
+        serde_json::json!({ "type": "object", "additionalProperties": T::schema() })
+    }
+}
+
+impl<T> Schema for BTreeMap<String, T>
+where
+    T: Schema,
+{
+    fn schema() -> Value {
+        HashMap::<String, T>::schema()
+    }
+}
+
+impl Schema for Value {
+    fn schema() -> Value {
+        Value::Object(Map::new())
+    }
+}
+
+/// trait for request extractor types that can describe their input to [Operation].
+pub trait OperationInput {
+    fn operation_input(op: &mut Operation);
+}
+
+/// trait for responder types that can describe their output to [Operation].
+pub trait OperationOutput {
+    fn operation_output(op: &mut Operation);
+}
+
+impl<T, const LIMIT: usize> OperationInput for Json<T, LIMIT>
+where
+    T: Schema,
+{
+    fn operation_input(op: &mut Operation) {
+        op.request_body("application/json", T::schema());
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl<T, const LIMIT: usize> OperationInput for crate::handler::form::Form<T, LIMIT>
+where
+    T: Schema,
+{
+    fn operation_input(op: &mut Operation) {
+        op.request_body("application/x-www-form-urlencoded", T::schema());
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl<T> OperationInput for crate::handler::query::Query<T>
+where
+    T: Schema,
+{
+    fn operation_input(op: &mut Operation) {
+        fields_into(op, "query", T::schema());
+    }
+}
+
+#[cfg(feature = "params")]
+impl<T> OperationInput for crate::handler::params::Params<T>
+where
+    T: Schema,
+{
+    fn operation_input(op: &mut Operation) {
+        fields_into(op, "path", T::schema());
+    }
+}
+
+// split properties of object schema into individual parameters.
+#[cfg(any(feature = "urlencoded", feature = "params"))]
+fn fields_into(op: &mut Operation, location: &'static str, schema: Value) {
+    let Value::Object(mut obj) = schema else { return };
+
+    let required = match obj.remove("required") {
+        Some(Value::Array(required)) => required,
+        _ => Vec::new(),
+    };
+
+    if let Some(Value::Object(properties)) = obj.remove("properties") {
+        for (name, schema) in properties {
+            // path parameters are always required.
+            let req = location == "path" || required.iter().any(|r| r.as_str() == Some(name.as_str()));
+            op.parameter(name, location, req, schema);
+        }
+    }
+}
+
+impl<T> OperationOutput for Json<T>
+where
+    T: Schema,
+{
+    fn operation_output(op: &mut Operation) {
+        op.response(200, Some("application/json"), Some(T::schema()));
+    }
+}
+
+impl OperationOutput for Value {
+    fn operation_output(op: &mut Operation) {
+        op.response(200, Some("application/json"), Some(Value::schema()));
+    }
+}
+
+impl OperationOutput for String {
+    fn operation_output(op: &mut Operation) {
+        op.response(200, Some("text/plain; charset=utf-8"), Some(String::schema()));
+    }
+}
+
+impl OperationOutput for &'static str {
+    fn operation_output(op: &mut Operation) {
+        String::operation_output(op)
+    }
+}
+
+impl<T> OperationOutput for Text<T> {
+    fn operation_output(op: &mut Operation) {
+        String::operation_output(op)
+    }
+}
+
+impl<T> OperationOutput for Html<T> {
+    fn operation_output(op: &mut Operation) {
+        op.response(200, Some("text/html; charset=utf-8"), Some(String::schema()));
+    }
+}
+
+impl<T, E> OperationOutput for Result<T, E>
+where
+    T: OperationOutput,
+{
+    fn operation_output(op: &mut Operation) {
+        T::operation_output(op)
+    }
+}
+
+/// description of an operation of route.
+#[derive(Clone, Debug, Default)]
+pub struct Operation {
+    operation_id: Option<String>,
+    summary: Option<String>,
+    parameters: Vec<Value>,
+    request_body: Option<Map<String, Value>>,
+    responses: Map<String, Value>,
+}
+
+impl Operation {
+    /// construct an empty operation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// set unique identifier of operation.
+    pub fn operation_id(mut self, id: impl Into<String>) -> Self {
+        self.operation_id = Some(id.into());
+        self
+    }
+
+    /// set short summary of operation.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// add parameter to operation. location can be "path", "query", "header" or "cookie".
+    /// parameter with the same name and location as existing one would be ignored.
+    pub fn parameter(&mut self, name: impl Into<String>, location: &str, required: bool, schema: Value) -> &mut Self {
+        let name = name.into();
+        let exists = self
+            .parameters
+            .iter()
+            .any(|p| p["name"].as_str() == Some(name.as_str()) && p["in"].as_str() == Some(location));
+        if !exists {
+            self.parameters.push(serde_json::json!({
+                "name": name,
+                "in": location,
+                "required": required,
+                "schema": schema,
+            }));
+        }
+        self
+    }
+
+    /// add request body of given content type to operation.
+    pub fn request_body(&mut self, content_type: &str, schema: Value) -> &mut Self {
+        self.request_body
+            .get_or_insert_with(Map::new)
+            .insert(content_type.into(), serde_json::json!({ "schema": schema }));
+        self
+    }
+
+    /// add response of given status code to operation. content type and schema are optional for
+    /// response without body.
+    pub fn response(&mut self, status: u16, content_type: Option<&str>, schema: Option<Value>) -> &mut Self {
+        let res = self
+            .responses
+            .entry(status.to_string())
+            .or_insert_with(|| serde_json::json!({ "description": status_description(status) }));
+
+        if let Some(content_type) = content_type {
+            let mut media = Map::new();
+            if let Some(schema) = schema {
+                media.insert("schema".into(), schema);
+            }
+            res.as_object_mut()
+                .unwrap()
+                .entry("content")
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .unwrap()
+                .insert(content_type.into(), Value::Object(media));
+        }
+
+        self
+    }
+
+    fn to_value(&self, path_params: &[(String, Value)]) -> Value {
+        let mut obj = Map::new();
+
+        if let Some(ref id) = self.operation_id {
+            obj.insert("operationId".into(), id.as_str().into());
+        }
+
+        if let Some(ref summary) = self.summary {
+            obj.insert("summary".into(), summary.as_str().into());
+        }
+
+        let mut parameters = self.parameters.clone();
+        for (name, schema) in path_params {
+            let exists = parameters
+                .iter()
+                .any(|p| p["name"].as_str() == Some(name.as_str()) && p["in"] == "path");
+            if !exists {
+                parameters.push(serde_json::json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": schema,
+                }));
+            }
+        }
+        if !parameters.is_empty() {
+            obj.insert("parameters".into(), Value::Array(parameters));
+        }
+
+        if let Some(ref content) = self.request_body {
+            obj.insert(
+                "requestBody".into(),
+                serde_json::json!({ "required": true, "content": content }),
+            );
+        }
+
+        let responses = if self.responses.is_empty() {
+            serde_json::json!({ "default": { "description": "default response" } })
+        } else {
+            Value::Object(self.responses.clone())
+        };
+        obj.insert("responses".into(), responses);
+
+        Value::Object(obj)
+    }
+}
+
+fn status_description(status: u16) -> &'static str {
+    crate::http::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("response")
+}
+
+/// route service builder that attach [Operation] to route information. See [App::routes](crate::App::routes).
+pub struct Documented<R> {
+    route: R,
+    operation: Operation,
+}
+
+impl<R> Documented<R> {
+    /// construct documented route with given route service builder and operation.
+    pub fn new(route: R, operation: Operation) -> Self {
+        Self { route, operation }
+    }
+}
+
+impl<R> PathGen for Documented<R>
+where
+    R: PathGen,
+{
+    fn path_gen(&mut self, prefix: &str) -> String {
+        self.route.path_gen(prefix)
+    }
+
+    fn named_path_gen(&mut self) -> HashMap<&'static str, String> {
+        self.route.named_path_gen()
+    }
+
+    fn route_info(&self, info: RouteInfo, routes: &mut Vec<RouteInfo>) {
+        let start = routes.len();
+        self.route.route_info(info, routes);
+        for info in routes[start..].iter_mut() {
+            info.extensions_mut().insert(self.operation.clone());
+        }
+    }
+}
+
+impl<R> RouteGen for Documented<R>
+where
+    R: RouteGen,
+{
+    type Route<R1> = R::Route<R1>;
+
+    fn route_gen<R1>(route: R1) -> Self::Route<R1> {
+        R::route_gen(route)
+    }
+}
+
+impl<R, Arg> Service<Arg> for Documented<R>
+where
+    R: Service<Arg>,
+{
+    type Response = R::Response;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        self.route.call(arg).await
+    }
+}
+
+/// OpenAPI document generator.
+#[derive(Clone, Debug)]
+pub struct OpenApi {
+    title: String,
+    version: String,
+    description: Option<String>,
+    host: Option<&'static str>,
+}
+
+impl OpenApi {
+    /// construct generator with title and version of api.
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            version: version.into(),
+            description: None,
+            host: None,
+        }
+    }
+
+    /// set description of api.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// describe routes of given virtual host pattern. See [App::host](crate::App::host).
+    ///
+    /// request matching a host is dispatched to it's service before path routing and one document can only
+    /// describe a single host. By default routes without host are described.
+    pub fn host(mut self, host: &'static str) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// generate OpenAPI 3 document from route information. See [App::routes](crate::App::routes).
+    ///
+    /// routes of other hosts (See [OpenApi::host]), routes with unnamed catch all parameter, routes accepting
+    /// any method and methods that are not supported by OpenAPI are skipped.
+    pub fn document(&self, routes: &[RouteInfo]) -> Value {
+        let mut paths = Map::new();
+
+        for route in routes {
+            if route.host() != self.host {
+                continue;
+            }
+
+            let Some((path, params)) = openapi_path(route.path()) else {
+                continue;
+            };
+
+            let op = route.extensions().get::<Operation>().cloned().unwrap_or_default();
+
+            for method in route.methods() {
+                let Some(method) = method_key(method) else {
+                    continue;
+                };
+
+                let item = paths
+                    .entry(path.as_str())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .unwrap();
+
+                let mut op = op.to_value(&params);
+                if let Some(name) = route.name() {
+                    if let Value::Object(ref mut op) = op {
+                        op.entry("operationId").or_insert_with(|| name.into());
+                    }
+                }
+                item.insert(method.into(), op);
+            }
+        }
+
+        let mut info = Map::new();
+        info.insert("title".into(), self.title.as_str().into());
+        info.insert("version".into(), self.version.as_str().into());
+        if let Some(ref description) = self.description {
+            info.insert("description".into(), description.as_str().into());
+        }
+
+        serde_json::json!({
+            "openapi": "3.0.3",
+            "info": info,
+            "paths": paths,
+        })
+    }
+}
+
+fn method_key(method: &Method) -> Option<&'static str> {
+    Some(match *method {
+        Method::GET => "get",
+        Method::PUT => "put",
+        Method::POST => "post",
+        Method::DELETE => "delete",
+        Method::OPTIONS => "options",
+        Method::HEAD => "head",
+        Method::PATCH => "patch",
+        Method::TRACE => "trace",
+        _ => return None,
+    })
+}
+
+// convert router path to OpenAPI path template and collect path parameters with their schema.
+fn openapi_path(mut path: &str) -> Option<(String, Vec<(String, Value)>)> {
+    let mut out = String::with_capacity(path.len());
+    let mut params = Vec::new();
+
+    while let Some(i) = path.find([':', '*']) {
+        out.push_str(&path[..i]);
+
+        let rest = &path[i + 1..];
+        let end = rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+
+        if end == 0 {
+            return None;
+        }
+
+        let name = &rest[..end];
+        let (constraint, rest) = split_constraint(&rest[end..]);
+
+        let schema = match constraint {
+            "int" | "uint" => i64::schema(),
+            _ => String::schema(),
+        };
+
+        out.push('{');
+        out.push_str(name);
+        out.push('}');
+        params.push((name.to_owned(), schema));
+
+        path = rest;
+    }
+
+    out.push_str(path);
+
+    Some((out, params))
+}
+
+#[doc(hidden)]
+/// a hidden module for macro to describe operation of route with autoref specialization.
+pub mod __private {
+    use super::*;
+
+    pub struct Probe<T>(PhantomData<fn() -> T>);
+
+    impl<T> Probe<T> {
+        #[allow(clippy::new_without_default)]
+        pub const fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    pub trait DescribeInput {
+        fn describe_input(&self, op: &mut Operation);
+    }
+
+    impl<T> DescribeInput for Probe<T>
+    where
+        T: OperationInput,
+    {
+        fn describe_input(&self, op: &mut Operation) {
+            T::operation_input(op)
+        }
+    }
+
+    pub trait DescribeInputFallback {
+        fn describe_input(&self, _: &mut Operation) {}
+    }
+
+    impl<T> DescribeInputFallback for &Probe<T> {}
+
+    pub trait DescribeOutput {
+        fn describe_output(&self, op: &mut Operation);
+    }
+
+    impl<T> DescribeOutput for Probe<T>
+    where
+        T: OperationOutput,
+    {
+        fn describe_output(&self, op: &mut Operation) {
+            T::operation_output(op)
+        }
+    }
+
+    pub trait DescribeOutputFallback {
+        fn describe_output(&self, _: &mut Operation) {}
+    }
+
+    impl<T> DescribeOutputFallback for &Probe<T> {}
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{request, StatusCode},
+        route::{get, post},
+        test::collect_string_body,
+        App, NestApp,
+    };
+
+    use super::*;
+
+    struct User {
+        _name: String,
+    }
+
+    impl Schema for User {
+        fn schema() -> Value {
+            object([("name", String::schema(), true), ("age", Option::<u8>::schema(), false)])
+        }
+    }
+
+    #[test]
+    fn path_template() {
+        let (path, params) = openapi_path("/users/:id{int}/files/*rest").unwrap();
+        assert_eq!(path, "/users/{id}/files/{rest}");
+        assert_eq!(params[0], (String::from("id"), i64::schema()));
+        assert_eq!(params[1], (String::from("rest"), String::schema()));
+        assert!(openapi_path("/static/*").is_none());
+
+        // braces inside constraint are balanced.
+        let (path, params) = openapi_path("/files/:name{[a-z]{2,3}}.:ext{alpha}/:id{uint}").unwrap();
+        assert_eq!(path, "/files/{name}.{ext}/{id}");
+        assert_eq!(params[0], (String::from("name"), String::schema()));
+        assert_eq!(params[1], (String::from("ext"), String::schema()));
+        assert_eq!(params[2], (String::from("id"), i64::schema()));
+    }
+
+    #[test]
+    fn document_host() {
+        let op = |id| Documented::new(get(handler_service(|| async { "" })), Operation::new().operation_id(id));
+
+        let nest = |id| -> NestApp<()> { App::new().at("/users", op(id)) };
+
+        let app: NestApp<()> = App::new()
+            .at("/users", op("users"))
+            .host("a.com", nest("a_users"))
+            .host(":sub.b.com", nest("b_users"));
+        let routes = app.routes();
+
+        let doc = OpenApi::new("test", "0.1.0").document(&routes);
+        assert_eq!(doc["paths"]["/users"]["get"]["operationId"], "users");
+
+        let doc = OpenApi::new("test", "0.1.0").host("a.com").document(&routes);
+        assert_eq!(doc["paths"]["/users"]["get"]["operationId"], "a_users");
+
+        let doc = OpenApi::new("test", "0.1.0").host(":sub.b.com").document(&routes);
+        assert_eq!(doc["paths"]["/users"]["get"]["operationId"], "b_users");
+
+        let doc = OpenApi::new("test", "0.1.0").host("c.com").document(&routes);
+        assert!(doc["paths"].as_object().unwrap().is_empty());
+    }
+
+    #[test]
+    fn document() {
+        let mut op = Operation::new().operation_id("create_user").summary("create user");
+        op.request_body("application/json", User::schema());
+        Json::<User>::operation_output(&mut op);
+
+        let api = App::new()
+            .at(
+                "/users/:id",
+                Documented::new(post(handler_service(|| async { "" })), op),
+            )
+            .at_named("index", "/", get(handler_service(|| async { "" })))
+            .at("/any", handler_service(|| async { "" }))
+            .openapi("/openapi.json", OpenApi::new("test", "0.1.0").description("test api"));
+
+        let doc = api.routes();
+        assert_eq!(doc.len(), 4);
+
+        let res = api
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(
+                request::Builder::default()
+                    .uri("/openapi.json")
+                    .body(Default::default())
+                    .unwrap(),
+            )
+            .now_or_panic()
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        let doc: Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(doc["openapi"], "3.0.3");
+        assert_eq!(doc["info"]["title"], "test");
+        assert_eq!(doc["info"]["description"], "test api");
+        assert_eq!(doc["paths"]["/"]["get"]["operationId"], "index");
+        assert_eq!(
+            doc["paths"]["/"]["get"]["responses"]["default"]["description"],
+            "default response"
+        );
+        assert!(doc["paths"]["/any"].is_null());
+        assert!(doc["paths"]["/openapi.json"]["get"].is_null());
+
+        let post = &doc["paths"]["/users/{id}"]["post"];
+        assert_eq!(post["operationId"], "create_user");
+        assert_eq!(post["summary"], "create user");
+        assert_eq!(post["parameters"][0]["name"], "id");
+        assert_eq!(post["parameters"][0]["in"], "path");
+        let schema = &post["requestBody"]["content"]["application/json"]["schema"];
+        assert_eq!(schema["required"], serde_json::json!(["name"]));
+        assert_eq!(schema["properties"]["age"]["nullable"], true);
+        assert_eq!(
+            post["responses"]["200"]["content"]["application/json"]["schema"]["type"],
+            "object"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn probe() {
+        use __private::{DescribeInput, DescribeInputFallback, DescribeOutput, DescribeOutputFallback, Probe};
+
+        let mut op = Operation::new();
+        (&Probe::<Json<User>>::new()).describe_input(&mut op);
+        (&Probe::<String>::new()).describe_input(&mut op);
+        (&Probe::<Result<Json<User>, ()>>::new()).describe_output(&mut op);
+        (&Probe::<()>::new()).describe_output(&mut op);
+
+        assert!(op.request_body.unwrap().contains_key("application/json"));
+        assert!(op.responses.contains_key("200"));
+    }
+}
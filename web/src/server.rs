@@ -200,6 +200,15 @@ where
         Ok(self)
     }
 
+    /// Bind to given address with openssl [SslAcceptorBuilder](openssl_crate::ssl::SslAcceptorBuilder).
+    ///
+    /// Certificates can be selected by SNI host name and reloaded at runtime through a resolver like
+    /// `xitca_tls::openssl::SniResolver` installed with `SniResolver::install`. Contexts switched to by the
+    /// resolver must select `h2` and `http/1.1` ALPN protocols in the same way as this method does.
+    ///
+    /// Client certificate authentication can be enabled with `xitca_tls::openssl::set_client_cert_verifier_from_pem`
+    /// called on the builder. Verified client certificate is then available to handlers through
+    /// `RequestExt::peer_certificate` or `&PeerCertificate` extractor.
     #[cfg(feature = "openssl")]
     pub fn bind_openssl<A: std::net::ToSocketAddrs, ResB, BE>(
         mut self,
@@ -256,6 +265,15 @@ where
         Ok(self)
     }
 
+    /// Bind to given address with rustls [ServerConfig](rustls_crate::ServerConfig).
+    ///
+    /// Certificates can be selected by SNI host name and reloaded at runtime through a certificate resolver
+    /// like `xitca_tls::rustls::SniResolver` set with `ServerConfig::builder().with_cert_resolver`.
+    ///
+    /// Client certificate authentication can be enabled with a client certificate verifier like the one from
+    /// `xitca_tls::rustls::client_cert_verifier_from_pem` set with `ServerConfig::builder().with_client_cert_verifier`.
+    /// Verified client certificate is then available to handlers through `RequestExt::peer_certificate` or
+    /// `&PeerCertificate` extractor.
     #[cfg(feature = "rustls")]
     pub fn bind_rustls<A: std::net::ToSocketAddrs, ResB, BE>(
         mut self,
@@ -69,10 +69,10 @@ impl Service for ServeDir {
 }
 
 mod service {
-    use http_file::{ServeDir, ServeError};
+    use http_file::{FileRange, ServeDir, ServeError};
 
     use crate::{
-        body::ResponseBody,
+        body::{ResponseBody, SendFile},
         context::WebContext,
         error::{Error, ErrorStatus, MatchError, MethodNotAllowed, RouterError},
         http::{Method, StatusCode, WebResponse},
@@ -87,7 +87,13 @@ mod service {
 
         async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
             match self.0.serve(ctx.req()).await {
-                Ok(res) => Ok(res.map(ResponseBody::box_stream)),
+                Ok(mut res) => {
+                    // hint http/1 dispatcher for zero copy transfer of file when possible.
+                    if let Some(FileRange { file, offset, len }) = res.extensions_mut().remove() {
+                        res.extensions_mut().insert(SendFile::new(file, offset, len));
+                    }
+                    Ok(res.map(ResponseBody::box_stream))
+                }
                 Err(ServeError::NotModified) => {
                     let mut res = ctx.into_response(ResponseBody::none());
                     *res.status_mut() = StatusCode::NOT_MODIFIED;
@@ -17,6 +17,7 @@ use xitca_http::{
 use xitca_unsafe_collection::fake::FakeSend;
 
 use crate::{
+    app::RouteNames,
     body::ResponseBody,
     bytes::{Buf, Bytes, BytesMut},
     context::WebContext,
@@ -80,8 +81,9 @@ where
 
     async fn call(&self, mut ctx: WebContext<'r, C, ReqB>) -> Result<Self::Response, Self::Error> {
         let (parts, ext) = ctx.take_request().into_parts();
-        let ctx = ctx.state().clone();
-        let req = Request::from_parts(parts, CompatReqBody::new(ext, ctx));
+        let mut body = CompatReqBody::new(ext, ctx.state().clone());
+        body.names = ctx.names.cloned();
+        let req = Request::from_parts(parts, body);
         let fut = tower_service::Service::call(&mut *self.0.borrow_mut(), req);
         fut.await.map(|res| res.map(CompatResBody::new))
     }
@@ -97,6 +99,8 @@ impl<S> ReadyService for TowerCompatService<S> {
 pub struct CompatReqBody<B, C> {
     body: FakeSend<B>,
     ctx: C,
+    // named route paths passed through tower service to the wrapped xitca-web service.
+    pub(crate) names: Option<RouteNames>,
 }
 
 impl<B, C> CompatReqBody<B, C> {
@@ -105,6 +109,7 @@ impl<B, C> CompatReqBody<B, C> {
         Self {
             body: FakeSend::new(body),
             ctx,
+            names: None,
         }
     }
 
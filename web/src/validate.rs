@@ -0,0 +1,611 @@
+//! declarative validation for deserialized extractor types.
+//!
+//! Validation is opt-in and happens after deserialization of extractor by wrapping it with [Valid] type.
+//! Failed validation produces [ValidationError] listing every failed field and responds with
+//! `422 Unprocessable Entity` and json body. The error can be accessed by custom error handlers through
+//! [Error::upcast].
+//!
+//! # Example
+//! ```rust
+//! # use xitca_web::{
+//! #   error::Error,
+//! #   handler::{handler_service, json::Json},
+//! #   validate::{rule, Valid, Validate, ValidationError},
+//! #   App, WebContext,
+//! # };
+//! #[derive(serde::Deserialize)]
+//! struct User {
+//!     name: String,
+//!     email: String,
+//! }
+//!
+//! // describe validation rules of type. with codegen feature it can be derived by #[derive(Validate)].
+//! impl Validate for User {
+//!     fn validate(&self) -> Result<(), ValidationError> {
+//!         let mut errors = ValidationError::new();
+//!         if let Err(e) = rule::length(&self.name, Some(1), Some(32)) {
+//!             errors.add("name", e);
+//!         }
+//!         if let Err(e) = rule::email(&self.email) {
+//!             errors.add("email", e);
+//!         }
+//!         errors.into_result()
+//!     }
+//! }
+//!
+//! // request body failed validation would be rejected with 422 response.
+//! async fn handler(Valid(Json(user)): Valid<Json<User>>) -> String {
+//!     user.name
+//! }
+//!
+//! // error handler can access validation error.
+//! async fn error_handler(e: &Error) {
+//!     if let Some(e) = e.upcast().downcast_ref::<ValidationError>() {
+//!         for e in e.errors() {
+//!             println!("{}: {}", e.field(), e.message());
+//!         }
+//!     }
+//! }
+//!
+//! App::new()
+//!     .at("/users", handler_service(handler))
+//!     # .at("/", handler_service(|_: &WebContext<'_>| async { "used for infer type" }));
+//! ```
+//!
+//! [Error::upcast]: crate::error::Error::upcast
+
+use core::{
+    convert::Infallible,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use std::error;
+
+use serde_json::{json, Value};
+
+use crate::{
+    body::ResponseBody,
+    context::WebContext,
+    error::{error_from_service, Error},
+    handler::FromRequest,
+    http::{const_header_value::JSON, header::CONTENT_TYPE, StatusCode, WebResponse},
+    service::Service,
+};
+
+#[cfg(feature = "codegen")]
+/// Derive macro for [Validate] trait. Struct with named fields is supported and rules are declared with
+/// `#[validate(..)]` attribute on fields. Rules of [Option] field are only checked when value is present.
+///
+/// # Rules
+/// - `length(min = <usize>, max = <usize>)`: see [rule::length]. either bound can be omitted.
+/// - `range(min = <value>, max = <value>)`: see [rule::range]. either bound can be omitted.
+/// - `regex = "<pattern>"`: see [rule::regex]. pattern is compiled once on first use.
+/// - `email`: see [rule::email].
+/// - `custom = <function path>`: function with `fn(&T) -> Result<(), FieldError>` signature.
+/// - `nested`: validate field with it's own [Validate] impl. errors are prefixed with field name.
+///
+/// # Example
+/// ```rust
+/// # use xitca_web::validate::Validate;
+/// #[derive(Validate)]
+/// struct User {
+///     #[validate(length(min = 1, max = 32), regex = "^[a-z0-9_]+$")]
+///     name: String,
+///     #[validate(email)]
+///     email: Option<String>,
+///     #[validate(range(min = 18, max = 150))]
+///     age: u8,
+///     #[validate(nested)]
+///     address: Address,
+/// }
+///
+/// #[derive(Validate)]
+/// struct Address {
+///     #[validate(length(min = 1))]
+///     city: String,
+/// }
+///
+/// let user = User {
+///     name: String::from("Foo"),
+///     email: Some(String::from("foo@example")),
+///     age: 18,
+///     address: Address { city: String::new() },
+/// };
+///
+/// let e = user.validate().unwrap_err();
+/// let fields = e.errors().iter().map(|e| (e.field(), e.code())).collect::<Vec<_>>();
+/// assert_eq!(fields, [("name", "regex"), ("email", "email"), ("address.city", "length")]);
+/// ```
+pub use xitca_codegen::Validate;
+
+/// trait for validating value after it's deserialized.
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+impl<T> Validate for Box<T>
+where
+    T: Validate + ?Sized,
+{
+    fn validate(&self) -> Result<(), ValidationError> {
+        T::validate(self)
+    }
+}
+
+impl<T> Validate for Option<T>
+where
+    T: Validate,
+{
+    fn validate(&self) -> Result<(), ValidationError> {
+        match *self {
+            Some(ref t) => t.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T> Validate for [T]
+where
+    T: Validate,
+{
+    fn validate(&self) -> Result<(), ValidationError> {
+        let mut errors = ValidationError::new();
+        for (i, t) in self.iter().enumerate() {
+            if let Err(e) = t.validate() {
+                errors.nest(&format!("[{i}]"), e);
+            }
+        }
+        errors.into_result()
+    }
+}
+
+impl<T> Validate for Vec<T>
+where
+    T: Validate,
+{
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.as_slice().validate()
+    }
+}
+
+/// extractor wrapper type that validates value of inner extractor after it's extracted. inner extractor
+/// must dereference to type implementing [Validate] trait. ex: [Json], [Form] and [Query].
+///
+/// [Json]: crate::handler::json::Json
+/// [Form]: crate::handler::form::Form
+/// [Query]: crate::handler::query::Query
+pub struct Valid<E>(pub E);
+
+impl<E> fmt::Debug for Valid<E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Valid").field(&self.0).finish()
+    }
+}
+
+impl<E> Deref for Valid<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E> DerefMut for Valid<E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, 'r, C, B, E> FromRequest<'a, WebContext<'r, C, B>> for Valid<E>
+where
+    E: for<'b> FromRequest<'b, WebContext<'r, C, B>, Error = Error<C>> + Deref,
+    E::Target: Validate,
+{
+    type Type<'b> = Valid<E>;
+    type Error = Error<C>;
+
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        let e = E::from_request(ctx).await?;
+        Validate::validate(&*e)?;
+        Ok(Valid(e))
+    }
+}
+
+/// error of a single field failed validation rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    field: String,
+    code: &'static str,
+    message: String,
+}
+
+impl FieldError {
+    /// construct field error with code of failed rule and human readable message.
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field: String::new(),
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// path of failed field. nested fields are joined with dot. ex: `address.city` and `tags[0]`.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// code of failed rule. ex: `length`, `range`, `regex` and `email`.
+    pub fn code(&self) -> &str {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn prefix(&mut self, prefix: &str) {
+        self.field = match self.field.as_str() {
+            "" => prefix.to_owned(),
+            f if f.starts_with('[') => format!("{prefix}{f}"),
+            f => format!("{prefix}.{f}"),
+        };
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl error::Error for FieldError {}
+
+/// collection of field errors produced by [Validate] trait.
+///
+/// Responds with `422 Unprocessable Entity` and json body listing every field error. ex:
+/// `{"errors":[{"field":"name","code":"length","message":"length must be between 1 and 32"}]}`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationError {
+    errors: Vec<FieldError>,
+}
+
+impl ValidationError {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add error of given field.
+    pub fn add(&mut self, field: &str, mut err: FieldError) {
+        err.prefix(field);
+        self.errors.push(err);
+    }
+
+    /// add errors of nested value with given field as prefix of their paths.
+    pub fn nest(&mut self, field: &str, errors: ValidationError) {
+        self.errors.extend(errors.errors.into_iter().map(|mut e| {
+            e.prefix(field);
+            e
+        }));
+    }
+
+    pub fn errors(&self) -> &[FieldError] {
+        &self.errors
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// `Ok(())` when there is no error.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let errors = self
+            .errors
+            .iter()
+            .map(|e| json!({ "field": e.field, "code": e.code, "message": e.message }))
+            .collect::<Vec<_>>();
+        json!({ "errors": errors })
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("validation error")?;
+        for (i, e) in self.errors.iter().enumerate() {
+            f.write_str(if i == 0 { ": " } else { ", " })?;
+            fmt::Display::fmt(e, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ValidationError {}
+
+error_from_service!(ValidationError);
+
+impl<'r, C, B> Service<WebContext<'r, C, B>> for ValidationError {
+    type Response = WebResponse;
+    type Error = Infallible;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let body = serde_json::to_vec(&self.to_json()).unwrap_or_default();
+        let mut res = ctx.into_response(ResponseBody::from(body));
+        *res.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+        res.headers_mut().insert(CONTENT_TYPE, JSON);
+        Ok(res)
+    }
+}
+
+pub mod rule {
+    //! validation rules used by [Validate](super::Validate) impls.
+
+    use core::fmt;
+
+    pub use regex::Regex;
+
+    use super::FieldError;
+
+    /// type with length.
+    pub trait Length {
+        /// length of value. string length is counted in chars.
+        fn length(&self) -> usize;
+    }
+
+    impl Length for str {
+        fn length(&self) -> usize {
+            self.chars().count()
+        }
+    }
+
+    impl Length for String {
+        fn length(&self) -> usize {
+            self.as_str().length()
+        }
+    }
+
+    impl<T> Length for [T] {
+        fn length(&self) -> usize {
+            self.len()
+        }
+    }
+
+    impl<T> Length for Vec<T> {
+        fn length(&self) -> usize {
+            self.len()
+        }
+    }
+
+    /// value's length must be in range of given inclusive bounds.
+    pub fn length<T>(value: &T, min: Option<usize>, max: Option<usize>) -> Result<(), FieldError>
+    where
+        T: Length + ?Sized,
+    {
+        check_range(&value.length(), min, max, "length", "length")
+    }
+
+    /// value must be in range of given inclusive bounds.
+    pub fn range<T>(value: &T, min: Option<T>, max: Option<T>) -> Result<(), FieldError>
+    where
+        T: PartialOrd + fmt::Display,
+    {
+        check_range(value, min, max, "range", "value")
+    }
+
+    /// value must match given regular expression.
+    pub fn regex<T>(value: &T, re: &Regex) -> Result<(), FieldError>
+    where
+        T: AsRef<str> + ?Sized,
+    {
+        if re.is_match(value.as_ref()) {
+            Ok(())
+        } else {
+            Err(FieldError::new(
+                "regex",
+                format!("value must match pattern {}", re.as_str()),
+            ))
+        }
+    }
+
+    /// value must be an email address in form of `local@domain` where domain consists of at least two
+    /// labels.
+    pub fn email<T>(value: &T) -> Result<(), FieldError>
+    where
+        T: AsRef<str> + ?Sized,
+    {
+        if is_email(value.as_ref()) {
+            Ok(())
+        } else {
+            Err(FieldError::new("email", "value must be a valid email address"))
+        }
+    }
+
+    fn check_range<T>(
+        value: &T,
+        min: Option<T>,
+        max: Option<T>,
+        code: &'static str,
+        name: &str,
+    ) -> Result<(), FieldError>
+    where
+        T: PartialOrd + fmt::Display,
+    {
+        match (min, max) {
+            (Some(min), Some(max)) if *value < min || *value > max => {
+                Err(FieldError::new(code, format!("{name} must be between {min} and {max}")))
+            }
+            (Some(min), None) if *value < min => Err(FieldError::new(code, format!("{name} must be at least {min}"))),
+            (None, Some(max)) if *value > max => Err(FieldError::new(code, format!("{name} must be at most {max}"))),
+            _ => Ok(()),
+        }
+    }
+
+    fn is_email(value: &str) -> bool {
+        let Some((local, domain)) = value.rsplit_once('@') else {
+            return false;
+        };
+
+        let local_valid = !local.is_empty()
+            && local.len() <= 64
+            && !local.starts_with('.')
+            && !local.ends_with('.')
+            && !local.contains("..")
+            && local
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~.".contains(c));
+
+        let mut labels = domain.split('.');
+        let domain_valid = domain.len() <= 253
+            && labels.clone().count() >= 2
+            && labels.all(|label| {
+                !label.is_empty()
+                    && label.len() <= 63
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+
+        local_valid && domain_valid
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn rules() {
+            assert!(length("996", Some(1), Some(3)).is_ok());
+            assert_eq!(
+                length("9966", Some(1), Some(3)).unwrap_err().message(),
+                "length must be between 1 and 3"
+            );
+            assert!(length(&vec![1, 2], None, Some(1)).is_err());
+
+            assert!(range(&18u8, Some(18), None).is_ok());
+            assert_eq!(range(&0.5, None, Some(0.1)).unwrap_err().code(), "range");
+
+            let re = Regex::new("^[a-z]+$").unwrap();
+            assert!(regex("abc", &re).is_ok());
+            assert!(regex("ABC", &re).is_err());
+
+            assert!(email("foo.bar+baz@example.com").is_ok());
+            assert!(email("foo@localhost").is_err());
+            assert!(email("foo@-example.com").is_err());
+            assert!(email("@example.com").is_err());
+            assert!(email("foo..bar@example.com").is_err());
+            assert!(email("foo bar@example.com").is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        handler::{handler_service, json::Json},
+        http::{header::CONTENT_LENGTH, request, Method, RequestExt},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    struct User {
+        name: String,
+        tags: Vec<Tag>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Tag {
+        value: String,
+    }
+
+    impl Validate for User {
+        fn validate(&self) -> Result<(), ValidationError> {
+            let mut errors = ValidationError::new();
+            if let Err(e) = rule::length(&self.name, Some(1), Some(4)) {
+                errors.add("name", e);
+            }
+            if let Err(e) = self.tags.validate() {
+                errors.nest("tags", e);
+            }
+            errors.into_result()
+        }
+    }
+
+    impl Validate for Tag {
+        fn validate(&self) -> Result<(), ValidationError> {
+            let mut errors = ValidationError::new();
+            if let Err(e) = rule::length(&self.value, Some(1), None) {
+                errors.add("value", e);
+            }
+            errors.into_result()
+        }
+    }
+
+    #[test]
+    fn valid_json() {
+        async fn handler(Valid(Json(user)): Valid<Json<User>>) -> String {
+            user.name
+        }
+
+        async fn error_handler<S>(s: &S, ctx: WebContext<'_>) -> Result<WebResponse, Error>
+        where
+            S: for<'r> Service<WebContext<'r>, Response = WebResponse, Error = Error>,
+        {
+            s.call(ctx).await.inspect_err(|e| {
+                let e = e.upcast().downcast_ref::<ValidationError>().unwrap();
+                assert_eq!(e.errors().len(), 2);
+            })
+        }
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed_fn(error_handler)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |body: &'static str| {
+            let req = request::Builder::default()
+                .method(Method::POST)
+                .header(CONTENT_TYPE, JSON)
+                .header(CONTENT_LENGTH, body.len())
+                .body(RequestExt::default().map_body(|_: ()| RequestBody::from(body.as_bytes())))
+                .unwrap();
+            let res = service.call(req).now_or_panic().unwrap();
+            let status = res.status().as_u16();
+            let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+            (status, body)
+        };
+
+        assert_eq!(
+            call(r#"{"name":"foo","tags":[{"value":"bar"}]}"#),
+            (200, String::from("foo"))
+        );
+
+        let (status, body) = call(r#"{"name":"foobar","tags":[{"value":"bar"},{"value":""}]}"#);
+        assert_eq!(status, 422);
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap(),
+            json!({ "errors": [
+                { "field": "name", "code": "length", "message": "length must be between 1 and 4" },
+                { "field": "tags[1].value", "code": "length", "message": "length must be at least 1" },
+            ] })
+        );
+    }
+}